@@ -0,0 +1,452 @@
+use crate::{
+    entry_as,
+    sdt::{SdtHeader, Signature},
+    AcpiTable,
+    EntryIter,
+};
+use bit_field::BitField;
+use core::{mem, slice};
+
+/// Represents the Arm Error Source Table (AEST). This describes the error nodes of an Arm platform that implements
+/// the RAS extension - the processors, caches, memory controllers, SMMUs, and other components that can record
+/// errors - and how each of them reports their errors to the OS.
+#[repr(C, packed)]
+pub struct Aest {
+    header: SdtHeader,
+}
+
+impl AcpiTable for Aest {
+    fn header(&self) -> &SdtHeader {
+        &self.header
+    }
+}
+
+impl Aest {
+    pub fn nodes(&self) -> AestNodeIter<'_> {
+        AestNodeIter {
+            entries: unsafe {
+                EntryIter::new(
+                    Signature::AEST,
+                    (self as *const Aest as *const u8).add(mem::size_of::<Aest>()),
+                    self.header.length.saturating_sub(mem::size_of::<Aest>() as u32),
+                    |_| mem::size_of::<AestNode>(),
+                )
+            },
+        }
+    }
+}
+
+pub struct AestNodeIter<'a> {
+    entries: EntryIter<'a, AestNode>,
+}
+
+impl<'a> Iterator for AestNodeIter<'a> {
+    type Item = &'a AestNode;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (_, node) = self.entries.next()?;
+        Some(unsafe { entry_as(node) })
+    }
+}
+
+/// An error node. The header of each node contains the offsets of its node-specific data, interface structure,
+/// and interrupt structures, which all lie within the node. Each of these is checked to fit within the node's
+/// length before it's returned.
+#[repr(C, packed)]
+pub struct AestNode {
+    node_type: u8,
+    length: u16,
+    _reserved1: u8,
+    node_data_offset: u32,
+    node_interface_offset: u32,
+    node_interrupt_offset: u32,
+    node_interrupt_count: u32,
+    timestamp_rate: u64,
+    _reserved2: u64,
+    error_injection_countdown_rate: u64,
+}
+
+impl crate::EntryHeader for AestNode {
+    fn entry_type(&self) -> u16 {
+        self.node_type.into()
+    }
+
+    fn length(&self) -> u32 {
+        self.length.into()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AestNodeData {
+    Processor {
+        /// The ACPI processor UID of the processor the node belongs to.
+        processor_id: u32,
+        /// If true, the node describes a resource that's shared by every processor in the system, and
+        /// `processor_id` should be ignored.
+        global: bool,
+        /// If true, the node describes a resource that's shared by several processors. `processor_affinity`
+        /// then gives the offset of the PPTT node for the processor hierarchy that shares it.
+        shared: bool,
+        processor_affinity: u64,
+        resource: ProcessorResource,
+    },
+    Memory {
+        /// The proximity domain of the memory controller, as used in the SRAT.
+        proximity_domain: u32,
+    },
+    Smmu {
+        /// The offset of the SMMU's node in the IORT.
+        iort_node_reference: u32,
+        subcomponent_reference: u32,
+    },
+    VendorDefined {
+        hardware_id: u32,
+        unique_id: u32,
+        vendor_data: [u8; 16],
+    },
+    Gic {
+        interface_type: GicInterfaceType,
+        instance_id: u32,
+    },
+    Reserved(u8),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ProcessorResource {
+    /// A cache, given by the offset of its cache type structure in the PPTT.
+    Cache {
+        cache_reference: u32,
+    },
+    Tlb {
+        level: u32,
+    },
+    Generic {
+        resource: u32,
+    },
+    Reserved(u8),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GicInterfaceType {
+    GicCpu,
+    GicDistributor,
+    GicRedistributor,
+    GicIts,
+    Reserved(u32),
+}
+
+impl AestNode {
+    /// The frequency of the timestamps recorded by the node's error records, in Hz.
+    pub fn timestamp_rate(&self) -> u64 {
+        self.timestamp_rate
+    }
+
+    /// The frequency of the node's error injection countdown timer, in Hz.
+    pub fn error_injection_countdown_rate(&self) -> u64 {
+        self.error_injection_countdown_rate
+    }
+
+    /// Decode the node-specific data. Returns `None` if it doesn't fit within the node.
+    pub fn node_data(&self) -> Option<AestNodeData> {
+        let required = match self.node_type {
+            AEST_PROCESSOR_NODE => mem::size_of::<ProcessorNodeData>(),
+            AEST_MEMORY_NODE => mem::size_of::<u32>(),
+            AEST_SMMU_NODE => 2 * mem::size_of::<u32>(),
+            AEST_VENDOR_NODE => 2 * mem::size_of::<u32>() + 16,
+            AEST_GIC_NODE => 2 * mem::size_of::<u32>(),
+            other => return Some(AestNodeData::Reserved(other)),
+        };
+        let data = self.bytes_at(self.node_data_offset, required)?;
+        let read_u32 = |offset: usize| {
+            u32::from_le_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
+        };
+
+        Some(match self.node_type {
+            AEST_PROCESSOR_NODE => {
+                let processor = unsafe { &*(data.as_ptr() as *const ProcessorNodeData) };
+                let resource = match processor.resource_type {
+                    0 => ProcessorResource::Cache { cache_reference: processor.resource },
+                    1 => ProcessorResource::Tlb { level: processor.resource },
+                    2 => ProcessorResource::Generic { resource: processor.resource },
+                    other => ProcessorResource::Reserved(other),
+                };
+                AestNodeData::Processor {
+                    processor_id: processor.processor_id,
+                    global: processor.flags.get_bit(0),
+                    shared: processor.flags.get_bit(1),
+                    processor_affinity: processor.processor_affinity,
+                    resource,
+                }
+            }
+            AEST_MEMORY_NODE => AestNodeData::Memory { proximity_domain: read_u32(0) },
+            AEST_SMMU_NODE => {
+                AestNodeData::Smmu { iort_node_reference: read_u32(0), subcomponent_reference: read_u32(4) }
+            }
+            AEST_VENDOR_NODE => {
+                let mut vendor_data = [0; 16];
+                vendor_data.copy_from_slice(&data[8..24]);
+                AestNodeData::VendorDefined { hardware_id: read_u32(0), unique_id: read_u32(4), vendor_data }
+            }
+            _ => {
+                let interface_type = match read_u32(0) {
+                    0 => GicInterfaceType::GicCpu,
+                    1 => GicInterfaceType::GicDistributor,
+                    2 => GicInterfaceType::GicRedistributor,
+                    3 => GicInterfaceType::GicIts,
+                    other => GicInterfaceType::Reserved(other),
+                };
+                AestNodeData::Gic { interface_type, instance_id: read_u32(4) }
+            }
+        })
+    }
+
+    /// Get the interface structure, which describes how the node's error records are accessed. Returns `None` if
+    /// it doesn't fit within the node.
+    pub fn interface(&self) -> Option<&NodeInterface> {
+        let bytes = self.bytes_at(self.node_interface_offset, mem::size_of::<NodeInterface>())?;
+        Some(unsafe { &*(bytes.as_ptr() as *const NodeInterface) })
+    }
+
+    /// Get the interrupts the node uses to report errors. If they don't fit within the node, this returns an
+    /// empty slice.
+    pub fn interrupts(&self) -> &[NodeInterrupt] {
+        let length = (self.node_interrupt_count as usize).checked_mul(mem::size_of::<NodeInterrupt>());
+        match length.and_then(|length| self.bytes_at(self.node_interrupt_offset, length)) {
+            Some(bytes) => unsafe {
+                slice::from_raw_parts(bytes.as_ptr() as *const NodeInterrupt, self.node_interrupt_count as usize)
+            },
+            None => &[],
+        }
+    }
+
+    /// Get `length` bytes at the given offset within the node, if they fit within it.
+    fn bytes_at(&self, offset: u32, length: usize) -> Option<&[u8]> {
+        let end = (offset as usize).checked_add(length)?;
+        if end > self.length as usize {
+            return None;
+        }
+
+        Some(unsafe { slice::from_raw_parts((self as *const AestNode as *const u8).add(offset as usize), length) })
+    }
+}
+
+const AEST_PROCESSOR_NODE: u8 = 0;
+const AEST_MEMORY_NODE: u8 = 1;
+const AEST_SMMU_NODE: u8 = 2;
+const AEST_VENDOR_NODE: u8 = 3;
+const AEST_GIC_NODE: u8 = 4;
+
+#[repr(C, packed)]
+struct ProcessorNodeData {
+    processor_id: u32,
+    resource_type: u8,
+    _reserved: u8,
+    flags: u8,
+    revision: u8,
+    processor_affinity: u64,
+    /// The first field of the resource substructure, which is the only one that's used for any of the resource
+    /// types.
+    resource: u32,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum InterfaceType {
+    /// The error records are accessed through system registers.
+    SystemRegister,
+    /// The error records are accessed through a memory-mapped register block at `base_address`.
+    MemoryMapped,
+    Reserved(u8),
+}
+
+#[repr(C, packed)]
+pub struct NodeInterface {
+    interface_type: u8,
+    _reserved: [u8; 3],
+    flags: u32,
+    base_address: u64,
+    start_error_record_index: u32,
+    number_of_error_records: u32,
+    error_record_implemented: u64,
+    error_status_reporting: u64,
+    addressing_mode: u64,
+}
+
+impl NodeInterface {
+    pub fn interface_type(&self) -> InterfaceType {
+        match self.interface_type {
+            0 => InterfaceType::SystemRegister,
+            1 => InterfaceType::MemoryMapped,
+            other => InterfaceType::Reserved(other),
+        }
+    }
+
+    /// If true, the node's error records are shared with other nodes.
+    pub fn is_shared(&self) -> bool {
+        { self.flags }.get_bit(0)
+    }
+
+    /// The physical address of the node's error record registers, for memory-mapped interfaces.
+    pub fn base_address(&self) -> u64 {
+        self.base_address
+    }
+
+    pub fn start_error_record_index(&self) -> u32 {
+        self.start_error_record_index
+    }
+
+    pub fn number_of_error_records(&self) -> u32 {
+        self.number_of_error_records
+    }
+
+    /// A bitmap of the error records that are implemented, relative to `start_error_record_index`. If a bit is
+    /// set, the corresponding record is *not* implemented.
+    pub fn error_record_implemented(&self) -> u64 {
+        self.error_record_implemented
+    }
+
+    /// A bitmap of the error records that report errors through `ERRGSR`, rather than their own status
+    /// registers.
+    pub fn error_status_reporting(&self) -> u64 {
+        self.error_status_reporting
+    }
+
+    /// A bitmap of the error records that report physical addresses. If a bit is clear, the record reports
+    /// logical addresses.
+    pub fn addressing_mode(&self) -> u64 {
+        self.addressing_mode
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum InterruptType {
+    FaultHandling,
+    ErrorRecovery,
+    Reserved(u8),
+}
+
+#[repr(C, packed)]
+pub struct NodeInterrupt {
+    interrupt_type: u8,
+    _reserved1: u16,
+    flags: u8,
+    gsiv: u32,
+    iort_id: u8,
+    _reserved2: [u8; 3],
+}
+
+impl NodeInterrupt {
+    pub fn interrupt_type(&self) -> InterruptType {
+        match self.interrupt_type {
+            0 => InterruptType::FaultHandling,
+            1 => InterruptType::ErrorRecovery,
+            other => InterruptType::Reserved(other),
+        }
+    }
+
+    /// If true, the interrupt is level-triggered. Otherwise, it's edge-triggered.
+    pub fn is_level_triggered(&self) -> bool {
+        self.flags.get_bit(0)
+    }
+
+    pub fn gsiv(&self) -> u32 {
+        self.gsiv
+    }
+
+    /// For interrupts delivered as MSIs through a GIC ITS, the identifier of the ITS group node in the IORT.
+    pub fn iort_id(&self) -> u8 {
+        self.iort_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{make_sdt, table_from_bytes};
+    use alloc::vec::Vec;
+
+    /// Build a processor error node for the L1 data cache of a processor, which reports errors through system
+    /// registers.
+    fn processor_node(interrupt_count: u32) -> Vec<u8> {
+        let mut node = Vec::new();
+        node.push(AEST_PROCESSOR_NODE);
+        node.extend_from_slice(&(44u16 + 24 + 48 + 24).to_le_bytes());
+        node.push(0);
+        node.extend_from_slice(&44u32.to_le_bytes());
+        node.extend_from_slice(&(44u32 + 24).to_le_bytes());
+        node.extend_from_slice(&(44u32 + 24 + 48).to_le_bytes());
+        node.extend_from_slice(&interrupt_count.to_le_bytes());
+        node.extend_from_slice(&100_000_000u64.to_le_bytes());
+        node.extend_from_slice(&0u64.to_le_bytes());
+        node.extend_from_slice(&0u64.to_le_bytes());
+
+        // Node-specific data, for a cache
+        node.extend_from_slice(&3u32.to_le_bytes());
+        node.extend_from_slice(&[0, 0, 0, 1]);
+        node.extend_from_slice(&0u64.to_le_bytes());
+        node.extend_from_slice(&0x1c8u32.to_le_bytes());
+        node.extend_from_slice(&0u32.to_le_bytes());
+
+        // Interface structure
+        node.extend_from_slice(&[0, 0, 0, 0]);
+        node.extend_from_slice(&0u32.to_le_bytes());
+        node.extend_from_slice(&0u64.to_le_bytes());
+        node.extend_from_slice(&0u32.to_le_bytes());
+        node.extend_from_slice(&2u32.to_le_bytes());
+        node.extend_from_slice(&0u64.to_le_bytes());
+        node.extend_from_slice(&0u64.to_le_bytes());
+        node.extend_from_slice(&0b11u64.to_le_bytes());
+
+        // Fault-handling and error-recovery interrupts, both as PPIs
+        node.extend_from_slice(&[0, 0, 0, 1]);
+        node.extend_from_slice(&23u32.to_le_bytes());
+        node.extend_from_slice(&[0; 4]);
+        node.extend_from_slice(&[1, 0, 0, 1]);
+        node.extend_from_slice(&22u32.to_le_bytes());
+        node.extend_from_slice(&[0; 4]);
+        node
+    }
+
+    #[test]
+    fn test_processor_node() {
+        let table = make_sdt(b"AEST", 1, &processor_node(2));
+        let aest = table_from_bytes::<Aest>(&table);
+        let nodes: Vec<&AestNode> = aest.nodes().collect();
+        assert_eq!(nodes.len(), 1);
+
+        assert_eq!(nodes[0].timestamp_rate(), 100_000_000);
+        assert_eq!(
+            nodes[0].node_data(),
+            Some(AestNodeData::Processor {
+                processor_id: 3,
+                global: false,
+                shared: false,
+                processor_affinity: 0,
+                resource: ProcessorResource::Cache { cache_reference: 0x1c8 },
+            })
+        );
+
+        let interface = nodes[0].interface().unwrap();
+        assert_eq!(interface.interface_type(), InterfaceType::SystemRegister);
+        assert!(!interface.is_shared());
+        assert_eq!(interface.start_error_record_index(), 0);
+        assert_eq!(interface.number_of_error_records(), 2);
+        assert_eq!(interface.addressing_mode(), 0b11);
+
+        let interrupts = nodes[0].interrupts();
+        assert_eq!(interrupts.len(), 2);
+        assert_eq!(interrupts[0].interrupt_type(), InterruptType::FaultHandling);
+        assert!(interrupts[0].is_level_triggered());
+        assert_eq!(interrupts[0].gsiv(), 23);
+        assert_eq!(interrupts[1].interrupt_type(), InterruptType::ErrorRecovery);
+        assert_eq!(interrupts[1].gsiv(), 22);
+    }
+
+    #[test]
+    fn test_interrupts_outside_node() {
+        let table = make_sdt(b"AEST", 1, &processor_node(3));
+        let aest = table_from_bytes::<Aest>(&table);
+        let node = aest.nodes().next().unwrap();
+        assert!(node.interface().is_some());
+        assert!(node.interrupts().is_empty());
+    }
+}
@@ -0,0 +1,124 @@
+//! The ACPI Platform Error Interfaces (APEI) include two tables, the EINJ and the ERST, that describe each of
+//! their actions as a series of instruction entries, with the same format. The OS performs an action by running
+//! the entries for it, in the order they appear in the table.
+
+use crate::{
+    platform::address::{GenericAddress, RawGenericAddress},
+    AcpiError,
+    AcpiTable,
+};
+use bit_field::BitField;
+use core::{marker::PhantomData, mem, slice};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Instruction {
+    /// Read the register, and return the (masked) result.
+    ReadRegister,
+    /// Read the register, and compare the (masked) result with `value`.
+    ReadRegisterValue,
+    /// Write a value provided by the OS to the register.
+    WriteRegister,
+    /// Write `value` to the register.
+    WriteRegisterValue,
+    Noop,
+    /*
+     * The rest of the instructions can only be used by the ERST.
+     */
+    LoadVar1,
+    LoadVar2,
+    StoreVar1,
+    Add,
+    Subtract,
+    AddValue,
+    SubtractValue,
+    Stall,
+    StallWhileTrue,
+    SkipNextInstructionIfTrue,
+    Goto,
+    SetSrcAddressBase,
+    SetDstAddressBase,
+    MoveData,
+    Reserved(u8),
+}
+
+/// An instruction entry of the EINJ or ERST. `A` is the type of the actions of the table that the entry is in.
+#[derive(Clone, Copy, Debug)]
+#[repr(C, packed)]
+pub struct InstructionEntry<A> {
+    action: u8,
+    instruction: u8,
+    flags: u8,
+    _reserved: u8,
+    register_region: RawGenericAddress,
+    value: u64,
+    mask: u64,
+    _phantom: PhantomData<A>,
+}
+
+impl<A> InstructionEntry<A>
+where
+    A: From<u8>,
+{
+    pub fn action(&self) -> A {
+        A::from(self.action)
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        match self.instruction {
+            0x00 => Instruction::ReadRegister,
+            0x01 => Instruction::ReadRegisterValue,
+            0x02 => Instruction::WriteRegister,
+            0x03 => Instruction::WriteRegisterValue,
+            0x04 => Instruction::Noop,
+            0x05 => Instruction::LoadVar1,
+            0x06 => Instruction::LoadVar2,
+            0x07 => Instruction::StoreVar1,
+            0x08 => Instruction::Add,
+            0x09 => Instruction::Subtract,
+            0x0a => Instruction::AddValue,
+            0x0b => Instruction::SubtractValue,
+            0x0c => Instruction::Stall,
+            0x0d => Instruction::StallWhileTrue,
+            0x0e => Instruction::SkipNextInstructionIfTrue,
+            0x0f => Instruction::Goto,
+            0x10 => Instruction::SetSrcAddressBase,
+            0x11 => Instruction::SetDstAddressBase,
+            0x12 => Instruction::MoveData,
+            other => Instruction::Reserved(other),
+        }
+    }
+
+    /// If true, the bits of the register outside of `mask` must be preserved when it's written to. This means
+    /// the register needs to be read, and the new value merged into it, before being written back.
+    pub fn preserve_register(&self) -> bool {
+        self.flags.get_bit(0)
+    }
+
+    pub fn register(&self) -> Result<GenericAddress, AcpiError> {
+        GenericAddress::from_raw(self.register_region)
+    }
+
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    pub fn mask(&self) -> u64 {
+        self.mask
+    }
+}
+
+/// Get the `count` instruction entries that follow the fixed fields of `table`. If the table is too short to hold
+/// them all, only the entries that fit within it are returned.
+pub(crate) fn instruction_entries<T, A>(table: &T, count: u32) -> &[InstructionEntry<A>]
+where
+    T: AcpiTable,
+{
+    let available = (table.header().length as usize).saturating_sub(mem::size_of::<T>())
+        / mem::size_of::<InstructionEntry<A>>();
+    let num_entries = usize::min(count as usize, available);
+
+    unsafe {
+        let pointer = (table as *const T as *const u8).add(mem::size_of::<T>()) as *const InstructionEntry<A>;
+        slice::from_raw_parts(pointer, num_entries)
+    }
+}
@@ -0,0 +1,415 @@
+use crate::{sdt::SdtHeader, AcpiHandler, AcpiTable, PhysicalMapping};
+use bit_field::BitField;
+use core::{convert::TryInto, slice, str};
+
+/// Represents the Boot Error Record Table (BERT). This points to the Boot Error Region, which holds a Generic Error
+/// Status Block describing any errors that occurred during the previous boot (including any fatal error that
+/// caused it to end) that the platform wasn't able to report at the time.
+#[repr(C, packed)]
+pub struct Bert {
+    header: SdtHeader,
+    boot_error_region_length: u32,
+    boot_error_region: u64,
+}
+
+impl AcpiTable for Bert {
+    fn header(&self) -> &SdtHeader {
+        &self.header
+    }
+}
+
+impl Bert {
+    pub fn boot_error_region_length(&self) -> u32 {
+        self.boot_error_region_length
+    }
+
+    /// The physical address of the Boot Error Region.
+    pub fn boot_error_region(&self) -> u64 {
+        self.boot_error_region
+    }
+
+    /// Map the Boot Error Region using `handler`. Returns `None` if the table doesn't describe a region that is
+    /// large enough to hold a Generic Error Status Block.
+    ///
+    /// ### Safety
+    /// The Boot Error Region must be mappable with `handler`, which is true of any region described by a valid
+    /// BERT.
+    pub unsafe fn map_boot_error_region<H>(&self, handler: &H) -> Option<BootErrorRegion<H>>
+    where
+        H: AcpiHandler,
+    {
+        let address = self.boot_error_region;
+        let length = self.boot_error_region_length as usize;
+        if address == 0 || length < GenericErrorStatus::HEADER_LENGTH {
+            return None;
+        }
+
+        let mapping = unsafe { handler.map_physical_region::<u8>(address.try_into().ok()?, length) };
+        Some(BootErrorRegion { mapping, length })
+    }
+}
+
+/// A mapping of the Boot Error Region. See `Bert::map_boot_error_region`.
+pub struct BootErrorRegion<H>
+where
+    H: AcpiHandler,
+{
+    mapping: PhysicalMapping<H, u8>,
+    length: usize,
+}
+
+impl<H> BootErrorRegion<H>
+where
+    H: AcpiHandler,
+{
+    pub fn status(&self) -> GenericErrorStatus<'_> {
+        let bytes =
+            unsafe { slice::from_raw_parts(self.mapping.virtual_start().as_ptr() as *const u8, self.length) };
+        GenericErrorStatus::new(bytes).unwrap()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ErrorSeverity {
+    Recoverable,
+    Fatal,
+    Corrected,
+    None,
+    Reserved(u32),
+}
+
+impl From<u32> for ErrorSeverity {
+    fn from(severity: u32) -> ErrorSeverity {
+        match severity {
+            0 => ErrorSeverity::Recoverable,
+            1 => ErrorSeverity::Fatal,
+            2 => ErrorSeverity::Corrected,
+            3 => ErrorSeverity::None,
+            other => ErrorSeverity::Reserved(other),
+        }
+    }
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes(bytes[offset..(offset + 2)].try_into().unwrap())
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..(offset + 4)].try_into().unwrap())
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(bytes[offset..(offset + 8)].try_into().unwrap())
+}
+
+/// A Generic Error Status Block, which is used by APEI to report errors. It's made up of a header, followed by a
+/// list of Generic Error Data Entries that each describe an error, and optionally some raw error data.
+#[derive(Clone, Copy, Debug)]
+pub struct GenericErrorStatus<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> GenericErrorStatus<'a> {
+    const HEADER_LENGTH: usize = 20;
+
+    /// Interpret `bytes` as a Generic Error Status Block. Returns `None` if it's too short to hold the block's
+    /// header.
+    pub fn new(bytes: &'a [u8]) -> Option<GenericErrorStatus<'a>> {
+        if bytes.len() < GenericErrorStatus::HEADER_LENGTH {
+            return None;
+        }
+        Some(GenericErrorStatus { bytes })
+    }
+
+    fn block_status(&self) -> u32 {
+        read_u32(self.bytes, 0)
+    }
+
+    pub fn uncorrectable_error_valid(&self) -> bool {
+        self.block_status().get_bit(0)
+    }
+
+    pub fn correctable_error_valid(&self) -> bool {
+        self.block_status().get_bit(1)
+    }
+
+    pub fn multiple_uncorrectable_errors(&self) -> bool {
+        self.block_status().get_bit(2)
+    }
+
+    pub fn multiple_correctable_errors(&self) -> bool {
+        self.block_status().get_bit(3)
+    }
+
+    /// The number of Generic Error Data Entries in the block.
+    pub fn error_data_entry_count(&self) -> u16 {
+        self.block_status().get_bits(4..14) as u16
+    }
+
+    /// The raw error data of the block, which is in a platform-specific format. This is empty if the block
+    /// doesn't have any, or if it doesn't fit within the block.
+    pub fn raw_data(&self) -> &'a [u8] {
+        let offset = read_u32(self.bytes, 4) as usize;
+        let length = read_u32(self.bytes, 8) as usize;
+        match offset.checked_add(length) {
+            Some(end) if offset >= GenericErrorStatus::HEADER_LENGTH && end <= self.bytes.len() => {
+                &self.bytes[offset..end]
+            }
+            _ => &[],
+        }
+    }
+
+    /// The length of the Generic Error Data Entries, in bytes.
+    pub fn data_length(&self) -> u32 {
+        read_u32(self.bytes, 12)
+    }
+
+    pub fn error_severity(&self) -> ErrorSeverity {
+        ErrorSeverity::from(read_u32(self.bytes, 16))
+    }
+
+    /// Iterate over the Generic Error Data Entries. This yields at most `error_data_entry_count` entries, and stops
+    /// at the first entry that doesn't fit within `data_length` bytes (or within the block).
+    pub fn entries(&self) -> GenericErrorDataEntryIter<'a> {
+        let data = &self.bytes[GenericErrorStatus::HEADER_LENGTH..];
+        let length = usize::min(self.data_length() as usize, data.len());
+        GenericErrorDataEntryIter { data: &data[..length], remaining_entries: self.error_data_entry_count() }
+    }
+}
+
+pub struct GenericErrorDataEntryIter<'a> {
+    data: &'a [u8],
+    remaining_entries: u16,
+}
+
+impl<'a> Iterator for GenericErrorDataEntryIter<'a> {
+    type Item = GenericErrorDataEntry<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining_entries == 0 || self.data.len() < GenericErrorDataEntry::HEADER_LENGTH {
+            return None;
+        }
+
+        let revision = read_u16(self.data, 20);
+        let header_length = if revision >= 0x0300 {
+            GenericErrorDataEntry::HEADER_LENGTH_WITH_TIMESTAMP
+        } else {
+            GenericErrorDataEntry::HEADER_LENGTH
+        };
+        let error_data_length = read_u32(self.data, 24) as usize;
+
+        let length = match header_length.checked_add(error_data_length) {
+            Some(length) if length <= self.data.len() => length,
+            _ => {
+                self.data = &[];
+                return None;
+            }
+        };
+
+        let (entry, rest) = self.data.split_at(length);
+        self.data = rest;
+        self.remaining_entries -= 1;
+
+        Some(GenericErrorDataEntry { header: &entry[..header_length], data: &entry[header_length..] })
+    }
+}
+
+/// Describes a single error, in the format used by UEFI's Common Platform Error Records. The type of the error,
+/// and so the format of its data, is identified by its `section_type`.
+#[derive(Clone, Copy, Debug)]
+pub struct GenericErrorDataEntry<'a> {
+    header: &'a [u8],
+    data: &'a [u8],
+}
+
+impl<'a> GenericErrorDataEntry<'a> {
+    const HEADER_LENGTH: usize = 64;
+    /// Entries with a revision of `0x300` or later also have a timestamp.
+    const HEADER_LENGTH_WITH_TIMESTAMP: usize = 72;
+
+    /// The GUID identifying the type of the error, in its in-memory form.
+    pub fn section_type(&self) -> [u8; 16] {
+        self.header[0..16].try_into().unwrap()
+    }
+
+    pub fn error_severity(&self) -> ErrorSeverity {
+        ErrorSeverity::from(read_u32(self.header, 16))
+    }
+
+    pub fn revision(&self) -> u16 {
+        read_u16(self.header, 20)
+    }
+
+    fn validation_bits(&self) -> u8 {
+        self.header[22]
+    }
+
+    /// The raw flags of the entry, which e.g. report whether the error was the primary error in the record.
+    pub fn flags(&self) -> u8 {
+        self.header[23]
+    }
+
+    /// The GUID identifying the Field Replaceable Unit that reported the error, if it's provided.
+    pub fn fru_id(&self) -> Option<[u8; 16]> {
+        if self.validation_bits().get_bit(0) {
+            Some(self.header[28..44].try_into().unwrap())
+        } else {
+            None
+        }
+    }
+
+    /// A description of the Field Replaceable Unit that reported the error, if it's provided and is valid ASCII.
+    pub fn fru_text(&self) -> Option<&'a str> {
+        if !self.validation_bits().get_bit(1) {
+            return None;
+        }
+
+        let text = &self.header[44..64];
+        let text = match text.iter().position(|&byte| byte == 0) {
+            Some(end) => &text[..end],
+            None => text,
+        };
+        if text.is_ascii() {
+            str::from_utf8(text).ok()
+        } else {
+            None
+        }
+    }
+
+    /// The time at which the error was recorded, as a UEFI Error Record Timestamp, if it's provided.
+    pub fn timestamp(&self) -> Option<u64> {
+        if self.header.len() >= GenericErrorDataEntry::HEADER_LENGTH_WITH_TIMESTAMP
+            && self.validation_bits().get_bit(2)
+        {
+            Some(read_u64(self.header, 64))
+        } else {
+            None
+        }
+    }
+
+    /// The error data of the entry, the format of which is given by `section_type`.
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{make_sdt, table_from_bytes, TestHandler};
+    use alloc::vec::Vec;
+
+    const BOOT_ERROR_REGION_ADDRESS: usize = 0x7f00_0000;
+
+    fn entry_bytes(
+        section_type: [u8; 16],
+        severity: u32,
+        revision: u16,
+        fru_text: Option<&[u8]>,
+        data: &[u8],
+    ) -> Vec<u8> {
+        let mut entry = Vec::new();
+        entry.extend_from_slice(&section_type);
+        entry.extend_from_slice(&severity.to_le_bytes());
+        entry.extend_from_slice(&revision.to_le_bytes());
+        entry.push(if fru_text.is_some() { 0b110 } else { 0b100 });
+        entry.push(0x1);
+        entry.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        entry.extend_from_slice(&[0; 16]);
+        let mut text = [0; 20];
+        if let Some(fru_text) = fru_text {
+            text[..fru_text.len()].copy_from_slice(fru_text);
+        }
+        entry.extend_from_slice(&text);
+        if revision >= 0x0300 {
+            entry.extend_from_slice(&0x2026_1014_0000_0000u64.to_le_bytes());
+        }
+        entry.extend_from_slice(data);
+        entry
+    }
+
+    fn status_bytes(block_status: u32, severity: u32, entries: &[u8]) -> Vec<u8> {
+        let mut status = Vec::new();
+        status.extend_from_slice(&block_status.to_le_bytes());
+        status.extend_from_slice(&0u32.to_le_bytes());
+        status.extend_from_slice(&0u32.to_le_bytes());
+        status.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+        status.extend_from_slice(&severity.to_le_bytes());
+        status.extend_from_slice(entries);
+        status
+    }
+
+    fn make_bert(length: u32) -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&length.to_le_bytes());
+        body.extend_from_slice(&(BOOT_ERROR_REGION_ADDRESS as u64).to_le_bytes());
+        make_sdt(b"BERT", 1, &body)
+    }
+
+    #[test]
+    fn test_boot_error_region() {
+        let memory_error =
+            [0xa5, 0xbc, 0x11, 0xa2, 0x6c, 0xc1, 0xba, 0x4c, 0xa1, 0x19, 0x45, 0x30, 0x63, 0x90, 0x6a, 0x5c];
+        let mut entries = entry_bytes(memory_error, 1, 0x0300, Some(b"DIMM A1"), &[0xaa; 80]);
+        entries.extend(entry_bytes([0x11; 16], 2, 0x0201, None, &[0xbb; 4]));
+        let mut region = status_bytes(0b10_0001, 1, &entries);
+        region.extend_from_slice(&[0; 32]); // Unused space at the end of the region
+
+        let table = make_bert(region.len() as u32);
+        let bert = table_from_bytes::<Bert>(&table);
+        assert_eq!(bert.boot_error_region() as usize, BOOT_ERROR_REGION_ADDRESS);
+
+        let handler = TestHandler::with_mapping(BOOT_ERROR_REGION_ADDRESS, region.as_ptr());
+        let region = unsafe { bert.map_boot_error_region(&handler) }.unwrap();
+        let status = region.status();
+        assert!(status.uncorrectable_error_valid());
+        assert!(!status.correctable_error_valid());
+        assert_eq!(status.error_data_entry_count(), 2);
+        assert_eq!(status.error_severity(), ErrorSeverity::Fatal);
+        assert_eq!(status.raw_data(), &[]);
+
+        let entries: Vec<GenericErrorDataEntry> = status.entries().collect();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].section_type(), memory_error);
+        assert_eq!(entries[0].error_severity(), ErrorSeverity::Fatal);
+        assert_eq!(entries[0].fru_text(), Some("DIMM A1"));
+        assert_eq!(entries[0].timestamp(), Some(0x2026_1014_0000_0000));
+        assert_eq!(entries[0].data(), &[0xaa; 80][..]);
+        assert_eq!(entries[1].error_severity(), ErrorSeverity::Corrected);
+        assert_eq!(entries[1].fru_text(), None);
+        assert_eq!(entries[1].timestamp(), None);
+        assert_eq!(entries[1].data(), &[0xbb; 4]);
+    }
+
+    #[test]
+    fn test_entry_limits() {
+        let mut entries = entry_bytes([0x22; 16], 0, 0x0300, None, &[0xcc; 8]);
+        entries.extend(entry_bytes([0x33; 16], 0, 0x0300, None, &[0xdd; 8]));
+
+        // The entry count limits the iterator, even when there's more data
+        let status = status_bytes(1 << 4, 0, &entries);
+        let status = GenericErrorStatus::new(&status).unwrap();
+        assert_eq!(status.entries().count(), 1);
+
+        // As does the data length, even when the entry count claims there are more entries
+        let mut status = status_bytes(8 << 4, 0, &entries);
+        status[12..16].copy_from_slice(&((entries.len() / 2) as u32).to_le_bytes());
+        let status = GenericErrorStatus::new(&status).unwrap();
+        assert_eq!(status.entries().map(|entry| entry.section_type()).collect::<Vec<_>>(), [[0x22; 16]]);
+
+        // An entry whose data extends past the end of the block stops the iterator
+        let mut status = status_bytes(2 << 4, 0, &entries);
+        let second_entry = 20 + entries.len() / 2;
+        status[(second_entry + 24)..(second_entry + 28)].copy_from_slice(&0x1000u32.to_le_bytes());
+        let status = GenericErrorStatus::new(&status).unwrap();
+        assert_eq!(status.entries().count(), 1);
+    }
+
+    #[test]
+    fn test_no_boot_error_region() {
+        let table = make_bert(4);
+        let bert = table_from_bytes::<Bert>(&table);
+        let handler = TestHandler::with_mapping(BOOT_ERROR_REGION_ADDRESS, table.as_ptr());
+        assert!(unsafe { bert.map_boot_error_region(&handler) }.is_none());
+    }
+}
@@ -0,0 +1,344 @@
+use crate::{sdt::SdtHeader, AcpiError, AcpiHandler, AcpiTable, PhysicalMapping};
+use bit_field::BitField;
+use core::{convert::TryInto, slice};
+
+/// Represents the Boot Graphics Resource Table (BGRT). This describes the image (usually the platform vendor's
+/// logo) that the firmware drew on the screen during boot, so that the OS can keep it on the screen, or redraw it
+/// after changing the graphics mode.
+#[repr(C, packed)]
+pub struct Bgrt {
+    header: SdtHeader,
+    version: u16,
+    status: u8,
+    image_type: u8,
+    image_address: u64,
+    image_offset_x: u32,
+    image_offset_y: u32,
+}
+
+impl AcpiTable for Bgrt {
+    fn header(&self) -> &SdtHeader {
+        &self.header
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ImageType {
+    Bitmap,
+    Reserved(u8),
+}
+
+/// How the image has been rotated (clockwise) from its natural orientation to be displayed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Orientation {
+    None,
+    Rotated90,
+    Rotated180,
+    Rotated270,
+}
+
+impl Bgrt {
+    pub fn version(&self) -> u16 {
+        self.version
+    }
+
+    /// If true, the image is currently being displayed. This becomes false once anything else has been drawn over
+    /// it.
+    pub fn is_displayed(&self) -> bool {
+        self.status.get_bit(0)
+    }
+
+    pub fn orientation(&self) -> Orientation {
+        match self.status.get_bits(1..3) {
+            0 => Orientation::None,
+            1 => Orientation::Rotated90,
+            2 => Orientation::Rotated180,
+            3 => Orientation::Rotated270,
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn image_type(&self) -> ImageType {
+        match self.image_type {
+            0 => ImageType::Bitmap,
+            other => ImageType::Reserved(other),
+        }
+    }
+
+    /// The physical address of the image. This is in memory that the firmware has marked as `EfiBootServicesData`,
+    /// so the OS must read it before reclaiming that memory.
+    pub fn image_address(&self) -> u64 {
+        self.image_address
+    }
+
+    /// The position of the image's upper-left corner on the screen, in pixels.
+    pub fn image_offset(&self) -> (u32, u32) {
+        (self.image_offset_x, self.image_offset_y)
+    }
+
+    /// Map and decode the image using `handler`. The image must be an uncompressed 24-bit or 32-bit bitmap, which
+    /// is the only format allowed by the spec. `max_size` limits the size of the bitmap (as declared by its header)
+    /// that will be mapped, so that a corrupt header can't cause an absurd region to be mapped.
+    ///
+    /// ### Safety
+    /// The image must be mappable with `handler`, which is true of any image described by a valid BGRT before boot
+    /// services memory has been reclaimed.
+    pub unsafe fn image<H>(&self, handler: &H, max_size: usize) -> Result<BootImage<H>, AcpiError>
+    where
+        H: AcpiHandler,
+    {
+        if self.image_type() != ImageType::Bitmap {
+            return Err(AcpiError::InvalidBootImage);
+        }
+        let address: usize = self.image_address.try_into().map_err(|_| AcpiError::InvalidBootImage)?;
+
+        let header = {
+            let mapping =
+                unsafe { handler.map_physical_region::<[u8; BMP_HEADER_LENGTH]>(address, BMP_HEADER_LENGTH) };
+            *mapping
+        };
+        let image = BootImage::<H>::parse_header(&header)?;
+        if image.file_size > max_size {
+            return Err(AcpiError::InvalidBootImage);
+        }
+
+        let mapping = unsafe { handler.map_physical_region::<u8>(address, image.file_size) };
+        Ok(BootImage { mapping: Some(mapping), ..image })
+    }
+}
+
+/// The length of the headers at the start of a bitmap: the 14-byte file header, and the 40-byte
+/// `BITMAPINFOHEADER` (later versions of the info header extend this one).
+const BMP_HEADER_LENGTH: usize = 54;
+
+/// A mapped bitmap, decoded from the image described by the BGRT. See `Bgrt::image`.
+pub struct BootImage<H>
+where
+    H: AcpiHandler,
+{
+    mapping: Option<PhysicalMapping<H, u8>>,
+    file_size: usize,
+    pixel_data_offset: usize,
+    width: u32,
+    height: u32,
+    bytes_per_pixel: usize,
+    /// Bitmaps are normally stored with their bottom row first, unless they have a negative height.
+    top_down: bool,
+}
+
+impl<H> BootImage<H>
+where
+    H: AcpiHandler,
+{
+    fn parse_header(header: &[u8; BMP_HEADER_LENGTH]) -> Result<BootImage<H>, AcpiError> {
+        let u16_at = |offset: usize| u16::from_le_bytes([header[offset], header[offset + 1]]);
+        let u32_at = |offset: usize| {
+            u32::from_le_bytes([header[offset], header[offset + 1], header[offset + 2], header[offset + 3]])
+        };
+
+        let info_header_length = u32_at(14);
+        let planes = u16_at(26);
+        let bits_per_pixel = u16_at(28);
+        let compression = u32_at(30);
+        if &header[0..2] != b"BM"
+            || info_header_length < 40
+            || planes != 1
+            || !(bits_per_pixel == 24 || bits_per_pixel == 32)
+            || compression != 0
+        {
+            return Err(AcpiError::InvalidBootImage);
+        }
+
+        let width = u32_at(18) as i32;
+        let height = u32_at(22) as i32;
+        if width <= 0 || height == 0 {
+            return Err(AcpiError::InvalidBootImage);
+        }
+
+        let image = BootImage {
+            mapping: None,
+            file_size: u32_at(2) as usize,
+            pixel_data_offset: u32_at(10) as usize,
+            width: width as u32,
+            height: height.unsigned_abs(),
+            bytes_per_pixel: bits_per_pixel as usize / 8,
+            top_down: height < 0,
+        };
+
+        let pixel_data_length = (image.stride() as u64) * (image.height as u64);
+        if (image.pixel_data_offset as u64) < BMP_HEADER_LENGTH as u64
+            || image.pixel_data_offset as u64 + pixel_data_length > image.file_size as u64
+        {
+            return Err(AcpiError::InvalidBootImage);
+        }
+
+        Ok(image)
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn bits_per_pixel(&self) -> u8 {
+        (self.bytes_per_pixel * 8) as u8
+    }
+
+    /// The number of bytes between the start of each row, which are padded to a multiple of 4 bytes.
+    fn stride(&self) -> usize {
+        (self.width as usize * self.bytes_per_pixel).div_ceil(4) * 4
+    }
+
+    /// The colour of the pixel at `(x, y)`, counting from the top-left corner, as `[red, green, blue, alpha]`. The
+    /// fourth byte of 32-bit pixels is unused in uncompressed bitmaps, so every pixel is opaque. Returns `None` if
+    /// the position is outside the image.
+    pub fn pixel(&self, x: u32, y: u32) -> Option<[u8; 4]> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        let row = if self.top_down { y } else { self.height - 1 - y };
+        let offset = self.pixel_data_offset + row as usize * self.stride() + x as usize * self.bytes_per_pixel;
+        let bytes = &self.bytes()[offset..(offset + 3)];
+        Some([bytes[2], bytes[1], bytes[0], 0xff])
+    }
+
+    /// Iterate over the pixels of the image as `[red, green, blue, alpha]`, row by row from the top-left corner.
+    pub fn pixels(&self) -> impl Iterator<Item = [u8; 4]> + '_ {
+        (0..self.height).flat_map(move |y| (0..self.width).map(move |x| self.pixel(x, y).unwrap()))
+    }
+
+    /// The whole bitmap file, including its headers.
+    pub fn bytes(&self) -> &[u8] {
+        let mapping = self.mapping.as_ref().unwrap();
+        unsafe { slice::from_raw_parts(mapping.virtual_start().as_ptr(), self.file_size) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{make_sdt, table_from_bytes, TestMemory};
+    use alloc::vec::Vec;
+
+    /// Build a 24-bit bitmap (with rows padded to 4 bytes) from rows of `[red, green, blue]` pixels, given from the
+    /// top down.
+    fn make_bmp(rows: &[&[[u8; 3]]], top_down: bool) -> Vec<u8> {
+        let width = rows[0].len();
+        let stride = (width * 3 + 3) & !3;
+        let mut pixels = Vec::new();
+        let mut add_row = |row: &[[u8; 3]]| {
+            let start = pixels.len();
+            for &[red, green, blue] in row {
+                pixels.extend_from_slice(&[blue, green, red]);
+            }
+            pixels.resize(start + stride, 0);
+        };
+        if top_down {
+            rows.iter().for_each(|row| add_row(row));
+        } else {
+            rows.iter().rev().for_each(|row| add_row(row));
+        }
+
+        let height = if top_down { -(rows.len() as i32) } else { rows.len() as i32 };
+        let mut bmp = Vec::new();
+        bmp.extend_from_slice(b"BM");
+        bmp.extend_from_slice(&((BMP_HEADER_LENGTH + pixels.len()) as u32).to_le_bytes());
+        bmp.extend_from_slice(&[0; 4]);
+        bmp.extend_from_slice(&(BMP_HEADER_LENGTH as u32).to_le_bytes());
+        bmp.extend_from_slice(&40u32.to_le_bytes());
+        bmp.extend_from_slice(&(width as i32).to_le_bytes());
+        bmp.extend_from_slice(&height.to_le_bytes());
+        bmp.extend_from_slice(&1u16.to_le_bytes());
+        bmp.extend_from_slice(&24u16.to_le_bytes());
+        bmp.extend_from_slice(&0u32.to_le_bytes());
+        bmp.extend_from_slice(&(pixels.len() as u32).to_le_bytes());
+        bmp.extend_from_slice(&[0; 16]);
+        bmp.extend(pixels);
+        bmp
+    }
+
+    fn make_bgrt(image_address: usize) -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&1u16.to_le_bytes());
+        body.push(0b001);
+        body.push(0);
+        body.extend_from_slice(&(image_address as u64).to_le_bytes());
+        body.extend_from_slice(&400u32.to_le_bytes());
+        body.extend_from_slice(&300u32.to_le_bytes());
+        make_sdt(b"BGRT", 1, &body)
+    }
+
+    const RED: [u8; 3] = [0xff, 0, 0];
+    const GREEN: [u8; 3] = [0, 0xff, 0];
+    const BLUE: [u8; 3] = [0, 0, 0xff];
+    const WHITE: [u8; 3] = [0xff, 0xff, 0xff];
+
+    #[test]
+    fn test_bgrt() {
+        for &top_down in &[false, true] {
+            let mut memory = TestMemory::new(0x7e00_0000);
+            let image_address = memory.add(&make_bmp(&[&[RED, GREEN, BLUE], &[WHITE, RED, GREEN]], top_down));
+            let table = make_bgrt(image_address);
+            let bgrt = table_from_bytes::<Bgrt>(&table);
+
+            assert_eq!(bgrt.version(), 1);
+            assert!(bgrt.is_displayed());
+            assert_eq!(bgrt.orientation(), Orientation::None);
+            assert_eq!(bgrt.image_type(), ImageType::Bitmap);
+            assert_eq!(bgrt.image_offset(), (400, 300));
+
+            let image = unsafe { bgrt.image(&memory.handler(), 0x1000) }.unwrap();
+            assert_eq!(image.width(), 3);
+            assert_eq!(image.height(), 2);
+            assert_eq!(image.bits_per_pixel(), 24);
+            assert_eq!(image.pixel(0, 1), Some([0xff, 0xff, 0xff, 0xff]));
+            assert_eq!(image.pixel(3, 0), None);
+
+            let pixels: Vec<[u8; 4]> = image.pixels().collect();
+            assert_eq!(
+                pixels,
+                [
+                    [0xff, 0, 0, 0xff],
+                    [0, 0xff, 0, 0xff],
+                    [0, 0, 0xff, 0xff],
+                    [0xff, 0xff, 0xff, 0xff],
+                    [0xff, 0, 0, 0xff],
+                    [0, 0xff, 0, 0xff]
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_images() {
+        let mut memory = TestMemory::new(0x7e00_0000);
+        let row: &[[u8; 3]] = &[RED; 16];
+        let bmp = make_bmp(&[row; 16], false);
+        let image_address = memory.add(&bmp);
+
+        let mut compressed = bmp.clone();
+        compressed[30] = 1;
+        let compressed_address = memory.add(&compressed);
+
+        let mut truncated = bmp.clone();
+        truncated[2..6].copy_from_slice(&100u32.to_le_bytes());
+        let truncated_address = memory.add(&truncated);
+
+        let handler = memory.handler();
+        let image = |address, max_size| {
+            let table = make_bgrt(address);
+            unsafe { table_from_bytes::<Bgrt>(&table).image(&handler, max_size) }
+        };
+
+        assert!(image(image_address, bmp.len()).is_ok());
+        // The image is larger than the caller will allow
+        assert!(matches!(image(image_address, 256), Err(AcpiError::InvalidBootImage)));
+        assert!(matches!(image(compressed_address, 0x1000), Err(AcpiError::InvalidBootImage)));
+        // The header's file size is too small to hold the pixels
+        assert!(matches!(image(truncated_address, 0x1000), Err(AcpiError::InvalidBootImage)));
+    }
+}
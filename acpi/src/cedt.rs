@@ -0,0 +1,409 @@
+use crate::{
+    entry_as,
+    sdt::{SdtHeader, Signature},
+    AcpiTable,
+    EntryIter,
+};
+use bit_field::BitField;
+use core::{mem, ptr, slice};
+
+/// Represents the CXL Early Discovery Table (CEDT). This describes the CXL host bridges on the platform, and the
+/// fixed windows of the host physical address space that CXL memory can be mapped into.
+#[repr(C, packed)]
+pub struct Cedt {
+    header: SdtHeader,
+}
+
+impl AcpiTable for Cedt {
+    fn header(&self) -> &SdtHeader {
+        &self.header
+    }
+}
+
+impl Cedt {
+    pub fn structures(&self) -> CedtStructureIter<'_> {
+        CedtStructureIter {
+            entries: unsafe {
+                EntryIter::new(
+                    Signature::CEDT,
+                    (self as *const Cedt as *const u8).add(mem::size_of::<Cedt>()),
+                    self.header.length.saturating_sub(mem::size_of::<Cedt>() as u32),
+                    entry_minimum_length,
+                )
+            },
+        }
+    }
+}
+
+pub struct CedtStructureIter<'a> {
+    entries: EntryIter<'a, CedtStructureHeader>,
+}
+
+pub enum CedtStructure<'a> {
+    HostBridge(&'a CxlHostBridge),
+    FixedMemoryWindow(&'a CxlFixedMemoryWindow),
+    XorInterleaveMath(&'a CxlXorInterleaveMath),
+}
+
+impl<'a> Iterator for CedtStructureIter<'a> {
+    type Item = CedtStructure<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (entry_type, entry) in &mut self.entries {
+            let structure = unsafe {
+                match entry_type {
+                    0x0 => CedtStructure::HostBridge(entry_as(entry)),
+                    0x1 => CedtStructure::FixedMemoryWindow(entry_as(entry)),
+                    0x2 => CedtStructure::XorInterleaveMath(entry_as(entry)),
+
+                    /*
+                     * We don't yet support the other structure types, and skip them.
+                     */
+                    _ => continue,
+                }
+            };
+            return Some(structure);
+        }
+
+        None
+    }
+}
+
+#[derive(Clone, Copy)]
+#[repr(C, packed)]
+struct CedtStructureHeader {
+    structure_type: u8,
+    _reserved: u8,
+    length: u16,
+}
+
+impl crate::EntryHeader for CedtStructureHeader {
+    fn entry_type(&self) -> u16 {
+        self.structure_type.into()
+    }
+
+    fn length(&self) -> u32 {
+        self.length.into()
+    }
+}
+
+fn entry_minimum_length(header: &CedtStructureHeader) -> usize {
+    match header.structure_type {
+        0x0 => mem::size_of::<CxlHostBridge>(),
+        0x1 => mem::size_of::<CxlFixedMemoryWindow>(),
+        0x2 => mem::size_of::<CxlXorInterleaveMath>(),
+        _ => mem::size_of::<CedtStructureHeader>(),
+    }
+}
+
+/// The CXL Host Bridge Structure (CHBS), which describes the register block of a CXL host bridge.
+#[repr(C, packed)]
+pub struct CxlHostBridge {
+    header: CedtStructureHeader,
+    uid: u32,
+    cxl_version: u32,
+    _reserved: u32,
+    base: u64,
+    length: u64,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CxlVersion {
+    /// A CXL 1.1 host bridge. Its register block is the RCRB (Root Complex Register Block) of its downstream
+    /// port.
+    Cxl1_1,
+    /// A CXL 2.0 (or later) host bridge. Its register block holds the CXL Host Bridge Component Registers.
+    Cxl2_0,
+    Reserved(u32),
+}
+
+impl CxlHostBridge {
+    /// The `_UID` of the host bridge's object in the namespace.
+    pub fn uid(&self) -> u32 {
+        self.uid
+    }
+
+    pub fn cxl_version(&self) -> CxlVersion {
+        match self.cxl_version {
+            0 => CxlVersion::Cxl1_1,
+            1 => CxlVersion::Cxl2_0,
+            other => CxlVersion::Reserved(other),
+        }
+    }
+
+    /// The physical address of the host bridge's register block.
+    pub fn base(&self) -> u64 {
+        self.base
+    }
+
+    pub fn length(&self) -> u64 {
+        self.length
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum InterleaveArithmetic {
+    /// The target is selected by taking the address modulo the number of interleave ways.
+    Modulo,
+    /// The target is selected using the XOR maps in the CXIMS with the same granularity.
+    Xor,
+    Reserved(u8),
+}
+
+/// The restrictions on what memory can be mapped into a fixed memory window.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct WindowRestrictions(pub u16);
+
+impl WindowRestrictions {
+    /// If true, the window can hold device-coherent memory (e.g. of a CXL Type 2 device).
+    pub fn device_coherent(&self) -> bool {
+        self.0.get_bit(0)
+    }
+
+    /// If true, the window can hold host-only coherent memory (e.g. of a CXL Type 3 device).
+    pub fn host_only_coherent(&self) -> bool {
+        self.0.get_bit(1)
+    }
+
+    pub fn volatile(&self) -> bool {
+        self.0.get_bit(2)
+    }
+
+    pub fn persistent(&self) -> bool {
+        self.0.get_bit(3)
+    }
+
+    /// If true, the configuration of the window's devices is fixed by the firmware, and must not be changed by
+    /// the OS.
+    pub fn fixed_device_configuration(&self) -> bool {
+        self.0.get_bit(4)
+    }
+}
+
+/// An interleave target of a fixed memory window.
+#[derive(Clone, Copy)]
+#[repr(C, packed)]
+pub struct InterleaveTarget {
+    host_bridge_uid: u32,
+}
+
+impl InterleaveTarget {
+    /// The `_UID` of the target host bridge. This matches the `uid` of one of the CHBSs.
+    pub fn host_bridge_uid(&self) -> u32 {
+        self.host_bridge_uid
+    }
+}
+
+/// The CXL Fixed Memory Window Structure (CFMWS), which describes a window of the host physical address space that
+/// is interleaved across one or more CXL host bridges.
+#[repr(C, packed)]
+pub struct CxlFixedMemoryWindow {
+    header: CedtStructureHeader,
+    _reserved1: u32,
+    base_hpa: u64,
+    window_size: u64,
+    encoded_interleave_ways: u8,
+    interleave_arithmetic: u8,
+    _reserved2: u16,
+    host_bridge_interleave_granularity: u32,
+    window_restrictions: u16,
+    qtg_id: u16,
+    // Followed by the interleave target list
+}
+
+impl CxlFixedMemoryWindow {
+    /// The base of the window in the host physical address space.
+    pub fn base_hpa(&self) -> u64 {
+        self.base_hpa
+    }
+
+    pub fn window_size(&self) -> u64 {
+        self.window_size
+    }
+
+    /// The number of ways the window is interleaved across host bridges. Returns `None` if the encoding is
+    /// reserved.
+    pub fn interleave_ways(&self) -> Option<u8> {
+        match self.encoded_interleave_ways {
+            encoded @ 0..=4 => Some(1 << encoded),
+            8 => Some(3),
+            9 => Some(6),
+            10 => Some(12),
+            _ => None,
+        }
+    }
+
+    pub fn interleave_arithmetic(&self) -> InterleaveArithmetic {
+        match self.interleave_arithmetic {
+            0 => InterleaveArithmetic::Modulo,
+            1 => InterleaveArithmetic::Xor,
+            other => InterleaveArithmetic::Reserved(other),
+        }
+    }
+
+    /// The number of consecutive bytes mapped to each host bridge before moving to the next. Returns `None` if the
+    /// encoding is reserved.
+    pub fn interleave_granularity(&self) -> Option<u32> {
+        match self.host_bridge_interleave_granularity {
+            encoded @ 0..=6 => Some(256 << encoded),
+            _ => None,
+        }
+    }
+
+    pub fn window_restrictions(&self) -> WindowRestrictions {
+        WindowRestrictions(self.window_restrictions)
+    }
+
+    /// The ID of the QoS Throttling Group for the window.
+    pub fn qtg_id(&self) -> u16 {
+        self.qtg_id
+    }
+
+    /// The host bridges the window is interleaved across, in interleave order. This is empty if the number of
+    /// interleave ways is reserved, or if the list doesn't fit in the structure.
+    pub fn interleave_targets(&self) -> &[InterleaveTarget] {
+        let ways = self.interleave_ways().unwrap_or(0) as usize;
+        let available = (self.header.length as usize - mem::size_of::<CxlFixedMemoryWindow>())
+            / mem::size_of::<InterleaveTarget>();
+        if ways > available {
+            return &[];
+        }
+
+        unsafe {
+            slice::from_raw_parts(
+                (self as *const CxlFixedMemoryWindow as *const u8).add(mem::size_of::<CxlFixedMemoryWindow>())
+                    as *const InterleaveTarget,
+                ways,
+            )
+        }
+    }
+}
+
+/// The CXL XOR Interleave Math Structure (CXIMS), which gives the XOR maps used to select targets in windows with
+/// XOR interleave arithmetic.
+#[repr(C, packed)]
+pub struct CxlXorInterleaveMath {
+    header: CedtStructureHeader,
+    _reserved: u16,
+    host_bridge_interleave_granularity: u8,
+    num_xormaps: u8,
+    // Followed by `num_xormaps` XOR maps
+}
+
+impl CxlXorInterleaveMath {
+    /// The granularity of the windows these XOR maps apply to, in the same encoding as
+    /// `CxlFixedMemoryWindow::interleave_granularity`.
+    pub fn interleave_granularity(&self) -> Option<u32> {
+        match self.host_bridge_interleave_granularity {
+            encoded @ 0..=6 => Some(256 << encoded),
+            _ => None,
+        }
+    }
+
+    /// Each map selects the address bits that are XORed together to produce one bit of the target index.
+    pub fn xormaps(&self) -> impl Iterator<Item = u64> + '_ {
+        let available =
+            (self.header.length as usize - mem::size_of::<CxlXorInterleaveMath>()) / mem::size_of::<u64>();
+        let maps = unsafe {
+            (self as *const CxlXorInterleaveMath as *const u8).add(mem::size_of::<CxlXorInterleaveMath>())
+                as *const u64
+        };
+        (0..usize::min(self.num_xormaps as usize, available))
+            .map(move |i| unsafe { ptr::read_unaligned(maps.add(i)) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{make_sdt, table_from_bytes};
+    use alloc::vec::Vec;
+
+    fn host_bridge_bytes(uid: u32, base: u64) -> Vec<u8> {
+        let mut structure = Vec::new();
+        structure.extend_from_slice(&[0x00, 0x00, 32, 0x00]);
+        structure.extend_from_slice(&uid.to_le_bytes());
+        structure.extend_from_slice(&1u32.to_le_bytes());
+        structure.extend_from_slice(&0u32.to_le_bytes());
+        structure.extend_from_slice(&base.to_le_bytes());
+        structure.extend_from_slice(&0x1_0000u64.to_le_bytes());
+        structure
+    }
+
+    fn make_cedt() -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend(host_bridge_bytes(7, 0xfe00_0000));
+        body.extend(host_bridge_bytes(8, 0xfe01_0000));
+
+        // A 2-way interleaved window across both host bridges, with 4KiB granularity
+        body.extend_from_slice(&[0x01, 0x00, 44, 0x00]);
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body.extend_from_slice(&0x100_0000_0000u64.to_le_bytes());
+        body.extend_from_slice(&0x10_0000_0000u64.to_le_bytes());
+        body.extend_from_slice(&[1, 0]);
+        body.extend_from_slice(&0u16.to_le_bytes());
+        body.extend_from_slice(&4u32.to_le_bytes());
+        body.extend_from_slice(&0b0_0110u16.to_le_bytes());
+        body.extend_from_slice(&0u16.to_le_bytes());
+        body.extend_from_slice(&7u32.to_le_bytes());
+        body.extend_from_slice(&8u32.to_le_bytes());
+
+        make_sdt(b"CEDT", 1, &body)
+    }
+
+    #[test]
+    fn test_host_bridges() {
+        let table = make_cedt();
+        let cedt = table_from_bytes::<Cedt>(&table);
+        assert_eq!(cedt.structures().count(), 3);
+
+        let host_bridge = match cedt.structures().next() {
+            Some(CedtStructure::HostBridge(host_bridge)) => host_bridge,
+            _ => panic!("Expected a CHBS"),
+        };
+        assert_eq!(host_bridge.uid(), 7);
+        assert_eq!(host_bridge.cxl_version(), CxlVersion::Cxl2_0);
+        assert_eq!(host_bridge.base(), 0xfe00_0000);
+        assert_eq!(host_bridge.length(), 0x1_0000);
+    }
+
+    #[test]
+    fn test_fixed_memory_window() {
+        let table = make_cedt();
+        let cedt = table_from_bytes::<Cedt>(&table);
+        let window = cedt
+            .structures()
+            .find_map(|structure| match structure {
+                CedtStructure::FixedMemoryWindow(window) => Some(window),
+                _ => None,
+            })
+            .unwrap();
+
+        assert_eq!(window.base_hpa(), 0x100_0000_0000);
+        assert_eq!(window.window_size(), 0x10_0000_0000);
+        assert_eq!(window.interleave_ways(), Some(2));
+        assert_eq!(window.interleave_arithmetic(), InterleaveArithmetic::Modulo);
+        assert_eq!(window.interleave_granularity(), Some(0x1000));
+        assert!(window.window_restrictions().host_only_coherent());
+        assert!(window.window_restrictions().volatile());
+        assert!(!window.window_restrictions().persistent());
+        assert_eq!(
+            window.interleave_targets().iter().map(|target| target.host_bridge_uid()).collect::<Vec<u32>>(),
+            [7, 8]
+        );
+    }
+
+    #[test]
+    fn test_truncated_target_list() {
+        let mut table = make_cedt();
+        table[36 + 64 + 24] = 2; // Claim 4 ways, with only 2 targets in the structure
+        let cedt = table_from_bytes::<Cedt>(&table);
+        match cedt.structures().last() {
+            Some(CedtStructure::FixedMemoryWindow(window)) => {
+                assert_eq!(window.interleave_ways(), Some(4));
+                assert_eq!(window.interleave_targets().len(), 0);
+            }
+            _ => panic!("Expected a CFMWS"),
+        }
+    }
+}
@@ -0,0 +1,277 @@
+use crate::{
+    entry_as,
+    sdt::{SdtHeader, Signature},
+    AcpiTable,
+    EntryIter,
+};
+use core::{mem, slice};
+
+/// Represents the Core System Resource Table (CSRT). This is used on SoCs to describe resources that are shared
+/// between several devices, and so don't belong to any one device in the namespace - most often DMA controllers,
+/// but also timers and interrupt controllers. The resources are organised into groups, each of which is usually
+/// handled by the same driver.
+#[repr(C, packed)]
+pub struct Csrt {
+    header: SdtHeader,
+}
+
+impl AcpiTable for Csrt {
+    fn header(&self) -> &SdtHeader {
+        &self.header
+    }
+}
+
+impl Csrt {
+    pub fn resource_groups(&self) -> ResourceGroupIter<'_> {
+        ResourceGroupIter {
+            entries: unsafe {
+                EntryIter::new(
+                    Signature::CSRT,
+                    (self as *const Csrt as *const u8).add(mem::size_of::<Csrt>()),
+                    self.header.length.saturating_sub(mem::size_of::<Csrt>() as u32),
+                    |group: &ResourceGroup| {
+                        mem::size_of::<ResourceGroup>().saturating_add(group.shared_info_length as usize)
+                    },
+                )
+            },
+        }
+    }
+}
+
+pub struct ResourceGroupIter<'a> {
+    entries: EntryIter<'a, ResourceGroup>,
+}
+
+impl<'a> Iterator for ResourceGroupIter<'a> {
+    type Item = &'a ResourceGroup;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (_, group) = self.entries.next()?;
+        Some(unsafe { entry_as(group) })
+    }
+}
+
+/// A group of resources that are described together, usually because they're part of the same device (e.g. the
+/// channels of a DMA controller). The group's IDs are used to match it with a driver.
+#[repr(C, packed)]
+pub struct ResourceGroup {
+    length: u32,
+    vendor_id: u32,
+    subvendor_id: u32,
+    device_id: u16,
+    subdevice_id: u16,
+    revision: u16,
+    _reserved: u16,
+    shared_info_length: u32,
+}
+
+impl crate::EntryHeader for ResourceGroup {
+    fn entry_type(&self) -> u16 {
+        // Resource groups don't have a type
+        0
+    }
+
+    fn length(&self) -> u32 {
+        self.length
+    }
+}
+
+impl ResourceGroup {
+    /// The vendor of the resource group, as four ASCII characters (e.g. `b"INTL"`).
+    pub fn vendor_id(&self) -> [u8; 4] {
+        self.vendor_id.to_le_bytes()
+    }
+
+    pub fn subvendor_id(&self) -> u32 {
+        self.subvendor_id
+    }
+
+    pub fn device_id(&self) -> u16 {
+        self.device_id
+    }
+
+    pub fn subdevice_id(&self) -> u16 {
+        self.subdevice_id
+    }
+
+    pub fn revision(&self) -> u16 {
+        self.revision
+    }
+
+    /// Information shared between all of the resources in the group. The format of this is defined by the group's
+    /// vendor.
+    pub fn shared_info(&self) -> &[u8] {
+        unsafe {
+            slice::from_raw_parts(
+                (self as *const ResourceGroup as *const u8).add(mem::size_of::<ResourceGroup>()),
+                self.shared_info_length as usize,
+            )
+        }
+    }
+
+    pub fn resource_descriptors(&self) -> ResourceDescriptorIter<'_> {
+        let offset = mem::size_of::<ResourceGroup>() + self.shared_info_length as usize;
+        ResourceDescriptorIter {
+            entries: unsafe {
+                EntryIter::new(
+                    Signature::CSRT,
+                    (self as *const ResourceGroup as *const u8).add(offset),
+                    self.length - offset as u32,
+                    |_| mem::size_of::<ResourceDescriptor>(),
+                )
+            },
+        }
+    }
+}
+
+pub struct ResourceDescriptorIter<'a> {
+    entries: EntryIter<'a, ResourceDescriptor>,
+}
+
+impl<'a> Iterator for ResourceDescriptorIter<'a> {
+    type Item = &'a ResourceDescriptor;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (_, descriptor) = self.entries.next()?;
+        Some(unsafe { entry_as(descriptor) })
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ResourceType {
+    /// Subtype `0` describes an interrupt line, and subtype `1` an interrupt controller.
+    Interrupt,
+    /// Subtype `0` describes a timer.
+    Timer,
+    /// Subtype `0` describes a DMA channel, and subtype `1` a DMA controller.
+    Dma,
+    Reserved(u16),
+}
+
+#[repr(C, packed)]
+pub struct ResourceDescriptor {
+    length: u32,
+    resource_type: u16,
+    resource_subtype: u16,
+    uid: u32,
+}
+
+impl crate::EntryHeader for ResourceDescriptor {
+    fn entry_type(&self) -> u16 {
+        self.resource_type
+    }
+
+    fn length(&self) -> u32 {
+        self.length
+    }
+}
+
+impl ResourceDescriptor {
+    pub fn resource_type(&self) -> ResourceType {
+        match self.resource_type {
+            1 => ResourceType::Interrupt,
+            2 => ResourceType::Timer,
+            3 => ResourceType::Dma,
+            other => ResourceType::Reserved(other),
+        }
+    }
+
+    pub fn resource_subtype(&self) -> u16 {
+        self.resource_subtype
+    }
+
+    /// A unique ID for the resource, which is used by devices in the namespace to refer to it.
+    pub fn uid(&self) -> u32 {
+        self.uid
+    }
+
+    /// The resource-specific information that follows the descriptor. The format of this is defined by the
+    /// vendor of the resource group.
+    pub fn payload(&self) -> &[u8] {
+        unsafe {
+            slice::from_raw_parts(
+                (self as *const ResourceDescriptor as *const u8).add(mem::size_of::<ResourceDescriptor>()),
+                self.length as usize - mem::size_of::<ResourceDescriptor>(),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{make_sdt, table_from_bytes};
+    use alloc::vec::Vec;
+
+    fn descriptor_bytes(resource_type: u16, subtype: u16, uid: u32, payload: &[u8]) -> Vec<u8> {
+        let mut descriptor = Vec::new();
+        descriptor.extend_from_slice(&(12 + payload.len() as u32).to_le_bytes());
+        descriptor.extend_from_slice(&resource_type.to_le_bytes());
+        descriptor.extend_from_slice(&subtype.to_le_bytes());
+        descriptor.extend_from_slice(&uid.to_le_bytes());
+        descriptor.extend_from_slice(payload);
+        descriptor
+    }
+
+    /// Build a resource group describing a DMA controller with a single channel, in the form found on Intel Atom
+    /// SoCs.
+    fn dma_group(descriptors: &[u8]) -> Vec<u8> {
+        let shared_info = [0x20, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00];
+        let mut group = Vec::new();
+        group.extend_from_slice(&(24 + shared_info.len() as u32 + descriptors.len() as u32).to_le_bytes());
+        group.extend_from_slice(b"INTL");
+        group.extend_from_slice(&0u32.to_le_bytes());
+        group.extend_from_slice(&0x9c60u16.to_le_bytes());
+        group.extend_from_slice(&0u16.to_le_bytes());
+        group.extend_from_slice(&2u16.to_le_bytes());
+        group.extend_from_slice(&0u16.to_le_bytes());
+        group.extend_from_slice(&(shared_info.len() as u32).to_le_bytes());
+        group.extend_from_slice(&shared_info);
+        group.extend_from_slice(descriptors);
+        group
+    }
+
+    #[test]
+    fn test_resource_groups() {
+        let mut descriptors = descriptor_bytes(3, 1, 0, &[0x00, 0x00, 0x00, 0x00, 0x02]);
+        descriptors.extend(descriptor_bytes(3, 0, 1, &[]));
+        let table = make_sdt(b"CSRT", 0, &dma_group(&descriptors));
+        let csrt = table_from_bytes::<Csrt>(&table);
+
+        let groups: Vec<&ResourceGroup> = csrt.resource_groups().collect();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(&groups[0].vendor_id(), b"INTL");
+        assert_eq!(groups[0].subvendor_id(), 0);
+        assert_eq!(groups[0].device_id(), 0x9c60);
+        assert_eq!(groups[0].subdevice_id(), 0);
+        assert_eq!(groups[0].revision(), 2);
+        assert_eq!(groups[0].shared_info(), [0x20, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00]);
+
+        let descriptors: Vec<&ResourceDescriptor> = groups[0].resource_descriptors().collect();
+        assert_eq!(descriptors.len(), 2);
+        assert_eq!(descriptors[0].resource_type(), ResourceType::Dma);
+        assert_eq!(descriptors[0].resource_subtype(), 1);
+        assert_eq!(descriptors[0].uid(), 0);
+        assert_eq!(descriptors[0].payload(), [0x00, 0x00, 0x00, 0x00, 0x02]);
+        assert_eq!(descriptors[1].resource_type(), ResourceType::Dma);
+        assert_eq!(descriptors[1].resource_subtype(), 0);
+        assert_eq!(descriptors[1].uid(), 1);
+        assert!(descriptors[1].payload().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_descriptor_length() {
+        let mut descriptors = descriptor_bytes(2, 0, 0, &[0; 4]);
+        // A descriptor that claims to extend past the end of its group
+        let mut truncated = descriptor_bytes(1, 0, 1, &[0; 4]);
+        truncated[0] = 0x40;
+        descriptors.extend(truncated);
+        let table = make_sdt(b"CSRT", 0, &dma_group(&descriptors));
+        let csrt = table_from_bytes::<Csrt>(&table);
+
+        let group = csrt.resource_groups().next().unwrap();
+        let descriptors: Vec<&ResourceDescriptor> = group.resource_descriptors().collect();
+        assert_eq!(descriptors.len(), 1);
+        assert_eq!(descriptors[0].resource_type(), ResourceType::Timer);
+    }
+}
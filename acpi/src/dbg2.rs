@@ -0,0 +1,289 @@
+use crate::{
+    entry_as,
+    platform::address::{GenericAddress, RawGenericAddress},
+    sdt::{SdtHeader, Signature},
+    spcr::SerialInterfaceType,
+    AcpiError,
+    AcpiTable,
+    EntryIter,
+};
+use core::{mem, slice, str};
+
+/// Represents the Debug Port Table 2 (DBG2). This describes the debug ports available on the platform, which can
+/// be used by a kernel debugger (or for early boot logging, if the SPCR isn't present).
+#[repr(C, packed)]
+pub struct Dbg2 {
+    header: SdtHeader,
+    device_info_offset: u32,
+    num_device_info: u32,
+}
+
+impl AcpiTable for Dbg2 {
+    fn header(&self) -> &SdtHeader {
+        &self.header
+    }
+}
+
+impl Dbg2 {
+    pub fn devices(&self) -> Dbg2DeviceIter<'_> {
+        let offset = self.device_info_offset;
+        let remaining_length =
+            if offset as usize >= mem::size_of::<Dbg2>() { self.header.length.saturating_sub(offset) } else { 0 };
+
+        Dbg2DeviceIter {
+            entries: unsafe {
+                EntryIter::new(
+                    Signature::DBG2,
+                    (self as *const Dbg2 as *const u8).add(offset as usize),
+                    remaining_length,
+                    |_| mem::size_of::<Dbg2Device>(),
+                )
+            },
+            remaining_devices: self.num_device_info,
+        }
+    }
+}
+
+pub struct Dbg2DeviceIter<'a> {
+    entries: EntryIter<'a, Dbg2Device>,
+    remaining_devices: u32,
+}
+
+impl<'a> Iterator for Dbg2DeviceIter<'a> {
+    type Item = &'a Dbg2Device;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining_devices == 0 {
+            return None;
+        }
+
+        let (_, device) = self.entries.next()?;
+        self.remaining_devices -= 1;
+        Some(unsafe { entry_as(device) })
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Dbg2PortType {
+    Serial,
+    Ieee1394,
+    Usb,
+    Net,
+    Reserved(u16),
+}
+
+/// Describes a debug port. The fixed fields are followed by arrays of Generic Address Structures and address sizes
+/// describing the port's registers, its path in the namespace, and optionally some OEM-specific data, which are
+/// found using the offsets in this structure. The offsets are relative to the start of the structure.
+#[repr(C, packed)]
+pub struct Dbg2Device {
+    revision: u8,
+    length: u16,
+    num_base_address_registers: u8,
+    namespace_string_length: u16,
+    namespace_string_offset: u16,
+    oem_data_length: u16,
+    oem_data_offset: u16,
+    port_type: u16,
+    port_subtype: u16,
+    _reserved: u16,
+    base_address_register_offset: u16,
+    address_size_offset: u16,
+}
+
+impl crate::EntryHeader for Dbg2Device {
+    fn entry_type(&self) -> u16 {
+        self.port_type
+    }
+
+    fn length(&self) -> u32 {
+        self.length.into()
+    }
+}
+
+impl Dbg2Device {
+    pub fn port_type(&self) -> Dbg2PortType {
+        match self.port_type {
+            0x8000 => Dbg2PortType::Serial,
+            0x8001 => Dbg2PortType::Ieee1394,
+            0x8002 => Dbg2PortType::Usb,
+            0x8003 => Dbg2PortType::Net,
+            other => Dbg2PortType::Reserved(other),
+        }
+    }
+
+    /// The raw subtype of the port. For serial ports, this is decoded by `serial_interface_type`. For USB ports,
+    /// `0` is an XHCI controller and `1` is an EHCI controller. For network ports, it is the PCI vendor ID of the
+    /// controller.
+    pub fn port_subtype(&self) -> u16 {
+        self.port_subtype
+    }
+
+    /// The type of the port, if it is a serial port.
+    pub fn serial_interface_type(&self) -> Option<SerialInterfaceType> {
+        match self.port_type() {
+            Dbg2PortType::Serial => Some(SerialInterfaceType::from(self.port_subtype)),
+            _ => None,
+        }
+    }
+
+    /// Iterate over the registers of the port. The meaning of each register depends on the port type.
+    pub fn base_address_registers(&self) -> impl Iterator<Item = Result<GenericAddress, AcpiError>> + '_ {
+        let registers: &[RawGenericAddress] =
+            unsafe { self.array(self.base_address_register_offset, self.num_base_address_registers.into()) };
+        registers.iter().map(|&raw| GenericAddress::from_raw(raw))
+    }
+
+    /// The size of each of the port's registers, in the same order as `base_address_registers`.
+    pub fn address_sizes(&self) -> impl Iterator<Item = u32> + '_ {
+        let sizes: &[u8] = unsafe {
+            self.array(
+                self.address_size_offset,
+                usize::from(self.num_base_address_registers) * mem::size_of::<u32>(),
+            )
+        };
+        sizes
+            .chunks_exact(mem::size_of::<u32>())
+            .map(|size| u32::from_le_bytes([size[0], size[1], size[2], size[3]]))
+    }
+
+    /// The fully-qualified path to the port in the ACPI namespace, or `"."` if it isn't in the namespace. Returns
+    /// `None` if the path doesn't fit in the structure, or isn't valid ASCII.
+    pub fn namespace_string(&self) -> Option<&str> {
+        let name: &[u8] = unsafe { self.array(self.namespace_string_offset, self.namespace_string_length.into()) };
+        if name.is_empty() {
+            return None;
+        }
+        let name = match name.iter().position(|&byte| byte == 0) {
+            Some(end) => &name[..end],
+            None => name,
+        };
+
+        if name.is_ascii() {
+            str::from_utf8(name).ok()
+        } else {
+            None
+        }
+    }
+
+    pub fn oem_data(&self) -> &[u8] {
+        if self.oem_data_offset == 0 {
+            return &[];
+        }
+        unsafe { self.array(self.oem_data_offset, self.oem_data_length.into()) }
+    }
+
+    /// Get the array of `count` `T`s found `offset` bytes into the structure. Returns an empty slice if the array
+    /// doesn't fit within the structure.
+    ///
+    /// ### Safety
+    /// `T` must be valid for any bit pattern, and have an alignment of `1`.
+    unsafe fn array<T>(&self, offset: u16, count: usize) -> &[T] {
+        let end = (offset as usize).saturating_add(count.saturating_mul(mem::size_of::<T>()));
+        if count == 0 || (offset as usize) < mem::size_of::<Dbg2Device>() || end > self.length as usize {
+            return &[];
+        }
+
+        unsafe {
+            slice::from_raw_parts((self as *const Dbg2Device as *const u8).add(offset as usize) as *const T, count)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        platform::address::AddressSpace,
+        test_utils::{make_sdt, table_from_bytes},
+    };
+    use alloc::vec::Vec;
+
+    fn device_bytes(port_type: u16, port_subtype: u16, registers: &[(u8, u64, u32)], name: &[u8]) -> Vec<u8> {
+        let registers_offset = 22;
+        let sizes_offset = registers_offset + registers.len() * 12;
+        let name_offset = sizes_offset + registers.len() * 4;
+        let length = name_offset + name.len();
+
+        let mut device = Vec::new();
+        device.push(0);
+        device.extend_from_slice(&(length as u16).to_le_bytes());
+        device.push(registers.len() as u8);
+        device.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        device.extend_from_slice(&(name_offset as u16).to_le_bytes());
+        device.extend_from_slice(&0u16.to_le_bytes());
+        device.extend_from_slice(&0u16.to_le_bytes());
+        device.extend_from_slice(&port_type.to_le_bytes());
+        device.extend_from_slice(&port_subtype.to_le_bytes());
+        device.extend_from_slice(&0u16.to_le_bytes());
+        device.extend_from_slice(&(registers_offset as u16).to_le_bytes());
+        device.extend_from_slice(&(sizes_offset as u16).to_le_bytes());
+        for (address_space, address, _) in registers {
+            device.extend_from_slice(&[*address_space, 8, 0, 1]);
+            device.extend_from_slice(&address.to_le_bytes());
+        }
+        for (_, _, size) in registers {
+            device.extend_from_slice(&size.to_le_bytes());
+        }
+        device.extend_from_slice(name);
+        device
+    }
+
+    #[test]
+    fn test_devices() {
+        let mut body = Vec::new();
+        body.extend_from_slice(&44u32.to_le_bytes());
+        body.extend_from_slice(&2u32.to_le_bytes());
+        body.extend(device_bytes(0x8000, 0x0000, &[(0x01, 0x3f8, 8)], b"\\_SB.PCI0.UAR1\0"));
+        body.extend(device_bytes(
+            0x8002,
+            0x0000,
+            &[(0x00, 0xfe00_0000, 0x1_0000), (0x00, 0xfe01_0000, 0x1000)],
+            b".\0",
+        ));
+
+        let table = make_sdt(b"DBG2", 0, &body);
+        let dbg2 = table_from_bytes::<Dbg2>(&table);
+        let devices: Vec<&Dbg2Device> = dbg2.devices().collect();
+        assert_eq!(devices.len(), 2);
+
+        assert_eq!(devices[0].port_type(), Dbg2PortType::Serial);
+        assert_eq!(devices[0].serial_interface_type(), Some(SerialInterfaceType::Full16550));
+        let registers: Vec<GenericAddress> = devices[0].base_address_registers().map(Result::unwrap).collect();
+        assert_eq!(registers.len(), 1);
+        assert_eq!(registers[0].address_space, AddressSpace::SystemIo);
+        assert_eq!(registers[0].address, 0x3f8);
+        assert_eq!(devices[0].address_sizes().collect::<Vec<u32>>(), [8]);
+        assert_eq!(devices[0].namespace_string(), Some("\\_SB.PCI0.UAR1"));
+
+        assert_eq!(devices[1].port_type(), Dbg2PortType::Usb);
+        assert_eq!(devices[1].serial_interface_type(), None);
+        let registers: Vec<GenericAddress> = devices[1].base_address_registers().map(Result::unwrap).collect();
+        assert_eq!(
+            registers.iter().map(|register| register.address).collect::<Vec<u64>>(),
+            [0xfe00_0000, 0xfe01_0000]
+        );
+        assert_eq!(devices[1].address_sizes().collect::<Vec<u32>>(), [0x1_0000, 0x1000]);
+        assert_eq!(devices[1].namespace_string(), Some("."));
+        assert_eq!(devices[1].oem_data(), &[]);
+    }
+
+    #[test]
+    fn test_registers_outside_device() {
+        let mut device = device_bytes(0x8000, 0x0003, &[(0x00, 0x0900_0000, 0x1000)], b".\0");
+        device[3] = 4; // Claim more registers than fit in the structure
+
+        let mut body = Vec::new();
+        body.extend_from_slice(&44u32.to_le_bytes());
+        body.extend_from_slice(&1u32.to_le_bytes());
+        body.extend(device);
+
+        let table = make_sdt(b"DBG2", 0, &body);
+        let dbg2 = table_from_bytes::<Dbg2>(&table);
+        let device = dbg2.devices().next().unwrap();
+
+        assert_eq!(device.serial_interface_type(), Some(SerialInterfaceType::ArmPl011));
+        assert_eq!(device.base_address_registers().count(), 0);
+        assert_eq!(device.address_sizes().count(), 0);
+    }
+}
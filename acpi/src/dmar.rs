@@ -0,0 +1,559 @@
+use crate::{
+    entry_as,
+    sdt::{SdtHeader, Signature},
+    AcpiTable,
+    EntryIter,
+};
+use bit_field::BitField;
+use core::{mem, slice, str};
+
+/// Represents the DMA Remapping Reporting (DMAR) table. This describes the Intel Virtualization Technology for
+/// Directed I/O (VT-d) remapping hardware present on a platform, and the devices it manages. The fixed fields are
+/// followed by a list of variable-length remapping structures.
+#[repr(C, packed)]
+pub struct Dmar {
+    header: SdtHeader,
+    host_address_width: u8,
+    flags: u8,
+    _reserved: [u8; 10],
+}
+
+impl AcpiTable for Dmar {
+    fn header(&self) -> &SdtHeader {
+        &self.header
+    }
+}
+
+impl Dmar {
+    /// The maximum DMA physical addressability supported by the platform, in bits.
+    pub fn host_address_width(&self) -> u8 {
+        /*
+         * The table encodes the width as `N - 1`.
+         */
+        self.host_address_width.saturating_add(1)
+    }
+
+    pub fn flags(&self) -> DmarFlags {
+        DmarFlags(self.flags)
+    }
+
+    pub fn entries(&self) -> DmarEntryIter<'_> {
+        DmarEntryIter { structures: self.structures() }
+    }
+
+    /// Iterate over the Reserved Memory Region Reporting (RMRR) structures. Each of these describes a region of
+    /// memory that firmware may be performing DMA to or from (e.g. for USB legacy emulation, or for integrated
+    /// graphics), and so must be identity-mapped for the devices in its scope before DMA remapping is enabled.
+    pub fn reserved_regions(&self) -> impl Iterator<Item = &DmarRmrr> + '_ {
+        self.entries().filter_map(|entry| match entry {
+            DmarEntry::Rmrr(rmrr) => Some(rmrr),
+            _ => None,
+        })
+    }
+
+    fn structures(&self) -> EntryIter<'_, DmarStructureHeader> {
+        unsafe {
+            EntryIter::new(
+                Signature::DMAR,
+                (self as *const Dmar as *const u8).add(mem::size_of::<Dmar>()),
+                self.header.length.saturating_sub(mem::size_of::<Dmar>() as u32),
+                structure_minimum_length,
+            )
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DmarFlags(u8);
+
+impl DmarFlags {
+    /// If true, the platform supports interrupt remapping.
+    pub fn interrupt_remapping(&self) -> bool {
+        self.0.get_bit(0)
+    }
+
+    /// If true, firmware requests that the OS doesn't enable x2APIC mode, if it can avoid doing so.
+    pub fn x2apic_opt_out(&self) -> bool {
+        self.0.get_bit(1)
+    }
+
+    /// If true, firmware supports the OS protecting memory from DMA during early boot by using the DMA
+    /// remapping hardware. The devices it supports are described by the DRHD structures' device scopes.
+    pub fn dma_control_opt_in(&self) -> bool {
+        self.0.get_bit(2)
+    }
+}
+
+const DRHD_TYPE: u16 = 0;
+const RMRR_TYPE: u16 = 1;
+const ATSR_TYPE: u16 = 2;
+const RHSA_TYPE: u16 = 3;
+const ANDD_TYPE: u16 = 4;
+
+pub enum DmarEntry<'a> {
+    Drhd(&'a DmarDrhd),
+    Rmrr(&'a DmarRmrr),
+    Atsr(&'a DmarAtsr),
+    Rhsa(&'a DmarRhsa),
+    Andd(&'a DmarAndd),
+}
+
+pub struct DmarEntryIter<'a> {
+    structures: EntryIter<'a, DmarStructureHeader>,
+}
+
+impl<'a> Iterator for DmarEntryIter<'a> {
+    type Item = DmarEntry<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (structure_type, structure) in &mut self.structures {
+            match structure_type {
+                DRHD_TYPE => return Some(DmarEntry::Drhd(unsafe { entry_as::<DmarDrhd>(structure) })),
+                RMRR_TYPE => return Some(DmarEntry::Rmrr(unsafe { entry_as::<DmarRmrr>(structure) })),
+                ATSR_TYPE => return Some(DmarEntry::Atsr(unsafe { entry_as::<DmarAtsr>(structure) })),
+                RHSA_TYPE => return Some(DmarEntry::Rhsa(unsafe { entry_as::<DmarRhsa>(structure) })),
+                ANDD_TYPE => return Some(DmarEntry::Andd(unsafe { entry_as::<DmarAndd>(structure) })),
+
+                /*
+                 * Other structure types are reserved by the VT-d specification, or describe structures we don't
+                 * support yet. We skip them.
+                 */
+                _ => (),
+            }
+        }
+
+        None
+    }
+}
+
+#[derive(Clone, Copy)]
+#[repr(C, packed)]
+struct DmarStructureHeader {
+    structure_type: u16,
+    length: u16,
+}
+
+impl crate::EntryHeader for DmarStructureHeader {
+    fn entry_type(&self) -> u16 {
+        self.structure_type
+    }
+
+    fn length(&self) -> u32 {
+        self.length.into()
+    }
+}
+
+fn structure_minimum_length(header: &DmarStructureHeader) -> usize {
+    match header.structure_type {
+        DRHD_TYPE => mem::size_of::<DmarDrhd>(),
+        RMRR_TYPE => mem::size_of::<DmarRmrr>(),
+        ATSR_TYPE => mem::size_of::<DmarAtsr>(),
+        RHSA_TYPE => mem::size_of::<DmarRhsa>(),
+        ANDD_TYPE => mem::size_of::<DmarAndd>(),
+        _ => mem::size_of::<DmarStructureHeader>(),
+    }
+}
+
+/// A DMA Remapping Hardware Unit Definition (DRHD) structure. This describes a remapping hardware unit, and is
+/// followed by the device scopes of the devices it manages.
+#[repr(C, packed)]
+pub struct DmarDrhd {
+    header: DmarStructureHeader,
+    flags: u8,
+    size: u8,
+    segment: u16,
+    register_base_address: u64,
+}
+
+impl DmarDrhd {
+    /// If true, this unit manages all of the PCI devices in its segment, except those managed by other units in
+    /// the same segment. Its device scopes then only list I/O APICs and HPETs.
+    pub fn includes_pci_all(&self) -> bool {
+        self.flags.get_bit(0)
+    }
+
+    /// The PCI segment group the devices managed by this unit are in.
+    pub fn segment(&self) -> u16 {
+        self.segment
+    }
+
+    /// The physical address of the unit's register set.
+    pub fn register_base_address(&self) -> u64 {
+        self.register_base_address
+    }
+
+    /// The size of the unit's register set, in bytes. Before revision 3 of the table, the size wasn't reported,
+    /// and this returns 4KiB.
+    pub fn register_set_size(&self) -> u64 {
+        0x1000 << self.size.get_bits(0..4)
+    }
+
+    pub fn device_scopes(&self) -> DeviceScopeIter<'_> {
+        DeviceScopeIter::new(self as *const DmarDrhd as *const u8, mem::size_of::<DmarDrhd>(), self.header.length)
+    }
+}
+
+/// A Reserved Memory Region Reporting (RMRR) structure. The region is described by its base address and its
+/// limit address (the address of its last byte), and is followed by the device scopes of the devices that use it.
+#[repr(C, packed)]
+pub struct DmarRmrr {
+    header: DmarStructureHeader,
+    _reserved: u16,
+    segment: u16,
+    base_address: u64,
+    limit_address: u64,
+}
+
+impl DmarRmrr {
+    /// The PCI segment group the devices in this structure's scope are in.
+    pub fn segment(&self) -> u16 {
+        self.segment
+    }
+
+    /// The address of the first byte of the reserved region. This is aligned to 4KiB.
+    pub fn base_address(&self) -> u64 {
+        self.base_address
+    }
+
+    /// The address of the last byte of the reserved region. `limit_address + 1` is aligned to 4KiB.
+    pub fn limit_address(&self) -> u64 {
+        self.limit_address
+    }
+
+    pub fn device_scopes(&self) -> DeviceScopeIter<'_> {
+        DeviceScopeIter::new(self as *const DmarRmrr as *const u8, mem::size_of::<DmarRmrr>(), self.header.length)
+    }
+}
+
+/// A Root Port ATS Capability Reporting (ATSR) structure. This describes the PCIe root ports that support
+/// Address Translation Services, and is followed by their device scopes.
+#[repr(C, packed)]
+pub struct DmarAtsr {
+    header: DmarStructureHeader,
+    flags: u8,
+    _reserved: u8,
+    segment: u16,
+}
+
+impl DmarAtsr {
+    /// If true, all of the root ports in the segment support ATS, and there are no device scopes.
+    pub fn includes_all_root_ports(&self) -> bool {
+        self.flags.get_bit(0)
+    }
+
+    pub fn segment(&self) -> u16 {
+        self.segment
+    }
+
+    pub fn device_scopes(&self) -> DeviceScopeIter<'_> {
+        DeviceScopeIter::new(self as *const DmarAtsr as *const u8, mem::size_of::<DmarAtsr>(), self.header.length)
+    }
+}
+
+/// A Remapping Hardware Static Affinity (RHSA) structure. This associates a remapping hardware unit (identified by
+/// the register base address in its DRHD) with a proximity domain.
+#[repr(C, packed)]
+pub struct DmarRhsa {
+    header: DmarStructureHeader,
+    _reserved: u32,
+    register_base_address: u64,
+    proximity_domain: u32,
+}
+
+impl DmarRhsa {
+    pub fn register_base_address(&self) -> u64 {
+        self.register_base_address
+    }
+
+    pub fn proximity_domain(&self) -> u32 {
+        self.proximity_domain
+    }
+}
+
+/// An ACPI Name-space Device Declaration (ANDD) structure. This associates an ACPI namespace device with the
+/// enumeration ID used to refer to it in device scopes.
+#[repr(C, packed)]
+pub struct DmarAndd {
+    header: DmarStructureHeader,
+    _reserved: [u8; 3],
+    acpi_device_number: u8,
+    // Followed by a null-terminated ASCII path to the device in the namespace
+}
+
+impl DmarAndd {
+    pub fn acpi_device_number(&self) -> u8 {
+        self.acpi_device_number
+    }
+
+    /// The fully-qualified path to the device in the ACPI namespace. Returns `None` if the path isn't valid ASCII.
+    pub fn object_name(&self) -> Option<&str> {
+        let name = unsafe {
+            slice::from_raw_parts(
+                (self as *const DmarAndd as *const u8).add(mem::size_of::<DmarAndd>()),
+                self.header.length as usize - mem::size_of::<DmarAndd>(),
+            )
+        };
+        let name = match name.iter().position(|&byte| byte == 0) {
+            Some(end) => &name[..end],
+            None => name,
+        };
+
+        if name.is_ascii() {
+            str::from_utf8(name).ok()
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DeviceScopeType {
+    PciEndpoint,
+    PciSubHierarchy,
+    IoApic,
+    MsiCapableHpet,
+    AcpiNamespaceDevice,
+    Reserved(u8),
+}
+
+/// Describes a device (or hierarchy of devices) that a remapping structure applies to. The device is found by
+/// starting at `start_bus`, and then following each entry of `path` through PCI-PCI bridges.
+#[derive(Clone, Copy, Debug)]
+pub struct DeviceScope<'a> {
+    pub scope_type: DeviceScopeType,
+    /// For I/O APICs, the I/O APIC ID. For HPETs, the HPET number. For ACPI namespace devices, the enumeration ID
+    /// of the device in the ANDD structure. Not used for other types of device.
+    pub enumeration_id: u8,
+    pub start_bus: u8,
+    pub path: &'a [DeviceScopePathEntry],
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C, packed)]
+pub struct DeviceScopePathEntry {
+    pub device: u8,
+    pub function: u8,
+}
+
+pub struct DeviceScopeIter<'a> {
+    scopes: EntryIter<'a, DeviceScopeHeader>,
+}
+
+impl<'a> DeviceScopeIter<'a> {
+    /// Create an iterator over the device scopes found between `scopes_offset` bytes into the structure at
+    /// `structure`, and the structure's end.
+    fn new(structure: *const u8, scopes_offset: usize, structure_length: u16) -> DeviceScopeIter<'a> {
+        DeviceScopeIter {
+            scopes: unsafe {
+                EntryIter::new(
+                    Signature::DMAR,
+                    structure.add(scopes_offset),
+                    structure_length.saturating_sub(scopes_offset as u16).into(),
+                    |_| mem::size_of::<DeviceScopeHeader>(),
+                )
+            },
+        }
+    }
+}
+
+impl<'a> Iterator for DeviceScopeIter<'a> {
+    type Item = DeviceScope<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (_, scope) = self.scopes.next()?;
+        let header = unsafe { *entry_as::<DeviceScopeHeader>(scope) };
+        let path = unsafe {
+            slice::from_raw_parts(
+                scope.as_ptr().add(mem::size_of::<DeviceScopeHeader>()) as *const DeviceScopePathEntry,
+                (scope.len() - mem::size_of::<DeviceScopeHeader>()) / mem::size_of::<DeviceScopePathEntry>(),
+            )
+        };
+
+        Some(DeviceScope {
+            scope_type: match header.scope_type {
+                0x01 => DeviceScopeType::PciEndpoint,
+                0x02 => DeviceScopeType::PciSubHierarchy,
+                0x03 => DeviceScopeType::IoApic,
+                0x04 => DeviceScopeType::MsiCapableHpet,
+                0x05 => DeviceScopeType::AcpiNamespaceDevice,
+                other => DeviceScopeType::Reserved(other),
+            },
+            enumeration_id: header.enumeration_id,
+            start_bus: header.start_bus,
+            path,
+        })
+    }
+}
+
+#[derive(Clone, Copy)]
+#[repr(C, packed)]
+struct DeviceScopeHeader {
+    scope_type: u8,
+    length: u8,
+    _reserved: u16,
+    enumeration_id: u8,
+    start_bus: u8,
+}
+
+impl crate::EntryHeader for DeviceScopeHeader {
+    fn entry_type(&self) -> u16 {
+        self.scope_type.into()
+    }
+
+    fn length(&self) -> u32 {
+        self.length.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_utils::{collect_parse_warnings, make_sdt, table_from_bytes},
+        ParseWarning,
+    };
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_reserved_regions() {
+        let mut body = Vec::new();
+        body.extend_from_slice(&[38, 0x01]); // Host address width, flags
+        body.extend_from_slice(&[0; 10]);
+
+        // A DRHD, which should be skipped
+        body.extend_from_slice(&[0x00, 0x00, 0x10, 0x00]);
+        body.extend_from_slice(&[0; 12]);
+
+        // A RMRR for the USB controller at 00:14.0, followed by one device scope
+        body.extend_from_slice(&0x01u16.to_le_bytes());
+        body.extend_from_slice(&32u16.to_le_bytes());
+        body.extend_from_slice(&[0; 2]);
+        body.extend_from_slice(&0x0001u16.to_le_bytes());
+        body.extend_from_slice(&0x7b80_0000u64.to_le_bytes());
+        body.extend_from_slice(&0x7b81_ffffu64.to_le_bytes());
+        body.extend_from_slice(&[0x01, 0x08, 0x00, 0x00, 0x00, 0x00, 0x14, 0x00]);
+
+        let table = make_sdt(b"DMAR", 1, &body);
+        let dmar = table_from_bytes::<Dmar>(&table);
+        let regions: Vec<&DmarRmrr> = dmar.reserved_regions().collect();
+
+        assert_eq!(regions.len(), 1);
+        assert_eq!(regions[0].segment(), 1);
+        assert_eq!(regions[0].base_address(), 0x7b80_0000);
+        assert_eq!(regions[0].limit_address(), 0x7b81_ffff);
+
+        let scopes: Vec<DeviceScope> = regions[0].device_scopes().collect();
+        assert_eq!(scopes.len(), 1);
+        assert_eq!(scopes[0].scope_type, DeviceScopeType::PciEndpoint);
+        assert_eq!(scopes[0].start_bus, 0);
+        assert_eq!(scopes[0].path, &[DeviceScopePathEntry { device: 0x14, function: 0 }]);
+    }
+
+    #[test]
+    fn test_entries() {
+        let mut body = Vec::new();
+        body.extend_from_slice(&[46, 0b011]);
+        body.extend_from_slice(&[0; 10]);
+
+        // A DRHD for the integrated graphics at 00:02.0
+        body.extend_from_slice(&[0x00, 0x00, 0x18, 0x00, 0x00, 0x00, 0x00, 0x00]);
+        body.extend_from_slice(&0xfed9_0000u64.to_le_bytes());
+        body.extend_from_slice(&[0x01, 0x08, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00]);
+
+        // A DRHD covering everything else, with an I/O APIC and an HPET scope
+        body.extend_from_slice(&[0x00, 0x00, 0x20, 0x00, 0x01, 0x01, 0x00, 0x00]);
+        body.extend_from_slice(&0xfed9_1000u64.to_le_bytes());
+        body.extend_from_slice(&[0x03, 0x08, 0x00, 0x00, 0x02, 0xf0, 0x1f, 0x00]);
+        body.extend_from_slice(&[0x04, 0x08, 0x00, 0x00, 0x00, 0x00, 0x1e, 0x06]);
+
+        // A RMRR with no device scopes
+        body.extend_from_slice(&[0x01, 0x00, 0x18, 0x00]);
+        body.extend_from_slice(&[0; 4]);
+        body.extend_from_slice(&0x7c00_0000u64.to_le_bytes());
+        body.extend_from_slice(&0x7fff_ffffu64.to_le_bytes());
+
+        // An ATSR covering all root ports
+        body.extend_from_slice(&[0x02, 0x00, 0x08, 0x00, 0x01, 0x00, 0x00, 0x00]);
+
+        // A RHSA placing the second DRHD in proximity domain 1
+        body.extend_from_slice(&[0x03, 0x00, 0x14, 0x00]);
+        body.extend_from_slice(&[0; 4]);
+        body.extend_from_slice(&0xfed9_1000u64.to_le_bytes());
+        body.extend_from_slice(&1u32.to_le_bytes());
+
+        // An ANDD for a namespace device
+        body.extend_from_slice(&[0x04, 0x00, 0x17, 0x00, 0x00, 0x00, 0x00, 0x01]);
+        body.extend_from_slice(b"\\_SB.PCI0.I2C0\0");
+
+        let table = make_sdt(b"DMAR", 1, &body);
+        let dmar = table_from_bytes::<Dmar>(&table);
+
+        assert_eq!(dmar.host_address_width(), 47);
+        assert!(dmar.flags().interrupt_remapping());
+        assert!(dmar.flags().x2apic_opt_out());
+        assert!(!dmar.flags().dma_control_opt_in());
+
+        let (mut drhds, mut rmrrs, mut atsrs, mut rhsas, mut andds) = (0, 0, 0, 0, 0);
+        for entry in dmar.entries() {
+            match entry {
+                DmarEntry::Drhd(drhd) => {
+                    drhds += 1;
+                    if drhd.includes_pci_all() {
+                        assert_eq!(drhd.register_base_address(), 0xfed9_1000);
+                        assert_eq!(drhd.register_set_size(), 0x2000);
+                        let scopes: Vec<DeviceScope> = drhd.device_scopes().collect();
+                        assert_eq!(scopes.len(), 2);
+                        assert_eq!(scopes[0].scope_type, DeviceScopeType::IoApic);
+                        assert_eq!(scopes[0].enumeration_id, 2);
+                        assert_eq!(scopes[1].scope_type, DeviceScopeType::MsiCapableHpet);
+                        assert_eq!(scopes[1].path, &[DeviceScopePathEntry { device: 0x1e, function: 6 }]);
+                    } else {
+                        assert_eq!(drhd.device_scopes().count(), 1);
+                    }
+                }
+                DmarEntry::Rmrr(rmrr) => {
+                    rmrrs += 1;
+                    assert_eq!(rmrr.device_scopes().count(), 0);
+                }
+                DmarEntry::Atsr(atsr) => {
+                    atsrs += 1;
+                    assert!(atsr.includes_all_root_ports());
+                }
+                DmarEntry::Rhsa(rhsa) => {
+                    rhsas += 1;
+                    assert_eq!(rhsa.register_base_address(), 0xfed9_1000);
+                    assert_eq!(rhsa.proximity_domain(), 1);
+                }
+                DmarEntry::Andd(andd) => {
+                    andds += 1;
+                    assert_eq!(andd.acpi_device_number(), 1);
+                    assert_eq!(andd.object_name(), Some("\\_SB.PCI0.I2C0"));
+                }
+            }
+        }
+
+        assert_eq!((drhds, rmrrs, atsrs, rhsas, andds), (2, 1, 1, 1, 1));
+    }
+
+    #[test]
+    fn test_truncated_structure_warns() {
+        let mut body = Vec::new();
+        body.extend_from_slice(&[38, 0x01]);
+        body.extend_from_slice(&[0; 10]);
+
+        // A RMRR that claims to be longer than the rest of the table
+        body.extend_from_slice(&0x01u16.to_le_bytes());
+        body.extend_from_slice(&64u16.to_le_bytes());
+        body.extend_from_slice(&[0; 28]);
+
+        let table = make_sdt(b"DMAR", 1, &body);
+        let dmar = table_from_bytes::<Dmar>(&table);
+
+        let (count, warnings) = collect_parse_warnings(|| dmar.reserved_regions().count());
+        assert_eq!(count, 0);
+        assert_eq!(
+            warnings,
+            [ParseWarning::InvalidEntryLength { signature: Signature::DMAR, entry_type: 1, length: 64 }]
+        );
+    }
+}
@@ -0,0 +1,277 @@
+use crate::{sdt::SdtHeader, AcpiTable};
+use bit_field::BitField;
+use core::{convert::TryInto, mem, slice};
+
+/// Represents the Dynamic Root of Trust for Measurement Table (DRTM). This is defined by the TCG D-RTM
+/// Architecture specification, and describes the entry points of the Dynamic Configuration Environment (DCE), as
+/// well as the tables and resources that the DCE validates before launching the Dynamically Launched Measured
+/// Environment (DLME).
+///
+/// The fixed fields are followed by three variable-length structures, one after another: the list of validated
+/// tables, the list of resources, and the DLME Platform Specific ID (DPS ID). As each one's length depends on
+/// the counts of the ones before it, they are found by walking the table.
+#[repr(C, packed)]
+pub struct Drtm {
+    header: SdtHeader,
+    dl_entry_base: u64,
+    dl_entry_length: u64,
+    dl_entry_32: u32,
+    dl_entry_64: u64,
+    dlme_exit: u64,
+    log_area_start: u64,
+    log_area_length: u32,
+    architecture_dependent: u64,
+    flags: u32,
+}
+
+impl AcpiTable for Drtm {
+    fn header(&self) -> &SdtHeader {
+        &self.header
+    }
+}
+
+impl Drtm {
+    /// The physical address of the region of memory that contains the DCE's entry points.
+    pub fn dl_entry_base(&self) -> u64 {
+        self.dl_entry_base
+    }
+
+    /// The length of the region of memory that contains the DCE's entry points, in bytes.
+    pub fn dl_entry_length(&self) -> u64 {
+        self.dl_entry_length
+    }
+
+    /// The physical address of the DCE's entry point for 32-bit callers.
+    pub fn dl_entry_32(&self) -> u32 {
+        self.dl_entry_32
+    }
+
+    /// The physical address of the DCE's entry point for 64-bit callers.
+    pub fn dl_entry_64(&self) -> u64 {
+        self.dl_entry_64
+    }
+
+    /// The physical address of the entry point that the DLME calls when it exits.
+    pub fn dlme_exit(&self) -> u64 {
+        self.dlme_exit
+    }
+
+    /// The physical address of the area that the DCE logs its measurements to.
+    pub fn log_area_start(&self) -> u64 {
+        self.log_area_start
+    }
+
+    /// The length of the log area, in bytes.
+    pub fn log_area_length(&self) -> u32 {
+        self.log_area_length
+    }
+
+    /// The physical address of an architecture-dependent structure. Its contents are defined by the D-RTM
+    /// specification for each architecture.
+    pub fn architecture_dependent(&self) -> u64 {
+        self.architecture_dependent
+    }
+
+    pub fn flags(&self) -> DrtmFlags {
+        DrtmFlags(self.flags)
+    }
+
+    /// The physical addresses of the ACPI tables that the DCE validates. If the table is too short to hold the
+    /// number of addresses it claims to, only the addresses that fit within it are produced.
+    pub fn validated_tables(&self) -> impl Iterator<Item = u64> + '_ {
+        let (entries, count) = self.list_at(mem::size_of::<Drtm>(), mem::size_of::<u64>()).unwrap_or((&[], 0));
+        entries
+            .chunks_exact(mem::size_of::<u64>())
+            .take(count)
+            .map(|entry| u64::from_le_bytes(entry.try_into().unwrap()))
+    }
+
+    /// The resources (e.g. regions of memory) that the DCE validates. If the table is too short to hold the number
+    /// of resources it claims to, only the resources that fit within it are returned.
+    pub fn resources(&self) -> &[DrtmResource] {
+        let (entries, count) = self
+            .resources_offset()
+            .and_then(|offset| self.list_at(offset, mem::size_of::<DrtmResource>()))
+            .unwrap_or((&[], 0));
+        unsafe { slice::from_raw_parts(entries.as_ptr() as *const DrtmResource, count) }
+    }
+
+    /// The DLME Platform Specific ID. Returns `None` if the table is too short to contain it.
+    pub fn dps_id(&self) -> Option<&[u8]> {
+        let offset = self.dps_id_offset()?;
+        let length = self.read_u32(offset)? as usize;
+        self.bytes().get(offset + mem::size_of::<u32>()..)?.get(..usize::min(length, DPS_ID_LENGTH))
+    }
+
+    fn resources_offset(&self) -> Option<usize> {
+        let offset = mem::size_of::<Drtm>();
+        let count = self.read_u32(offset)? as usize;
+        count.checked_mul(mem::size_of::<u64>())?.checked_add(offset + mem::size_of::<u32>())
+    }
+
+    fn dps_id_offset(&self) -> Option<usize> {
+        let offset = self.resources_offset()?;
+        let count = self.read_u32(offset)? as usize;
+        count.checked_mul(mem::size_of::<DrtmResource>())?.checked_add(offset + mem::size_of::<u32>())
+    }
+
+    /// Find the entries of the list at `offset`, which starts with a count of its entries. Returns the bytes after
+    /// the count, and the number of entries, limited to those that fit within the table.
+    fn list_at(&self, offset: usize, entry_size: usize) -> Option<(&[u8], usize)> {
+        let count = self.read_u32(offset)? as usize;
+        let entries = &self.bytes()[offset + mem::size_of::<u32>()..];
+        Some((entries, usize::min(count, entries.len() / entry_size)))
+    }
+
+    fn read_u32(&self, offset: usize) -> Option<u32> {
+        let bytes = self.bytes().get(offset..offset.checked_add(mem::size_of::<u32>())?)?;
+        Some(u32::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    fn bytes(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self as *const Drtm as *const u8, self.header.length as usize) }
+    }
+}
+
+const DPS_ID_LENGTH: usize = 16;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DrtmFlags(u32);
+
+impl DrtmFlags {
+    /// If true, the DLME is allowed to access the ACPI namespace.
+    pub fn access_allowed(&self) -> bool {
+        self.0.get_bit(0)
+    }
+
+    /// If true, the DCE enables its gap code, which is run on resume from S3 and when the DLME exits.
+    pub fn enable_gap_code(&self) -> bool {
+        self.0.get_bit(1)
+    }
+
+    /// If true, the DCE doesn't measure everything that the DLME depends on, and the OS must measure the rest.
+    pub fn incomplete_measurements(&self) -> bool {
+        self.0.get_bit(2)
+    }
+
+    /// If true, the PCR authorities are extended in a different order to that described by the D-RTM
+    /// specification.
+    pub fn authority_order(&self) -> bool {
+        self.0.get_bit(3)
+    }
+}
+
+/// Describes a resource that the DCE validates.
+#[derive(Clone, Copy, Debug)]
+#[repr(C, packed)]
+pub struct DrtmResource {
+    size: [u8; 7],
+    resource_type: u8,
+    address: u64,
+}
+
+impl DrtmResource {
+    /// The size of the resource, in bytes.
+    pub fn size(&self) -> u64 {
+        let s = self.size;
+        u64::from_le_bytes([s[0], s[1], s[2], s[3], s[4], s[5], s[6], 0])
+    }
+
+    /// The type of the resource, as defined by the D-RTM specification.
+    pub fn resource_type(&self) -> u8 {
+        self.resource_type
+    }
+
+    pub fn address(&self) -> u64 {
+        self.address
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{make_sdt, table_from_bytes};
+    use alloc::vec::Vec;
+
+    fn make_drtm(validated_tables: &[u64], resources: &[(u64, u8, u64)], dps_id: Option<&[u8; 16]>) -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&0x7f00_0000u64.to_le_bytes());
+        body.extend_from_slice(&0x1_0000u64.to_le_bytes());
+        body.extend_from_slice(&0x7f00_0100u32.to_le_bytes());
+        body.extend_from_slice(&0x7f00_0200u64.to_le_bytes());
+        body.extend_from_slice(&0x7f00_0300u64.to_le_bytes());
+        body.extend_from_slice(&0x7e00_0000u64.to_le_bytes());
+        body.extend_from_slice(&0x8000u32.to_le_bytes());
+        body.extend_from_slice(&0x7d00_0000u64.to_le_bytes());
+        body.extend_from_slice(&0b0101u32.to_le_bytes());
+
+        body.extend_from_slice(&(validated_tables.len() as u32).to_le_bytes());
+        for address in validated_tables {
+            body.extend_from_slice(&address.to_le_bytes());
+        }
+        body.extend_from_slice(&(resources.len() as u32).to_le_bytes());
+        for &(size, resource_type, address) in resources {
+            body.extend_from_slice(&size.to_le_bytes()[..7]);
+            body.push(resource_type);
+            body.extend_from_slice(&address.to_le_bytes());
+        }
+        if let Some(dps_id) = dps_id {
+            body.extend_from_slice(&16u32.to_le_bytes());
+            body.extend_from_slice(dps_id);
+        }
+        make_sdt(b"DRTM", 1, &body)
+    }
+
+    #[test]
+    fn test_drtm() {
+        let table =
+            make_drtm(&[0x7ff0_0000], &[(0x1000, 1, 0xfed4_0000), (0x20_0000, 2, 0x1000_0000)], Some(&[0xab; 16]));
+        let drtm = table_from_bytes::<Drtm>(&table);
+
+        assert_eq!(drtm.dl_entry_base(), 0x7f00_0000);
+        assert_eq!(drtm.dl_entry_length(), 0x1_0000);
+        assert_eq!(drtm.dl_entry_32(), 0x7f00_0100);
+        assert_eq!(drtm.dl_entry_64(), 0x7f00_0200);
+        assert_eq!(drtm.dlme_exit(), 0x7f00_0300);
+        assert_eq!(drtm.log_area_start(), 0x7e00_0000);
+        assert_eq!(drtm.log_area_length(), 0x8000);
+        assert_eq!(drtm.architecture_dependent(), 0x7d00_0000);
+        let flags = drtm.flags();
+        assert!(flags.access_allowed());
+        assert!(!flags.enable_gap_code());
+        assert!(flags.incomplete_measurements());
+        assert!(!flags.authority_order());
+
+        assert_eq!(drtm.validated_tables().collect::<Vec<_>>(), [0x7ff0_0000]);
+        let resources = drtm.resources();
+        assert_eq!(resources.len(), 2);
+        assert_eq!(resources[0].size(), 0x1000);
+        assert_eq!(resources[0].resource_type(), 1);
+        assert_eq!(resources[0].address(), 0xfed4_0000);
+        assert_eq!(resources[1].size(), 0x20_0000);
+        assert_eq!(resources[1].resource_type(), 2);
+        assert_eq!(resources[1].address(), 0x1000_0000);
+        assert_eq!(drtm.dps_id(), Some(&[0xab; 16][..]));
+    }
+
+    #[test]
+    fn test_truncated_lists() {
+        // The table ends part of the way through the second resource, so there's no DPS ID
+        let mut table = make_drtm(&[], &[(0x1000, 1, 0xfed4_0000), (0x2000, 1, 0xfed5_0000)], None);
+        table.truncate(table.len() - 8);
+        crate::sdt::SdtHeader::finalize(&mut table);
+        let drtm = table_from_bytes::<Drtm>(&table);
+        assert_eq!(drtm.validated_tables().count(), 0);
+        assert_eq!(drtm.resources().len(), 1);
+        assert_eq!(drtm.dps_id(), None);
+
+        // The list of validated tables claims more addresses than fit in the table
+        let mut table = make_drtm(&[0x7ff0_0000, 0x7ff1_0000], &[], None);
+        table[96..100].copy_from_slice(&3u32.to_le_bytes());
+        crate::sdt::SdtHeader::finalize(&mut table);
+        let drtm = table_from_bytes::<Drtm>(&table);
+        assert_eq!(drtm.validated_tables().count(), 2);
+        assert!(drtm.resources().is_empty());
+        assert_eq!(drtm.dps_id(), None);
+    }
+}
@@ -0,0 +1,112 @@
+use crate::{
+    platform::address::{GenericAddress, RawGenericAddress},
+    sdt::SdtHeader,
+    AcpiError,
+    AcpiTable,
+};
+use core::{mem, slice, str};
+
+/// Represents the Embedded Controller Boot Resources Table (ECDT). This describes the embedded controller's
+/// registers, so that the OS can access it before the namespace has been loaded (e.g. because AML in the DSDT
+/// needs to access operation regions in the EC's address space).
+#[repr(C, packed)]
+pub struct Ecdt {
+    header: SdtHeader,
+    ec_control: RawGenericAddress,
+    ec_data: RawGenericAddress,
+    uid: u32,
+    gpe_bit: u8,
+    // Followed by the EC's path in the namespace, as a null-terminated string
+}
+
+impl AcpiTable for Ecdt {
+    fn header(&self) -> &SdtHeader {
+        &self.header
+    }
+}
+
+impl Ecdt {
+    /// The EC's command/status register.
+    pub fn control_register(&self) -> Result<GenericAddress, AcpiError> {
+        GenericAddress::from_raw(self.ec_control)
+    }
+
+    pub fn data_register(&self) -> Result<GenericAddress, AcpiError> {
+        GenericAddress::from_raw(self.ec_data)
+    }
+
+    /// The unique ID of the EC, which matches the `_UID` of its object in the namespace.
+    pub fn uid(&self) -> u32 {
+        self.uid
+    }
+
+    /// The bit of the GPE registers that the EC's System Control Interrupt is wired to.
+    pub fn gpe_bit(&self) -> u8 {
+        self.gpe_bit
+    }
+
+    /// The fully-qualified path to the EC in the ACPI namespace. Returns `None` if the string isn't terminated
+    /// within the table, or isn't valid ASCII.
+    pub fn ec_id(&self) -> Option<&str> {
+        let length = (self.header.length as usize).checked_sub(mem::size_of::<Ecdt>())?;
+        let bytes = unsafe {
+            slice::from_raw_parts((self as *const Ecdt as *const u8).add(mem::size_of::<Ecdt>()), length)
+        };
+
+        let name = &bytes[..bytes.iter().position(|&byte| byte == 0)?];
+        if name.is_ascii() {
+            str::from_utf8(name).ok()
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        platform::address::AddressSpace,
+        test_utils::{make_sdt, table_from_bytes},
+    };
+    use alloc::vec::Vec;
+
+    /// Build an ECDT in the form found on many laptops, with the EC's registers at the standard ports.
+    fn make_ecdt(ec_id: &[u8]) -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&[0x01, 8, 0, 1]);
+        body.extend_from_slice(&0x66u64.to_le_bytes());
+        body.extend_from_slice(&[0x01, 8, 0, 1]);
+        body.extend_from_slice(&0x62u64.to_le_bytes());
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body.push(0x16);
+        body.extend_from_slice(ec_id);
+        make_sdt(b"ECDT", 1, &body)
+    }
+
+    #[test]
+    fn test_ecdt() {
+        let table = make_ecdt(b"\\_SB.PCI0.LPCB.EC0\0");
+        let ecdt = table_from_bytes::<Ecdt>(&table);
+
+        let control = ecdt.control_register().unwrap();
+        assert_eq!(control.address_space, AddressSpace::SystemIo);
+        assert_eq!(control.bit_width, 8);
+        assert_eq!(control.address, 0x66);
+
+        let data = ecdt.data_register().unwrap();
+        assert_eq!(data.address_space, AddressSpace::SystemIo);
+        assert_eq!(data.address, 0x62);
+
+        assert_eq!(ecdt.uid(), 0);
+        assert_eq!(ecdt.gpe_bit(), 0x16);
+        assert_eq!(ecdt.ec_id(), Some("\\_SB.PCI0.LPCB.EC0"));
+    }
+
+    #[test]
+    fn test_unterminated_ec_id() {
+        let table = make_ecdt(b"\\_SB.PCI0.LPCB.EC0");
+        let ecdt = table_from_bytes::<Ecdt>(&table);
+        assert_eq!(ecdt.ec_id(), None);
+    }
+}
@@ -0,0 +1,134 @@
+use crate::{
+    apei::{self, InstructionEntry},
+    sdt::SdtHeader,
+    AcpiTable,
+};
+
+/// Represents the Error Injection Table (EINJ). This describes how the OS can inject hardware errors into the
+/// platform, to test its error handling. Each injection action is made up of one or more instruction entries,
+/// which should be performed in the order they appear in the table.
+#[repr(C, packed)]
+pub struct Einj {
+    header: SdtHeader,
+    injection_header_size: u32,
+    injection_flags: u8,
+    _reserved: [u8; 3],
+    injection_entry_count: u32,
+    // Followed by `injection_entry_count` entries with format `InstructionEntry`
+}
+
+impl AcpiTable for Einj {
+    fn header(&self) -> &SdtHeader {
+        &self.header
+    }
+}
+
+impl Einj {
+    /// Get the instruction entries. If the table is too short to hold the number of entries it claims to, only the
+    /// entries that fit within it are returned.
+    pub fn instruction_entries(&self) -> &[InstructionEntry<InjectionAction>] {
+        apei::instruction_entries(self, self.injection_entry_count)
+    }
+
+    /// Iterate over the instruction entries that make up the given action, in the order they should be
+    /// performed.
+    pub fn action_entries(
+        &self,
+        action: InjectionAction,
+    ) -> impl Iterator<Item = &InstructionEntry<InjectionAction>> {
+        self.instruction_entries().iter().filter(move |entry| entry.action() == action)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum InjectionAction {
+    BeginInjectionOperation,
+    /// Get the physical address of the Trigger Error Action Table, which describes how to trigger the error once
+    /// it's been injected.
+    GetTriggerErrorActionTable,
+    SetErrorType,
+    /// Get the types of error that the platform can inject.
+    GetErrorType,
+    EndOperation,
+    ExecuteOperation,
+    CheckBusyStatus,
+    GetCommandStatus,
+    SetErrorTypeWithAddress,
+    GetExecuteOperationTimings,
+    /// Only used in the Trigger Error Action Table.
+    TriggerError,
+    Reserved(u8),
+}
+
+impl From<u8> for InjectionAction {
+    fn from(action: u8) -> Self {
+        match action {
+            0x00 => InjectionAction::BeginInjectionOperation,
+            0x01 => InjectionAction::GetTriggerErrorActionTable,
+            0x02 => InjectionAction::SetErrorType,
+            0x03 => InjectionAction::GetErrorType,
+            0x04 => InjectionAction::EndOperation,
+            0x05 => InjectionAction::ExecuteOperation,
+            0x06 => InjectionAction::CheckBusyStatus,
+            0x07 => InjectionAction::GetCommandStatus,
+            0x08 => InjectionAction::SetErrorTypeWithAddress,
+            0x09 => InjectionAction::GetExecuteOperationTimings,
+            0xff => InjectionAction::TriggerError,
+            other => InjectionAction::Reserved(other),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        apei::Instruction,
+        platform::address::AddressSpace,
+        test_utils::{make_sdt, table_from_bytes},
+    };
+    use alloc::vec::Vec;
+
+    fn entry_bytes(action: u8, instruction: u8, flags: u8, address: u64, value: u64, mask: u64) -> Vec<u8> {
+        let mut entry = Vec::new();
+        entry.extend_from_slice(&[action, instruction, flags, 0]);
+        entry.extend_from_slice(&[0x00, 64, 0, 4]);
+        entry.extend_from_slice(&address.to_le_bytes());
+        entry.extend_from_slice(&value.to_le_bytes());
+        entry.extend_from_slice(&mask.to_le_bytes());
+        entry
+    }
+
+    #[test]
+    fn test_actions() {
+        let mut body = Vec::new();
+        body.extend_from_slice(&12u32.to_le_bytes());
+        body.extend_from_slice(&[0; 4]);
+        body.extend_from_slice(&3u32.to_le_bytes());
+        body.extend(entry_bytes(0x00, 0x03, 0x01, 0x7fff_0000, 0x1, 0xffff));
+        body.extend(entry_bytes(0x02, 0x02, 0x00, 0x7fff_0008, 0, u64::MAX));
+        body.extend(entry_bytes(0x04, 0x04, 0x00, 0, 0, 0));
+        let table = make_sdt(b"EINJ", 1, &body);
+        let einj = table_from_bytes::<Einj>(&table);
+        assert_eq!(einj.instruction_entries().len(), 3);
+
+        let begin: Vec<&InstructionEntry<InjectionAction>> =
+            einj.action_entries(InjectionAction::BeginInjectionOperation).collect();
+        assert_eq!(begin.len(), 1);
+        assert_eq!(begin[0].instruction(), Instruction::WriteRegisterValue);
+        assert!(begin[0].preserve_register());
+        let register = begin[0].register().unwrap();
+        assert_eq!(register.address_space, AddressSpace::SystemMemory);
+        assert_eq!(register.bit_width, 64);
+        assert_eq!(register.address, 0x7fff_0000);
+        assert_eq!(begin[0].value(), 0x1);
+        assert_eq!(begin[0].mask(), 0xffff);
+
+        let entries = einj.instruction_entries();
+        assert_eq!(entries[1].action(), InjectionAction::SetErrorType);
+        assert_eq!(entries[1].instruction(), Instruction::WriteRegister);
+        assert!(!entries[1].preserve_register());
+        assert_eq!(entries[2].action(), InjectionAction::EndOperation);
+        assert_eq!(entries[2].instruction(), Instruction::Noop);
+    }
+}
@@ -0,0 +1,152 @@
+use crate::{
+    apei::{self, InstructionEntry},
+    sdt::SdtHeader,
+    AcpiTable,
+};
+
+/// Represents the Error Record Serialization Table (ERST). This describes how the OS can save error records to
+/// persistent storage provided by the platform, and read them back (e.g. after a reboot caused by a fatal
+/// error). Each serialization action is made up of one or more instruction entries, which should be performed in
+/// the order they appear in the table.
+#[repr(C, packed)]
+pub struct Erst {
+    header: SdtHeader,
+    serialization_header_size: u32,
+    _reserved: u32,
+    instruction_entry_count: u32,
+    // Followed by `instruction_entry_count` entries with format `InstructionEntry`
+}
+
+impl AcpiTable for Erst {
+    fn header(&self) -> &SdtHeader {
+        &self.header
+    }
+}
+
+impl Erst {
+    /// Get the instruction entries. If the table is too short to hold the number of entries it claims to, only the
+    /// entries that fit within it are returned.
+    pub fn instruction_entries(&self) -> &[InstructionEntry<SerializationAction>] {
+        apei::instruction_entries(self, self.instruction_entry_count)
+    }
+
+    /// Iterate over the instruction entries that make up the given action, in the order they should be
+    /// performed.
+    pub fn action_entries(
+        &self,
+        action: SerializationAction,
+    ) -> impl Iterator<Item = &InstructionEntry<SerializationAction>> {
+        self.instruction_entries().iter().filter(move |entry| entry.action() == action)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SerializationAction {
+    BeginWriteOperation,
+    BeginReadOperation,
+    BeginClearOperation,
+    EndOperation,
+    SetRecordOffset,
+    ExecuteOperation,
+    CheckBusyStatus,
+    GetCommandStatus,
+    GetRecordIdentifier,
+    SetRecordIdentifier,
+    GetRecordCount,
+    BeginDummyWriteOperation,
+    /// Get the physical address of the range of memory that error records are copied through.
+    GetErrorLogAddressRange,
+    GetErrorLogAddressRangeLength,
+    GetErrorLogAddressRangeAttributes,
+    GetExecuteOperationTimings,
+    Reserved(u8),
+}
+
+impl From<u8> for SerializationAction {
+    fn from(action: u8) -> Self {
+        match action {
+            0x00 => SerializationAction::BeginWriteOperation,
+            0x01 => SerializationAction::BeginReadOperation,
+            0x02 => SerializationAction::BeginClearOperation,
+            0x03 => SerializationAction::EndOperation,
+            0x04 => SerializationAction::SetRecordOffset,
+            0x05 => SerializationAction::ExecuteOperation,
+            0x06 => SerializationAction::CheckBusyStatus,
+            0x07 => SerializationAction::GetCommandStatus,
+            0x08 => SerializationAction::GetRecordIdentifier,
+            0x09 => SerializationAction::SetRecordIdentifier,
+            0x0a => SerializationAction::GetRecordCount,
+            0x0b => SerializationAction::BeginDummyWriteOperation,
+            0x0d => SerializationAction::GetErrorLogAddressRange,
+            0x0e => SerializationAction::GetErrorLogAddressRangeLength,
+            0x0f => SerializationAction::GetErrorLogAddressRangeAttributes,
+            0x10 => SerializationAction::GetExecuteOperationTimings,
+            other => SerializationAction::Reserved(other),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        apei::Instruction,
+        platform::address::AddressSpace,
+        test_utils::{make_sdt, table_from_bytes},
+    };
+    use alloc::vec::Vec;
+
+    fn entry_bytes(action: u8, instruction: u8, address: u64, value: u64, mask: u64) -> Vec<u8> {
+        let mut entry = Vec::new();
+        entry.extend_from_slice(&[action, instruction, 0, 0]);
+        entry.extend_from_slice(&[0x00, 64, 0, 4]);
+        entry.extend_from_slice(&address.to_le_bytes());
+        entry.extend_from_slice(&value.to_le_bytes());
+        entry.extend_from_slice(&mask.to_le_bytes());
+        entry
+    }
+
+    fn make_erst(instruction_entry_count: u32, entries: &[u8]) -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&48u32.to_le_bytes());
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body.extend_from_slice(&instruction_entry_count.to_le_bytes());
+        body.extend_from_slice(entries);
+        make_sdt(b"ERST", 1, &body)
+    }
+
+    #[test]
+    fn test_actions() {
+        let mut entries = Vec::new();
+        entries.extend(entry_bytes(0x0d, 0x00, 0x7fff_0010, 0, u64::MAX));
+        entries.extend(entry_bytes(0x06, 0x01, 0x7fff_0018, 0x1, 0x1));
+        entries.extend(entry_bytes(0x0c, 0x0c, 0, 0, 0));
+        let table = make_erst(3, &entries);
+        let erst = table_from_bytes::<Erst>(&table);
+        assert_eq!(erst.instruction_entries().len(), 3);
+
+        let log_range: Vec<&InstructionEntry<SerializationAction>> =
+            erst.action_entries(SerializationAction::GetErrorLogAddressRange).collect();
+        assert_eq!(log_range.len(), 1);
+        assert_eq!(log_range[0].instruction(), Instruction::ReadRegister);
+        let register = log_range[0].register().unwrap();
+        assert_eq!(register.address_space, AddressSpace::SystemMemory);
+        assert_eq!(register.address, 0x7fff_0010);
+        assert_eq!(log_range[0].mask(), u64::MAX);
+
+        let entries = erst.instruction_entries();
+        assert_eq!(entries[1].action(), SerializationAction::CheckBusyStatus);
+        assert_eq!(entries[1].instruction(), Instruction::ReadRegisterValue);
+        assert_eq!(entries[1].value(), 0x1);
+        // Action `0x0c` is reserved
+        assert_eq!(entries[2].action(), SerializationAction::Reserved(0x0c));
+        assert_eq!(entries[2].instruction(), Instruction::Stall);
+    }
+
+    #[test]
+    fn test_truncated_entries() {
+        let table = make_erst(4, &entry_bytes(0x00, 0x03, 0x7fff_0000, 0x1, 0x1));
+        let erst = table_from_bytes::<Erst>(&table);
+        assert_eq!(erst.instruction_entries().len(), 1);
+    }
+}
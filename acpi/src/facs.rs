@@ -0,0 +1,262 @@
+use crate::{fadt::Fadt, sdt::Signature, AcpiError, AcpiHandler, PhysicalMapping};
+use bit_field::BitField;
+use core::{mem, ptr};
+
+/// Represents the Firmware ACPI Control Structure (FACS). Unlike the other tables, this isn't found through the
+/// RSDT/XSDT, but through the FADT, and it doesn't have the standard SDT header (or a checksum). It holds the
+/// waking vector used to resume from sleep, and the Global Lock shared between the OS and the firmware.
+///
+/// The FACS is shared with the firmware, which may read and write it at any time, so all of its mutable fields
+/// are accessed with volatile operations. The FACS must be 64-byte aligned, so its fields are naturally aligned.
+#[repr(C)]
+pub struct Facs {
+    signature: Signature,
+    length: u32,
+    hardware_signature: u32,
+    firmware_waking_vector: u32,
+    global_lock: u32,
+    flags: u32,
+    x_firmware_waking_vector: u64,
+    version: u8,
+    _reserved1: [u8; 3],
+    ospm_flags: u32,
+    _reserved2: [u8; 24],
+}
+
+/// The state of the Global Lock.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct GlobalLock {
+    /// A request for ownership of the lock is pending, and the owner should signal when it releases it.
+    pub pending: bool,
+    pub owned: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FacsFlags(u32);
+
+impl FacsFlags {
+    /// If true, the platform supports the OS requesting S4 through the `S4BIOS_REQ` command.
+    pub fn s4bios_supported(&self) -> bool {
+        self.0.get_bit(0)
+    }
+
+    /// If true, the platform supports waking to a 64-bit environment through `x_firmware_waking_vector`.
+    pub fn supports_64bit_wake(&self) -> bool {
+        self.0.get_bit(1)
+    }
+}
+
+impl Facs {
+    /// Map the FACS pointed to by `fadt`, using `handler`. This fails with `AcpiError::TableMissing` if the FADT
+    /// doesn't point to a FACS, and also fails if it gives an invalid address, or if the structure found there
+    /// isn't a FACS.
+    ///
+    /// ### Safety
+    /// The FADT must point to memory that can be mapped with `handler`, which is true of any valid FADT.
+    pub unsafe fn from_fadt<H>(fadt: &Fadt, handler: &H) -> Result<PhysicalMapping<H, Facs>, AcpiError>
+    where
+        H: AcpiHandler,
+    {
+        let address = fadt.facs_address()?.ok_or(AcpiError::TableMissing(Signature::FACS))?;
+        if address % mem::align_of::<Facs>() != 0 {
+            return Err(AcpiError::InvalidFacsAddress);
+        }
+
+        let mapping = unsafe { handler.map_physical_region::<Facs>(address, mem::size_of::<Facs>()) };
+        if mapping.signature != Signature::FACS {
+            return Err(AcpiError::SdtInvalidSignature(Signature::FACS));
+        }
+        if (mapping.length as usize) < mem::size_of::<Facs>() {
+            return Err(AcpiError::InvalidFacsAddress);
+        }
+
+        Ok(mapping)
+    }
+
+    pub fn length(&self) -> u32 {
+        self.length
+    }
+
+    /// A value that changes if the platform's hardware configuration changes while it's asleep. If this differs
+    /// on resuming from S4, the OS should boot normally rather than restoring its saved image.
+    pub fn hardware_signature(&self) -> u32 {
+        self.hardware_signature
+    }
+
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// The physical address of the OS's waking vector, which is entered in real mode on waking from sleep.
+    pub fn firmware_waking_vector(&self) -> u32 {
+        unsafe { ptr::read_volatile(&self.firmware_waking_vector) }
+    }
+
+    /// The physical address of the OS's waking vector, for platforms that can wake to protected mode or 64-bit
+    /// mode. If this is nonzero, it takes precedence over `firmware_waking_vector`. Returns `None` for version `0`
+    /// of the FACS, which doesn't have this field.
+    pub fn x_firmware_waking_vector(&self) -> Option<u64> {
+        if self.version >= 1 {
+            Some(unsafe { ptr::read_volatile(&self.x_firmware_waking_vector) })
+        } else {
+            None
+        }
+    }
+
+    pub fn global_lock(&self) -> GlobalLock {
+        let lock = unsafe { ptr::read_volatile(&self.global_lock) };
+        GlobalLock { pending: lock.get_bit(0), owned: lock.get_bit(1) }
+    }
+
+    /// The physical address of the Global Lock, which is needed to acquire and release it atomically.
+    pub fn global_lock_address<H>(mapping: &PhysicalMapping<H, Facs>) -> usize
+    where
+        H: AcpiHandler,
+    {
+        mapping.physical_start() + 16
+    }
+
+    pub fn flags(&self) -> FacsFlags {
+        FacsFlags(self.flags)
+    }
+
+    /// Whether the OS has asked to be woken in 64-bit mode through `x_firmware_waking_vector`.
+    pub fn wake_64bit(&self) -> bool {
+        let flags = unsafe { ptr::read_volatile(&self.ospm_flags) };
+        flags.get_bit(0)
+    }
+
+    /// Set the waking vector that the platform enters in real mode on waking from sleep. This also clears
+    /// `x_firmware_waking_vector` (if it exists), as it would otherwise take precedence.
+    pub fn set_firmware_waking_vector<H>(mapping: &PhysicalMapping<H, Facs>, vector: u32)
+    where
+        H: AcpiHandler,
+    {
+        let facs = mapping.virtual_start().as_ptr();
+        unsafe {
+            if (*facs).version >= 1 {
+                ptr::write_volatile(ptr::addr_of_mut!((*facs).x_firmware_waking_vector), 0);
+            }
+            ptr::write_volatile(ptr::addr_of_mut!((*facs).firmware_waking_vector), vector);
+        }
+    }
+
+    /// Set the waking vector that the platform enters in protected mode on waking from sleep, or in 64-bit mode
+    /// if `wake_64bit` is set. Fails if this version of the FACS doesn't have `x_firmware_waking_vector`, or if
+    /// 64-bit mode is requested and the platform doesn't support it.
+    pub fn set_x_firmware_waking_vector<H>(
+        mapping: &PhysicalMapping<H, Facs>,
+        vector: u64,
+        wake_64bit: bool,
+    ) -> Result<(), AcpiError>
+    where
+        H: AcpiHandler,
+    {
+        if mapping.version < 1 || (wake_64bit && !mapping.flags().supports_64bit_wake()) {
+            return Err(AcpiError::UnsupportedWakingVector);
+        }
+
+        let facs = mapping.virtual_start().as_ptr();
+        unsafe {
+            let ospm_flags = ptr::addr_of_mut!((*facs).ospm_flags);
+            ptr::write_volatile(ospm_flags, *ptr::read_volatile(ospm_flags).set_bit(0, wake_64bit));
+            ptr::write_volatile(ptr::addr_of_mut!((*facs).x_firmware_waking_vector), vector);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{make_sdt, table_from_bytes, TestHandler};
+    use alloc::vec::Vec;
+
+    const FACS_ADDRESS: usize = 0x7fe0_0000;
+
+    #[repr(C, align(64))]
+    struct FacsBytes([u8; 64]);
+
+    fn make_facs(version: u8, flags: u32) -> FacsBytes {
+        let mut facs = [0; 64];
+        facs[0..4].copy_from_slice(b"FACS");
+        facs[4..8].copy_from_slice(&64u32.to_le_bytes());
+        facs[8..12].copy_from_slice(&0x1234_abcdu32.to_le_bytes());
+        facs[12..16].copy_from_slice(&0x9a000u32.to_le_bytes());
+        facs[16..20].copy_from_slice(&0b10u32.to_le_bytes());
+        facs[20..24].copy_from_slice(&flags.to_le_bytes());
+        facs[32] = version;
+        FacsBytes(facs)
+    }
+
+    /// Build a FADT with `firmware_ctrl` pointing at the FACS.
+    fn make_fadt() -> Vec<u8> {
+        let mut body = [0; 240];
+        body[0..4].copy_from_slice(&(FACS_ADDRESS as u32).to_le_bytes());
+        make_sdt(b"FACP", 6, &body)
+    }
+
+    #[test]
+    fn test_facs() {
+        let facs = make_facs(2, 0b11);
+        let fadt = make_fadt();
+        let handler = TestHandler::with_mapping(FACS_ADDRESS, facs.0.as_ptr());
+        let facs = unsafe { Facs::from_fadt(table_from_bytes::<Fadt>(&fadt), &handler) }.unwrap();
+
+        assert_eq!(facs.length(), 64);
+        assert_eq!(facs.hardware_signature(), 0x1234_abcd);
+        assert_eq!(facs.firmware_waking_vector(), 0x9a000);
+        assert_eq!(facs.x_firmware_waking_vector(), Some(0));
+        assert_eq!(facs.global_lock(), GlobalLock { pending: false, owned: true });
+        assert_eq!(Facs::global_lock_address(&facs), FACS_ADDRESS + 16);
+        assert!(facs.flags().s4bios_supported());
+        assert!(facs.flags().supports_64bit_wake());
+        assert!(!facs.wake_64bit());
+    }
+
+    #[test]
+    fn test_set_waking_vector() {
+        let mut bytes = make_facs(2, 0b10);
+        let fadt = make_fadt();
+        let handler = TestHandler::with_mapping(FACS_ADDRESS, bytes.0.as_mut_ptr());
+        let facs = unsafe { Facs::from_fadt(table_from_bytes::<Fadt>(&fadt), &handler) }.unwrap();
+
+        Facs::set_x_firmware_waking_vector(&facs, 0x1_0000_8000, true).unwrap();
+        assert_eq!(facs.x_firmware_waking_vector(), Some(0x1_0000_8000));
+        assert!(facs.wake_64bit());
+
+        Facs::set_firmware_waking_vector(&facs, 0x8000);
+        assert_eq!(facs.firmware_waking_vector(), 0x8000);
+        assert_eq!(facs.x_firmware_waking_vector(), Some(0));
+        drop(facs);
+
+        assert_eq!(&bytes.0[12..16], &0x8000u32.to_le_bytes());
+        assert_eq!(bytes.0[36], 0b1);
+    }
+
+    #[test]
+    fn test_version_0_facs() {
+        let mut bytes = make_facs(0, 0);
+        let fadt = make_fadt();
+        let handler = TestHandler::with_mapping(FACS_ADDRESS, bytes.0.as_mut_ptr());
+        let facs = unsafe { Facs::from_fadt(table_from_bytes::<Fadt>(&fadt), &handler) }.unwrap();
+
+        assert_eq!(facs.x_firmware_waking_vector(), None);
+        assert!(matches!(
+            Facs::set_x_firmware_waking_vector(&facs, 0x8000, false),
+            Err(AcpiError::UnsupportedWakingVector)
+        ));
+    }
+
+    #[test]
+    fn test_invalid_signature() {
+        let mut bytes = make_facs(2, 0);
+        bytes.0[0..4].copy_from_slice(b"FACP");
+        let fadt = make_fadt();
+        let handler = TestHandler::with_mapping(FACS_ADDRESS, bytes.0.as_ptr());
+        assert!(matches!(
+            unsafe { Facs::from_fadt(table_from_bytes::<Fadt>(&fadt), &handler) },
+            Err(AcpiError::SdtInvalidSignature(Signature::FACS))
+        ));
+    }
+}
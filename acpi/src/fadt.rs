@@ -1,10 +1,13 @@
 use crate::{
     platform::address::{AccessSize, AddressSpace, GenericAddress, RawGenericAddress},
+    sdei::Conduit,
     sdt::{ExtendedField, SdtHeader},
     AcpiError,
+    AcpiHandler,
     AcpiTable,
 };
 use bit_field::BitField;
+use core::{convert::TryFrom, hint};
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum PowerProfile {
@@ -20,6 +23,40 @@ pub enum PowerProfile {
     Reserved(u8),
 }
 
+/// A default CPU frequency policy, suggested by the platform's `PowerProfile`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CpuGovernorHint {
+    Performance,
+    Balanced,
+    PowerSave,
+}
+
+impl PowerProfile {
+    /// If true, the platform is a battery-powered device, such as a laptop or tablet.
+    pub fn is_mobile(&self) -> bool {
+        matches!(self, PowerProfile::Mobile | PowerProfile::Tablet)
+    }
+
+    pub fn is_server(&self) -> bool {
+        matches!(self, PowerProfile::EnterpriseServer | PowerProfile::SohoServer | PowerProfile::PerformanceServer)
+    }
+
+    /// Suggest a default CPU frequency policy, based on what the firmware says the platform is used for. Platforms
+    /// that don't give a (known) profile get `Balanced`.
+    pub fn suggested_cpu_governor(&self) -> CpuGovernorHint {
+        match self {
+            PowerProfile::Workstation | PowerProfile::EnterpriseServer | PowerProfile::PerformanceServer => {
+                CpuGovernorHint::Performance
+            }
+            PowerProfile::Mobile | PowerProfile::Tablet | PowerProfile::AppliancePc => CpuGovernorHint::PowerSave,
+            PowerProfile::Desktop
+            | PowerProfile::SohoServer
+            | PowerProfile::Unspecified
+            | PowerProfile::Reserved(_) => CpuGovernorHint::Balanced,
+        }
+    }
+}
+
 /// Represents the Fixed ACPI Description Table (FADT). This table contains various fixed hardware
 /// details, such as the addresses of the hardware register blocks. It also contains a pointer to
 /// the Differentiated Definition Block (DSDT).
@@ -118,6 +155,11 @@ impl AcpiTable for Fadt {
     fn header(&self) -> &SdtHeader {
         &self.header
     }
+
+    /// The FADT of ACPI Version 1.0 ends after the `flags` field.
+    fn minimum_length() -> usize {
+        116
+    }
 }
 
 impl Fadt {
@@ -125,28 +167,53 @@ impl Fadt {
         self.header.validate(crate::sdt::Signature::FADT)
     }
 
-    pub fn facs_address(&self) -> Result<usize, AcpiError> {
-        unsafe {
-            self.x_firmware_ctrl
-                .access(self.header.revision)
-                .filter(|&p| p != 0)
-                .or(Some(self.firmware_ctrl as u64))
-                .filter(|&p| p != 0)
-                .map(|p| p as usize)
-                .ok_or(AcpiError::InvalidFacsAddress)
-        }
+    /// The physical address of the FACS, preferring `X_FIRMWARE_CTRL` over `FIRMWARE_CTRL`. Returns `None` if
+    /// neither is set, which is allowed on hardware-reduced platforms.
+    pub fn facs_address(&self) -> Result<Option<usize>, AcpiError> {
+        self.select_address(self.firmware_ctrl, &self.x_firmware_ctrl, 140, AcpiError::InvalidFacsAddress)
     }
 
+    /// The physical address of the DSDT, preferring `X_DSDT` over `DSDT`.
     pub fn dsdt_address(&self) -> Result<usize, AcpiError> {
-        unsafe {
-            self.x_dsdt_address
-                .access(self.header.revision)
-                .filter(|&p| p != 0)
-                .or(Some(self.dsdt_address as u64))
-                .filter(|&p| p != 0)
-                .map(|p| p as usize)
-                .ok_or(AcpiError::InvalidDsdtAddress)
+        self.select_address(self.dsdt_address, &self.x_dsdt_address, 148, AcpiError::InvalidDsdtAddress)?
+            .ok_or(AcpiError::InvalidDsdtAddress)
+    }
+
+    /// Pick between a 32-bit address field and its 64-bit extended counterpart, which ends at byte `field_end`
+    /// of the FADT. The 64-bit field is used if it's present and non-zero, unless it can't be addressed on this
+    /// platform, in which case the 32-bit field is used instead (if it's set). Firmware that puts garbage in the
+    /// upper bits of the 64-bit field then still works on 32-bit platforms.
+    fn select_address(
+        &self,
+        address_32: u32,
+        address_64: &ExtendedField<u64, 2>,
+        field_end: u32,
+        error: AcpiError,
+    ) -> Result<Option<usize>, AcpiError> {
+        let address_64 = self.extended_field(address_64, field_end).filter(|&address| address != 0);
+
+        if let Some(address) = address_64 {
+            match usize::try_from(address) {
+                Ok(address) => return Ok(Some(address)),
+                Err(_) if address_32 == 0 => return Err(error),
+                Err(_) => (),
+            }
+        }
+
+        Ok(match address_32 {
+            0 => None,
+            address => Some(address as usize),
+        })
+    }
+
+    /// Read one of the extended fields, which ends at byte `field_end` of the FADT. Returns `None` if the FADT's
+    /// revision is too old for it to have the field, or if the FADT is too short to contain it.
+    fn extended_field<T: Copy>(&self, field: &ExtendedField<T, 2>, field_end: u32) -> Option<T> {
+        if { self.header.length } < field_end {
+            return None;
         }
+
+        unsafe { field.access(self.header.revision) }
     }
 
     pub fn power_profile(&self) -> PowerProfile {
@@ -164,8 +231,17 @@ impl Fadt {
         }
     }
 
+    /// The index of the CMOS RTC register that holds the century, in BCD. Returns `None` if the field is zero,
+    /// which means the RTC doesn't support a century register, and the century has to be assumed.
+    pub fn century_register(&self) -> Option<u8> {
+        match self.century {
+            0 => None,
+            index => Some(index),
+        }
+    }
+
     pub fn pm1a_event_block(&self) -> Result<GenericAddress, AcpiError> {
-        if let Some(raw) = unsafe { self.x_pm1a_event_block.access(self.header().revision) } {
+        if let Some(raw) = self.extended_field(&self.x_pm1a_event_block, 160) {
             if raw.address != 0x0 {
                 return GenericAddress::from_raw(raw);
             }
@@ -181,7 +257,7 @@ impl Fadt {
     }
 
     pub fn pm1b_event_block(&self) -> Result<Option<GenericAddress>, AcpiError> {
-        if let Some(raw) = unsafe { self.x_pm1b_event_block.access(self.header().revision) } {
+        if let Some(raw) = self.extended_field(&self.x_pm1b_event_block, 172) {
             if raw.address != 0x0 {
                 return Ok(Some(GenericAddress::from_raw(raw)?));
             }
@@ -201,7 +277,7 @@ impl Fadt {
     }
 
     pub fn pm1a_control_block(&self) -> Result<GenericAddress, AcpiError> {
-        if let Some(raw) = unsafe { self.x_pm1a_control_block.access(self.header().revision) } {
+        if let Some(raw) = self.extended_field(&self.x_pm1a_control_block, 184) {
             if raw.address != 0x0 {
                 return GenericAddress::from_raw(raw);
             }
@@ -217,7 +293,7 @@ impl Fadt {
     }
 
     pub fn pm1b_control_block(&self) -> Result<Option<GenericAddress>, AcpiError> {
-        if let Some(raw) = unsafe { self.x_pm1b_control_block.access(self.header().revision) } {
+        if let Some(raw) = self.extended_field(&self.x_pm1b_control_block, 196) {
             if raw.address != 0x0 {
                 return Ok(Some(GenericAddress::from_raw(raw)?));
             }
@@ -237,7 +313,7 @@ impl Fadt {
     }
 
     pub fn pm2_control_block(&self) -> Result<Option<GenericAddress>, AcpiError> {
-        if let Some(raw) = unsafe { self.x_pm2_control_block.access(self.header().revision) } {
+        if let Some(raw) = self.extended_field(&self.x_pm2_control_block, 208) {
             if raw.address != 0x0 {
                 return Ok(Some(GenericAddress::from_raw(raw)?));
             }
@@ -257,7 +333,7 @@ impl Fadt {
     }
 
     pub fn pm_timer_block(&self) -> Result<Option<GenericAddress>, AcpiError> {
-        if let Some(raw) = unsafe { self.x_pm_timer_block.access(self.header().revision) } {
+        if let Some(raw) = self.extended_field(&self.x_pm_timer_block, 220) {
             if raw.address != 0x0 {
                 return Ok(Some(GenericAddress::from_raw(raw)?));
             }
@@ -277,7 +353,7 @@ impl Fadt {
     }
 
     pub fn gpe0_block(&self) -> Result<Option<GenericAddress>, AcpiError> {
-        if let Some(raw) = unsafe { self.x_gpe0_block.access(self.header().revision) } {
+        if let Some(raw) = self.extended_field(&self.x_gpe0_block, 232) {
             if raw.address != 0x0 {
                 return Ok(Some(GenericAddress::from_raw(raw)?));
             }
@@ -297,7 +373,7 @@ impl Fadt {
     }
 
     pub fn gpe1_block(&self) -> Result<Option<GenericAddress>, AcpiError> {
-        if let Some(raw) = unsafe { self.x_gpe1_block.access(self.header().revision) } {
+        if let Some(raw) = self.extended_field(&self.x_gpe1_block, 244) {
             if raw.address != 0x0 {
                 return Ok(Some(GenericAddress::from_raw(raw)?));
             }
@@ -316,12 +392,182 @@ impl Fadt {
         }
     }
 
+    pub fn feature_flags(&self) -> FixedFeatureFlags {
+        self.flags
+    }
+
+    /// Flags describing the legacy hardware on IA-PC platforms.
+    pub fn boot_architecture_flags(&self) -> IaPcBootArchFlags {
+        self.iapc_boot_arch
+    }
+
+    /// Flags describing how to boot the platform on ARM. Returns no flags if the FADT is too old to have them.
+    pub fn arm_boot_architecture_flags(&self) -> ArmBootArchFlags {
+        // The ARM boot architecture flags were added in ACPI 5.1, and end at byte 131 of the FADT
+        if { self.header.length } >= 131 {
+            self.arm_boot_arch
+        } else {
+            ArmBootArchFlags(0)
+        }
+    }
+
+    /// The conduit used to make PSCI calls, and SDEI calls if the platform has an [`Sdei`](crate::sdei::Sdei)
+    /// table. Returns `None` if the platform doesn't implement PSCI.
+    pub fn sdei_conduit(&self) -> Option<Conduit> {
+        let flags = self.arm_boot_architecture_flags();
+        match (flags.implements_psci(), flags.use_hvc_as_psci_conduit()) {
+            (false, _) => None,
+            (true, false) => Some(Conduit::Smc),
+            (true, true) => Some(Conduit::Hvc),
+        }
+    }
+
+    /// If true, the platform is hardware-reduced, and doesn't implement ACPI's fixed hardware (such as the PM
+    /// timer, and the PM1 event and control blocks).
+    pub fn is_hardware_reduced(&self) -> bool {
+        { self.flags }.system_is_hw_reduced_acpi()
+    }
+
+    /// The register that `reset_value` is written to, to reset the platform. Fails with
+    /// `AcpiError::ResetNotSupported` if the FADT is too old to have a reset register.
     pub fn reset_register(&self) -> Result<GenericAddress, AcpiError> {
+        if !self.has_reset_register() {
+            return Err(AcpiError::ResetNotSupported);
+        }
+
         GenericAddress::from_raw(self.reset_reg)
     }
 
+    /// If true, the platform can be reset by writing `reset_value` to the reset register.
+    pub fn reset_supported(&self) -> bool {
+        if !self.has_reset_register() {
+            return false;
+        }
+
+        { self.flags }.supports_system_reset_via_fadt() && { self.reset_reg }.address != 0
+    }
+
+    fn has_reset_register(&self) -> bool {
+        // The reset register and value were added in ACPI 2.0, and end at byte 129 of the FADT
+        let length = self.header.length;
+        length >= 129
+    }
+
+    /// Reset the platform, by writing `reset_value` to the reset register. This fails with
+    /// `AcpiError::ResetNotSupported` if the platform doesn't support resetting through the FADT.
+    ///
+    /// If this returns `Ok`, the write succeeded, but the platform may take a short time to reset. A caller might
+    /// want to wait for a while, and then fall back to another way of resetting the platform.
+    ///
+    /// The reset register may also be in the configuration space of a PCI device on bus 0, but this crate can't
+    /// access PCI configuration space, so that fails with `AcpiError::UnsupportedAddressSpace`. The caller can
+    /// write `reset_value` to the register described by [`Fadt::reset_register`] itself instead.
+    ///
+    /// ### Safety
+    /// This resets the platform, so should only be called once the system is ready for that.
+    pub unsafe fn reset<H>(&self, handler: &H) -> Result<(), AcpiError>
+    where
+        H: AcpiHandler,
+    {
+        if !self.reset_supported() {
+            return Err(AcpiError::ResetNotSupported);
+        }
+
+        /*
+         * The reset register can only be in system memory, system I/O, or the configuration space of a PCI
+         * device on bus 0.
+         */
+        let reset_register = self.reset_register()?;
+        match reset_register.address_space {
+            AddressSpace::SystemMemory | AddressSpace::SystemIo => (),
+            AddressSpace::PciConfigSpace => {
+                return Err(AcpiError::UnsupportedAddressSpace(AddressSpace::PciConfigSpace))
+            }
+            _ => return Err(AcpiError::InvalidGenericAddress),
+        }
+
+        unsafe { reset_register.write(handler, self.reset_value as u64) }
+    }
+
+    /// The I/O port of the SMI command register. Returns `None` if the platform doesn't support System Management
+    /// Mode, in which case it's always in ACPI mode.
+    pub fn smi_command_port(&self) -> Option<u32> {
+        match self.smi_cmd_port {
+            0 => None,
+            port => Some(port),
+        }
+    }
+
+    /// The value to write to the SMI command register to transfer ownership of the ACPI hardware registers to the
+    /// OS.
+    pub fn acpi_enable_value(&self) -> u8 {
+        self.acpi_enable
+    }
+
+    /// The value to write to the SMI command register to return ownership of the ACPI hardware registers to the
+    /// firmware.
+    pub fn acpi_disable_value(&self) -> u8 {
+        self.acpi_disable
+    }
+
+    /// Check the `SCI_EN` bit of the PM1 control registers, which is set once the platform is in ACPI mode.
+    unsafe fn sci_enabled<H>(&self, handler: &H) -> Result<bool, AcpiError>
+    where
+        H: AcpiHandler,
+    {
+        let mut control = unsafe { self.pm1a_control_block()?.read(handler)? };
+        if let Some(pm1b_control_block) = self.pm1b_control_block()? {
+            control |= unsafe { pm1b_control_block.read(handler)? };
+        }
+        Ok(control.get_bit(0))
+    }
+
+    /// Transfer ownership of the ACPI hardware registers from the firmware to the OS, by writing `acpi_enable` to
+    /// the SMI command register, and then waiting for `SCI_EN` to be set. `SCI_EN` is checked `timeout_loops`
+    /// times before giving up with `AcpiError::AcpiModeTimeout`. This does nothing on platforms that don't
+    /// support System Management Mode (including hardware-reduced platforms), or that are already in ACPI mode.
+    ///
+    /// The SMI command register is an I/O port, so `handler` must provide [`PortIo`](crate::PortIo) for this to
+    /// do anything other than check whether the platform is already in ACPI mode.
+    ///
+    /// ### Safety
+    /// This must only be called from the boot processor, and SCIs should be masked until it has finished.
+    pub unsafe fn enable_acpi_mode<H>(&self, handler: &H, timeout_loops: u64) -> Result<(), AcpiError>
+    where
+        H: AcpiHandler,
+    {
+        if self.is_hardware_reduced() {
+            return Ok(());
+        }
+        let port = match self.smi_command_port() {
+            Some(port) => port,
+            None => return Ok(()),
+        };
+        if unsafe { self.sci_enabled(handler)? } {
+            return Ok(());
+        }
+
+        let smi_command = GenericAddress {
+            address_space: AddressSpace::SystemIo,
+            bit_width: 8,
+            bit_offset: 0,
+            access_size: AccessSize::ByteAccess,
+            address: port.into(),
+        };
+        unsafe { smi_command.write(handler, self.acpi_enable.into())? };
+
+        for _ in 0..timeout_loops {
+            if unsafe { self.sci_enabled(handler)? } {
+                return Ok(());
+            }
+            hint::spin_loop();
+        }
+
+        Err(AcpiError::AcpiModeTimeout)
+    }
+
     pub fn sleep_control_register(&self) -> Result<Option<GenericAddress>, AcpiError> {
-        if let Some(raw) = unsafe { self.sleep_control_reg.access(self.header().revision) } {
+        if let Some(raw) = self.extended_field(&self.sleep_control_reg, 256) {
             Ok(Some(GenericAddress::from_raw(raw)?))
         } else {
             Ok(None)
@@ -329,7 +575,7 @@ impl Fadt {
     }
 
     pub fn sleep_status_register(&self) -> Result<Option<GenericAddress>, AcpiError> {
-        if let Some(raw) = unsafe { self.sleep_status_reg.access(self.header().revision) } {
+        if let Some(raw) = self.extended_field(&self.sleep_status_reg, 268) {
             Ok(Some(GenericAddress::from_raw(raw)?))
         } else {
             Ok(None)
@@ -455,6 +701,31 @@ impl FixedFeatureFlags {
     pub fn no_benefit_to_s3(&self) -> bool {
         self.0.get_bit(21)
     }
+
+    /// If true, the platform supports low-power S0 idle (also called Modern Standby), and the OS should use it
+    /// instead of S3. This is the same bit as `no_benefit_to_s3`.
+    pub fn low_power_s0_idle_capable(&self) -> bool {
+        self.0.get_bit(21)
+    }
+
+    /// Whether the contents of the CPU caches are preserved across power loss.
+    pub fn persistent_cpu_caches(&self) -> CpuCachePersistence {
+        match self.0.get_bits(22..24) {
+            0 => CpuCachePersistence::NotReported,
+            1 => CpuCachePersistence::NotPersistent,
+            2 => CpuCachePersistence::Persistent,
+            3 => CpuCachePersistence::Reserved,
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CpuCachePersistence {
+    NotReported,
+    NotPersistent,
+    Persistent,
+    Reserved,
 }
 
 #[derive(Clone, Copy)]
@@ -509,3 +780,488 @@ impl ArmBootArchFlags {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_utils::{make_sdt, table_from_bytes, TestHandler},
+        PhysicalMapping,
+        PortIo,
+    };
+    use alloc::vec::Vec;
+    use core::cell::Cell;
+
+    const RESET_REGISTER_ADDRESS: usize = 0xfee0_0000;
+
+    /// Build a FADT with a reset register in system memory at `RESET_REGISTER_ADDRESS`.
+    fn make_fadt(flags: u32) -> Vec<u8> {
+        let mut body = [0; 240];
+        body[76..80].copy_from_slice(&flags.to_le_bytes());
+        body[80..84].copy_from_slice(&[0x00, 8, 0, 1]);
+        body[84..92].copy_from_slice(&(RESET_REGISTER_ADDRESS as u64).to_le_bytes());
+        body[92] = 0x06;
+        make_sdt(b"FACP", 6, &body)
+    }
+
+    const PM1A_CONTROL_ADDRESS: usize = 0xfee1_0000;
+
+    /// Build a FADT for a legacy platform with an SMI command port, and its PM1a control block in system memory at
+    /// `PM1A_CONTROL_ADDRESS`.
+    fn make_legacy_fadt() -> Vec<u8> {
+        let mut body = [0; 240];
+        body[12..16].copy_from_slice(&0xb2u32.to_le_bytes());
+        body[16] = 0xf0;
+        body[17] = 0xf1;
+        body[53] = 2;
+        body[136..140].copy_from_slice(&[0x00, 16, 0, 2]);
+        body[140..148].copy_from_slice(&(PM1A_CONTROL_ADDRESS as u64).to_le_bytes());
+        make_sdt(b"FACP", 6, &body)
+    }
+
+    #[test]
+    fn test_enable_acpi_mode() {
+        let table = make_legacy_fadt();
+        let fadt = table_from_bytes::<Fadt>(&table);
+        assert_eq!(fadt.smi_command_port(), Some(0xb2));
+        assert_eq!(fadt.acpi_enable_value(), 0xf0);
+        assert_eq!(fadt.acpi_disable_value(), 0xf1);
+
+        // The platform is already in ACPI mode, so the SMI command register shouldn't be written
+        let mut pm1a_control = 0x0001u16;
+        let handler = TestHandler::with_mapping(PM1A_CONTROL_ADDRESS, &mut pm1a_control as *mut u16 as *mut u8);
+        unsafe { fadt.enable_acpi_mode(&handler, 100) }.unwrap();
+
+        // The SMI command register is an I/O port, which can't be accessed yet
+        pm1a_control = 0;
+        let handler = TestHandler::with_mapping(PM1A_CONTROL_ADDRESS, &mut pm1a_control as *mut u16 as *mut u8);
+        assert!(matches!(
+            unsafe { fadt.enable_acpi_mode(&handler, 100) },
+            Err(AcpiError::UnsupportedAddressSpace(AddressSpace::SystemIo))
+        ));
+    }
+
+    /// A mock of the I/O ports used to switch a legacy platform into ACPI mode. `SCI_EN` is set in the PM1a
+    /// control register at port `0x604` once `acpi_enable` has been written to the SMI command port at `0xb2`, and
+    /// the control register has then been read `polls_until_enabled` times.
+    struct Pm1Ports {
+        smi_commands: Cell<u32>,
+        polls_until_enabled: Cell<Option<u32>>,
+        pm1a_control: Cell<u16>,
+    }
+
+    impl Pm1Ports {
+        fn new(polls_until_enabled: Option<u32>) -> Pm1Ports {
+            Pm1Ports {
+                smi_commands: Cell::new(0),
+                polls_until_enabled: Cell::new(polls_until_enabled),
+                pm1a_control: Cell::new(0),
+            }
+        }
+    }
+
+    impl PortIo for Pm1Ports {
+        unsafe fn read_u8(&self, port: u16) -> u8 {
+            panic!("Unexpected 8-bit read from port {:#x}", port);
+        }
+
+        unsafe fn read_u16(&self, port: u16) -> u16 {
+            assert_eq!(port, 0x604);
+            if self.smi_commands.get() > 0 {
+                match self.polls_until_enabled.get() {
+                    Some(0) => self.pm1a_control.set(self.pm1a_control.get() | 1),
+                    Some(polls) => self.polls_until_enabled.set(Some(polls - 1)),
+                    None => (),
+                }
+            }
+            self.pm1a_control.get()
+        }
+
+        unsafe fn read_u32(&self, port: u16) -> u32 {
+            panic!("Unexpected 32-bit read from port {:#x}", port);
+        }
+
+        unsafe fn write_u8(&self, port: u16, value: u8) {
+            assert_eq!(port, 0xb2);
+            assert_eq!(value, 0xf0);
+            self.smi_commands.set(self.smi_commands.get() + 1);
+        }
+
+        unsafe fn write_u16(&self, port: u16, _value: u16) {
+            panic!("Unexpected 16-bit write to port {:#x}", port);
+        }
+
+        unsafe fn write_u32(&self, port: u16, _value: u32) {
+            panic!("Unexpected 32-bit write to port {:#x}", port);
+        }
+    }
+
+    #[derive(Clone)]
+    struct PortIoHandler<'a>(&'a Pm1Ports);
+
+    impl AcpiHandler for PortIoHandler<'_> {
+        unsafe fn map_physical_region<T>(
+            &self,
+            physical_address: usize,
+            _size: usize,
+        ) -> PhysicalMapping<Self, T> {
+            panic!("Unexpected mapping of {:#x}", physical_address);
+        }
+
+        fn unmap_physical_region<T>(_region: &PhysicalMapping<Self, T>) {}
+
+        fn port_io(&self) -> Option<&dyn PortIo> {
+            Some(self.0)
+        }
+    }
+
+    #[test]
+    fn test_enable_acpi_mode_through_ports() {
+        let mut body = [0; 240];
+        body[12..16].copy_from_slice(&0xb2u32.to_le_bytes());
+        body[16] = 0xf0;
+        body[17] = 0xf1;
+        body[28..32].copy_from_slice(&0x604u32.to_le_bytes());
+        body[53] = 2;
+        let table = make_sdt(b"FACP", 6, &body);
+        let fadt = table_from_bytes::<Fadt>(&table);
+        assert_eq!(fadt.pm1a_control_block().unwrap().address_space, AddressSpace::SystemIo);
+
+        let ports = Pm1Ports::new(Some(5));
+        unsafe { fadt.enable_acpi_mode(&PortIoHandler(&ports), 100) }.unwrap();
+        assert_eq!(ports.smi_commands.get(), 1);
+        assert_eq!(ports.pm1a_control.get(), 1);
+
+        // Already in ACPI mode, so the SMI command port shouldn't be written again
+        unsafe { fadt.enable_acpi_mode(&PortIoHandler(&ports), 100) }.unwrap();
+        assert_eq!(ports.smi_commands.get(), 1);
+
+        // The firmware never sets `SCI_EN`
+        let ports = Pm1Ports::new(None);
+        assert!(matches!(
+            unsafe { fadt.enable_acpi_mode(&PortIoHandler(&ports), 100) },
+            Err(AcpiError::AcpiModeTimeout)
+        ));
+        assert_eq!(ports.smi_commands.get(), 1);
+    }
+
+    #[test]
+    fn test_enable_acpi_mode_hardware_reduced() {
+        let mut body = [0; 240];
+        body[12..16].copy_from_slice(&0xb2u32.to_le_bytes());
+        body[76..80].copy_from_slice(&(1u32 << 20).to_le_bytes());
+        let table = make_sdt(b"FACP", 6, &body);
+        let fadt = table_from_bytes::<Fadt>(&table);
+
+        // There are no PM1 control registers to check, so this must return before touching any
+        let handler = TestHandler::with_mapping(0, core::ptr::NonNull::<u8>::dangling().as_ptr());
+        unsafe { fadt.enable_acpi_mode(&handler, 100) }.unwrap();
+
+        let table = make_sdt(b"FACP", 6, &[0; 240]);
+        let fadt = table_from_bytes::<Fadt>(&table);
+        assert_eq!(fadt.smi_command_port(), None);
+        unsafe { fadt.enable_acpi_mode(&handler, 100) }.unwrap();
+    }
+
+    /// Build the body of a FADT with the legacy register block fields of QEMU's `q35` machine.
+    fn make_register_blocks_body() -> [u8; 240] {
+        let mut body = [0; 240];
+        body[20..24].copy_from_slice(&0x600u32.to_le_bytes());
+        body[28..32].copy_from_slice(&0x604u32.to_le_bytes());
+        body[40..44].copy_from_slice(&0x608u32.to_le_bytes());
+        body[44..48].copy_from_slice(&0x620u32.to_le_bytes());
+        body[52] = 4;
+        body[53] = 2;
+        body[55] = 4;
+        body[56] = 16;
+        body
+    }
+
+    #[test]
+    fn test_legacy_register_blocks() {
+        // An ACPI 1.0 FADT, which doesn't have the extended fields
+        let mut table = make_sdt(b"FACP", 1, &make_register_blocks_body()[..80]);
+        table.resize(core::mem::size_of::<Fadt>(), 0);
+        let fadt = table_from_bytes::<Fadt>(&table);
+
+        let system_io = |address, bit_width| GenericAddress {
+            address_space: AddressSpace::SystemIo,
+            bit_width,
+            bit_offset: 0,
+            access_size: AccessSize::Undefined,
+            address,
+        };
+        assert_eq!(fadt.pm1a_event_block().unwrap(), system_io(0x600, 32));
+        assert_eq!(fadt.pm1b_event_block().unwrap(), None);
+        assert_eq!(fadt.pm1a_control_block().unwrap(), system_io(0x604, 16));
+        assert_eq!(fadt.pm1b_control_block().unwrap(), None);
+        assert_eq!(fadt.pm2_control_block().unwrap(), None);
+        assert_eq!(fadt.pm_timer_block().unwrap(), Some(system_io(0x608, 32)));
+        assert_eq!(fadt.gpe0_block().unwrap(), Some(system_io(0x620, 128)));
+        assert_eq!(fadt.gpe1_block().unwrap(), None);
+    }
+
+    /// Build a FADT that's `length` bytes long, followed by bytes that would be invalid if they were read as the
+    /// fields that it's too short to contain.
+    fn make_short_fadt(length: usize) -> Vec<u8> {
+        let mut body = make_register_blocks_body();
+        body[76..80].copy_from_slice(&(1u32 << 10).to_le_bytes());
+        body[80..84].copy_from_slice(&[0x00, 8, 0, 1]);
+        body[84..92].copy_from_slice(&(RESET_REGISTER_ADDRESS as u64).to_le_bytes());
+        body[92] = 0x06;
+
+        let mut table = make_sdt(b"FACP", 2, &body[..length - 36]);
+        table.resize(core::mem::size_of::<Fadt>(), 0xff);
+        table
+    }
+
+    /// Check that the register blocks of a FADT built by `make_short_fadt` come from the legacy fields.
+    fn check_legacy_register_blocks(fadt: &Fadt) {
+        let system_io = |address, bit_width| GenericAddress {
+            address_space: AddressSpace::SystemIo,
+            bit_width,
+            bit_offset: 0,
+            access_size: AccessSize::Undefined,
+            address,
+        };
+        assert_eq!(fadt.pm1a_event_block().unwrap(), system_io(0x600, 32));
+        assert_eq!(fadt.pm1b_event_block().unwrap(), None);
+        assert_eq!(fadt.pm1a_control_block().unwrap(), system_io(0x604, 16));
+        assert_eq!(fadt.pm1b_control_block().unwrap(), None);
+        assert_eq!(fadt.pm2_control_block().unwrap(), None);
+        assert_eq!(fadt.pm_timer_block().unwrap(), Some(system_io(0x608, 32)));
+        assert_eq!(fadt.gpe0_block().unwrap(), Some(system_io(0x620, 128)));
+        assert_eq!(fadt.gpe1_block().unwrap(), None);
+        assert_eq!(fadt.sleep_control_register().unwrap(), None);
+        assert_eq!(fadt.sleep_status_register().unwrap(), None);
+        assert_eq!(fadt.arm_boot_architecture_flags().0, 0);
+    }
+
+    #[test]
+    fn test_acpi_1_0_length_fadt() {
+        let mut register = [0u8; 8];
+        let handler = TestHandler::with_mapping(RESET_REGISTER_ADDRESS, register.as_mut_ptr());
+
+        let table = make_short_fadt(116);
+        let fadt = table_from_bytes::<Fadt>(&table);
+        check_legacy_register_blocks(fadt);
+        assert!(matches!(fadt.reset_register(), Err(AcpiError::ResetNotSupported)));
+        assert!(!fadt.reset_supported());
+        assert!(matches!(unsafe { fadt.reset(&handler) }, Err(AcpiError::ResetNotSupported)));
+        assert_eq!(register[0], 0x00);
+    }
+
+    #[test]
+    fn test_acpi_2_0_fadt_without_extended_fields() {
+        let mut register = [0u8; 8];
+        let handler = TestHandler::with_mapping(RESET_REGISTER_ADDRESS, register.as_mut_ptr());
+
+        let table = make_short_fadt(129);
+        let fadt = table_from_bytes::<Fadt>(&table);
+        check_legacy_register_blocks(fadt);
+        assert_eq!(fadt.reset_register().unwrap().address, RESET_REGISTER_ADDRESS as u64);
+        assert!(fadt.reset_supported());
+        unsafe { fadt.reset(&handler) }.unwrap();
+        assert_eq!(register[0], 0x06);
+    }
+
+    #[test]
+    fn test_extended_register_blocks() {
+        let mut body = make_register_blocks_body();
+        body[32..36].copy_from_slice(&0x680u32.to_le_bytes());
+        body[112..116].copy_from_slice(&[0x00, 32, 0, 3]);
+        body[116..124].copy_from_slice(&0xfed8_0600u64.to_le_bytes());
+        body[136..140].copy_from_slice(&[0x00, 16, 0, 2]);
+        body[140..148].copy_from_slice(&0xfed8_0604u64.to_le_bytes());
+        body[172..176].copy_from_slice(&[0x01, 32, 0, 3]);
+        body[176..184].copy_from_slice(&0x408u64.to_le_bytes());
+        let table = make_sdt(b"FACP", 6, &body);
+        let fadt = table_from_bytes::<Fadt>(&table);
+
+        // The extended fields should be used where they're set, and the legacy fields otherwise
+        let pm1a_event = fadt.pm1a_event_block().unwrap();
+        assert_eq!(pm1a_event.address_space, AddressSpace::SystemMemory);
+        assert_eq!(pm1a_event.address, 0xfed8_0600);
+        assert_eq!(pm1a_event.access_size, AccessSize::DWordAccess);
+        let pm1a_control = fadt.pm1a_control_block().unwrap();
+        assert_eq!(pm1a_control.address_space, AddressSpace::SystemMemory);
+        assert_eq!(pm1a_control.address, 0xfed8_0604);
+        let pm1b_control = fadt.pm1b_control_block().unwrap().unwrap();
+        assert_eq!(pm1b_control.address_space, AddressSpace::SystemIo);
+        assert_eq!(pm1b_control.address, 0x680);
+        let pm_timer = fadt.pm_timer_block().unwrap().unwrap();
+        assert_eq!(pm_timer.address, 0x408);
+        assert_eq!(pm_timer.access_size, AccessSize::DWordAccess);
+        assert_eq!(fadt.gpe0_block().unwrap().unwrap().address, 0x620);
+    }
+
+    #[test]
+    fn test_flags() {
+        // A legacy PC, with an 8042, a 32-bit PM timer, and a working WBINVD
+        let mut body = [0; 240];
+        body[73..75].copy_from_slice(&0b11u16.to_le_bytes());
+        body[76..80].copy_from_slice(&((1u32 << 0) | (1 << 4) | (1 << 8) | (1 << 10)).to_le_bytes());
+        let table = make_sdt(b"FACP", 6, &body);
+        let fadt = table_from_bytes::<Fadt>(&table);
+
+        assert!(!fadt.is_hardware_reduced());
+        let boot_arch = fadt.boot_architecture_flags();
+        assert!(boot_arch.legacy_devices_are_accessible());
+        assert!(boot_arch.motherboard_implements_8042());
+        assert!(!boot_arch.dont_probe_vga());
+        assert!(!boot_arch.dont_enable_msi());
+        let flags = fadt.feature_flags();
+        assert!(flags.supports_equivalent_to_wbinvd());
+        assert!(flags.power_button_is_control_method());
+        assert!(flags.pm_timer_is_32_bit());
+        assert!(flags.supports_system_reset_via_fadt());
+        assert!(!flags.low_power_s0_idle_capable());
+        assert_eq!(flags.persistent_cpu_caches(), CpuCachePersistence::NotReported);
+        assert!(!fadt.arm_boot_architecture_flags().implements_psci());
+
+        // A hardware-reduced ARM platform, like QEMU's `virt` machine, that uses HVC for PSCI
+        let mut body = [0; 240];
+        body[73..75].copy_from_slice(&0b111100u16.to_le_bytes());
+        body[76..80].copy_from_slice(&((1u32 << 20) | (1 << 21) | (0b01 << 22)).to_le_bytes());
+        body[93..95].copy_from_slice(&0b11u16.to_le_bytes());
+        let table = make_sdt(b"FACP", 6, &body);
+        let fadt = table_from_bytes::<Fadt>(&table);
+
+        assert!(fadt.is_hardware_reduced());
+        let boot_arch = fadt.boot_architecture_flags();
+        assert!(!boot_arch.legacy_devices_are_accessible());
+        assert!(boot_arch.dont_probe_vga());
+        assert!(boot_arch.dont_enable_msi());
+        assert!(boot_arch.dont_enable_pcie_aspm());
+        assert!(boot_arch.use_time_and_alarm_namespace_for_rtc());
+        let flags = fadt.feature_flags();
+        assert!(flags.low_power_s0_idle_capable());
+        assert_eq!(flags.persistent_cpu_caches(), CpuCachePersistence::NotPersistent);
+        let arm_boot_arch = fadt.arm_boot_architecture_flags();
+        assert!(arm_boot_arch.implements_psci());
+        assert!(arm_boot_arch.use_hvc_as_psci_conduit());
+
+        // An ACPI 2.0 FADT is too short to have the ARM boot architecture flags, so the bytes after it are ignored
+        let mut table = make_sdt(b"FACP", 3, &body[..80]);
+        table.extend_from_slice(&body[80..]);
+        let fadt = table_from_bytes::<Fadt>(&table);
+        assert!(!fadt.arm_boot_architecture_flags().implements_psci());
+    }
+
+    #[test]
+    fn test_power_profile() {
+        #[rustfmt::skip]
+        let expected = [
+            (PowerProfile::Unspecified, false, false, CpuGovernorHint::Balanced),
+            (PowerProfile::Desktop, false, false, CpuGovernorHint::Balanced),
+            (PowerProfile::Mobile, true, false, CpuGovernorHint::PowerSave),
+            (PowerProfile::Workstation, false, false, CpuGovernorHint::Performance),
+            (PowerProfile::EnterpriseServer, false, true, CpuGovernorHint::Performance),
+            (PowerProfile::SohoServer, false, true, CpuGovernorHint::Balanced),
+            (PowerProfile::AppliancePc, false, false, CpuGovernorHint::PowerSave),
+            (PowerProfile::PerformanceServer, false, true, CpuGovernorHint::Performance),
+            (PowerProfile::Tablet, true, false, CpuGovernorHint::PowerSave),
+            (PowerProfile::Reserved(9), false, false, CpuGovernorHint::Balanced),
+        ];
+
+        for (value, &(profile, is_mobile, is_server, governor)) in expected.iter().enumerate() {
+            let mut body = [0; 240];
+            body[9] = value as u8;
+            let table = make_sdt(b"FACP", 6, &body);
+            let fadt = table_from_bytes::<Fadt>(&table);
+
+            assert_eq!(fadt.power_profile(), profile);
+            assert_eq!(profile.is_mobile(), is_mobile);
+            assert_eq!(profile.is_server(), is_server);
+            assert_eq!(profile.suggested_cpu_governor(), governor);
+        }
+    }
+
+    #[test]
+    fn test_table_addresses() {
+        // An ACPI 1.0 FADT, which doesn't have the 64-bit fields. The bytes after it should be ignored.
+        let mut body = [0xff; 240];
+        body[0..4].copy_from_slice(&0x7ffe_0000u32.to_le_bytes());
+        body[4..8].copy_from_slice(&0x7ffd_0000u32.to_le_bytes());
+        let mut table = make_sdt(b"FACP", 1, &body[..80]);
+        table.extend_from_slice(&body[80..]);
+        let fadt = table_from_bytes::<Fadt>(&table);
+        assert_eq!(fadt.facs_address().unwrap(), Some(0x7ffe_0000));
+        assert_eq!(fadt.dsdt_address().unwrap(), 0x7ffd_0000);
+
+        // A FADT with both, which should prefer the 64-bit fields
+        let mut body = [0; 240];
+        body[0..4].copy_from_slice(&0x7ffe_0000u32.to_le_bytes());
+        body[4..8].copy_from_slice(&0x7ffd_0000u32.to_le_bytes());
+        body[96..104].copy_from_slice(&0x1_7ffe_0000u64.to_le_bytes());
+        body[104..112].copy_from_slice(&0x1_7ffd_0000u64.to_le_bytes());
+        let table = make_sdt(b"FACP", 6, &body);
+        let fadt = table_from_bytes::<Fadt>(&table);
+        assert_eq!(fadt.facs_address().unwrap(), Some(0x1_7ffe_0000));
+        assert_eq!(fadt.dsdt_address().unwrap(), 0x1_7ffd_0000);
+
+        // The 32-bit fields are used if the 64-bit ones are zero
+        body[96..112].copy_from_slice(&[0; 16]);
+        let table = make_sdt(b"FACP", 6, &body);
+        let fadt = table_from_bytes::<Fadt>(&table);
+        assert_eq!(fadt.facs_address().unwrap(), Some(0x7ffe_0000));
+        assert_eq!(fadt.dsdt_address().unwrap(), 0x7ffd_0000);
+
+        // A hardware-reduced platform doesn't need a FACS, but every platform needs a DSDT
+        let table = make_sdt(b"FACP", 6, &[0; 240]);
+        let fadt = table_from_bytes::<Fadt>(&table);
+        assert_eq!(fadt.facs_address().unwrap(), None);
+        assert!(matches!(fadt.dsdt_address(), Err(AcpiError::InvalidDsdtAddress)));
+    }
+
+    #[test]
+    fn test_century_register() {
+        let mut body = [0; 240];
+        let table = make_sdt(b"FACP", 6, &body);
+        assert_eq!(table_from_bytes::<Fadt>(&table).century_register(), None);
+
+        body[72] = 0x32;
+        let table = make_sdt(b"FACP", 6, &body);
+        assert_eq!(table_from_bytes::<Fadt>(&table).century_register(), Some(0x32));
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut register = [0u8; 8];
+        let handler = TestHandler::with_mapping(RESET_REGISTER_ADDRESS, register.as_mut_ptr());
+
+        let fadt = make_fadt(1 << 10);
+        let fadt = table_from_bytes::<Fadt>(&fadt);
+        assert!(fadt.reset_supported());
+        unsafe { fadt.reset(&handler) }.unwrap();
+        assert_eq!(register[0], 0x06);
+    }
+
+    #[test]
+    fn test_reset_not_supported() {
+        let mut register = [0u8; 8];
+        let handler = TestHandler::with_mapping(RESET_REGISTER_ADDRESS, register.as_mut_ptr());
+
+        let fadt = make_fadt(0);
+        let fadt = table_from_bytes::<Fadt>(&fadt);
+        assert!(!fadt.reset_supported());
+        assert!(matches!(unsafe { fadt.reset(&handler) }, Err(AcpiError::ResetNotSupported)));
+        assert_eq!(register[0], 0x00);
+    }
+
+    #[test]
+    fn test_reset_pci_config_space() {
+        let mut register = [0u8; 8];
+        let handler = TestHandler::with_mapping(RESET_REGISTER_ADDRESS, register.as_mut_ptr());
+
+        let mut fadt = make_fadt(1 << 10);
+        fadt[116] = 0x02;
+        SdtHeader::finalize(&mut fadt);
+        let fadt = table_from_bytes::<Fadt>(&fadt);
+        assert!(fadt.reset_supported());
+        assert!(matches!(
+            unsafe { fadt.reset(&handler) },
+            Err(AcpiError::UnsupportedAddressSpace(AddressSpace::PciConfigSpace))
+        ));
+        assert_eq!(register[0], 0x00);
+    }
+}
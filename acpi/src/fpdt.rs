@@ -0,0 +1,278 @@
+use crate::{
+    entry_as,
+    sdt::{SdtHeader, Signature},
+    AcpiHandler,
+    AcpiTable,
+    EntryIter,
+};
+use core::{convert::TryInto, mem};
+
+/// Represents the Firmware Performance Data Table (FPDT). This points to tables of records, kept by the firmware,
+/// of how long it spent booting the platform and resuming it from S3.
+#[repr(C, packed)]
+pub struct Fpdt {
+    header: SdtHeader,
+}
+
+impl AcpiTable for Fpdt {
+    fn header(&self) -> &SdtHeader {
+        &self.header
+    }
+}
+
+impl Fpdt {
+    pub fn records(&self) -> FpdtRecordIter<'_> {
+        FpdtRecordIter {
+            entries: unsafe {
+                EntryIter::new(
+                    Signature::FPDT,
+                    (self as *const Fpdt as *const u8).add(mem::size_of::<Fpdt>()),
+                    self.header.length.saturating_sub(mem::size_of::<Fpdt>() as u32),
+                    entry_minimum_length,
+                )
+            },
+        }
+    }
+
+    /// Follow the Firmware Basic Boot Performance Pointer Record to read the Firmware Basic Boot Performance Data
+    /// Record, which gives the times at which the firmware reached various stages of booting. Returns `None` if
+    /// the FPDT doesn't point to a Firmware Basic Boot Performance Table, or the table doesn't contain the record.
+    ///
+    /// ### Safety
+    /// The Firmware Basic Boot Performance Table must be mappable with `handler`, which is true of any pointed to
+    /// by a valid FPDT.
+    pub unsafe fn basic_boot_performance<H>(&self, handler: &H) -> Option<BasicBootPerformance>
+    where
+        H: AcpiHandler,
+    {
+        let address = self.records().find_map(|record| match record {
+            PerformanceRecord::BasicBootPerformancePointer(address) => Some(address),
+            _ => None,
+        })?;
+        let address: usize = address.try_into().ok()?;
+
+        let header = *unsafe {
+            handler
+                .map_physical_region::<PerformanceTableHeader>(address, mem::size_of::<PerformanceTableHeader>())
+        };
+        if header.signature != *b"FBPT" {
+            return None;
+        }
+
+        let mapping = unsafe { handler.map_physical_region::<u8>(address, header.length as usize) };
+        let mut pointer =
+            unsafe { mapping.virtual_start().as_ptr().add(mem::size_of::<PerformanceTableHeader>()) };
+        let mut remaining_length =
+            { header.length }.saturating_sub(mem::size_of::<PerformanceTableHeader>() as u32);
+
+        while remaining_length >= mem::size_of::<RecordHeader>() as u32 {
+            let record_header = unsafe { *(pointer as *const RecordHeader) };
+            if record_header.length < mem::size_of::<RecordHeader>() as u8
+                || record_header.length as u32 > remaining_length
+            {
+                return None;
+            }
+
+            if { record_header.record_type } == 0x0002
+                && record_header.length as usize >= mem::size_of::<BasicBootPerformanceRecord>()
+            {
+                let record = unsafe { *(pointer as *const BasicBootPerformanceRecord) };
+                return Some(BasicBootPerformance {
+                    reset_end: record.reset_end,
+                    os_loader_load_image_start: record.os_loader_load_image_start,
+                    os_loader_start_image_start: record.os_loader_start_image_start,
+                    exit_boot_services_entry: record.exit_boot_services_entry,
+                    exit_boot_services_exit: record.exit_boot_services_exit,
+                });
+            }
+
+            pointer = unsafe { pointer.add(record_header.length as usize) };
+            remaining_length -= record_header.length as u32;
+        }
+
+        None
+    }
+}
+
+pub struct FpdtRecordIter<'a> {
+    entries: EntryIter<'a, RecordHeader>,
+}
+
+/// A record in the FPDT, each of which points to a table of performance records. The addresses are physical.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PerformanceRecord {
+    /// Points to the Firmware Basic Boot Performance Table.
+    BasicBootPerformancePointer(u64),
+    /// Points to the S3 Performance Table, which records how long the last resume from S3 took.
+    S3PerformanceTablePointer(u64),
+}
+
+impl<'a> Iterator for FpdtRecordIter<'a> {
+    type Item = PerformanceRecord;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (record_type, record) in &mut self.entries {
+            match record_type {
+                0x0000 => {
+                    let record = unsafe { *entry_as::<PointerRecord>(record) };
+                    return Some(PerformanceRecord::BasicBootPerformancePointer(record.address));
+                }
+                0x0001 => {
+                    let record = unsafe { *entry_as::<PointerRecord>(record) };
+                    return Some(PerformanceRecord::S3PerformanceTablePointer(record.address));
+                }
+
+                /*
+                 * Other record types are reserved by the ACPI standard, or are specific to the platform. We skip
+                 * them.
+                 */
+                _ => (),
+            }
+        }
+
+        None
+    }
+}
+
+/// The times that the firmware reached each stage of the last boot. All of the times are in nanoseconds since the
+/// processor was reset, and are zero if the firmware didn't record them.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BasicBootPerformance {
+    /// When the firmware finished resetting the platform and started executing.
+    pub reset_end: u64,
+    /// When the OS loader was loaded into memory by the firmware.
+    pub os_loader_load_image_start: u64,
+    /// When the firmware started executing the OS loader.
+    pub os_loader_start_image_start: u64,
+    /// When the OS loader called `ExitBootServices`.
+    pub exit_boot_services_entry: u64,
+    /// When `ExitBootServices` returned to the OS loader.
+    pub exit_boot_services_exit: u64,
+}
+
+#[derive(Clone, Copy)]
+#[repr(C, packed)]
+struct RecordHeader {
+    record_type: u16,
+    length: u8,
+    revision: u8,
+}
+
+impl crate::EntryHeader for RecordHeader {
+    fn entry_type(&self) -> u16 {
+        self.record_type
+    }
+
+    fn length(&self) -> u32 {
+        self.length.into()
+    }
+}
+
+fn entry_minimum_length(header: &RecordHeader) -> usize {
+    match header.record_type {
+        0x0000 | 0x0001 => mem::size_of::<PointerRecord>(),
+        _ => mem::size_of::<RecordHeader>(),
+    }
+}
+
+#[derive(Clone, Copy)]
+#[repr(C, packed)]
+struct PointerRecord {
+    header: RecordHeader,
+    _reserved: u32,
+    address: u64,
+}
+
+/// The header of the tables that the FPDT points to. Unlike the header of an SDT, this doesn't have a checksum.
+#[derive(Clone, Copy)]
+#[repr(C, packed)]
+struct PerformanceTableHeader {
+    signature: [u8; 4],
+    length: u32,
+}
+
+#[derive(Clone, Copy)]
+#[repr(C, packed)]
+struct BasicBootPerformanceRecord {
+    header: RecordHeader,
+    _reserved: u32,
+    reset_end: u64,
+    os_loader_load_image_start: u64,
+    os_loader_start_image_start: u64,
+    exit_boot_services_entry: u64,
+    exit_boot_services_exit: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{make_sdt, table_from_bytes, TestMemory};
+    use alloc::vec::Vec;
+
+    fn pointer_record(record_type: u16, address: u64) -> Vec<u8> {
+        let mut record = Vec::new();
+        record.extend_from_slice(&record_type.to_le_bytes());
+        record.extend_from_slice(&[16, 1]);
+        record.extend_from_slice(&[0; 4]);
+        record.extend_from_slice(&address.to_le_bytes());
+        record
+    }
+
+    /// Build a Firmware Basic Boot Performance Table, with a platform-specific record before the boot performance
+    /// record.
+    fn make_fbpt() -> Vec<u8> {
+        let mut table = Vec::new();
+        table.extend_from_slice(b"FBPT");
+        table.extend_from_slice(&(8u32 + 8 + 48).to_le_bytes());
+        table.extend_from_slice(&0x3000u16.to_le_bytes());
+        table.extend_from_slice(&[8, 1]);
+        table.extend_from_slice(&[0xaa; 4]);
+        table.extend_from_slice(&0x0002u16.to_le_bytes());
+        table.extend_from_slice(&[48, 2]);
+        table.extend_from_slice(&[0; 4]);
+        for timestamp in &[1_250_000_000u64, 3_900_000_000, 3_950_000_000, 5_100_000_000, 5_100_200_000] {
+            table.extend_from_slice(&timestamp.to_le_bytes());
+        }
+        table
+    }
+
+    #[test]
+    fn test_records() {
+        let mut body = pointer_record(0x0000, 0x7ffe_1000);
+        body.extend(pointer_record(0x0001, 0x7ffe_2000));
+        let table = make_sdt(b"FPDT", 1, &body);
+        let fpdt = table_from_bytes::<Fpdt>(&table);
+
+        let records: Vec<PerformanceRecord> = fpdt.records().collect();
+        assert_eq!(
+            records,
+            [
+                PerformanceRecord::BasicBootPerformancePointer(0x7ffe_1000),
+                PerformanceRecord::S3PerformanceTablePointer(0x7ffe_2000)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_basic_boot_performance() {
+        let mut memory = TestMemory::new(0x7ffe_0000);
+        let fbpt = memory.add(&make_fbpt());
+        let s3pt = memory.add(b"S3PT\x08\0\0\0");
+        let mut body = pointer_record(0x0001, s3pt as u64);
+        body.extend(pointer_record(0x0000, fbpt as u64));
+        let table = make_sdt(b"FPDT", 1, &body);
+        let fpdt = table_from_bytes::<Fpdt>(&table);
+
+        let performance = unsafe { fpdt.basic_boot_performance(&memory.handler()) }.unwrap();
+        assert_eq!(performance.reset_end, 1_250_000_000);
+        assert_eq!(performance.os_loader_load_image_start, 3_900_000_000);
+        assert_eq!(performance.os_loader_start_image_start, 3_950_000_000);
+        assert_eq!(performance.exit_boot_services_entry, 5_100_000_000);
+        assert_eq!(performance.exit_boot_services_exit, 5_100_200_000);
+
+        // A FPDT that only points to the S3 Performance Table
+        let table = make_sdt(b"FPDT", 1, &pointer_record(0x0001, s3pt as u64));
+        let fpdt = table_from_bytes::<Fpdt>(&table);
+        assert_eq!(unsafe { fpdt.basic_boot_performance(&memory.handler()) }, None);
+    }
+}
@@ -0,0 +1,425 @@
+use crate::{
+    entry_as,
+    platform::interrupt::{Polarity, TriggerMode},
+    sdt::{SdtHeader, Signature},
+    AcpiTable,
+    EntryIter,
+};
+use bit_field::BitField;
+use core::{mem, slice};
+
+/// Represents the Generic Timer Description Table (GTDT). On ARM platforms, this describes the interrupts used by
+/// the per-processor architected timers, and the memory-mapped timer blocks and watchdogs available to the system.
+#[repr(C, packed)]
+pub struct Gtdt {
+    header: SdtHeader,
+    counter_control_base: u64,
+    _reserved: u32,
+    secure_el1_gsiv: u32,
+    secure_el1_flags: u32,
+    non_secure_el1_gsiv: u32,
+    non_secure_el1_flags: u32,
+    virtual_el1_gsiv: u32,
+    virtual_el1_flags: u32,
+    el2_gsiv: u32,
+    el2_flags: u32,
+    counter_read_base: u64,
+    platform_timer_count: u32,
+    platform_timer_offset: u32,
+    /*
+     * The following fields were added in revision 3 of the table (ACPI 6.3).
+     */
+    virtual_el2_gsiv: u32,
+    virtual_el2_flags: u32,
+}
+
+impl AcpiTable for Gtdt {
+    fn header(&self) -> &SdtHeader {
+        &self.header
+    }
+
+    /// The GTDT before revision 3 ends after the `platform_timer_offset` field.
+    fn minimum_length() -> usize {
+        96
+    }
+}
+
+/// Describes the interrupt of a timer.
+#[derive(Debug)]
+pub struct TimerInterrupt {
+    pub gsiv: u32,
+    pub trigger_mode: TriggerMode,
+    pub polarity: Polarity,
+    /// If true, the timer keeps running (and can wake the processor) in low-power states.
+    pub always_on: bool,
+}
+
+impl TimerInterrupt {
+    fn new(gsiv: u32, flags: u32) -> TimerInterrupt {
+        TimerInterrupt {
+            gsiv,
+            trigger_mode: if flags.get_bit(0) { TriggerMode::Edge } else { TriggerMode::Level },
+            polarity: if flags.get_bit(1) { Polarity::ActiveLow } else { Polarity::ActiveHigh },
+            always_on: flags.get_bit(2),
+        }
+    }
+}
+
+impl Gtdt {
+    /// The physical address of the `CNTControlBase` frame, or `0xffff_ffff_ffff_ffff` if it isn't provided (e.g.
+    /// because it's only accessible from the secure world).
+    pub fn counter_control_base(&self) -> u64 {
+        self.counter_control_base
+    }
+
+    /// The physical address of the `CNTReadBase` frame, or `0xffff_ffff_ffff_ffff` if it isn't provided.
+    pub fn counter_read_base(&self) -> u64 {
+        self.counter_read_base
+    }
+
+    /// The interrupt of the secure EL1 physical timer. The GSIV is `0` if the timer isn't available to the OS.
+    pub fn secure_el1_timer(&self) -> TimerInterrupt {
+        TimerInterrupt::new(self.secure_el1_gsiv, self.secure_el1_flags)
+    }
+
+    pub fn non_secure_el1_timer(&self) -> TimerInterrupt {
+        TimerInterrupt::new(self.non_secure_el1_gsiv, self.non_secure_el1_flags)
+    }
+
+    pub fn virtual_el1_timer(&self) -> TimerInterrupt {
+        TimerInterrupt::new(self.virtual_el1_gsiv, self.virtual_el1_flags)
+    }
+
+    pub fn el2_timer(&self) -> TimerInterrupt {
+        TimerInterrupt::new(self.el2_gsiv, self.el2_flags)
+    }
+
+    /// The interrupt of the virtual EL2 timer. This is only reported from revision 3 of the table, and so is `None`
+    /// for older tables.
+    pub fn virtual_el2_timer(&self) -> Option<TimerInterrupt> {
+        if self.header.revision >= 3 && self.header.length as usize >= mem::size_of::<Gtdt>() {
+            Some(TimerInterrupt::new(self.virtual_el2_gsiv, self.virtual_el2_flags))
+        } else {
+            None
+        }
+    }
+
+    pub fn platform_timers(&self) -> PlatformTimerIter<'_> {
+        let table_length = self.header.length;
+        let offset = self.platform_timer_offset;
+
+        /*
+         * When there are no platform timers, some firmware sets the offset to `0`.
+         */
+        let remaining_length =
+            if offset as usize >= mem::size_of::<SdtHeader>() { table_length.saturating_sub(offset) } else { 0 };
+
+        PlatformTimerIter {
+            entries: unsafe {
+                EntryIter::new(
+                    Signature::GTDT,
+                    (self as *const Gtdt as *const u8).add(offset as usize),
+                    remaining_length,
+                    entry_minimum_length,
+                )
+            },
+            remaining_timers: self.platform_timer_count,
+        }
+    }
+}
+
+pub struct PlatformTimerIter<'a> {
+    entries: EntryIter<'a, PlatformTimerHeader>,
+    remaining_timers: u32,
+}
+
+pub enum PlatformTimer<'a> {
+    GtBlock(&'a GtBlock),
+    Watchdog(&'a ArmWatchdog),
+}
+
+impl<'a> Iterator for PlatformTimerIter<'a> {
+    type Item = PlatformTimer<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.remaining_timers > 0 {
+            let (timer_type, entry) = self.entries.next()?;
+            self.remaining_timers -= 1;
+
+            match timer_type {
+                0x0 => return Some(PlatformTimer::GtBlock(unsafe { entry_as(entry) })),
+                0x1 => return Some(PlatformTimer::Watchdog(unsafe { entry_as(entry) })),
+
+                /*
+                 * Other timer types are reserved by the ACPI standard. We skip them.
+                 */
+                _ => (),
+            }
+        }
+
+        None
+    }
+}
+
+#[derive(Clone, Copy)]
+#[repr(C, packed)]
+struct PlatformTimerHeader {
+    timer_type: u8,
+    length: u16,
+}
+
+impl crate::EntryHeader for PlatformTimerHeader {
+    fn entry_type(&self) -> u16 {
+        self.timer_type.into()
+    }
+
+    fn length(&self) -> u32 {
+        self.length.into()
+    }
+}
+
+fn entry_minimum_length(header: &PlatformTimerHeader) -> usize {
+    match header.timer_type {
+        0x0 => mem::size_of::<GtBlock>(),
+        0x1 => mem::size_of::<ArmWatchdog>(),
+        _ => mem::size_of::<PlatformTimerHeader>(),
+    }
+}
+
+/// A memory-mapped Generic Timer block. This contains up to eight timer frames, each of which can be used as a
+/// system timer.
+#[repr(C, packed)]
+pub struct GtBlock {
+    header: PlatformTimerHeader,
+    _reserved: u8,
+    control_base: u64,
+    timer_count: u32,
+    timer_offset: u32,
+}
+
+impl GtBlock {
+    /// The physical address of the block's `CNTCTLBase` frame.
+    pub fn control_base(&self) -> u64 {
+        self.control_base
+    }
+
+    pub fn frames(&self) -> &[GtBlockTimerFrame] {
+        /*
+         * A block can contain at most eight frames. We also make sure the frames claimed by the block fit within
+         * it, and ignore them if not.
+         */
+        let timer_count = usize::min(self.timer_count as usize, 8);
+        let frames_end =
+            (self.timer_offset as usize).saturating_add(timer_count * mem::size_of::<GtBlockTimerFrame>());
+        if timer_count == 0 || frames_end > self.header.length as usize {
+            return &[];
+        }
+
+        unsafe {
+            slice::from_raw_parts(
+                (self as *const GtBlock as *const u8).add(self.timer_offset as usize) as *const GtBlockTimerFrame,
+                timer_count,
+            )
+        }
+    }
+}
+
+#[repr(C, packed)]
+pub struct GtBlockTimerFrame {
+    frame_number: u8,
+    _reserved: [u8; 3],
+    base_address: u64,
+    el0_base_address: u64,
+    physical_timer_gsiv: u32,
+    physical_timer_flags: u32,
+    virtual_timer_gsiv: u32,
+    virtual_timer_flags: u32,
+    common_flags: u32,
+}
+
+impl GtBlockTimerFrame {
+    /// The number of the frame within its block, from `0` to `7`.
+    pub fn frame_number(&self) -> u8 {
+        self.frame_number
+    }
+
+    /// The physical address of the frame's `CNTBaseN` frame.
+    pub fn base_address(&self) -> u64 {
+        self.base_address
+    }
+
+    /// The physical address of the frame's `CNTEL0BaseN` frame, or `0xffff_ffff_ffff_ffff` if it isn't
+    /// implemented.
+    pub fn el0_base_address(&self) -> u64 {
+        self.el0_base_address
+    }
+
+    pub fn physical_timer(&self) -> TimerInterrupt {
+        TimerInterrupt {
+            always_on: self.is_always_on(),
+            ..TimerInterrupt::new(self.physical_timer_gsiv, { self.physical_timer_flags }.get_bits(0..2))
+        }
+    }
+
+    /// The interrupt of the frame's virtual timer. The GSIV is `0` if the frame doesn't have a virtual timer.
+    pub fn virtual_timer(&self) -> TimerInterrupt {
+        TimerInterrupt {
+            always_on: self.is_always_on(),
+            ..TimerInterrupt::new(self.virtual_timer_gsiv, { self.virtual_timer_flags }.get_bits(0..2))
+        }
+    }
+
+    pub fn is_secure(&self) -> bool {
+        { self.common_flags }.get_bit(0)
+    }
+
+    pub fn is_always_on(&self) -> bool {
+        { self.common_flags }.get_bit(1)
+    }
+}
+
+/// An SBSA Generic Watchdog.
+#[repr(C, packed)]
+pub struct ArmWatchdog {
+    header: PlatformTimerHeader,
+    _reserved: u8,
+    refresh_frame_address: u64,
+    control_frame_address: u64,
+    gsiv: u32,
+    flags: u32,
+}
+
+impl ArmWatchdog {
+    pub fn refresh_frame_address(&self) -> u64 {
+        self.refresh_frame_address
+    }
+
+    pub fn control_frame_address(&self) -> u64 {
+        self.control_frame_address
+    }
+
+    pub fn interrupt(&self) -> TimerInterrupt {
+        TimerInterrupt { always_on: false, ..TimerInterrupt::new(self.gsiv, { self.flags }.get_bits(0..2)) }
+    }
+
+    pub fn is_secure(&self) -> bool {
+        { self.flags }.get_bit(2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_utils::{make_sdt, make_xsdt, table_from_bytes, TestMemory},
+        AcpiTables,
+    };
+    use alloc::vec::Vec;
+
+    /// A revision 3 GTDT with the architected timers on their usual PPIs, a GT block with two frames, and a
+    /// watchdog.
+    fn make_gtdt() -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&u64::MAX.to_le_bytes());
+        body.extend_from_slice(&[0; 4]);
+        for (gsiv, flags) in [(29u32, 0b100u32), (30, 0b100), (27, 0b100), (26, 0b100)].iter() {
+            body.extend_from_slice(&gsiv.to_le_bytes());
+            body.extend_from_slice(&flags.to_le_bytes());
+        }
+        body.extend_from_slice(&u64::MAX.to_le_bytes());
+        body.extend_from_slice(&2u32.to_le_bytes());
+        body.extend_from_slice(&104u32.to_le_bytes());
+        body.extend_from_slice(&28u32.to_le_bytes());
+        body.extend_from_slice(&0b100u32.to_le_bytes());
+
+        // GT block
+        body.extend_from_slice(&[0x0, 100, 0, 0]);
+        body.extend_from_slice(&0x2a81_0000u64.to_le_bytes());
+        body.extend_from_slice(&2u32.to_le_bytes());
+        body.extend_from_slice(&20u32.to_le_bytes());
+        for (frame_number, base) in [(0u8, 0x2a82_0000u64), (1, 0x2a83_0000)].iter() {
+            body.extend_from_slice(&[*frame_number, 0, 0, 0]);
+            body.extend_from_slice(&base.to_le_bytes());
+            body.extend_from_slice(&u64::MAX.to_le_bytes());
+            body.extend_from_slice(&(92 + u32::from(*frame_number)).to_le_bytes());
+            body.extend_from_slice(&0b00u32.to_le_bytes());
+            body.extend_from_slice(&0u32.to_le_bytes());
+            body.extend_from_slice(&0u32.to_le_bytes());
+            body.extend_from_slice(&0b10u32.to_le_bytes());
+        }
+
+        // Watchdog
+        body.extend_from_slice(&[0x1, 28, 0, 0]);
+        body.extend_from_slice(&0x2a44_0000u64.to_le_bytes());
+        body.extend_from_slice(&0x2a45_0000u64.to_le_bytes());
+        body.extend_from_slice(&93u32.to_le_bytes());
+        body.extend_from_slice(&0b001u32.to_le_bytes());
+
+        make_sdt(b"GTDT", 3, &body)
+    }
+
+    #[test]
+    fn test_architected_timers() {
+        let table = make_gtdt();
+        let gtdt = table_from_bytes::<Gtdt>(&table);
+
+        assert_eq!(gtdt.counter_control_base(), u64::MAX);
+        assert_eq!(gtdt.non_secure_el1_timer().gsiv, 30);
+        assert!(gtdt.non_secure_el1_timer().always_on);
+        assert!(matches!(gtdt.non_secure_el1_timer().trigger_mode, TriggerMode::Level));
+        assert!(matches!(gtdt.non_secure_el1_timer().polarity, Polarity::ActiveHigh));
+        assert_eq!(gtdt.virtual_el1_timer().gsiv, 27);
+        assert_eq!(gtdt.el2_timer().gsiv, 26);
+        assert_eq!(gtdt.virtual_el2_timer().map(|timer| timer.gsiv), Some(28));
+    }
+
+    #[test]
+    fn test_platform_timers() {
+        let table = make_gtdt();
+        let gtdt = table_from_bytes::<Gtdt>(&table);
+        let timers: Vec<PlatformTimer> = gtdt.platform_timers().collect();
+        assert_eq!(timers.len(), 2);
+
+        match timers[0] {
+            PlatformTimer::GtBlock(block) => {
+                assert_eq!(block.control_base(), 0x2a81_0000);
+                let frames = block.frames();
+                assert_eq!(frames.len(), 2);
+                assert_eq!(frames[1].frame_number(), 1);
+                assert_eq!(frames[1].base_address(), 0x2a83_0000);
+                assert_eq!(frames[1].physical_timer().gsiv, 93);
+                assert!(frames[1].physical_timer().always_on);
+                assert!(!frames[1].is_secure());
+            }
+            _ => panic!("Expected a GT block"),
+        }
+        match timers[1] {
+            PlatformTimer::Watchdog(watchdog) => {
+                assert_eq!(watchdog.refresh_frame_address(), 0x2a44_0000);
+                assert_eq!(watchdog.interrupt().gsiv, 93);
+                assert!(matches!(watchdog.interrupt().trigger_mode, TriggerMode::Edge));
+                assert!(!watchdog.is_secure());
+            }
+            _ => panic!("Expected a watchdog"),
+        }
+    }
+
+    #[test]
+    fn test_revision_2() {
+        // A revision 2 table has no virtual EL2 timer, and so is 96 bytes long if it has no platform timers
+        let mut body = make_gtdt()[36..96].to_vec();
+        body[52..56].copy_from_slice(&0u32.to_le_bytes());
+        body[56..60].copy_from_slice(&0u32.to_le_bytes());
+        let mut memory = TestMemory::new(0x7ff0_0000);
+        let gtdt = memory.add(&make_sdt(b"GTDT", 2, &body));
+        let xsdt = memory.add(&make_xsdt(&[gtdt]));
+
+        let tables = unsafe { AcpiTables::from_rsdt(memory.handler(), 2, xsdt) }.unwrap();
+        let gtdt = unsafe { tables.get_sdt::<Gtdt>(Signature::GTDT) }.unwrap().unwrap();
+        assert_eq!({ gtdt.header().length }, 96);
+        assert_eq!(gtdt.non_secure_el1_timer().gsiv, 30);
+        assert_eq!(gtdt.el2_timer().gsiv, 26);
+        assert!(gtdt.virtual_el2_timer().is_none());
+        assert_eq!(gtdt.platform_timers().count(), 0);
+    }
+}
@@ -0,0 +1,872 @@
+use crate::{
+    parse_warning,
+    platform::address::{GenericAddress, RawGenericAddress},
+    sdt::{SdtHeader, Signature},
+    AcpiError,
+    AcpiTable,
+    ParseWarning,
+};
+use bit_field::BitField;
+use core::{marker::PhantomData, mem, slice};
+
+/// Represents the Hardware Error Source Table (HEST). This describes the platform's sources of hardware errors -
+/// machine checks, PCIe Advanced Error Reporting, and firmware-reported (generic) errors - and how the OS is
+/// notified of each.
+#[repr(C, packed)]
+pub struct Hest {
+    header: SdtHeader,
+    error_source_count: u32,
+}
+
+impl AcpiTable for Hest {
+    fn header(&self) -> &SdtHeader {
+        &self.header
+    }
+}
+
+impl Hest {
+    pub fn error_source_count(&self) -> u32 {
+        self.error_source_count
+    }
+
+    pub fn error_sources(&self) -> ErrorSourceIter<'_> {
+        ErrorSourceIter {
+            pointer: unsafe { (self as *const Hest as *const u8).add(mem::size_of::<Hest>()) },
+            remaining_length: self.header.length.saturating_sub(mem::size_of::<Hest>() as u32),
+            remaining_sources: self.error_source_count,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+pub struct ErrorSourceIter<'a> {
+    pointer: *const u8,
+    remaining_length: u32,
+    remaining_sources: u32,
+    _phantom: PhantomData<&'a ()>,
+}
+
+pub enum ErrorSource<'a> {
+    MachineCheck(&'a MachineCheck),
+    CorrectedMachineCheck(&'a CorrectedMachineCheck),
+    Nmi(&'a NmiErrorSource),
+    PcieRootPortAer(&'a PcieRootPortAer),
+    PcieDeviceAer(&'a PcieAer),
+    PcieBridgeAer(&'a PcieBridgeAer),
+    GenericHardwareErrorSource(&'a GenericHardwareErrorSource),
+    GenericHardwareErrorSourceV2(&'a GenericHardwareErrorSourceV2),
+    /// A source of deferred machine check errors. This has the same layout as a corrected machine check source.
+    DeferredMachineCheck(&'a CorrectedMachineCheck),
+}
+
+impl<'a> Iterator for ErrorSourceIter<'a> {
+    type Item = ErrorSource<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining_sources == 0 || self.remaining_length < mem::size_of::<u16>() as u32 {
+            return None;
+        }
+
+        /*
+         * Error sources don't have a length field: the length of each is implied by its type (and, for the
+         * machine check sources, by its number of banks). This means we can't skip types we don't know about, and
+         * so have to stop at the first one.
+         */
+        let source_type = unsafe { (self.pointer as *const u16).read_unaligned() };
+        let fixed_length = match source_type {
+            0 => mem::size_of::<MachineCheck>(),
+            1 | 11 => mem::size_of::<CorrectedMachineCheck>(),
+            2 => mem::size_of::<NmiErrorSource>(),
+            6 => mem::size_of::<PcieRootPortAer>(),
+            7 => mem::size_of::<PcieAer>(),
+            8 => mem::size_of::<PcieBridgeAer>(),
+            9 => mem::size_of::<GenericHardwareErrorSource>(),
+            10 => mem::size_of::<GenericHardwareErrorSourceV2>(),
+            _ => {
+                self.remaining_length = 0;
+                return None;
+            }
+        };
+
+        let length = if fixed_length as u32 > self.remaining_length {
+            fixed_length
+        } else {
+            let num_banks = match source_type {
+                0 => unsafe { (*(self.pointer as *const MachineCheck)).num_hardware_banks },
+                1 | 11 => unsafe { (*(self.pointer as *const CorrectedMachineCheck)).num_hardware_banks },
+                _ => 0,
+            };
+            fixed_length + num_banks as usize * mem::size_of::<MachineCheckBank>()
+        };
+        if length as u32 > self.remaining_length {
+            parse_warning(ParseWarning::InvalidEntryLength {
+                signature: Signature::HEST,
+                entry_type: source_type,
+                length: length as u32,
+            });
+            self.remaining_length = 0;
+            return None;
+        }
+
+        let source_pointer = self.pointer;
+        self.pointer = unsafe { self.pointer.add(length) };
+        self.remaining_length -= length as u32;
+        self.remaining_sources -= 1;
+
+        Some(unsafe {
+            match source_type {
+                0 => ErrorSource::MachineCheck(&*(source_pointer as *const _)),
+                1 => ErrorSource::CorrectedMachineCheck(&*(source_pointer as *const _)),
+                2 => ErrorSource::Nmi(&*(source_pointer as *const _)),
+                6 => ErrorSource::PcieRootPortAer(&*(source_pointer as *const _)),
+                7 => ErrorSource::PcieDeviceAer(&*(source_pointer as *const _)),
+                8 => ErrorSource::PcieBridgeAer(&*(source_pointer as *const _)),
+                9 => ErrorSource::GenericHardwareErrorSource(&*(source_pointer as *const _)),
+                10 => ErrorSource::GenericHardwareErrorSourceV2(&*(source_pointer as *const _)),
+                11 => ErrorSource::DeferredMachineCheck(&*(source_pointer as *const _)),
+                _ => unreachable!(),
+            }
+        })
+    }
+}
+
+/// How the OS is notified of errors from an error source.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Notification {
+    /// The OS should poll the error source every `poll_interval` milliseconds.
+    Polled {
+        poll_interval: u32,
+    },
+    ExternalInterrupt {
+        vector: u32,
+    },
+    LocalInterrupt {
+        vector: u32,
+    },
+    Sci,
+    Nmi,
+    /// A Corrected Machine Check Interrupt.
+    Cmci,
+    /// A Machine Check Exception.
+    Mce,
+    /// The error is signalled through a GPIO, using the Generic Event Device.
+    GpioSignal,
+    /// An Armv8 Synchronous External Abort.
+    ArmSea,
+    /// An Armv8 SError Interrupt.
+    ArmSei,
+    /// The error is signalled through the given Global System Interrupt.
+    Gsiv {
+        gsiv: u32,
+    },
+    /// The error is signalled through the given Software Delegated Exception event.
+    SoftwareDelegatedException {
+        event: u32,
+    },
+    Reserved(u8),
+}
+
+/// The Hardware Error Notification structure, which describes how the OS is notified of errors from an error
+/// source, and the thresholds at which it should switch to polling for them or raise them.
+#[derive(Clone, Copy, Debug)]
+#[repr(C, packed)]
+pub struct HardwareErrorNotification {
+    notification_type: u8,
+    length: u8,
+    configuration_write_enable: u16,
+    poll_interval: u32,
+    vector: u32,
+    switch_to_polling_threshold_value: u32,
+    switch_to_polling_threshold_window: u32,
+    error_threshold_value: u32,
+    error_threshold_window: u32,
+}
+
+impl HardwareErrorNotification {
+    pub fn notification(&self) -> Notification {
+        match self.notification_type {
+            0 => Notification::Polled { poll_interval: self.poll_interval },
+            1 => Notification::ExternalInterrupt { vector: self.vector },
+            2 => Notification::LocalInterrupt { vector: self.vector },
+            3 => Notification::Sci,
+            4 => Notification::Nmi,
+            5 => Notification::Cmci,
+            6 => Notification::Mce,
+            7 => Notification::GpioSignal,
+            8 => Notification::ArmSea,
+            9 => Notification::ArmSei,
+            10 => Notification::Gsiv { gsiv: self.vector },
+            11 => Notification::SoftwareDelegatedException { event: self.vector },
+            other => Notification::Reserved(other),
+        }
+    }
+
+    /// The raw mask of the fields of this structure that the OS is allowed to change.
+    pub fn configuration_write_enable(&self) -> u16 {
+        self.configuration_write_enable
+    }
+
+    /// The number of errors, within `switch_to_polling_threshold_window` milliseconds, after which the OS should
+    /// switch from interrupt-driven notification to polling.
+    pub fn switch_to_polling_threshold_value(&self) -> u32 {
+        self.switch_to_polling_threshold_value
+    }
+
+    pub fn switch_to_polling_threshold_window(&self) -> u32 {
+        self.switch_to_polling_threshold_window
+    }
+
+    /// The number of errors, within `error_threshold_window` milliseconds, after which the OS should report an
+    /// error.
+    pub fn error_threshold_value(&self) -> u32 {
+        self.error_threshold_value
+    }
+
+    pub fn error_threshold_window(&self) -> u32 {
+        self.error_threshold_window
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StatusDataFormat {
+    Ia32,
+    Intel64,
+    Amd64,
+    Reserved(u8),
+}
+
+/// Describes a bank of machine check registers.
+#[repr(C, packed)]
+pub struct MachineCheckBank {
+    bank_number: u8,
+    clear_status_on_initialization: u8,
+    status_data_format: u8,
+    _reserved: u8,
+    control_register_msr: u32,
+    control_init_data: u64,
+    status_register_msr: u32,
+    address_register_msr: u32,
+    misc_register_msr: u32,
+}
+
+impl MachineCheckBank {
+    pub fn bank_number(&self) -> u8 {
+        self.bank_number
+    }
+
+    /// If true, the OS should clear the bank's status register when it initializes.
+    pub fn clear_status_on_initialization(&self) -> bool {
+        self.clear_status_on_initialization != 0
+    }
+
+    pub fn status_data_format(&self) -> StatusDataFormat {
+        match self.status_data_format {
+            0 => StatusDataFormat::Ia32,
+            1 => StatusDataFormat::Intel64,
+            2 => StatusDataFormat::Amd64,
+            other => StatusDataFormat::Reserved(other),
+        }
+    }
+
+    pub fn control_register_msr(&self) -> u32 {
+        self.control_register_msr
+    }
+
+    /// The value the OS should write to the bank's control register when it initializes.
+    pub fn control_init_data(&self) -> u64 {
+        self.control_init_data
+    }
+
+    pub fn status_register_msr(&self) -> u32 {
+        self.status_register_msr
+    }
+
+    pub fn address_register_msr(&self) -> u32 {
+        self.address_register_msr
+    }
+
+    pub fn misc_register_msr(&self) -> u32 {
+        self.misc_register_msr
+    }
+}
+
+/*
+ * Most error sources share the same flags.
+ */
+fn firmware_first(flags: u8) -> bool {
+    flags.get_bit(0)
+}
+
+fn global(flags: u8) -> bool {
+    flags.get_bit(1)
+}
+
+fn ghes_assist(flags: u8) -> bool {
+    flags.get_bit(2)
+}
+
+/// Get the banks that follow a machine check error source.
+///
+/// ### Safety
+/// `source` must point to a machine check error source that is followed by `count` banks.
+unsafe fn banks<T>(source: &T, count: u8) -> &[MachineCheckBank] {
+    unsafe {
+        slice::from_raw_parts(
+            (source as *const T as *const u8).add(mem::size_of::<T>()) as *const MachineCheckBank,
+            count as usize,
+        )
+    }
+}
+
+/// An IA-32 Machine Check Exception error source.
+#[repr(C, packed)]
+pub struct MachineCheck {
+    source_type: u16,
+    source_id: u16,
+    _reserved1: u16,
+    flags: u8,
+    enabled: u8,
+    num_records_to_preallocate: u32,
+    max_sections_per_record: u32,
+    global_capability_init_data: u64,
+    global_control_init_data: u64,
+    num_hardware_banks: u8,
+    _reserved2: [u8; 7],
+    // Followed by `num_hardware_banks` banks
+}
+
+impl MachineCheck {
+    pub fn source_id(&self) -> u16 {
+        self.source_id
+    }
+
+    pub fn firmware_first(&self) -> bool {
+        firmware_first(self.flags)
+    }
+
+    pub fn ghes_assist(&self) -> bool {
+        ghes_assist(self.flags)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled != 0
+    }
+
+    pub fn num_records_to_preallocate(&self) -> u32 {
+        self.num_records_to_preallocate
+    }
+
+    pub fn max_sections_per_record(&self) -> u32 {
+        self.max_sections_per_record
+    }
+
+    /// The value of the `IA32_MCG_CAP` MSR, which the OS should use to initialize machine checks.
+    pub fn global_capability_init_data(&self) -> u64 {
+        self.global_capability_init_data
+    }
+
+    /// The value the OS should write to the `IA32_MCG_CTL` MSR.
+    pub fn global_control_init_data(&self) -> u64 {
+        self.global_control_init_data
+    }
+
+    pub fn banks(&self) -> &[MachineCheckBank] {
+        unsafe { banks(self, self.num_hardware_banks) }
+    }
+}
+
+/// An IA-32 Corrected Machine Check error source. This layout is also used by IA-32 Deferred Machine Check error
+/// sources.
+#[repr(C, packed)]
+pub struct CorrectedMachineCheck {
+    source_type: u16,
+    source_id: u16,
+    _reserved1: u16,
+    flags: u8,
+    enabled: u8,
+    num_records_to_preallocate: u32,
+    max_sections_per_record: u32,
+    notification: HardwareErrorNotification,
+    num_hardware_banks: u8,
+    _reserved2: [u8; 3],
+    // Followed by `num_hardware_banks` banks
+}
+
+impl CorrectedMachineCheck {
+    pub fn source_id(&self) -> u16 {
+        self.source_id
+    }
+
+    pub fn firmware_first(&self) -> bool {
+        firmware_first(self.flags)
+    }
+
+    pub fn ghes_assist(&self) -> bool {
+        ghes_assist(self.flags)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled != 0
+    }
+
+    pub fn num_records_to_preallocate(&self) -> u32 {
+        self.num_records_to_preallocate
+    }
+
+    pub fn max_sections_per_record(&self) -> u32 {
+        self.max_sections_per_record
+    }
+
+    pub fn notification(&self) -> HardwareErrorNotification {
+        self.notification
+    }
+
+    pub fn banks(&self) -> &[MachineCheckBank] {
+        unsafe { banks(self, self.num_hardware_banks) }
+    }
+}
+
+/// An error source that signals errors with an NMI.
+#[repr(C, packed)]
+pub struct NmiErrorSource {
+    source_type: u16,
+    source_id: u16,
+    _reserved: u32,
+    num_records_to_preallocate: u32,
+    max_sections_per_record: u32,
+    max_raw_data_length: u32,
+}
+
+impl NmiErrorSource {
+    pub fn source_id(&self) -> u16 {
+        self.source_id
+    }
+
+    pub fn num_records_to_preallocate(&self) -> u32 {
+        self.num_records_to_preallocate
+    }
+
+    pub fn max_sections_per_record(&self) -> u32 {
+        self.max_sections_per_record
+    }
+
+    pub fn max_raw_data_length(&self) -> u32 {
+        self.max_raw_data_length
+    }
+}
+
+/// A PCIe device that supports Advanced Error Reporting. This is also the first part of the root port and bridge
+/// AER error sources.
+#[repr(C, packed)]
+pub struct PcieAer {
+    source_type: u16,
+    source_id: u16,
+    _reserved1: u16,
+    flags: u8,
+    enabled: u8,
+    num_records_to_preallocate: u32,
+    max_sections_per_record: u32,
+    bus: u32,
+    device: u16,
+    function: u16,
+    device_control: u16,
+    _reserved2: u16,
+    uncorrectable_error_mask: u32,
+    uncorrectable_error_severity: u32,
+    correctable_error_mask: u32,
+    advanced_error_capabilities_and_control: u32,
+}
+
+impl PcieAer {
+    pub fn source_id(&self) -> u16 {
+        self.source_id
+    }
+
+    pub fn firmware_first(&self) -> bool {
+        firmware_first(self.flags)
+    }
+
+    /// If true, the settings of this source apply to every device of its type, and its `segment_bus`, `device`
+    /// and `function` should be ignored.
+    pub fn global(&self) -> bool {
+        global(self.flags)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled != 0
+    }
+
+    pub fn num_records_to_preallocate(&self) -> u32 {
+        self.num_records_to_preallocate
+    }
+
+    pub fn max_sections_per_record(&self) -> u32 {
+        self.max_sections_per_record
+    }
+
+    /// The PCI segment (in bits `8..24`) and bus (in bits `0..8`) of the device.
+    pub fn segment_bus(&self) -> u32 {
+        self.bus
+    }
+
+    pub fn device(&self) -> u16 {
+        self.device
+    }
+
+    pub fn function(&self) -> u16 {
+        self.function
+    }
+
+    /// The value the OS should write to the Device Control register of the device.
+    pub fn device_control(&self) -> u16 {
+        self.device_control
+    }
+
+    pub fn uncorrectable_error_mask(&self) -> u32 {
+        self.uncorrectable_error_mask
+    }
+
+    pub fn uncorrectable_error_severity(&self) -> u32 {
+        self.uncorrectable_error_severity
+    }
+
+    pub fn correctable_error_mask(&self) -> u32 {
+        self.correctable_error_mask
+    }
+
+    pub fn advanced_error_capabilities_and_control(&self) -> u32 {
+        self.advanced_error_capabilities_and_control
+    }
+}
+
+/// A PCIe root port that supports Advanced Error Reporting.
+#[repr(C, packed)]
+pub struct PcieRootPortAer {
+    aer: PcieAer,
+    root_error_command: u32,
+}
+
+impl PcieRootPortAer {
+    pub fn aer(&self) -> &PcieAer {
+        &self.aer
+    }
+
+    /// The value the OS should write to the Root Error Command register of the root port.
+    pub fn root_error_command(&self) -> u32 {
+        self.root_error_command
+    }
+}
+
+/// A PCIe bridge that supports Advanced Error Reporting.
+#[repr(C, packed)]
+pub struct PcieBridgeAer {
+    aer: PcieAer,
+    secondary_uncorrectable_error_mask: u32,
+    secondary_uncorrectable_error_severity: u32,
+    secondary_advanced_error_capabilities_and_control: u32,
+}
+
+impl PcieBridgeAer {
+    pub fn aer(&self) -> &PcieAer {
+        &self.aer
+    }
+
+    pub fn secondary_uncorrectable_error_mask(&self) -> u32 {
+        self.secondary_uncorrectable_error_mask
+    }
+
+    pub fn secondary_uncorrectable_error_severity(&self) -> u32 {
+        self.secondary_uncorrectable_error_severity
+    }
+
+    pub fn secondary_advanced_error_capabilities_and_control(&self) -> u32 {
+        self.secondary_advanced_error_capabilities_and_control
+    }
+}
+
+/// A Generic Hardware Error Source (GHES), which reports errors detected by the firmware through a Generic Error
+/// Status Block at `error_status_address`.
+#[repr(C, packed)]
+pub struct GenericHardwareErrorSource {
+    source_type: u16,
+    source_id: u16,
+    related_source_id: u16,
+    flags: u8,
+    enabled: u8,
+    num_records_to_preallocate: u32,
+    max_sections_per_record: u32,
+    max_raw_data_length: u32,
+    error_status_address: RawGenericAddress,
+    notification: HardwareErrorNotification,
+    error_status_block_length: u32,
+}
+
+impl GenericHardwareErrorSource {
+    pub fn source_id(&self) -> u16 {
+        self.source_id
+    }
+
+    /// The source ID of the error source this source is an alternative for (e.g. in firmware-first handling), or
+    /// `None` if it's an independent source.
+    pub fn related_source_id(&self) -> Option<u16> {
+        match self.related_source_id {
+            0xffff => None,
+            id => Some(id),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled != 0
+    }
+
+    pub fn num_records_to_preallocate(&self) -> u32 {
+        self.num_records_to_preallocate
+    }
+
+    pub fn max_sections_per_record(&self) -> u32 {
+        self.max_sections_per_record
+    }
+
+    pub fn max_raw_data_length(&self) -> u32 {
+        self.max_raw_data_length
+    }
+
+    /// The address of a register that holds the physical address of the source's Generic Error Status Block.
+    pub fn error_status_address(&self) -> Result<GenericAddress, AcpiError> {
+        GenericAddress::from_raw(self.error_status_address)
+    }
+
+    pub fn notification(&self) -> HardwareErrorNotification {
+        self.notification
+    }
+
+    pub fn error_status_block_length(&self) -> u32 {
+        self.error_status_block_length
+    }
+}
+
+/// Version 2 of the Generic Hardware Error Source, which adds a Read Ack register that the OS must write to once
+/// it has consumed an error, so the firmware knows it can reuse the Generic Error Status Block.
+#[repr(C, packed)]
+pub struct GenericHardwareErrorSourceV2 {
+    ghes: GenericHardwareErrorSource,
+    read_ack_register: RawGenericAddress,
+    read_ack_preserve: u64,
+    read_ack_write: u64,
+}
+
+impl GenericHardwareErrorSourceV2 {
+    pub fn ghes(&self) -> &GenericHardwareErrorSource {
+        &self.ghes
+    }
+
+    pub fn read_ack_register(&self) -> Result<GenericAddress, AcpiError> {
+        GenericAddress::from_raw(self.read_ack_register)
+    }
+
+    /// The bits of the Read Ack register that must be preserved when acknowledging an error.
+    pub fn read_ack_preserve(&self) -> u64 {
+        self.read_ack_preserve
+    }
+
+    /// The bits that must be set in the Read Ack register to acknowledge an error.
+    pub fn read_ack_write(&self) -> u64 {
+        self.read_ack_write
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        platform::address::AddressSpace,
+        test_utils::{make_sdt, table_from_bytes},
+    };
+    use alloc::vec::Vec;
+
+    fn notification_bytes(notification_type: u8, poll_interval: u32, vector: u32) -> Vec<u8> {
+        let mut notification = Vec::new();
+        notification.extend_from_slice(&[notification_type, 28]);
+        notification.extend_from_slice(&0u16.to_le_bytes());
+        notification.extend_from_slice(&poll_interval.to_le_bytes());
+        notification.extend_from_slice(&vector.to_le_bytes());
+        notification.extend_from_slice(&[0; 16]);
+        notification
+    }
+
+    fn bank_bytes(bank_number: u8) -> Vec<u8> {
+        let msr_base = 0x400 + 4 * bank_number as u32;
+        let mut bank = Vec::new();
+        bank.extend_from_slice(&[bank_number, 1, 1, 0]);
+        bank.extend_from_slice(&msr_base.to_le_bytes());
+        bank.extend_from_slice(&u64::MAX.to_le_bytes());
+        for offset in 1..4 {
+            bank.extend_from_slice(&(msr_base + offset).to_le_bytes());
+        }
+        bank
+    }
+
+    fn ghes_bytes(source_type: u16, source_id: u16, notification: Vec<u8>) -> Vec<u8> {
+        let mut ghes = Vec::new();
+        ghes.extend_from_slice(&source_type.to_le_bytes());
+        ghes.extend_from_slice(&source_id.to_le_bytes());
+        ghes.extend_from_slice(&0xffffu16.to_le_bytes());
+        ghes.extend_from_slice(&[0, 1]);
+        ghes.extend_from_slice(&1u32.to_le_bytes());
+        ghes.extend_from_slice(&1u32.to_le_bytes());
+        ghes.extend_from_slice(&0x1000u32.to_le_bytes());
+        ghes.extend_from_slice(&[0x00, 64, 0, 4]);
+        ghes.extend_from_slice(&(0x7e00_0000u64 + source_id as u64 * 8).to_le_bytes());
+        ghes.extend(notification);
+        ghes.extend_from_slice(&0x1000u32.to_le_bytes());
+        ghes
+    }
+
+    fn make_hest() -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&5u32.to_le_bytes());
+
+        // Machine check, with two banks
+        body.extend_from_slice(&0u16.to_le_bytes());
+        body.extend_from_slice(&0u16.to_le_bytes());
+        body.extend_from_slice(&0u16.to_le_bytes());
+        body.extend_from_slice(&[0, 1]);
+        body.extend_from_slice(&1u32.to_le_bytes());
+        body.extend_from_slice(&1u32.to_le_bytes());
+        body.extend_from_slice(&0x0c09u64.to_le_bytes());
+        body.extend_from_slice(&u64::MAX.to_le_bytes());
+        body.push(2);
+        body.extend_from_slice(&[0; 7]);
+        body.extend(bank_bytes(0));
+        body.extend(bank_bytes(1));
+
+        // Corrected machine check, polled every 5 seconds, with one bank
+        body.extend_from_slice(&1u16.to_le_bytes());
+        body.extend_from_slice(&1u16.to_le_bytes());
+        body.extend_from_slice(&0u16.to_le_bytes());
+        body.extend_from_slice(&[0b1, 1]);
+        body.extend_from_slice(&1u32.to_le_bytes());
+        body.extend_from_slice(&1u32.to_le_bytes());
+        body.extend(notification_bytes(0, 5000, 0));
+        body.push(1);
+        body.extend_from_slice(&[0; 3]);
+        body.extend(bank_bytes(2));
+
+        // Root port AER, applying to all root ports
+        body.extend_from_slice(&6u16.to_le_bytes());
+        body.extend_from_slice(&2u16.to_le_bytes());
+        body.extend_from_slice(&0u16.to_le_bytes());
+        body.extend_from_slice(&[0b10, 1]);
+        body.extend_from_slice(&1u32.to_le_bytes());
+        body.extend_from_slice(&1u32.to_le_bytes());
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body.extend_from_slice(&0u16.to_le_bytes());
+        body.extend_from_slice(&0u16.to_le_bytes());
+        body.extend_from_slice(&0x000fu16.to_le_bytes());
+        body.extend_from_slice(&0u16.to_le_bytes());
+        body.extend_from_slice(&0x0010_0000u32.to_le_bytes());
+        body.extend_from_slice(&0x0006_2030u32.to_le_bytes());
+        body.extend_from_slice(&0x0000_2000u32.to_le_bytes());
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body.extend_from_slice(&0x7u32.to_le_bytes());
+
+        // A GHES signalled by an SCI, and a GHESv2 signalled by GSIV 0x20
+        body.extend(ghes_bytes(9, 3, notification_bytes(3, 0, 0)));
+        body.extend(ghes_bytes(10, 4, notification_bytes(10, 0, 0x20)));
+        body.extend_from_slice(&[0x00, 64, 0, 4]);
+        body.extend_from_slice(&0x7e00_1000u64.to_le_bytes());
+        body.extend_from_slice(&(!0x1u64).to_le_bytes());
+        body.extend_from_slice(&0x1u64.to_le_bytes());
+
+        make_sdt(b"HEST", 1, &body)
+    }
+
+    #[test]
+    fn test_error_sources() {
+        let table = make_hest();
+        let hest = table_from_bytes::<Hest>(&table);
+        let sources: Vec<ErrorSource> = hest.error_sources().collect();
+        assert_eq!(sources.len(), 5);
+
+        match sources[0] {
+            ErrorSource::MachineCheck(machine_check) => {
+                assert!(machine_check.is_enabled());
+                assert_eq!(machine_check.global_capability_init_data(), 0x0c09);
+                let banks = machine_check.banks();
+                assert_eq!(banks.len(), 2);
+                assert_eq!(banks[1].bank_number(), 1);
+                assert_eq!(banks[1].status_data_format(), StatusDataFormat::Intel64);
+                assert_eq!(banks[1].control_register_msr(), 0x404);
+                assert_eq!(banks[1].status_register_msr(), 0x405);
+            }
+            _ => panic!("Expected a machine check source"),
+        }
+
+        match sources[1] {
+            ErrorSource::CorrectedMachineCheck(machine_check) => {
+                assert!(machine_check.firmware_first());
+                assert_eq!(
+                    machine_check.notification().notification(),
+                    Notification::Polled { poll_interval: 5000 }
+                );
+                assert_eq!(machine_check.banks().len(), 1);
+                assert_eq!(machine_check.banks()[0].address_register_msr(), 0x40a);
+            }
+            _ => panic!("Expected a corrected machine check source"),
+        }
+
+        match sources[2] {
+            ErrorSource::PcieRootPortAer(root_port) => {
+                assert!(root_port.aer().global());
+                assert!(!root_port.aer().firmware_first());
+                assert_eq!(root_port.aer().device_control(), 0x000f);
+                assert_eq!(root_port.aer().uncorrectable_error_severity(), 0x0006_2030);
+                assert_eq!(root_port.root_error_command(), 0x7);
+            }
+            _ => panic!("Expected a PCIe root port AER source"),
+        }
+
+        match sources[3] {
+            ErrorSource::GenericHardwareErrorSource(ghes) => {
+                assert_eq!(ghes.source_id(), 3);
+                assert_eq!(ghes.related_source_id(), None);
+                assert_eq!(ghes.notification().notification(), Notification::Sci);
+                assert_eq!(ghes.error_status_address().unwrap().address, 0x7e00_0018);
+            }
+            _ => panic!("Expected a GHES"),
+        }
+    }
+
+    #[test]
+    fn test_ghes_v2_registers() {
+        let table = make_hest();
+        let hest = table_from_bytes::<Hest>(&table);
+        let ghes = match hest.error_sources().last() {
+            Some(ErrorSource::GenericHardwareErrorSourceV2(ghes)) => ghes,
+            _ => panic!("Expected a GHESv2"),
+        };
+
+        assert_eq!(ghes.ghes().source_id(), 4);
+        assert_eq!(ghes.ghes().notification().notification(), Notification::Gsiv { gsiv: 0x20 });
+
+        let error_status = ghes.ghes().error_status_address().unwrap();
+        assert_eq!(error_status.address_space, AddressSpace::SystemMemory);
+        assert_eq!(error_status.address, 0x7e00_0020);
+
+        let read_ack = ghes.read_ack_register().unwrap();
+        assert_eq!(read_ack.address_space, AddressSpace::SystemMemory);
+        assert_eq!(read_ack.bit_width, 64);
+        assert_eq!(read_ack.address, 0x7e00_1000);
+        assert_eq!(ghes.read_ack_preserve(), !0x1);
+        assert_eq!(ghes.read_ack_write(), 0x1);
+    }
+
+    #[test]
+    fn test_banks_past_end_of_table() {
+        let mut table = make_hest();
+        table[36..40].copy_from_slice(&1u32.to_le_bytes());
+        table[40 + 32] = 200; // Claim more banks than fit in the table
+        let hest = table_from_bytes::<Hest>(&table);
+        assert_eq!(hest.error_sources().count(), 0);
+    }
+}
@@ -0,0 +1,549 @@
+use crate::{
+    entry_as,
+    sdt::{SdtHeader, Signature},
+    AcpiTable,
+    EntryIter,
+};
+use bit_field::BitField;
+use core::{mem, ptr};
+
+/// Represents the Heterogeneous Memory Attribute Table (HMAT). This describes the performance of memory as seen
+/// from each initiator (e.g. a processor) - its latency and bandwidth - and the memory-side caches in front of it.
+/// Where the SLIT only gives relative distances, the HMAT gives absolute values keyed by proximity domain.
+///
+/// This only supports the layout used since ACPI 6.3 (revision 2 of the table).
+#[repr(C, packed)]
+pub struct Hmat {
+    header: SdtHeader,
+    _reserved: u32,
+}
+
+impl AcpiTable for Hmat {
+    fn header(&self) -> &SdtHeader {
+        &self.header
+    }
+}
+
+impl Hmat {
+    pub fn structures(&self) -> HmatStructureIter<'_> {
+        HmatStructureIter {
+            entries: unsafe {
+                EntryIter::new(
+                    Signature::HMAT,
+                    (self as *const Hmat as *const u8).add(mem::size_of::<Hmat>()),
+                    self.header.length.saturating_sub(mem::size_of::<Hmat>() as u32),
+                    entry_minimum_length,
+                )
+            },
+        }
+    }
+}
+
+pub struct HmatStructureIter<'a> {
+    entries: EntryIter<'a, HmatStructureHeader>,
+}
+
+pub enum HmatStructure<'a> {
+    MemoryProximityDomainAttributes(&'a MemoryProximityDomainAttributes),
+    LatencyBandwidthInfo(&'a LatencyBandwidthInfo),
+    MemorySideCacheInfo(&'a MemorySideCacheInfo),
+}
+
+impl<'a> Iterator for HmatStructureIter<'a> {
+    type Item = HmatStructure<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (entry_type, entry) in &mut self.entries {
+            let structure = unsafe {
+                match entry_type {
+                    0x0 => HmatStructure::MemoryProximityDomainAttributes(entry_as(entry)),
+                    0x1 => HmatStructure::LatencyBandwidthInfo(entry_as(entry)),
+                    0x2 => HmatStructure::MemorySideCacheInfo(entry_as(entry)),
+
+                    /*
+                     * Other structure types are reserved by the ACPI standard. We skip them.
+                     */
+                    _ => continue,
+                }
+            };
+            return Some(structure);
+        }
+
+        None
+    }
+}
+
+#[derive(Clone, Copy)]
+#[repr(C, packed)]
+struct HmatStructureHeader {
+    structure_type: u16,
+    _reserved: u16,
+    length: u32,
+}
+
+impl crate::EntryHeader for HmatStructureHeader {
+    fn entry_type(&self) -> u16 {
+        self.structure_type
+    }
+
+    fn length(&self) -> u32 {
+        self.length
+    }
+}
+
+fn entry_minimum_length(header: &HmatStructureHeader) -> usize {
+    match header.structure_type {
+        0x0 => mem::size_of::<MemoryProximityDomainAttributes>(),
+        0x1 => mem::size_of::<LatencyBandwidthInfo>(),
+        0x2 => mem::size_of::<MemorySideCacheInfo>(),
+        _ => mem::size_of::<HmatStructureHeader>(),
+    }
+}
+
+/// Describes the initiator that is attached to a memory proximity domain (e.g. the processor whose memory
+/// controller owns it).
+#[repr(C, packed)]
+pub struct MemoryProximityDomainAttributes {
+    header: HmatStructureHeader,
+    flags: u16,
+    _reserved1: u16,
+    initiator_proximity_domain: u32,
+    memory_proximity_domain: u32,
+    _reserved2: u32,
+    _reserved3: u64,
+    _reserved4: u64,
+}
+
+impl MemoryProximityDomainAttributes {
+    pub fn initiator_proximity_domain(&self) -> Option<u32> {
+        if { self.flags }.get_bit(0) {
+            Some(self.initiator_proximity_domain)
+        } else {
+            None
+        }
+    }
+
+    pub fn memory_proximity_domain(&self) -> u32 {
+        self.memory_proximity_domain
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MemoryHierarchy {
+    Memory,
+    FirstLevelCache,
+    SecondLevelCache,
+    ThirdLevelCache,
+    Reserved(u8),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LocalityDataType {
+    AccessLatency,
+    ReadLatency,
+    WriteLatency,
+    AccessBandwidth,
+    ReadBandwidth,
+    WriteBandwidth,
+    Reserved(u8),
+}
+
+impl LocalityDataType {
+    pub fn is_latency(&self) -> bool {
+        matches!(
+            self,
+            LocalityDataType::AccessLatency | LocalityDataType::ReadLatency | LocalityDataType::WriteLatency
+        )
+    }
+
+    pub fn is_bandwidth(&self) -> bool {
+        matches!(
+            self,
+            LocalityDataType::AccessBandwidth | LocalityDataType::ReadBandwidth | LocalityDataType::WriteBandwidth
+        )
+    }
+}
+
+/// The System Locality Latency and Bandwidth Information structure. This holds a matrix of either latencies or
+/// bandwidths (see `data_type`) between a list of initiator proximity domains and a list of target proximity
+/// domains. Each entry is scaled by `entry_base_unit`.
+#[repr(C, packed)]
+pub struct LatencyBandwidthInfo {
+    header: HmatStructureHeader,
+    flags: u8,
+    data_type: u8,
+    min_transfer_size: u8,
+    _reserved1: u8,
+    num_initiator_proximity_domains: u32,
+    num_target_proximity_domains: u32,
+    _reserved2: u32,
+    entry_base_unit: u64,
+    // Followed by the initiator proximity domains, the target proximity domains, and then the entries
+}
+
+impl LatencyBandwidthInfo {
+    /// An entry with this value means the data isn't provided.
+    pub const NOT_PROVIDED: u16 = 0;
+    /// An entry with this value means the target isn't reachable from the initiator.
+    pub const UNREACHABLE: u16 = 0xffff;
+
+    pub fn memory_hierarchy(&self) -> MemoryHierarchy {
+        match self.flags.get_bits(0..4) {
+            0 => MemoryHierarchy::Memory,
+            1 => MemoryHierarchy::FirstLevelCache,
+            2 => MemoryHierarchy::SecondLevelCache,
+            3 => MemoryHierarchy::ThirdLevelCache,
+            other => MemoryHierarchy::Reserved(other),
+        }
+    }
+
+    pub fn data_type(&self) -> LocalityDataType {
+        match self.data_type {
+            0 => LocalityDataType::AccessLatency,
+            1 => LocalityDataType::ReadLatency,
+            2 => LocalityDataType::WriteLatency,
+            3 => LocalityDataType::AccessBandwidth,
+            4 => LocalityDataType::ReadBandwidth,
+            5 => LocalityDataType::WriteBandwidth,
+            other => LocalityDataType::Reserved(other),
+        }
+    }
+
+    /// The minimum size of a transfer for the values to apply, in bytes, or `0` if this isn't specified.
+    pub fn min_transfer_size(&self) -> u8 {
+        self.min_transfer_size
+    }
+
+    /// The unit of each entry. Latencies are in picoseconds, and bandwidths are in MB/s, once multiplied by this.
+    pub fn entry_base_unit(&self) -> u64 {
+        self.entry_base_unit
+    }
+
+    pub fn initiator_proximity_domains(&self) -> impl Iterator<Item = u32> + '_ {
+        let (num_initiators, _) = self.dimensions();
+        let domains = self.data_pointer() as *const u32;
+        (0..num_initiators).map(move |i| unsafe { ptr::read_unaligned(domains.add(i)) })
+    }
+
+    pub fn target_proximity_domains(&self) -> impl Iterator<Item = u32> + '_ {
+        let (num_initiators, num_targets) = self.dimensions();
+        let domains = unsafe { (self.data_pointer() as *const u32).add(num_initiators) };
+        (0..num_targets).map(move |i| unsafe { ptr::read_unaligned(domains.add(i)) })
+    }
+
+    /// Get the raw entry from `initiator_domain` to `target_domain`. Returns `None` if either domain isn't in the
+    /// structure.
+    pub fn entry(&self, initiator_domain: u32, target_domain: u32) -> Option<u16> {
+        let (num_initiators, num_targets) = self.dimensions();
+        let initiator = self.initiator_proximity_domains().position(|domain| domain == initiator_domain)?;
+        let target = self.target_proximity_domains().position(|domain| domain == target_domain)?;
+
+        let entries =
+            unsafe { (self.data_pointer() as *const u32).add(num_initiators + num_targets) as *const u16 };
+        Some(unsafe { ptr::read_unaligned(entries.add(initiator * num_targets + target)) })
+    }
+
+    /// The latency from `initiator_domain` to `target_domain`, in picoseconds. Returns `None` if this structure
+    /// doesn't describe latencies, or if the latency isn't provided or the target is unreachable.
+    pub fn latency(&self, initiator_domain: u32, target_domain: u32) -> Option<u64> {
+        if !self.data_type().is_latency() {
+            return None;
+        }
+        self.scaled_entry(initiator_domain, target_domain)
+    }
+
+    /// The bandwidth from `initiator_domain` to `target_domain`, in MB/s. Returns `None` if this structure doesn't
+    /// describe bandwidths, or if the bandwidth isn't provided or the target is unreachable.
+    pub fn bandwidth(&self, initiator_domain: u32, target_domain: u32) -> Option<u64> {
+        if !self.data_type().is_bandwidth() {
+            return None;
+        }
+        self.scaled_entry(initiator_domain, target_domain)
+    }
+
+    fn scaled_entry(&self, initiator_domain: u32, target_domain: u32) -> Option<u64> {
+        match self.entry(initiator_domain, target_domain)? {
+            LatencyBandwidthInfo::NOT_PROVIDED | LatencyBandwidthInfo::UNREACHABLE => None,
+            entry => u64::from(entry).checked_mul(self.entry_base_unit),
+        }
+    }
+
+    fn data_pointer(&self) -> *const u8 {
+        unsafe { (self as *const LatencyBandwidthInfo as *const u8).add(mem::size_of::<LatencyBandwidthInfo>()) }
+    }
+
+    /// The number of initiator and target domains, or `(0, 0)` if the lists and entries don't fit in the
+    /// structure.
+    fn dimensions(&self) -> (usize, usize) {
+        let num_initiators = self.num_initiator_proximity_domains as usize;
+        let num_targets = self.num_target_proximity_domains as usize;
+        let needed = num_initiators
+            .checked_add(num_targets)
+            .and_then(|domains| domains.checked_mul(mem::size_of::<u32>()))
+            .and_then(|domains| {
+                let entries = num_initiators.checked_mul(num_targets)?.checked_mul(mem::size_of::<u16>())?;
+                domains.checked_add(entries)
+            })
+            .and_then(|data| data.checked_add(mem::size_of::<LatencyBandwidthInfo>()));
+
+        match needed {
+            Some(needed) if needed <= self.header.length as usize => (num_initiators, num_targets),
+            _ => (0, 0),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CacheAssociativity {
+    None,
+    DirectMapped,
+    ComplexCacheIndexing,
+    Reserved(u8),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CacheWritePolicy {
+    None,
+    WriteBack,
+    WriteThrough,
+    Reserved(u8),
+}
+
+/// Describes a memory-side cache in front of a memory proximity domain.
+#[repr(C, packed)]
+pub struct MemorySideCacheInfo {
+    header: HmatStructureHeader,
+    memory_proximity_domain: u32,
+    _reserved1: u32,
+    cache_size: u64,
+    cache_attributes: u32,
+    _reserved2: u16,
+    num_smbios_handles: u16,
+    // Followed by `num_smbios_handles` SMBIOS handles
+}
+
+impl MemorySideCacheInfo {
+    pub fn memory_proximity_domain(&self) -> u32 {
+        self.memory_proximity_domain
+    }
+
+    /// The size of the cache, in bytes.
+    pub fn cache_size(&self) -> u64 {
+        self.cache_size
+    }
+
+    /// The number of levels of memory-side cache in front of the memory proximity domain.
+    pub fn total_cache_levels(&self) -> u8 {
+        { self.cache_attributes }.get_bits(0..4) as u8
+    }
+
+    /// The level of this cache, where `1` is the level nearest the processor.
+    pub fn cache_level(&self) -> u8 {
+        { self.cache_attributes }.get_bits(4..8) as u8
+    }
+
+    pub fn associativity(&self) -> CacheAssociativity {
+        match { self.cache_attributes }.get_bits(8..12) as u8 {
+            0 => CacheAssociativity::None,
+            1 => CacheAssociativity::DirectMapped,
+            2 => CacheAssociativity::ComplexCacheIndexing,
+            other => CacheAssociativity::Reserved(other),
+        }
+    }
+
+    pub fn write_policy(&self) -> CacheWritePolicy {
+        match { self.cache_attributes }.get_bits(12..16) as u8 {
+            0 => CacheWritePolicy::None,
+            1 => CacheWritePolicy::WriteBack,
+            2 => CacheWritePolicy::WriteThrough,
+            other => CacheWritePolicy::Reserved(other),
+        }
+    }
+
+    /// The size of each cache line, in bytes.
+    pub fn line_size(&self) -> u16 {
+        { self.cache_attributes }.get_bits(16..32) as u16
+    }
+
+    /// The SMBIOS handles of the memory devices that implement the cache.
+    pub fn smbios_handles(&self) -> impl Iterator<Item = u16> + '_ {
+        let available =
+            (self.header.length as usize - mem::size_of::<MemorySideCacheInfo>()) / mem::size_of::<u16>();
+        let handles = unsafe {
+            (self as *const MemorySideCacheInfo as *const u8).add(mem::size_of::<MemorySideCacheInfo>())
+                as *const u16
+        };
+        (0..usize::min(self.num_smbios_handles as usize, available))
+            .map(move |i| unsafe { ptr::read_unaligned(handles.add(i)) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{make_sdt, table_from_bytes};
+    use alloc::vec::Vec;
+
+    fn latency_bandwidth_bytes(
+        data_type: u8,
+        entry_base_unit: u64,
+        initiators: &[u32],
+        targets: &[u32],
+        entries: &[u16],
+    ) -> Vec<u8> {
+        let length = 32 + 4 * (initiators.len() + targets.len()) + 2 * entries.len();
+
+        let mut structure = Vec::new();
+        structure.extend_from_slice(&1u16.to_le_bytes());
+        structure.extend_from_slice(&0u16.to_le_bytes());
+        structure.extend_from_slice(&(length as u32).to_le_bytes());
+        structure.extend_from_slice(&[0, data_type, 0, 0]);
+        structure.extend_from_slice(&(initiators.len() as u32).to_le_bytes());
+        structure.extend_from_slice(&(targets.len() as u32).to_le_bytes());
+        structure.extend_from_slice(&0u32.to_le_bytes());
+        structure.extend_from_slice(&entry_base_unit.to_le_bytes());
+        for domain in initiators.iter().chain(targets) {
+            structure.extend_from_slice(&domain.to_le_bytes());
+        }
+        for entry in entries {
+            structure.extend_from_slice(&entry.to_le_bytes());
+        }
+        structure
+    }
+
+    /// Build an HMAT for two nodes that each have a processor and some memory, plus a third, CPU-less node with
+    /// slower memory.
+    fn make_hmat() -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&[0; 4]);
+
+        // Memory proximity domain attributes, for domain 2 (which has no attached initiator)
+        body.extend_from_slice(&0u16.to_le_bytes());
+        body.extend_from_slice(&0u16.to_le_bytes());
+        body.extend_from_slice(&40u32.to_le_bytes());
+        body.extend_from_slice(&0u16.to_le_bytes());
+        body.extend_from_slice(&0u16.to_le_bytes());
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body.extend_from_slice(&2u32.to_le_bytes());
+        body.extend_from_slice(&[0; 20]);
+
+        // Read latencies, in units of 10ns
+        body.extend(latency_bandwidth_bytes(1, 10_000, &[0, 1], &[0, 1, 2], &[8, 14, 30, 14, 8, 0xffff]));
+
+        // Write bandwidths, in units of 100MB/s
+        body.extend(latency_bandwidth_bytes(5, 100, &[0, 1], &[0, 1, 2], &[200, 100, 20, 100, 200, 0]));
+
+        // A 1GiB direct-mapped memory-side cache in front of domain 2
+        body.extend_from_slice(&2u16.to_le_bytes());
+        body.extend_from_slice(&0u16.to_le_bytes());
+        body.extend_from_slice(&34u32.to_le_bytes());
+        body.extend_from_slice(&2u32.to_le_bytes());
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body.extend_from_slice(&0x4000_0000u64.to_le_bytes());
+        body.extend_from_slice(&((64 << 16) | (1 << 12) | (1 << 8) | (1 << 4) | 1u32).to_le_bytes());
+        body.extend_from_slice(&0u16.to_le_bytes());
+        body.extend_from_slice(&1u16.to_le_bytes());
+        body.extend_from_slice(&0x0023u16.to_le_bytes());
+
+        make_sdt(b"HMAT", 2, &body)
+    }
+
+    #[test]
+    fn test_read_latency() {
+        let table = make_hmat();
+        let hmat = table_from_bytes::<Hmat>(&table);
+        let info = hmat
+            .structures()
+            .find_map(|structure| match structure {
+                HmatStructure::LatencyBandwidthInfo(info) if info.data_type() == LocalityDataType::ReadLatency => {
+                    Some(info)
+                }
+                _ => None,
+            })
+            .unwrap();
+
+        assert_eq!(info.memory_hierarchy(), MemoryHierarchy::Memory);
+        assert_eq!(info.initiator_proximity_domains().collect::<Vec<u32>>(), [0, 1]);
+        assert_eq!(info.target_proximity_domains().collect::<Vec<u32>>(), [0, 1, 2]);
+        assert_eq!(info.latency(0, 0), Some(80_000));
+        assert_eq!(info.latency(0, 1), Some(140_000));
+        assert_eq!(info.latency(0, 2), Some(300_000));
+        assert_eq!(info.latency(1, 0), Some(140_000));
+        assert_eq!(info.latency(1, 2), None);
+        assert_eq!(info.entry(1, 2), Some(LatencyBandwidthInfo::UNREACHABLE));
+        assert_eq!(info.latency(2, 0), None);
+        assert_eq!(info.bandwidth(0, 0), None);
+    }
+
+    #[test]
+    fn test_write_bandwidth() {
+        let table = make_hmat();
+        let hmat = table_from_bytes::<Hmat>(&table);
+        let info = hmat
+            .structures()
+            .find_map(|structure| match structure {
+                HmatStructure::LatencyBandwidthInfo(info)
+                    if info.data_type() == LocalityDataType::WriteBandwidth =>
+                {
+                    Some(info)
+                }
+                _ => None,
+            })
+            .unwrap();
+
+        assert_eq!(info.bandwidth(0, 0), Some(20_000));
+        assert_eq!(info.bandwidth(0, 2), Some(2_000));
+        assert_eq!(info.bandwidth(1, 0), Some(10_000));
+        assert_eq!(info.bandwidth(1, 2), None);
+        assert_eq!(info.latency(0, 0), None);
+    }
+
+    #[test]
+    fn test_other_structures() {
+        let table = make_hmat();
+        let hmat = table_from_bytes::<Hmat>(&table);
+        assert_eq!(hmat.structures().count(), 4);
+
+        match hmat.structures().next() {
+            Some(HmatStructure::MemoryProximityDomainAttributes(attributes)) => {
+                assert_eq!(attributes.memory_proximity_domain(), 2);
+                assert_eq!(attributes.initiator_proximity_domain(), None);
+            }
+            _ => panic!("Expected memory proximity domain attributes"),
+        }
+
+        match hmat.structures().last() {
+            Some(HmatStructure::MemorySideCacheInfo(cache)) => {
+                assert_eq!(cache.memory_proximity_domain(), 2);
+                assert_eq!(cache.cache_size(), 0x4000_0000);
+                assert_eq!(cache.total_cache_levels(), 1);
+                assert_eq!(cache.cache_level(), 1);
+                assert_eq!(cache.associativity(), CacheAssociativity::DirectMapped);
+                assert_eq!(cache.write_policy(), CacheWritePolicy::WriteBack);
+                assert_eq!(cache.line_size(), 64);
+                assert_eq!(cache.smbios_handles().collect::<Vec<u16>>(), [0x0023]);
+            }
+            _ => panic!("Expected memory side cache information"),
+        }
+    }
+
+    #[test]
+    fn test_domains_outside_structure() {
+        let mut structure = latency_bandwidth_bytes(0, 1000, &[0], &[0], &[10]);
+        structure[12..16].copy_from_slice(&0x1000_0000u32.to_le_bytes());
+
+        let mut body = Vec::new();
+        body.extend_from_slice(&[0; 4]);
+        body.extend(structure);
+        let table = make_sdt(b"HMAT", 2, &body);
+        let hmat = table_from_bytes::<Hmat>(&table);
+
+        match hmat.structures().next() {
+            Some(HmatStructure::LatencyBandwidthInfo(info)) => {
+                assert_eq!(info.initiator_proximity_domains().count(), 0);
+                assert_eq!(info.latency(0, 0), None);
+            }
+            _ => panic!("Expected latency and bandwidth information"),
+        }
+    }
+}
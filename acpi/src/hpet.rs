@@ -1,5 +1,14 @@
-use crate::{platform::address::RawGenericAddress, sdt::SdtHeader, AcpiError, AcpiHandler, AcpiTable, AcpiTables};
+use crate::{
+    platform::address::RawGenericAddress,
+    sdt::SdtHeader,
+    AcpiError,
+    AcpiHandler,
+    AcpiTable,
+    AcpiTables,
+    PhysicalMapping,
+};
 use bit_field::BitField;
+use core::ptr;
 
 #[derive(Debug)]
 pub enum PageProtection {
@@ -11,6 +20,18 @@ pub enum PageProtection {
     Other,
 }
 
+impl PageProtection {
+    /// The number of bytes, starting at the HPET's base address, that can be accessed without generating a
+    /// fault. Returns `None` if the firmware doesn't guarantee anything beyond the register block itself.
+    pub fn guaranteed_bytes(&self) -> Option<usize> {
+        match self {
+            PageProtection::Protected4K => Some(0x1000),
+            PageProtection::Protected64K => Some(0x10000),
+            PageProtection::None | PageProtection::Other => None,
+        }
+    }
+}
+
 /// Information about the High Precision Event Timer (HPET)
 #[derive(Debug)]
 pub struct HpetInfo {
@@ -90,3 +111,233 @@ impl AcpiTable for HpetTable {
         &self.header
     }
 }
+
+/// The size of the HPET's register block.
+const REGISTER_BLOCK_SIZE: usize = 0x400;
+
+const GENERAL_CAPABILITIES: usize = 0x00;
+const GENERAL_CONFIG: usize = 0x10;
+const MAIN_COUNTER: usize = 0xf0;
+const COMPARATORS: usize = 0x100;
+const COMPARATOR_SIZE: usize = 0x20;
+
+/// The number of comparators whose registers fit in the register block. The capabilities register can claim up
+/// to 32 comparators, but any past this would lie outside the mapping.
+const MAX_COMPARATORS: usize = (REGISTER_BLOCK_SIZE - COMPARATORS) / COMPARATOR_SIZE;
+
+/// A mapping of the HPET's register block, which provides access to its main counter and comparators. See
+/// `HpetRegisters::new`.
+pub struct HpetRegisters<H>
+where
+    H: AcpiHandler,
+{
+    mapping: PhysicalMapping<H, u8>,
+    main_counter_is_64bits: bool,
+}
+
+impl<H> HpetRegisters<H>
+where
+    H: AcpiHandler,
+{
+    /// Map the register block of the HPET described by `info`, using `handler`.
+    ///
+    /// ### Safety
+    /// The HPET's register block must be mappable with `handler`, and must not be accessed by anything else while
+    /// the `HpetRegisters` exists.
+    pub unsafe fn new(info: &HpetInfo, handler: &H) -> HpetRegisters<H> {
+        let mapping = unsafe { handler.map_physical_region::<u8>(info.base_address, REGISTER_BLOCK_SIZE) };
+        HpetRegisters { mapping, main_counter_is_64bits: info.main_counter_is_64bits() }
+    }
+
+    fn read(&self, offset: usize) -> u64 {
+        unsafe { ptr::read_volatile(self.mapping.virtual_start().as_ptr().add(offset) as *const u64) }
+    }
+
+    fn write(&self, offset: usize, value: u64) {
+        unsafe { ptr::write_volatile(self.mapping.virtual_start().as_ptr().add(offset) as *mut u64, value) }
+    }
+
+    /// The contents of the General Capabilities and ID register.
+    pub fn capabilities(&self) -> u64 {
+        self.read(GENERAL_CAPABILITIES)
+    }
+
+    /// The period of the main counter, in femtoseconds.
+    pub fn femtoseconds_per_tick(&self) -> u32 {
+        self.capabilities().get_bits(32..64) as u32
+    }
+
+    pub fn num_comparators(&self) -> u8 {
+        self.capabilities().get_bits(8..13) as u8 + 1
+    }
+
+    /// Start the main counter, and allow the comparators to generate interrupts.
+    pub fn enable(&self) {
+        self.write(GENERAL_CONFIG, *self.read(GENERAL_CONFIG).set_bit(0, true));
+    }
+
+    /// Stop the main counter, and stop the comparators from generating interrupts.
+    pub fn disable(&self) {
+        self.write(GENERAL_CONFIG, *self.read(GENERAL_CONFIG).set_bit(0, false));
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.read(GENERAL_CONFIG).get_bit(0)
+    }
+
+    /// Read the main counter. If the counter is only 32 bits wide, only the lower half of the register is read.
+    pub fn main_counter(&self) -> u64 {
+        if self.main_counter_is_64bits {
+            self.read(MAIN_COUNTER)
+        } else {
+            unsafe {
+                ptr::read_volatile(self.mapping.virtual_start().as_ptr().add(MAIN_COUNTER) as *const u32) as u64
+            }
+        }
+    }
+
+    /// Set the main counter. This should only be done while the HPET is disabled.
+    pub fn set_main_counter(&self, value: u64) {
+        self.write(MAIN_COUNTER, value);
+    }
+
+    /// Get the comparator with index `n`. Returns `None` if the HPET doesn't have that many comparators, or if
+    /// the comparator's registers would lie outside the register block.
+    pub fn comparator(&self, n: u8) -> Option<HpetComparator<'_, H>> {
+        if n < self.num_comparators() && (n as usize) < MAX_COMPARATORS {
+            Some(HpetComparator { registers: self, offset: COMPARATORS + COMPARATOR_SIZE * n as usize })
+        } else {
+            None
+        }
+    }
+}
+
+/// One of the HPET's comparators (also called timers). See `HpetRegisters::comparator`.
+pub struct HpetComparator<'a, H>
+where
+    H: AcpiHandler,
+{
+    registers: &'a HpetRegisters<H>,
+    offset: usize,
+}
+
+impl<'a, H> HpetComparator<'a, H>
+where
+    H: AcpiHandler,
+{
+    /// The contents of the comparator's Configuration and Capabilities register.
+    pub fn config(&self) -> u64 {
+        self.registers.read(self.offset)
+    }
+
+    pub fn set_config(&self, value: u64) {
+        self.registers.write(self.offset, value);
+    }
+
+    /// The I/O APIC inputs that the comparator's interrupt can be routed to, as a bitmap.
+    pub fn interrupt_routing_capabilities(&self) -> u32 {
+        self.config().get_bits(32..64) as u32
+    }
+
+    pub fn comparator_value(&self) -> u64 {
+        self.registers.read(self.offset + 0x08)
+    }
+
+    pub fn set_comparator_value(&self, value: u64) {
+        self.registers.write(self.offset + 0x08, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestHandler;
+
+    const HPET_ADDRESS: usize = 0xfed0_0000;
+
+    #[repr(C, align(8))]
+    struct RegisterBlock([u8; REGISTER_BLOCK_SIZE]);
+
+    /// Build the register block of an HPET like QEMU's, with three comparators and a 100MHz counter.
+    fn make_register_block() -> RegisterBlock {
+        let mut block = [0; REGISTER_BLOCK_SIZE];
+        block[0..8].copy_from_slice(&0x0098_9680_8086_a201u64.to_le_bytes());
+        block[0xf0..0xf8].copy_from_slice(&0x1_0000_0123u64.to_le_bytes());
+        for n in 0..3 {
+            let offset = 0x100 + 0x20 * n;
+            block[offset..(offset + 8)].copy_from_slice(&0x00f0_0000_0000_0030u64.to_le_bytes());
+        }
+        RegisterBlock(block)
+    }
+
+    fn make_info(event_timer_block_id: u32) -> HpetInfo {
+        HpetInfo {
+            event_timer_block_id,
+            base_address: HPET_ADDRESS,
+            hpet_number: 0,
+            clock_tick_unit: 0,
+            page_protection: PageProtection::None,
+        }
+    }
+
+    #[test]
+    fn test_registers() {
+        let mut block = make_register_block();
+        let handler = TestHandler::with_mapping(HPET_ADDRESS, block.0.as_mut_ptr());
+        let registers = unsafe { HpetRegisters::new(&make_info(0x8086_a201), &handler) };
+
+        assert_eq!(registers.femtoseconds_per_tick(), 10_000_000);
+        assert_eq!(registers.num_comparators(), 3);
+        assert_eq!(registers.main_counter(), 0x1_0000_0123);
+
+        assert!(!registers.is_enabled());
+        registers.enable();
+        assert!(registers.is_enabled());
+        registers.disable();
+        assert!(!registers.is_enabled());
+        registers.set_main_counter(0);
+        assert_eq!(registers.main_counter(), 0);
+
+        let comparator = registers.comparator(2).unwrap();
+        assert_eq!(comparator.interrupt_routing_capabilities(), 0x00f0_0000);
+        comparator.set_config(comparator.config() | 0b100);
+        comparator.set_comparator_value(0x5f5_e100);
+        assert!(registers.comparator(3).is_none());
+
+        registers.enable();
+        drop(registers);
+        assert_eq!(block.0[0x10], 0b1);
+        assert_eq!(&block.0[0x140..0x148], &0x00f0_0000_0000_0034u64.to_le_bytes());
+        assert_eq!(&block.0[0x148..0x150], &0x5f5_e100u64.to_le_bytes());
+    }
+
+    #[test]
+    fn test_page_protection_guaranteed_bytes() {
+        assert_eq!(PageProtection::None.guaranteed_bytes(), None);
+        assert_eq!(PageProtection::Protected4K.guaranteed_bytes(), Some(4096));
+        assert_eq!(PageProtection::Protected64K.guaranteed_bytes(), Some(65536));
+        assert_eq!(PageProtection::Other.guaranteed_bytes(), None);
+    }
+
+    #[test]
+    fn test_32bit_main_counter() {
+        let mut block = make_register_block();
+        let handler = TestHandler::with_mapping(HPET_ADDRESS, block.0.as_mut_ptr());
+        let registers = unsafe { HpetRegisters::new(&make_info(0x8086_8201), &handler) };
+        assert_eq!(registers.main_counter(), 0x123);
+    }
+
+    #[test]
+    fn test_comparators_outside_register_block() {
+        // Claim 32 comparators, more than fit in the register block
+        let mut block = make_register_block();
+        block.0[1] = 0xbf;
+        let handler = TestHandler::with_mapping(HPET_ADDRESS, block.0.as_mut_ptr());
+        let registers = unsafe { HpetRegisters::new(&make_info(0x8086_bf01), &handler) };
+
+        assert_eq!(registers.num_comparators(), 32);
+        assert!(registers.comparator(23).is_some());
+        assert!(registers.comparator(24).is_none());
+        assert!(registers.comparator(31).is_none());
+    }
+}
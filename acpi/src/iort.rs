@@ -0,0 +1,682 @@
+use crate::{
+    check_entry_length,
+    parse_warning,
+    sdt::{SdtHeader, Signature},
+    AcpiTable,
+    ParseWarning,
+};
+use bit_field::BitField;
+use core::{mem, slice, str};
+
+/// Represents the IO Remapping Table (IORT). On ARM platforms, this describes the IO topology: the PCI root
+/// complexes and other devices, the SMMUs that sit between them and memory, and the GIC Interrupt Translation
+/// Services (ITSs) their MSIs are sent to. Devices are connected to each other by ID mappings, which describe how
+/// the IDs emitted by one node (e.g. PCI RequesterIDs) are translated into the IDs understood by the next (e.g.
+/// SMMU StreamIDs, or ITS DeviceIDs).
+#[repr(C, packed)]
+pub struct Iort {
+    header: SdtHeader,
+    num_nodes: u32,
+    node_array_offset: u32,
+    _reserved: u32,
+}
+
+impl AcpiTable for Iort {
+    fn header(&self) -> &SdtHeader {
+        &self.header
+    }
+}
+
+impl Iort {
+    pub fn nodes(&self) -> IortNodeIter<'_> {
+        IortNodeIter { iort: self, offset: self.node_array_offset, remaining_nodes: self.num_nodes }
+    }
+
+    /// Get the node that starts `offset` bytes into the table. This is how nodes refer to each other (e.g. in the
+    /// `output_reference` of an ID mapping).
+    pub fn node_at(&self, offset: u32) -> Option<IortNode<'_>> {
+        let table_length = self.header.length;
+        if offset < mem::size_of::<Iort>() as u32
+            || table_length.saturating_sub(offset) < mem::size_of::<IortNodeHeader>() as u32
+        {
+            return None;
+        }
+
+        let pointer = unsafe { (self as *const Iort as *const u8).add(offset as usize) };
+        let header = unsafe { &*(pointer as *const IortNodeHeader) };
+        let minimum_length = match header.node_type {
+            ITS_GROUP_TYPE => mem::size_of::<ItsGroup>(),
+            NAMED_COMPONENT_TYPE => mem::size_of::<NamedComponent>(),
+            ROOT_COMPLEX_TYPE => mem::size_of::<RootComplex>(),
+            SMMU_V1_V2_TYPE => mem::size_of::<SmmuV1V2>(),
+            SMMU_V3_TYPE => mem::size_of::<SmmuV3>(),
+            PMCG_TYPE => mem::size_of::<Pmcg>(),
+            _ => mem::size_of::<IortNodeHeader>(),
+        };
+        if !check_entry_length(
+            Signature::IORT,
+            header.node_type.into(),
+            header.length.into(),
+            minimum_length,
+            table_length - offset,
+        ) {
+            return None;
+        }
+
+        match header.node_type {
+            ITS_GROUP_TYPE => Some(IortNode::ItsGroup(unsafe { &*(pointer as *const ItsGroup) })),
+            NAMED_COMPONENT_TYPE => {
+                Some(IortNode::NamedComponent(unsafe { &*(pointer as *const NamedComponent) }))
+            }
+            ROOT_COMPLEX_TYPE => Some(IortNode::RootComplex(unsafe { &*(pointer as *const RootComplex) })),
+            SMMU_V1_V2_TYPE => Some(IortNode::SmmuV1V2(unsafe { &*(pointer as *const SmmuV1V2) })),
+            SMMU_V3_TYPE => Some(IortNode::SmmuV3(unsafe { &*(pointer as *const SmmuV3) })),
+            PMCG_TYPE => Some(IortNode::Pmcg(unsafe { &*(pointer as *const Pmcg) })),
+            _ => None,
+        }
+    }
+
+    /// Translate the ID `id`, emitted by `node`, through the chain of ID mappings until it reaches a node that
+    /// doesn't translate it any further (usually an ITS group). Returns that node, and the ID as it is seen by that
+    /// node. For example, this can be used to find the ITS DeviceID that a PCI device's MSIs will be tagged with
+    /// from its RequesterID, even if an SMMU sits between the root complex and the ITS.
+    ///
+    /// Returns `None` if `node` doesn't have a mapping for `id`, or if the chain of mappings is broken or contains
+    /// a cycle.
+    pub fn resolve_mapping<'a>(&'a self, node: IortNode<'a>, id: u32) -> Option<(IortNode<'a>, u32)> {
+        let (mut node, mut id) = self.map_id(node, id)?;
+
+        /*
+         * Each step must move to a different node, so a well-formed chain can't be longer than the number of nodes
+         * in the table. We use this to stop following malformed tables that contain a cycle.
+         */
+        for _ in 0..self.num_nodes {
+            match self.map_id(node, id) {
+                Some((next_node, next_id)) => {
+                    node = next_node;
+                    id = next_id;
+                }
+                None => return Some((node, id)),
+            }
+        }
+
+        None
+    }
+
+    fn map_id<'a>(&'a self, node: IortNode<'a>, id: u32) -> Option<(IortNode<'a>, u32)> {
+        node.id_mappings().find_map(|mapping| {
+            let output_id = mapping.map(id)?;
+            Some((self.node_at(mapping.output_reference)?, output_id))
+        })
+    }
+}
+
+const ITS_GROUP_TYPE: u8 = 0;
+const NAMED_COMPONENT_TYPE: u8 = 1;
+const ROOT_COMPLEX_TYPE: u8 = 2;
+const SMMU_V1_V2_TYPE: u8 = 3;
+const SMMU_V3_TYPE: u8 = 4;
+const PMCG_TYPE: u8 = 5;
+
+pub struct IortNodeIter<'a> {
+    iort: &'a Iort,
+    offset: u32,
+    remaining_nodes: u32,
+}
+
+impl<'a> Iterator for IortNodeIter<'a> {
+    type Item = IortNode<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.remaining_nodes > 0 {
+            let table_length = self.iort.header.length;
+            if table_length.saturating_sub(self.offset) < mem::size_of::<IortNodeHeader>() as u32 {
+                return None;
+            }
+
+            let header = unsafe {
+                &*((self.iort as *const Iort as *const u8).add(self.offset as usize) as *const IortNodeHeader)
+            };
+            if !check_entry_length(
+                Signature::IORT,
+                header.node_type.into(),
+                header.length.into(),
+                mem::size_of::<IortNodeHeader>(),
+                table_length - self.offset,
+            ) {
+                self.remaining_nodes = 0;
+                return None;
+            }
+
+            let node = self.iort.node_at(self.offset);
+            self.offset += header.length as u32;
+            self.remaining_nodes -= 1;
+
+            /*
+             * `node_at` returns `None` for node types we don't support (which we skip), and for nodes that don't fit
+             * in the table (in which case the next iteration will stop, as the following node can't fit either).
+             */
+            if let Some(node) = node {
+                return Some(node);
+            }
+        }
+
+        None
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum IortNode<'a> {
+    ItsGroup(&'a ItsGroup),
+    NamedComponent(&'a NamedComponent),
+    RootComplex(&'a RootComplex),
+    SmmuV1V2(&'a SmmuV1V2),
+    SmmuV3(&'a SmmuV3),
+    Pmcg(&'a Pmcg),
+}
+
+impl<'a> IortNode<'a> {
+    fn header(&self) -> &'a IortNodeHeader {
+        match *self {
+            IortNode::ItsGroup(node) => &node.header,
+            IortNode::NamedComponent(node) => &node.header,
+            IortNode::RootComplex(node) => &node.header,
+            IortNode::SmmuV1V2(node) => &node.header,
+            IortNode::SmmuV3(node) => &node.header,
+            IortNode::Pmcg(node) => &node.header,
+        }
+    }
+
+    pub fn revision(&self) -> u8 {
+        self.header().revision
+    }
+
+    /// The identifier of the node, which is unique within the table. Before revision 5 of the table (IORT issue
+    /// E.b), this field was reserved, and will be `0`.
+    pub fn identifier(&self) -> u32 {
+        self.header().identifier
+    }
+
+    pub fn id_mappings(&self) -> IdMappingIter<'a> {
+        let header = self.header();
+        let num_mappings = header.num_id_mappings as usize;
+        let array_end = (header.id_array_offset as usize)
+            .checked_add(num_mappings.saturating_mul(mem::size_of::<IdMapping>()));
+
+        let mappings = match array_end {
+            _ if num_mappings == 0 => &[][..],
+            Some(array_end) if array_end <= header.length as usize => unsafe {
+                slice::from_raw_parts(
+                    (header as *const IortNodeHeader as *const u8).add(header.id_array_offset as usize)
+                        as *const IdMapping,
+                    num_mappings,
+                )
+            },
+            _ => {
+                parse_warning(ParseWarning::InvalidEntryLength {
+                    signature: Signature::IORT,
+                    entry_type: header.node_type.into(),
+                    length: header.length.into(),
+                });
+                &[][..]
+            }
+        };
+
+        IdMappingIter { mappings: mappings.iter() }
+    }
+}
+
+pub struct IdMappingIter<'a> {
+    mappings: slice::Iter<'a, IdMapping>,
+}
+
+impl<'a> Iterator for IdMappingIter<'a> {
+    type Item = &'a IdMapping;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.mappings.next()
+    }
+}
+
+#[repr(C, packed)]
+struct IortNodeHeader {
+    node_type: u8,
+    length: u16,
+    revision: u8,
+    identifier: u32,
+    num_id_mappings: u32,
+    id_array_offset: u32,
+}
+
+/// Describes how a range of input IDs emitted by a node is translated into the output IDs seen by another node.
+#[derive(Clone, Copy, Debug)]
+#[repr(C, packed)]
+pub struct IdMapping {
+    input_base: u32,
+    /// The number of IDs in the range, minus one.
+    num_ids: u32,
+    output_base: u32,
+    output_reference: u32,
+    flags: u32,
+}
+
+impl IdMapping {
+    pub fn input_base(&self) -> u32 {
+        self.input_base
+    }
+
+    /// The number of IDs in the input range.
+    pub fn num_ids(&self) -> u64 {
+        u64::from(self.num_ids) + 1
+    }
+
+    pub fn output_base(&self) -> u32 {
+        self.output_base
+    }
+
+    /// The offset of the node the output IDs are seen by, from the start of the table. This can be looked up with
+    /// `Iort::node_at`.
+    pub fn output_reference(&self) -> u32 {
+        self.output_reference
+    }
+
+    /// If true, this mapping doesn't translate input IDs, but instead describes the single ID used by the node
+    /// itself (e.g. for an SMMU's own MSIs). `output_base` is that ID.
+    pub fn is_single_mapping(&self) -> bool {
+        { self.flags }.get_bit(0)
+    }
+
+    /// Translate an input ID through this mapping, if it falls within its input range.
+    pub fn map(&self, id: u32) -> Option<u32> {
+        if self.is_single_mapping() {
+            return None;
+        }
+
+        let offset = id.checked_sub(self.input_base)?;
+        if offset <= self.num_ids {
+            self.output_base.checked_add(offset)
+        } else {
+            None
+        }
+    }
+}
+
+/// A group of GIC Interrupt Translation Services. IDs mapped to this group are the DeviceIDs used by the ITSs.
+#[repr(C, packed)]
+pub struct ItsGroup {
+    header: IortNodeHeader,
+    num_its: u32,
+    // Followed by `num_its` ITS identifiers
+}
+
+impl ItsGroup {
+    /// The identifiers of the ITSs in the group, which match the `GIC ITS ID`s of their MADT entries.
+    pub fn its_ids(&self) -> impl Iterator<Item = u32> + '_ {
+        let available = (self.header.length as usize - mem::size_of::<ItsGroup>()) / mem::size_of::<u32>();
+        let ids = unsafe {
+            slice::from_raw_parts(
+                (self as *const ItsGroup as *const u8).add(mem::size_of::<ItsGroup>()),
+                usize::min(self.num_its as usize, available) * mem::size_of::<u32>(),
+            )
+        };
+        ids.chunks_exact(mem::size_of::<u32>()).map(|id| u32::from_le_bytes([id[0], id[1], id[2], id[3]]))
+    }
+}
+
+/// A device described in the ACPI namespace, which isn't a PCI device (e.g. a platform device behind an SMMU).
+#[repr(C, packed)]
+pub struct NamedComponent {
+    header: IortNodeHeader,
+    node_flags: u32,
+    memory_access_properties: u64,
+    device_memory_address_size_limit: u8,
+    // Followed by a null-terminated path to the device in the namespace
+}
+
+impl NamedComponent {
+    pub fn memory_access_properties(&self) -> u64 {
+        self.memory_access_properties
+    }
+
+    /// The number of bits of address the device is capable of generating.
+    pub fn device_memory_address_size_limit(&self) -> u8 {
+        self.device_memory_address_size_limit
+    }
+
+    /// The fully-qualified path to the device in the ACPI namespace. Returns `None` if the path isn't valid ASCII.
+    pub fn object_name(&self) -> Option<&str> {
+        let name = unsafe {
+            slice::from_raw_parts(
+                (self as *const NamedComponent as *const u8).add(mem::size_of::<NamedComponent>()),
+                self.header.length as usize - mem::size_of::<NamedComponent>(),
+            )
+        };
+        let name = match name.iter().position(|&byte| byte == 0) {
+            Some(end) => &name[..end],
+            None => name,
+        };
+
+        if name.is_ascii() {
+            str::from_utf8(name).ok()
+        } else {
+            None
+        }
+    }
+}
+
+/// A PCI root complex. The input IDs of its ID mappings are PCI RequesterIDs.
+#[repr(C, packed)]
+pub struct RootComplex {
+    header: IortNodeHeader,
+    memory_access_properties: u64,
+    ats_attribute: u32,
+    pci_segment_number: u32,
+    memory_address_size_limit: u8,
+    _reserved: [u8; 3],
+}
+
+impl RootComplex {
+    pub fn memory_access_properties(&self) -> u64 {
+        self.memory_access_properties
+    }
+
+    /// If true, the root complex supports PCIe Address Translation Services.
+    pub fn supports_ats(&self) -> bool {
+        { self.ats_attribute }.get_bit(0)
+    }
+
+    /// The PCI segment group of the root complex, which matches the segment group of its MCFG entry.
+    pub fn pci_segment_number(&self) -> u32 {
+        self.pci_segment_number
+    }
+
+    /// The number of bits of address the root complex is capable of generating. Before revision 1 of the node,
+    /// this was reserved, and will be `0`.
+    pub fn memory_address_size_limit(&self) -> u8 {
+        self.memory_address_size_limit
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SmmuV1V2Model {
+    GenericSmmuV1,
+    GenericSmmuV2,
+    ArmCoreLink400,
+    ArmCoreLink401,
+    ArmCoreLink500,
+    CaviumThunderX,
+    Reserved(u32),
+}
+
+/// An SMMUv1 or SMMUv2.
+#[repr(C, packed)]
+pub struct SmmuV1V2 {
+    header: IortNodeHeader,
+    base_address: u64,
+    span: u64,
+    model: u32,
+    flags: u32,
+    global_interrupt_array_offset: u32,
+    num_context_interrupts: u32,
+    context_interrupt_array_offset: u32,
+    num_pmu_interrupts: u32,
+    pmu_interrupt_array_offset: u32,
+}
+
+impl SmmuV1V2 {
+    pub fn base_address(&self) -> u64 {
+        self.base_address
+    }
+
+    /// The length of the SMMU's memory-mapped register region.
+    pub fn span(&self) -> u64 {
+        self.span
+    }
+
+    pub fn model(&self) -> SmmuV1V2Model {
+        match self.model {
+            0 => SmmuV1V2Model::GenericSmmuV1,
+            1 => SmmuV1V2Model::GenericSmmuV2,
+            2 => SmmuV1V2Model::ArmCoreLink400,
+            3 => SmmuV1V2Model::ArmCoreLink401,
+            4 => SmmuV1V2Model::ArmCoreLink500,
+            5 => SmmuV1V2Model::CaviumThunderX,
+            other => SmmuV1V2Model::Reserved(other),
+        }
+    }
+
+    pub fn is_dvm_supported(&self) -> bool {
+        { self.flags }.get_bit(0)
+    }
+
+    pub fn is_coherent(&self) -> bool {
+        { self.flags }.get_bit(1)
+    }
+}
+
+/// An SMMUv3. The input IDs of its ID mappings are StreamIDs.
+#[repr(C, packed)]
+pub struct SmmuV3 {
+    header: IortNodeHeader,
+    base_address: u64,
+    flags: u32,
+    _reserved: u32,
+    vatos_address: u64,
+    model: u32,
+    event_gsiv: u32,
+    pri_gsiv: u32,
+    gerr_gsiv: u32,
+    sync_gsiv: u32,
+    proximity_domain: u32,
+    device_id_mapping_index: u32,
+}
+
+impl SmmuV3 {
+    pub fn base_address(&self) -> u64 {
+        self.base_address
+    }
+
+    pub fn is_coherent(&self) -> bool {
+        { self.flags }.get_bit(1)
+    }
+
+    /// The proximity domain of the SMMU, if it has one. This matches the proximity domains used in the SRAT.
+    pub fn proximity_domain(&self) -> Option<u32> {
+        if { self.flags }.get_bit(3) {
+            Some(self.proximity_domain)
+        } else {
+            None
+        }
+    }
+
+    /// The base address of the SMMU's VATOS interface, if it has one.
+    pub fn vatos_address(&self) -> Option<u64> {
+        match self.vatos_address {
+            0 => None,
+            address => Some(address),
+        }
+    }
+
+    pub fn model(&self) -> u32 {
+        self.model
+    }
+
+    /// The GSIVs of the SMMU's event, PRI, global error, and CMD_SYNC interrupts. These are `0` for interrupts that
+    /// are not wired, which are signalled with MSIs instead.
+    pub fn event_gsiv(&self) -> u32 {
+        self.event_gsiv
+    }
+
+    pub fn pri_gsiv(&self) -> u32 {
+        self.pri_gsiv
+    }
+
+    pub fn gerr_gsiv(&self) -> u32 {
+        self.gerr_gsiv
+    }
+
+    pub fn sync_gsiv(&self) -> u32 {
+        self.sync_gsiv
+    }
+
+    /// The index of the ID mapping that describes the DeviceID of the SMMU's own MSIs. This is only valid if one
+    /// of the interrupts is not wired.
+    pub fn device_id_mapping_index(&self) -> u32 {
+        self.device_id_mapping_index
+    }
+}
+
+/// A Performance Monitoring Counter Group, which belongs to an SMMUv3 or a root complex.
+#[repr(C, packed)]
+pub struct Pmcg {
+    header: IortNodeHeader,
+    page0_base_address: u64,
+    overflow_gsiv: u32,
+    node_reference: u32,
+    page1_base_address: u64,
+}
+
+impl Pmcg {
+    pub fn page0_base_address(&self) -> u64 {
+        self.page0_base_address
+    }
+
+    pub fn page1_base_address(&self) -> u64 {
+        self.page1_base_address
+    }
+
+    /// The GSIV of the overflow interrupt. This is `0` if the interrupt is signalled with an MSI instead.
+    pub fn overflow_gsiv(&self) -> u32 {
+        self.overflow_gsiv
+    }
+
+    /// The offset of the node the counter group belongs to, from the start of the table. This can be looked up
+    /// with `Iort::node_at`.
+    pub fn node_reference(&self) -> u32 {
+        self.node_reference
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{make_sdt, table_from_bytes};
+    use alloc::vec::Vec;
+
+    fn node_header(node_type: u8, length: u16, num_id_mappings: u32, id_array_offset: u32) -> Vec<u8> {
+        let mut header = Vec::new();
+        header.push(node_type);
+        header.extend_from_slice(&length.to_le_bytes());
+        header.push(0);
+        header.extend_from_slice(&0u32.to_le_bytes());
+        header.extend_from_slice(&num_id_mappings.to_le_bytes());
+        header.extend_from_slice(&id_array_offset.to_le_bytes());
+        header
+    }
+
+    fn id_mapping(input_base: u32, num_ids: u32, output_base: u32, output_reference: u32) -> Vec<u8> {
+        [input_base, num_ids, output_base, output_reference, 0]
+            .iter()
+            .flat_map(|field| field.to_le_bytes())
+            .collect()
+    }
+
+    /// An IORT with a root complex and a named component behind an SMMUv3, which sends MSIs to a single ITS.
+    fn make_iort() -> Vec<u8> {
+        const ITS_GROUP_OFFSET: u32 = 48;
+        const SMMU_OFFSET: u32 = 72;
+
+        let mut body = Vec::new();
+        body.extend_from_slice(&4u32.to_le_bytes());
+        body.extend_from_slice(&ITS_GROUP_OFFSET.to_le_bytes());
+        body.extend_from_slice(&0u32.to_le_bytes());
+
+        // ITS group
+        body.extend(node_header(0, 24, 0, 0));
+        body.extend_from_slice(&1u32.to_le_bytes());
+        body.extend_from_slice(&0u32.to_le_bytes());
+
+        // SMMUv3, with StreamIDs mapped to DeviceIDs starting at 0x10000
+        body.extend(node_header(4, 88, 1, 68));
+        body.extend_from_slice(&0x0900_0000u64.to_le_bytes());
+        body.extend_from_slice(&0b1010u32.to_le_bytes());
+        body.extend_from_slice(&[0; 4]);
+        body.extend_from_slice(&0u64.to_le_bytes());
+        body.extend_from_slice(&0u32.to_le_bytes());
+        for gsiv in [106u32, 107, 108, 109].iter() {
+            body.extend_from_slice(&gsiv.to_le_bytes());
+        }
+        body.extend_from_slice(&1u32.to_le_bytes());
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body.extend(id_mapping(0x0, 0xffff, 0x10000, ITS_GROUP_OFFSET));
+
+        // Root complex, with RequesterIDs mapped to StreamIDs starting at 0x100
+        body.extend(node_header(2, 56, 1, 36));
+        body.extend_from_slice(&0u64.to_le_bytes());
+        body.extend_from_slice(&1u32.to_le_bytes());
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body.extend_from_slice(&[48, 0, 0, 0]);
+        body.extend(id_mapping(0x0, 0xffff, 0x100, SMMU_OFFSET));
+
+        // Named component, with its single ID mapped to StreamID 0x20
+        body.extend(node_header(1, 60, 1, 40));
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body.extend_from_slice(&0u64.to_le_bytes());
+        body.push(40);
+        body.extend_from_slice(b"\\_SB.ETH0\0\0");
+        body.extend(id_mapping(0x0, 0x0, 0x20, SMMU_OFFSET));
+
+        make_sdt(b"IORT", 3, &body)
+    }
+
+    #[test]
+    fn test_nodes() {
+        let table = make_iort();
+        let iort = table_from_bytes::<Iort>(&table);
+        let nodes: Vec<IortNode> = iort.nodes().collect();
+
+        assert_eq!(nodes.len(), 4);
+        match nodes[0] {
+            IortNode::ItsGroup(its_group) => assert_eq!(its_group.its_ids().collect::<Vec<u32>>(), [0]),
+            _ => panic!("Expected an ITS group"),
+        }
+        match nodes[1] {
+            IortNode::SmmuV3(smmu) => {
+                assert_eq!(smmu.base_address(), 0x0900_0000);
+                assert!(smmu.is_coherent());
+                assert_eq!(smmu.proximity_domain(), Some(1));
+                assert_eq!(smmu.event_gsiv(), 106);
+            }
+            _ => panic!("Expected an SMMUv3"),
+        }
+        match nodes[2] {
+            IortNode::RootComplex(root_complex) => {
+                assert!(root_complex.supports_ats());
+                assert_eq!(root_complex.memory_address_size_limit(), 48);
+            }
+            _ => panic!("Expected a root complex"),
+        }
+        match nodes[3] {
+            IortNode::NamedComponent(component) => assert_eq!(component.object_name(), Some("\\_SB.ETH0")),
+            _ => panic!("Expected a named component"),
+        }
+    }
+
+    #[test]
+    fn test_resolve_mapping() {
+        let table = make_iort();
+        let iort = table_from_bytes::<Iort>(&table);
+        let nodes: Vec<IortNode> = iort.nodes().collect();
+
+        match iort.resolve_mapping(nodes[2], 0x0008) {
+            Some((IortNode::ItsGroup(_), device_id)) => assert_eq!(device_id, 0x10108),
+            _ => panic!("Expected the RequesterID to be mapped to an ITS"),
+        }
+        match iort.resolve_mapping(nodes[3], 0) {
+            Some((IortNode::ItsGroup(_), device_id)) => assert_eq!(device_id, 0x10020),
+            _ => panic!("Expected the named component's ID to be mapped to an ITS"),
+        }
+        assert!(iort.resolve_mapping(nodes[2], 0x10000).is_none());
+        assert!(iort.resolve_mapping(nodes[0], 0).is_none());
+    }
+}
@@ -0,0 +1,597 @@
+use crate::{
+    entry_as,
+    parse_warning,
+    sdt::{SdtHeader, Signature},
+    AcpiTable,
+    EntryIter,
+    ParseWarning,
+};
+use bit_field::BitField;
+use core::{convert::TryInto, mem, ops::RangeInclusive};
+
+/// Represents the I/O Virtualization Reporting Structure (IVRS). This describes the AMD I/O Virtualization
+/// Technology (AMD-Vi) IOMMUs present on a platform, the devices they manage, and memory that the IOMMUs must
+/// treat specially. The fixed fields are followed by a list of variable-length I/O Virtualization Definition
+/// Blocks (IVDBs).
+#[repr(C, packed)]
+pub struct Ivrs {
+    header: SdtHeader,
+    iv_info: u32,
+    _reserved: u64,
+}
+
+impl AcpiTable for Ivrs {
+    fn header(&self) -> &SdtHeader {
+        &self.header
+    }
+}
+
+impl Ivrs {
+    /// If true, the IOMMUs support the Extended Feature Register, and the `EFRSup` field of each type 11h and
+    /// 40h IVHD block is valid.
+    pub fn efr_supported(&self) -> bool {
+        { self.iv_info }.get_bit(0)
+    }
+
+    /// The encoded size of the guest virtual addresses supported by the IOMMUs. A value of `0b010` means 48
+    /// bits, and all other values are reserved.
+    pub fn guest_virtual_address_size(&self) -> u8 {
+        { self.iv_info }.get_bits(5..8) as u8
+    }
+
+    /// The size of the physical addresses supported by the IOMMUs, in bits.
+    pub fn physical_address_size(&self) -> u8 {
+        { self.iv_info }.get_bits(8..15) as u8
+    }
+
+    /// The size of the virtual addresses supported by the IOMMUs, in bits.
+    pub fn virtual_address_size(&self) -> u8 {
+        { self.iv_info }.get_bits(15..22) as u8
+    }
+
+    /// Iterate over the I/O Virtualization Hardware Definition (IVHD) blocks, each of which describes a single
+    /// IOMMU and the devices it manages. A platform with a single IOMMU will often list it several times, in
+    /// blocks of different types, and the OS should use the block with the newest type that it supports.
+    pub fn hardware_definitions(&self) -> impl Iterator<Item = HardwareDefinition<'_>> + '_ {
+        self.blocks().filter_map(|(block_type, block)| {
+            if !matches!(block_type, IVHD_TYPE_10 | IVHD_TYPE_11 | IVHD_TYPE_40) {
+                return None;
+            }
+
+            let entry = unsafe { entry_as::<IvhdEntry>(block) };
+            let (extended_feature_register, entries_offset) = match block_type {
+                IVHD_TYPE_10 => (None, mem::size_of::<IvhdEntry>()),
+                _ => {
+                    let extended = unsafe { entry_as::<ExtendedIvhdEntry>(block) };
+                    (Some(extended.efr_register_image), mem::size_of::<ExtendedIvhdEntry>())
+                }
+            };
+            let device_entries = &block[entries_offset..];
+
+            Some(HardwareDefinition {
+                block_type,
+                flags: entry.header.flags,
+                device_id: entry.device_id,
+                capability_offset: entry.capability_offset,
+                base_address: entry.iommu_base_address,
+                pci_segment: entry.pci_segment_group,
+                iommu_info: entry.iommu_info,
+                iommu_attributes: entry.iommu_attributes,
+                extended_feature_register,
+                device_entries,
+            })
+        })
+    }
+
+    /// Iterate over the memory regions described by the I/O Virtualization Memory Definition (IVMD) blocks. These
+    /// are the AMD equivalent of the DMAR's Reserved Memory Region Reporting structures, and describe memory that
+    /// is used by devices (often firmware-initiated DMA) and so must be identity-mapped in the IOMMU's page tables
+    /// for those devices (or excluded from translation altogether, if the block describes an exclusion range).
+    pub fn unity_maps(&self) -> impl Iterator<Item = UnityMap> + '_ {
+        self.blocks().filter_map(|(block_type, block)| {
+            if !matches!(block_type, IVMD_ALL_PERIPHERALS | IVMD_SPECIFIED_PERIPHERAL | IVMD_PERIPHERAL_RANGE) {
+                return None;
+            }
+
+            let entry = unsafe { entry_as::<IvmdEntry>(block) };
+            let devices = match block_type {
+                IVMD_ALL_PERIPHERALS => 0..=u16::MAX,
+                IVMD_SPECIFIED_PERIPHERAL => entry.device_id..=entry.device_id,
+                _ => entry.device_id..=entry.auxiliary_data,
+            };
+
+            Some(UnityMap {
+                devices,
+                base_address: entry.start_address,
+                length: entry.memory_block_length,
+                flags: IvmdFlags(entry.header.flags),
+            })
+        })
+    }
+
+    /// Iterate over the I/O Virtualization Definition Blocks, yielding the type of each block and its bytes.
+    fn blocks(&self) -> impl Iterator<Item = (u8, &[u8])> {
+        let blocks: EntryIter<'_, IvdbHeader> = unsafe {
+            EntryIter::new(
+                Signature::IVRS,
+                (self as *const Ivrs as *const u8).add(mem::size_of::<Ivrs>()),
+                self.header.length.saturating_sub(mem::size_of::<Ivrs>() as u32),
+                block_minimum_length,
+            )
+        };
+        blocks.map(|(block_type, block)| (block_type as u8, block))
+    }
+}
+
+const IVHD_TYPE_10: u8 = 0x10;
+const IVHD_TYPE_11: u8 = 0x11;
+const IVHD_TYPE_40: u8 = 0x40;
+const IVMD_ALL_PERIPHERALS: u8 = 0x20;
+const IVMD_SPECIFIED_PERIPHERAL: u8 = 0x21;
+const IVMD_PERIPHERAL_RANGE: u8 = 0x22;
+
+#[derive(Clone, Copy)]
+#[repr(C, packed)]
+struct IvdbHeader {
+    block_type: u8,
+    flags: u8,
+    length: u16,
+}
+
+impl crate::EntryHeader for IvdbHeader {
+    fn entry_type(&self) -> u16 {
+        self.block_type.into()
+    }
+
+    fn length(&self) -> u32 {
+        self.length.into()
+    }
+}
+
+fn block_minimum_length(header: &IvdbHeader) -> usize {
+    match header.block_type {
+        IVHD_TYPE_10 => mem::size_of::<IvhdEntry>(),
+        IVHD_TYPE_11 | IVHD_TYPE_40 => mem::size_of::<ExtendedIvhdEntry>(),
+        IVMD_ALL_PERIPHERALS | IVMD_SPECIFIED_PERIPHERAL | IVMD_PERIPHERAL_RANGE => mem::size_of::<IvmdEntry>(),
+        _ => mem::size_of::<IvdbHeader>(),
+    }
+}
+
+#[repr(C, packed)]
+struct IvhdEntry {
+    header: IvdbHeader,
+    /// The device ID of the IOMMU itself.
+    device_id: u16,
+    capability_offset: u16,
+    iommu_base_address: u64,
+    pci_segment_group: u16,
+    iommu_info: u16,
+    /// For type 10h blocks, this is the IOMMU Feature Reporting field. For type 11h and 40h blocks, it's the
+    /// IOMMU Attributes field.
+    iommu_attributes: u32,
+}
+
+#[repr(C, packed)]
+struct ExtendedIvhdEntry {
+    entry: IvhdEntry,
+    efr_register_image: u64,
+    _reserved: u64,
+}
+
+#[repr(C, packed)]
+struct IvmdEntry {
+    header: IvdbHeader,
+    /// For IVMD blocks that apply to a single device, its device ID. For those that apply to a range of devices,
+    /// the first device ID in the range.
+    device_id: u16,
+    /// For IVMD blocks that apply to a range of devices, the last device ID in the range.
+    auxiliary_data: u16,
+    _reserved: u64,
+    start_address: u64,
+    memory_block_length: u64,
+}
+
+/// A region of memory described by an IVMD block, which the IOMMU must identity-map (or exclude from translation)
+/// for the given devices.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnityMap {
+    /// The range of device IDs (in the form `bus << 8 | device << 3 | function`) this region applies to.
+    pub devices: RangeInclusive<u16>,
+    pub base_address: u64,
+    pub length: u64,
+    pub flags: IvmdFlags,
+}
+
+/// An IOMMU described by an IVHD block.
+#[derive(Clone, Debug)]
+pub struct HardwareDefinition<'a> {
+    /// The type of the IVHD block (`0x10`, `0x11`, or `0x40`). Type `0x40` blocks can also contain ACPI HID
+    /// device entries.
+    pub block_type: u8,
+    pub flags: u8,
+    /// The device ID (in the form `bus << 8 | device << 3 | function`) of the IOMMU itself.
+    pub device_id: u16,
+    /// The offset of the IOMMU's capability block within its PCI configuration space.
+    pub capability_offset: u16,
+    /// The physical address of the IOMMU's MMIO registers.
+    pub base_address: u64,
+    pub pci_segment: u16,
+    pub iommu_info: u16,
+    /// For type 10h blocks, the IOMMU Feature Reporting field. For type 11h and 40h blocks, the IOMMU Attributes
+    /// field.
+    pub iommu_attributes: u32,
+    /// The value of the IOMMU's Extended Feature Register, for type 11h and 40h blocks.
+    pub extended_feature_register: Option<u64>,
+    device_entries: &'a [u8],
+}
+
+impl<'a> HardwareDefinition<'a> {
+    /// Iterate over the device entries, which describe the devices the IOMMU manages, and how the IOMMU should
+    /// treat their requests.
+    pub fn device_entries(&self) -> DeviceEntryIter<'a> {
+        DeviceEntryIter { bytes: self.device_entries }
+    }
+}
+
+/// The settings that should be used for a device's Device Table Entry. The bits are laid out in the same way as
+/// the `Data Setting` field of the device entries.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DteSettings(pub u8);
+
+impl DteSettings {
+    /// If true, the device can send INIT interrupts through the IOMMU.
+    pub fn init_pass(&self) -> bool {
+        self.0.get_bit(0)
+    }
+
+    pub fn ext_int_pass(&self) -> bool {
+        self.0.get_bit(1)
+    }
+
+    pub fn nmi_pass(&self) -> bool {
+        self.0.get_bit(2)
+    }
+
+    pub fn system_management(&self) -> u8 {
+        self.0.get_bits(4..6)
+    }
+
+    pub fn lint0_pass(&self) -> bool {
+        self.0.get_bit(6)
+    }
+
+    pub fn lint1_pass(&self) -> bool {
+        self.0.get_bit(7)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpecialDeviceVariety {
+    IoApic,
+    Hpet,
+    Reserved(u8),
+}
+
+/// A device entry from an IVHD block. Ranges of devices are described by a start-of-range entry, followed by an
+/// end-of-range entry - these are combined into a single `DeviceEntry`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DeviceEntry<'a> {
+    /// The settings apply to every device managed by the IOMMU.
+    All {
+        settings: DteSettings,
+    },
+    Select {
+        device_id: u16,
+        settings: DteSettings,
+    },
+    Range {
+        devices: RangeInclusive<u16>,
+        settings: DteSettings,
+    },
+    /// The device's requests appear to the IOMMU with the ID `source_id`, instead of its own.
+    AliasSelect {
+        device_id: u16,
+        settings: DteSettings,
+        source_id: u16,
+    },
+    AliasRange {
+        devices: RangeInclusive<u16>,
+        settings: DteSettings,
+        source_id: u16,
+    },
+    ExtendedSelect {
+        device_id: u16,
+        settings: DteSettings,
+        extended_settings: u32,
+    },
+    ExtendedRange {
+        devices: RangeInclusive<u16>,
+        settings: DteSettings,
+        extended_settings: u32,
+    },
+    /// Describes the device ID used by a device that isn't a PCI function, such as an IOAPIC or HPET. `handle`
+    /// is the IOAPIC ID or HPET number.
+    Special {
+        handle: u8,
+        device_id: u16,
+        variety: SpecialDeviceVariety,
+        settings: DteSettings,
+    },
+    /// A device described in the namespace by its hardware ID. `uid` is the raw contents of the UID field, the
+    /// format of which depends on the firmware.
+    AcpiHid {
+        device_id: u16,
+        settings: DteSettings,
+        hid: &'a [u8],
+        cid: &'a [u8],
+        uid: &'a [u8],
+    },
+}
+
+const DEVICE_ENTRY_ALL: u8 = 0x01;
+const DEVICE_ENTRY_SELECT: u8 = 0x02;
+const DEVICE_ENTRY_RANGE_START: u8 = 0x03;
+const DEVICE_ENTRY_RANGE_END: u8 = 0x04;
+const DEVICE_ENTRY_ALIAS_SELECT: u8 = 0x42;
+const DEVICE_ENTRY_ALIAS_RANGE_START: u8 = 0x43;
+const DEVICE_ENTRY_EXTENDED_SELECT: u8 = 0x46;
+const DEVICE_ENTRY_EXTENDED_RANGE_START: u8 = 0x47;
+const DEVICE_ENTRY_SPECIAL: u8 = 0x48;
+const DEVICE_ENTRY_ACPI_HID: u8 = 0xf0;
+
+pub struct DeviceEntryIter<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> DeviceEntryIter<'a> {
+    /// Split the next raw entry off the list. Apart from ACPI HID entries, the size of each entry is encoded in
+    /// the top two bits of its type.
+    fn next_raw(&mut self) -> Option<&'a [u8]> {
+        let entry_type = *self.bytes.first()?;
+        let length = match entry_type {
+            DEVICE_ENTRY_ACPI_HID => self.bytes.get(21).map_or(usize::MAX, |&uid_length| 22 + uid_length as usize),
+            _ => 4 << (entry_type >> 6),
+        };
+
+        if length > self.bytes.len() {
+            parse_warning(ParseWarning::InvalidEntryLength {
+                signature: Signature::IVRS,
+                entry_type: entry_type.into(),
+                length: length.try_into().unwrap_or(u32::MAX),
+            });
+            self.bytes = &[];
+            return None;
+        }
+
+        let (entry, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+        Some(entry)
+    }
+}
+
+impl<'a> Iterator for DeviceEntryIter<'a> {
+    type Item = DeviceEntry<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let entry = self.next_raw()?;
+            let device_id = u16::from_le_bytes([entry[1], entry[2]]);
+            let settings = DteSettings(entry[3]);
+
+            /*
+             * A start-of-range entry must be followed by an end-of-range entry, which gives the last device ID in
+             * the range. If it isn't, we skip the start of the range.
+             */
+            let mut range_end = || match self.bytes.first() {
+                Some(&DEVICE_ENTRY_RANGE_END) => {
+                    self.next_raw().map(|end| device_id..=u16::from_le_bytes([end[1], end[2]]))
+                }
+                _ => None,
+            };
+
+            match entry[0] {
+                DEVICE_ENTRY_ALL => return Some(DeviceEntry::All { settings }),
+                DEVICE_ENTRY_SELECT => return Some(DeviceEntry::Select { device_id, settings }),
+                DEVICE_ENTRY_RANGE_START => {
+                    if let Some(devices) = range_end() {
+                        return Some(DeviceEntry::Range { devices, settings });
+                    }
+                }
+                DEVICE_ENTRY_ALIAS_SELECT => {
+                    let source_id = u16::from_le_bytes([entry[5], entry[6]]);
+                    return Some(DeviceEntry::AliasSelect { device_id, settings, source_id });
+                }
+                DEVICE_ENTRY_ALIAS_RANGE_START => {
+                    let source_id = u16::from_le_bytes([entry[5], entry[6]]);
+                    if let Some(devices) = range_end() {
+                        return Some(DeviceEntry::AliasRange { devices, settings, source_id });
+                    }
+                }
+                DEVICE_ENTRY_EXTENDED_SELECT => {
+                    let extended_settings = u32::from_le_bytes(entry[4..8].try_into().unwrap());
+                    return Some(DeviceEntry::ExtendedSelect { device_id, settings, extended_settings });
+                }
+                DEVICE_ENTRY_EXTENDED_RANGE_START => {
+                    let extended_settings = u32::from_le_bytes(entry[4..8].try_into().unwrap());
+                    if let Some(devices) = range_end() {
+                        return Some(DeviceEntry::ExtendedRange { devices, settings, extended_settings });
+                    }
+                }
+                DEVICE_ENTRY_SPECIAL => {
+                    let variety = match entry[7] {
+                        1 => SpecialDeviceVariety::IoApic,
+                        2 => SpecialDeviceVariety::Hpet,
+                        other => SpecialDeviceVariety::Reserved(other),
+                    };
+                    return Some(DeviceEntry::Special {
+                        handle: entry[4],
+                        device_id: u16::from_le_bytes([entry[5], entry[6]]),
+                        variety,
+                        settings,
+                    });
+                }
+                DEVICE_ENTRY_ACPI_HID => {
+                    return Some(DeviceEntry::AcpiHid {
+                        device_id,
+                        settings,
+                        hid: &entry[4..12],
+                        cid: &entry[12..20],
+                        uid: &entry[22..],
+                    })
+                }
+
+                /*
+                 * Padding entries, stray end-of-range entries, and reserved entry types are skipped.
+                 */
+                _ => continue,
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IvmdFlags(u8);
+
+impl IvmdFlags {
+    /// If true, the region must be identity-mapped using the permissions described by `read_permission` and
+    /// `write_permission`.
+    pub fn unity(&self) -> bool {
+        self.0.get_bit(0)
+    }
+
+    /// If true, devices may read from the region.
+    pub fn read_permission(&self) -> bool {
+        self.0.get_bit(1)
+    }
+
+    /// If true, devices may write to the region.
+    pub fn write_permission(&self) -> bool {
+        self.0.get_bit(2)
+    }
+
+    /// If true, accesses by the devices to the region are not translated by the IOMMU at all.
+    pub fn exclusion_range(&self) -> bool {
+        self.0.get_bit(3)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{make_sdt, table_from_bytes};
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_unity_maps() {
+        let mut body = Vec::new();
+        body.extend_from_slice(&0x0020_3040u32.to_le_bytes()); // IVinfo
+        body.extend_from_slice(&[0; 8]);
+
+        // An IVHD block with no device entries, which should be skipped
+        body.extend_from_slice(&[0x10, 0xb0, 0x18, 0x00]);
+        body.extend_from_slice(&[0; 20]);
+
+        // An IVMD block covering devices 00:14.0 to 00:14.7, with read and write permissions
+        body.extend_from_slice(&[0x22, 0b0111, 0x20, 0x00]);
+        body.extend_from_slice(&0x00a0u16.to_le_bytes());
+        body.extend_from_slice(&0x00a7u16.to_le_bytes());
+        body.extend_from_slice(&[0; 8]);
+        body.extend_from_slice(&0x7a00_0000u64.to_le_bytes());
+        body.extend_from_slice(&0x0010_0000u64.to_le_bytes());
+
+        let table = make_sdt(b"IVRS", 2, &body);
+        let ivrs = table_from_bytes::<Ivrs>(&table);
+        let maps: Vec<UnityMap> = ivrs.unity_maps().collect();
+
+        assert_eq!(maps.len(), 1);
+        assert_eq!(maps[0].devices, 0xa0..=0xa7);
+        assert_eq!(maps[0].base_address, 0x7a00_0000);
+        assert_eq!(maps[0].length, 0x0010_0000);
+        assert!(maps[0].flags.unity());
+        assert!(maps[0].flags.read_permission());
+        assert!(maps[0].flags.write_permission());
+        assert!(!maps[0].flags.exclusion_range());
+    }
+
+    #[test]
+    fn test_hardware_definitions() {
+        let mut body = Vec::new();
+        body.extend_from_slice(&0x0020_3040u32.to_le_bytes()); // IVinfo
+        body.extend_from_slice(&[0; 8]);
+
+        // A type 11h IVHD block for an IOMMU at 00:00.2
+        let mut ivhd = Vec::new();
+        ivhd.extend_from_slice(&0x0002u16.to_le_bytes());
+        ivhd.extend_from_slice(&0x0040u16.to_le_bytes());
+        ivhd.extend_from_slice(&0xfd20_0000u64.to_le_bytes());
+        ivhd.extend_from_slice(&0u16.to_le_bytes());
+        ivhd.extend_from_slice(&0u16.to_le_bytes());
+        ivhd.extend_from_slice(&0u32.to_le_bytes());
+        ivhd.extend_from_slice(&0x0000_0000_2a00_0000u64.to_le_bytes());
+        ivhd.extend_from_slice(&[0; 8]);
+        // Devices 00:00.0 to 00:14.7, with INIT passthrough
+        ivhd.extend_from_slice(&[0x03, 0x00, 0x00, 0x01]);
+        ivhd.extend_from_slice(&[0x04, 0xa7, 0x00, 0x00]);
+        // Padding, which should be skipped
+        ivhd.extend_from_slice(&[0x00, 0x00, 0x00, 0x00]);
+        // IOAPIC 0x21, which uses device 00:14.0
+        ivhd.extend_from_slice(&[0x48, 0x00, 0x00, 0xd7, 0x21, 0xa0, 0x00, 0x01]);
+        // Device 01:00.0, which appears as 00:01.1
+        ivhd.extend_from_slice(&[0x42, 0x00, 0x01, 0x00, 0x00, 0x09, 0x00, 0x00]);
+        body.extend_from_slice(&[0x11, 0xb0]);
+        body.extend_from_slice(&(4 + ivhd.len() as u16).to_le_bytes());
+        body.extend(ivhd);
+
+        let table = make_sdt(b"IVRS", 2, &body);
+        let ivrs = table_from_bytes::<Ivrs>(&table);
+        assert_eq!(ivrs.physical_address_size(), 0x30);
+        assert_eq!(ivrs.virtual_address_size(), 0x40);
+        assert_eq!(ivrs.guest_virtual_address_size(), 0b010);
+        assert!(!ivrs.efr_supported());
+
+        let iommus: Vec<HardwareDefinition> = ivrs.hardware_definitions().collect();
+        assert_eq!(iommus.len(), 1);
+        assert_eq!(iommus[0].block_type, 0x11);
+        assert_eq!(iommus[0].device_id, 0x0002);
+        assert_eq!(iommus[0].capability_offset, 0x40);
+        assert_eq!(iommus[0].base_address, 0xfd20_0000);
+        assert_eq!(iommus[0].pci_segment, 0);
+        assert_eq!(iommus[0].extended_feature_register, Some(0x2a00_0000));
+
+        let entries: Vec<DeviceEntry> = iommus[0].device_entries().collect();
+        assert_eq!(
+            entries,
+            [
+                DeviceEntry::Range { devices: 0x0000..=0x00a7, settings: DteSettings(0x01) },
+                DeviceEntry::Special {
+                    handle: 0x21,
+                    device_id: 0x00a0,
+                    variety: SpecialDeviceVariety::IoApic,
+                    settings: DteSettings(0xd7),
+                },
+                DeviceEntry::AliasSelect { device_id: 0x0100, settings: DteSettings(0x00), source_id: 0x0009 },
+            ]
+        );
+        assert!(DteSettings(0xd7).init_pass());
+        assert!(DteSettings(0xd7).lint1_pass());
+    }
+
+    #[test]
+    fn test_truncated_device_entry() {
+        let mut body = Vec::new();
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body.extend_from_slice(&[0; 8]);
+        body.extend_from_slice(&[0x10, 0x00, 0x20, 0x00]);
+        body.extend_from_slice(&[0; 20]);
+        // A select entry, followed by an alias select entry that's cut off by the end of the block
+        body.extend_from_slice(&[0x02, 0x08, 0x00, 0x00]);
+        body.extend_from_slice(&[0x42, 0x00, 0x01, 0x00]);
+
+        let table = make_sdt(b"IVRS", 2, &body);
+        let ivrs = table_from_bytes::<Ivrs>(&table);
+        let iommu = ivrs.hardware_definitions().next().unwrap();
+        assert_eq!(iommu.extended_feature_register, None);
+        let entries: Vec<DeviceEntry> = iommu.device_entries().collect();
+        assert_eq!(entries, [DeviceEntry::Select { device_id: 0x0008, settings: DteSettings(0) }]);
+    }
+}
@@ -7,13 +7,23 @@
 //! the `aml` crate, which is the (much less complete) AML parser used to parse the DSDT and SSDTs. These crates
 //! are separate because some kernels may want to detect the static tables, but delay AML parsing to a later stage.
 //!
-//! This crate requires `alloc` to make heap allocations. If you are trying to find the RSDP in an environment that
-//! does not have a heap (e.g. a bootloader), you can use the `rsdp` crate. The types from that crate are
-//! compatible with `acpi`.
+//! By default, this crate requires `alloc` to make heap allocations. In an environment that does not have a heap
+//! (e.g. a bootloader), the default `alloc` feature can be disabled. `AcpiTables` can still be constructed, and
+//! tables found with `get_sdt`, `get_sdts` and `headers`, but it then walks the RSDT/XSDT on each lookup instead
+//! of indexing the tables up front. The fixed-size tables (such as the FADT, HPET and BGRT), the iterators over
+//! the entries of the variable-length tables, and `PmTimer` are all available, but the types that collect
+//! information into `Vec`s (`PlatformInfo`, `InterruptModel`, `PciConfigRegions`, `DistanceMatrix` and
+//! `NvdimmStructures`) are not. If you only need to find the RSDP, you can also use the `rsdp` crate directly. The
+//! types from that crate are compatible with `acpi`.
+//!
+//! The `testing` feature enables the [`testing`](crate::testing) module, which helps with testing code that
+//! consumes ACPI tables by providing an `AcpiHandler` over tables built in memory.
 //!
 //! ### Usage
 //! To use the library, you will need to provide an implementation of the `AcpiHandler` trait, which allows the
 //! library to make requests such as mapping a particular region of physical memory into the virtual address space.
+//! If all of physical memory is already mapped at a fixed offset (as in many higher-half kernels), the provided
+//! `OffsetMapper` can be used instead.
 //!
 //! You then need to construct an instance of `AcpiTables`, which can be done in a few ways depending on how much
 //! information you have:
@@ -50,34 +60,90 @@
 #![no_std]
 #![deny(unsafe_op_in_unsafe_fn)]
 
+#[cfg(any(feature = "alloc", test))]
 extern crate alloc;
 #[cfg_attr(test, macro_use)]
 #[cfg(test)]
 extern crate std;
 
+#[cfg(test)]
+mod test_utils;
+
+pub mod aest;
+pub mod apei;
+pub mod bert;
+pub mod bgrt;
+pub mod cedt;
+pub mod csrt;
+pub mod dbg2;
+pub mod dmar;
+pub mod drtm;
+pub mod ecdt;
+pub mod einj;
+pub mod erst;
+pub mod facs;
 pub mod fadt;
+pub mod fpdt;
+pub mod gtdt;
+pub mod hest;
+pub mod hmat;
 pub mod hpet;
+pub mod iort;
+pub mod ivrs;
+pub mod lpit;
 pub mod madt;
 pub mod mcfg;
+pub mod mpst;
+pub mod msct;
+pub mod nfit;
+pub mod pcct;
+pub mod phat;
 pub mod platform;
+pub mod pptt;
+pub mod prmt;
+pub mod rasf;
+pub mod rhct;
+pub mod sbst;
+pub mod sdei;
 pub mod sdt;
+pub mod slit;
+pub mod spcr;
+pub mod srat;
+#[cfg(any(feature = "testing", test))]
+pub mod testing;
+pub mod tpm2;
+pub mod waet;
+pub mod wdat;
+pub mod wsmt;
 
+pub use crate::{fadt::PowerProfile, hpet::HpetInfo, madt::MadtError};
+#[cfg(feature = "alloc")]
 pub use crate::{
-    fadt::PowerProfile,
-    hpet::HpetInfo,
-    madt::MadtError,
     mcfg::PciConfigRegions,
-    platform::{interrupt::InterruptModel, PlatformInfo},
+    platform::{interrupt::InterruptModel, NumaInfo, PlatformInfo},
 };
 pub use rsdp::{
-    handler::{AcpiHandler, PhysicalMapping},
+    handler::{AcpiHandler, OffsetMapper, PhysicalMapping, PortIo},
     RsdpError,
 };
 
-use crate::sdt::{SdtHeader, Signature};
+use crate::{
+    platform::address::AddressSpace,
+    sdt::{SdtHeader, Signature},
+};
+#[cfg(feature = "alloc")]
 use alloc::{collections::BTreeMap, vec::Vec};
-use core::mem;
+use core::{
+    fmt,
+    marker::PhantomData,
+    mem,
+    ptr,
+    slice,
+    sync::atomic::{AtomicPtr, Ordering},
+};
+#[cfg(feature = "alloc")]
 use log::trace;
+use log::warn;
 use rsdp::Rsdp;
 
 #[derive(Debug)]
@@ -90,10 +156,214 @@ pub enum AcpiError {
     SdtInvalidChecksum(Signature),
 
     TableMissing(Signature),
+    /// The table with the given signature is too short to hold the fields that are always present in it.
+    TableMalformed(Signature),
     InvalidFacsAddress,
+    UnsupportedWakingVector,
     InvalidDsdtAddress,
     InvalidMadt(MadtError),
     InvalidGenericAddress,
+    UnsupportedAddressSpace(AddressSpace),
+    ResetNotSupported,
+    InvalidBootImage,
+    AcpiModeTimeout,
+    /// A buffer provided to build a table in was too small to hold it.
+    BufferTooSmall,
+}
+
+impl fmt::Display for AcpiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AcpiError::Rsdp(err) => write!(f, "{}", err),
+            AcpiError::SdtInvalidSignature(signature) => {
+                write!(f, "{} table has an incorrect signature", signature)
+            }
+            AcpiError::SdtInvalidOemId(signature) => {
+                write!(f, "{} table has an OEM ID that isn't valid UTF-8", signature)
+            }
+            AcpiError::SdtInvalidTableId(signature) => {
+                write!(f, "{} table has an OEM table ID that isn't valid UTF-8", signature)
+            }
+            AcpiError::SdtInvalidChecksum(signature) => write!(f, "{} table has an invalid checksum", signature),
+            AcpiError::TableMissing(signature) => write!(f, "couldn't find a {} table", signature),
+            AcpiError::TableMalformed(signature) => write!(f, "{} table is too short", signature),
+            AcpiError::InvalidFacsAddress => write!(f, "FADT has an invalid FACS address"),
+            AcpiError::UnsupportedWakingVector => write!(f, "FACS waking vector is not supported"),
+            AcpiError::InvalidDsdtAddress => write!(f, "FADT has an invalid DSDT address"),
+            AcpiError::InvalidMadt(err) => write!(f, "invalid MADT: {}", err),
+            AcpiError::InvalidGenericAddress => write!(f, "invalid generic address structure"),
+            AcpiError::UnsupportedAddressSpace(address_space) => {
+                write!(f, "unsupported address space: {}", address_space)
+            }
+            AcpiError::ResetNotSupported => write!(f, "platform doesn't support resetting through the FADT"),
+            AcpiError::InvalidBootImage => write!(f, "BGRT describes an invalid boot image"),
+            AcpiError::AcpiModeTimeout => write!(f, "timed out waiting for the platform to enter ACPI mode"),
+            AcpiError::BufferTooSmall => write!(f, "buffer is too small to hold the table"),
+        }
+    }
+}
+
+impl core::error::Error for AcpiError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            AcpiError::Rsdp(err) => Some(err),
+            AcpiError::InvalidMadt(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// Describes a problem found in a table that is not serious enough to stop the library from using the table. These
+/// are always logged, and can also be collected by installing a handler with [`set_parse_warning_handler`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseWarning {
+    /// An entry in a variable-length list within the table with the given signature has a length that is too
+    /// short to be valid, or extends past the end of its parent structure. The entry, and any entries after it in
+    /// the same list, are skipped.
+    InvalidEntryLength { signature: Signature, entry_type: u16, length: u32 },
+    /// An entry in a variable-length list within the table with the given signature has a type that the library
+    /// doesn't recognise. It's either reserved, or describes a structure the library doesn't support yet. The entry
+    /// is skipped.
+    UnknownEntryType { signature: Signature, entry_type: u16 },
+    /// The table with the given signature is `length` bytes long, but its fields say that it should be at least
+    /// `expected_length` bytes long. The data that would lie past the end of the table is treated as missing.
+    TruncatedTable { signature: Signature, length: u32, expected_length: u64 },
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseWarning::InvalidEntryLength { signature, entry_type, length } => write!(
+                f,
+                "Invalid entry of type {:#x} with length {} in {} table, skipping the rest of its list",
+                entry_type, length, signature
+            ),
+            ParseWarning::UnknownEntryType { signature, entry_type } => {
+                write!(f, "Unknown entry of type {:#x} in {} table, skipping it", entry_type, signature)
+            }
+            ParseWarning::TruncatedTable { signature, length, expected_length } => write!(
+                f,
+                "{} table is {} bytes long, but should be at least {} bytes long",
+                signature, length, expected_length
+            ),
+        }
+    }
+}
+
+static PARSE_WARNING_HANDLER: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+/// Install a handler that is called with each [`ParseWarning`] the library produces, in addition to it being
+/// logged. This is useful if you want to handle malformed tables in a structured way (e.g. to check a set of
+/// tables produces the expected warnings in a test). Passing `None` removes the handler. Like a logger, the
+/// handler is global, and so may be called from any thread that is using the library.
+pub fn set_parse_warning_handler(handler: Option<fn(&ParseWarning)>) {
+    let handler = handler.map_or(ptr::null_mut(), |handler| handler as *mut ());
+    PARSE_WARNING_HANDLER.store(handler, Ordering::Release);
+}
+
+/// Log a `ParseWarning`, and pass it to the installed warning handler, if there is one.
+pub(crate) fn parse_warning(warning: ParseWarning) {
+    warn!("{}", warning);
+
+    let handler = PARSE_WARNING_HANDLER.load(Ordering::Acquire);
+    if !handler.is_null() {
+        let handler = unsafe { mem::transmute::<*mut (), fn(&ParseWarning)>(handler) };
+        handler(&warning);
+    }
+}
+
+/// The header of each entry in one of the lists of variable-length entries that many tables contain. The header
+/// can be the whole fixed part of the entry, if the minimum length of the entry depends on more than its type.
+pub(crate) trait EntryHeader {
+    fn entry_type(&self) -> u16;
+    fn length(&self) -> u32;
+}
+
+/// Check that an entry that claims to be `length` bytes long is at least `minimum_length` bytes long, and fits in
+/// the `remaining_length` bytes left in its list. Otherwise, a `ParseWarning::InvalidEntryLength` is produced.
+pub(crate) fn check_entry_length(
+    signature: Signature,
+    entry_type: u16,
+    length: u32,
+    minimum_length: usize,
+    remaining_length: u32,
+) -> bool {
+    if (length as usize) < minimum_length || length > remaining_length {
+        parse_warning(ParseWarning::InvalidEntryLength { signature, entry_type, length });
+        return false;
+    }
+
+    true
+}
+
+/// Walks a list of variable-length entries with headers of type `H`, producing the type and bytes of each entry.
+/// If an entry is shorter than the minimum length for its type, or extends past the end of the list, a
+/// `ParseWarning::InvalidEntryLength` is produced, and the rest of the list is skipped. Entries of every type are
+/// produced, so the table can decide which to skip.
+pub(crate) struct EntryIter<'a, H> {
+    signature: Signature,
+    pointer: *const u8,
+    remaining_length: u32,
+    minimum_length: fn(&H) -> usize,
+    _phantom: PhantomData<&'a H>,
+}
+
+impl<'a, H> EntryIter<'a, H>
+where
+    H: EntryHeader,
+{
+    /// Walk the list of `length` bytes at `pointer`, in the table with the given signature. `minimum_length`
+    /// gives the minimum length of an entry, from its header.
+    ///
+    /// ### Safety
+    /// `pointer` must point to at least `length` bytes, which must live for `'a`.
+    pub(crate) unsafe fn new(
+        signature: Signature,
+        pointer: *const u8,
+        length: u32,
+        minimum_length: fn(&H) -> usize,
+    ) -> EntryIter<'a, H> {
+        EntryIter { signature, pointer, remaining_length: length, minimum_length, _phantom: PhantomData }
+    }
+
+    /// The bytes of the list that haven't been walked yet. This is empty if an invalid entry has been found.
+    pub(crate) fn rest(&self) -> &'a [u8] {
+        unsafe { slice::from_raw_parts(self.pointer, self.remaining_length as usize) }
+    }
+}
+
+impl<'a, H> Iterator for EntryIter<'a, H>
+where
+    H: EntryHeader,
+{
+    type Item = (u16, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining_length < mem::size_of::<H>() as u32 {
+            return None;
+        }
+
+        let header = unsafe { &*(self.pointer as *const H) };
+        let (entry_type, length) = (header.entry_type(), header.length());
+        let minimum_length = usize::max((self.minimum_length)(header), mem::size_of::<H>());
+        if !check_entry_length(self.signature, entry_type, length, minimum_length, self.remaining_length) {
+            self.remaining_length = 0;
+            return None;
+        }
+
+        let entry = unsafe { slice::from_raw_parts(self.pointer, length as usize) };
+        self.pointer = unsafe { self.pointer.add(length as usize) };
+        self.remaining_length -= length;
+        Some((entry_type, entry))
+    }
+}
+
+/// Interpret the bytes of an entry produced by an [`EntryIter`] as a `T`.
+///
+/// ### Safety
+/// The entry must be at least as long as `T`, and `T` must correctly represent its structure.
+pub(crate) unsafe fn entry_as<T>(entry: &[u8]) -> &T {
+    unsafe { &*(entry.as_ptr() as *const T) }
 }
 
 pub struct AcpiTables<H>
@@ -102,9 +372,21 @@ where
 {
     /// The revision of ACPI that the system uses, as inferred from the revision of the RSDT/XSDT.
     pub revision: u8,
+    /// Whether the tables were found through the XSDT (rather than the RSDT).
+    uses_xsdt: bool,
+    #[cfg(feature = "alloc")]
     pub sdts: BTreeMap<sdt::Signature, Sdt>,
+    #[cfg(feature = "alloc")]
     pub dsdt: Option<AmlTable>,
+    #[cfg(feature = "alloc")]
     pub ssdts: Vec<AmlTable>,
+    /// Every table listed by the RSDT/XSDT, in the order they're listed. This means looking up tables by signature
+    /// doesn't need to map each of their headers again.
+    #[cfg(feature = "alloc")]
+    table_index: Vec<ListedTable>,
+    /// Without an allocator, the tables aren't indexed, so the RSDT/XSDT is walked each time one is needed.
+    #[cfg(not(feature = "alloc"))]
+    root_table_address: usize,
     handler: H,
 }
 
@@ -122,7 +404,8 @@ where
 
     /// Search for the RSDP on a BIOS platform. This accesses BIOS-specific memory locations and will probably not
     /// work on UEFI platforms. See [Rsdp::search_for_rsdp_bios](rsdp_search::Rsdp::search_for_rsdp_bios) for
-    /// details.
+    /// details. This is only available on x86 and x86_64.
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
     pub unsafe fn search_for_rsdp_bios(handler: H) -> Result<AcpiTables<H>, AcpiError> {
         let rsdp_mapping = unsafe { Rsdp::search_for_on_bios(handler.clone()) }.map_err(AcpiError::Rsdp)?;
         Self::from_validated_rsdp(handler, rsdp_mapping)
@@ -137,19 +420,20 @@ where
     ) -> Result<AcpiTables<H>, AcpiError> {
         let revision = rsdp_mapping.revision();
 
-        if revision == 0 {
+        if revision >= 2 && rsdp_mapping.xsdt_address() != 0 {
             /*
-             * We're running on ACPI Version 1.0. We should use the 32-bit RSDT address.
+             * We're running on ACPI Version 2.0+. We should use the 64-bit XSDT address, truncated
+             * to 32 bits on x86. The RSDT can't point to tables above 4GiB, so it mustn't be used instead.
              */
-            let rsdt_address = rsdp_mapping.rsdt_address();
-            unsafe { Self::from_rsdt(handler, revision, rsdt_address as usize) }
+            let xsdt_address = rsdp_mapping.xsdt_address();
+            unsafe { Self::from_root_table(handler, revision, xsdt_address as usize, true) }
         } else {
             /*
-             * We're running on ACPI Version 2.0+. We should use the 64-bit XSDT address, truncated
-             * to 32 bits on x86.
+             * We're running on ACPI Version 1.0 (or the firmware hasn't provided a XSDT). We should use the 32-bit
+             * RSDT address.
              */
-            let xsdt_address = rsdp_mapping.xsdt_address();
-            unsafe { Self::from_rsdt(handler, revision, xsdt_address as usize) }
+            let rsdt_address = rsdp_mapping.rsdt_address();
+            unsafe { Self::from_root_table(handler, revision, rsdt_address as usize, false) }
         }
     }
 
@@ -157,46 +441,68 @@ where
     /// bootloader reads the RSDP and passes you the address of the RSDT. You also need to supply the correct ACPI
     /// revision - if `0`, a RSDT is expected, while a `XSDT` is expected for greater revisions.
     pub unsafe fn from_rsdt(handler: H, revision: u8, rsdt_address: usize) -> Result<AcpiTables<H>, AcpiError> {
-        let mut result = AcpiTables { revision, sdts: BTreeMap::new(), dsdt: None, ssdts: Vec::new(), handler };
+        unsafe { Self::from_root_table(handler, revision, rsdt_address, revision != 0) }
+    }
+
+    /// Create an `AcpiTables` if you have the physical address of the XSDT, but not the RSDP. Some hypervisors
+    /// and boot protocols pass this to their guests directly. As the XSDT was introduced in ACPI Version 2.0,
+    /// `revision` will report `2`.
+    ///
+    /// ### Safety
+    /// `xsdt_address` must be the physical address of a valid XSDT, and the tables it points to must be mappable
+    /// with `handler`.
+    pub unsafe fn from_xsdt(handler: H, xsdt_address: usize) -> Result<AcpiTables<H>, AcpiError> {
+        unsafe { Self::from_root_table(handler, 2, xsdt_address, true) }
+    }
 
-        let header = sdt::peek_at_sdt_header(&result.handler, rsdt_address);
-        let mapping =
-            unsafe { result.handler.map_physical_region::<SdtHeader>(rsdt_address, header.length as usize) };
+    unsafe fn from_root_table(
+        handler: H,
+        revision: u8,
+        rsdt_address: usize,
+        uses_xsdt: bool,
+    ) -> Result<AcpiTables<H>, AcpiError> {
+        let header = sdt::peek_at_sdt_header(&handler, rsdt_address);
+        let mapping = unsafe { handler.map_physical_region::<SdtHeader>(rsdt_address, header.length as usize) };
 
-        if revision == 0 {
+        if !uses_xsdt {
             /*
              * ACPI Version 1.0. It's a RSDT!
              */
             mapping.validate(sdt::Signature::RSDT)?;
-
-            let num_tables = (mapping.length as usize - mem::size_of::<SdtHeader>()) / mem::size_of::<u32>();
-            let tables_base =
-                ((mapping.virtual_start().as_ptr() as usize) + mem::size_of::<SdtHeader>()) as *const u32;
-
-            for i in 0..num_tables {
-                result.process_sdt(unsafe { *tables_base.add(i) as usize })?;
-            }
         } else {
             /*
              * ACPI Version 2.0+. It's a XSDT!
              */
             mapping.validate(sdt::Signature::XSDT)?;
+        }
 
-            let num_tables = (mapping.length as usize - mem::size_of::<SdtHeader>()) / mem::size_of::<u64>();
-            let tables_base =
-                ((mapping.virtual_start().as_ptr() as usize) + mem::size_of::<SdtHeader>()) as *const u64;
-
-            for i in 0..num_tables {
-                result.process_sdt(unsafe { *tables_base.add(i) as usize })?;
+        #[cfg(feature = "alloc")]
+        {
+            let mut result = AcpiTables {
+                revision,
+                uses_xsdt,
+                sdts: BTreeMap::new(),
+                dsdt: None,
+                ssdts: Vec::new(),
+                table_index: Vec::new(),
+                handler,
+            };
+            for address in root_table_entries(mapping, uses_xsdt) {
+                result.process_sdt(address)?;
             }
+            Ok(result)
         }
 
-        Ok(result)
+        #[cfg(not(feature = "alloc"))]
+        {
+            Ok(AcpiTables { revision, uses_xsdt, root_table_address: rsdt_address, handler })
+        }
     }
 
     /// Construct an `AcpiTables` from a custom set of "discovered" tables. This is provided to allow the library
     /// to be used from unconventional settings (e.g. in userspace), for example with a `AcpiHandler` that detects
     /// accesses to specific physical addresses, and provides the correct data.
+    #[cfg(feature = "alloc")]
     pub fn from_tables_direct(
         handler: H,
         revision: u8,
@@ -204,12 +510,51 @@ where
         dsdt: Option<AmlTable>,
         ssdts: Vec<AmlTable>,
     ) -> AcpiTables<H> {
-        AcpiTables { revision, sdts, dsdt, ssdts, handler }
+        /*
+         * We don't know the order the tables were originally listed in, so list the other tables (by signature)
+         * and then the SSDTs.
+         */
+        let table_index = sdts
+            .iter()
+            .map(|(&signature, sdt)| ListedTable {
+                signature,
+                physical_address: sdt.physical_address,
+                length: sdt.length,
+            })
+            .chain(ssdts.iter().map(|ssdt| ListedTable {
+                signature: Signature::SSDT,
+                physical_address: ssdt.address - mem::size_of::<SdtHeader>(),
+                length: ssdt.length + mem::size_of::<SdtHeader>() as u32,
+            }))
+            .collect();
+        AcpiTables { revision, uses_xsdt: revision != 0, sdts, dsdt, ssdts, table_index, handler }
     }
 
+    /// Read the signature of the table at `physical_address`, without mapping the rest of its header. This is
+    /// useful for tools that need to classify a pointer before deciding how to map it. Returns `None` if the bytes
+    /// there can't be a signature, as they aren't all ASCII letters, digits, or underscores.
+    ///
+    /// ### Safety
+    /// `physical_address` must point to four bytes of readable memory.
+    pub unsafe fn signature_at(handler: &H, physical_address: usize) -> Option<Signature> {
+        let mapping = unsafe { handler.map_physical_region::<[u8; 4]>(physical_address, 4) };
+        let bytes = *mapping;
+        if bytes.iter().all(|&byte| byte.is_ascii_alphanumeric() || byte == b'_') {
+            Some(Signature::from_bytes(&bytes))
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "alloc")]
     fn process_sdt(&mut self, physical_address: usize) -> Result<(), AcpiError> {
         let header = sdt::peek_at_sdt_header(&self.handler, physical_address);
         trace!("Found ACPI table with signature {:?} and length {:?}", header.signature, { header.length });
+        self.table_index.push(ListedTable {
+            signature: header.signature,
+            physical_address,
+            length: header.length,
+        });
 
         match header.signature {
             Signature::FADT => {
@@ -219,13 +564,14 @@ where
                  * For whatever reason, they chose to put the DSDT inside the FADT, instead of just listing it
                  * as another SDT. We extract it here to provide a nicer public API.
                  */
+                validate_table_length::<Fadt>(Signature::FADT, header.length)?;
                 let fadt_mapping =
                     unsafe { self.handler.map_physical_region::<Fadt>(physical_address, mem::size_of::<Fadt>()) };
                 fadt_mapping.validate()?;
 
                 let dsdt_address = fadt_mapping.dsdt_address()?;
                 let dsdt_header = sdt::peek_at_sdt_header(&self.handler, dsdt_address);
-                self.dsdt = Some(AmlTable::new(dsdt_address, dsdt_header.length));
+                self.dsdt = Some(AmlTable::new(dsdt_address, dsdt_header.length, Signature::DSDT)?);
 
                 /*
                  * We've already validated the FADT to get the DSDT out, so it doesn't need to be done again.
@@ -234,18 +580,83 @@ where
                     .insert(Signature::FADT, Sdt { physical_address, length: header.length, validated: true });
             }
             Signature::SSDT => {
-                self.ssdts.push(AmlTable::new(physical_address, header.length));
+                self.ssdts.push(AmlTable::new(physical_address, header.length, Signature::SSDT)?);
             }
             signature => {
-                self.sdts.insert(signature, Sdt { physical_address, length: header.length, validated: false });
+                /*
+                 * Some signatures can appear more than once. `get_sdt` should find the first table with the
+                 * signature, so don't replace it with later ones (which can be found with `get_sdts`).
+                 */
+                self.sdts.entry(signature).or_insert(Sdt {
+                    physical_address,
+                    length: header.length,
+                    validated: false,
+                });
             }
         }
 
         Ok(())
     }
 
+    /// The tables listed by the RSDT/XSDT, in the order they're listed.
+    #[cfg(feature = "alloc")]
+    fn listed_tables(&self) -> impl Iterator<Item = ListedTable> + '_ {
+        self.table_index.iter().copied()
+    }
+
+    /// The tables listed by the RSDT/XSDT, in the order they're listed. Without an index, the header of each table
+    /// has to be mapped to find its signature and length.
+    #[cfg(not(feature = "alloc"))]
+    fn listed_tables(&self) -> impl Iterator<Item = ListedTable> + '_ {
+        let header = sdt::peek_at_sdt_header(&self.handler, self.root_table_address);
+        let mapping = unsafe {
+            self.handler.map_physical_region::<SdtHeader>(self.root_table_address, header.length as usize)
+        };
+        root_table_entries(mapping, self.uses_xsdt).map(move |physical_address| {
+            let header = sdt::peek_at_sdt_header(&self.handler, physical_address);
+            ListedTable { signature: header.signature, physical_address, length: header.length }
+        })
+    }
+
+    /// The revision of ACPI that the system uses, as reported by the RSDP.
+    pub fn revision(&self) -> u8 {
+        self.revision
+    }
+
+    /// Whether the tables were found through the XSDT, which can point to tables anywhere in the physical address
+    /// space, or through the RSDT, which can only point to tables below 4GiB. The XSDT is used whenever the RSDP
+    /// reports ACPI Version 2.0+ and provides one.
+    pub fn uses_xsdt(&self) -> bool {
+        self.uses_xsdt
+    }
+
+    /// Iterate over the headers of the tables listed by the RSDT/XSDT, in the order they're listed. This is useful
+    /// for tools that want to list the tables without knowing their types. The headers aren't validated.
+    pub fn headers(&self) -> impl Iterator<Item = SdtHeader> + '_ {
+        self.listed_tables().map(move |table| sdt::peek_at_sdt_header(&self.handler, table.physical_address))
+    }
+
+    /// Validate every table listed by the RSDT/XSDT, and the DSDT, up front. Tables are otherwise only validated
+    /// when they're first mapped, so this can be used to find corrupt tables that would never be accessed. The
+    /// error for the first invalid table is returned, and names its signature.
+    pub fn validate_all(&self) -> Result<(), AcpiError> {
+        for table in self.listed_tables() {
+            let mapping = unsafe {
+                self.handler.map_physical_region::<SdtHeader>(table.physical_address, table.length as usize)
+            };
+            mapping.validate(table.signature)?;
+        }
+
+        if let Some(dsdt) = self.dsdt_table()? {
+            AmlStream::map(&self.handler, &dsdt, Signature::DSDT)?;
+        }
+
+        Ok(())
+    }
+
     /// Create a mapping to a SDT, given its signature. This validates the SDT if it has not already been
-    /// validated.
+    /// validated. If the SDT's `length` is shorter than [`AcpiTable::minimum_length`], this fails with
+    /// `AcpiError::TableMalformed`, even if the SDT has already been validated.
     ///
     /// ### Safety
     /// The table's memory is naively interpreted as a `T`, and so you must be careful in providing a type that
@@ -254,30 +665,167 @@ where
     /// page-faults, aliasing references, or derefencing uninitialized memory (the latter two of which are UB).
     /// This isn't forbidden, however, because some tables rely on `T` being larger than a provided SDT in some
     /// versions of ACPI (the [`ExtendedField`](crate::sdt::ExtendedField) type will be useful if you need to do
-    /// this. See our [`Fadt`](crate::fadt::Fadt) type for an example of this).
+    /// this, along with overriding [`AcpiTable::minimum_length`]. See our [`Fadt`](crate::fadt::Fadt) type for an
+    /// example of this).
     pub unsafe fn get_sdt<T>(&self, signature: sdt::Signature) -> Result<Option<PhysicalMapping<H, T>>, AcpiError>
     where
         T: AcpiTable,
     {
-        let sdt = match self.sdts.get(&signature) {
-            Some(sdt) => sdt,
+        #[cfg(feature = "alloc")]
+        {
+            let sdt = match self.sdts.get(&signature) {
+                Some(sdt) => sdt,
+                None => return Ok(None),
+            };
+            validate_table_length::<T>(signature, sdt.length)?;
+            let mapping =
+                unsafe { self.handler.map_physical_region::<T>(sdt.physical_address, sdt.length as usize) };
+
+            if !sdt.validated {
+                mapping.header().validate(signature)?;
+            }
+
+            Ok(Some(mapping))
+        }
+
+        #[cfg(not(feature = "alloc"))]
+        {
+            unsafe { self.get_sdts::<T>(signature) }.next().transpose()
+        }
+    }
+
+    /// Create mappings to every table with the given signature, in the order they're listed by the RSDT/XSDT. This
+    /// is needed for signatures that can legitimately appear more than once, such as the SSDT and some OEM tables;
+    /// `get_sdt` only finds the first. Each table is validated as it's mapped.
+    ///
+    /// ### Safety
+    /// The same requirements apply to `T` as for [`get_sdt`](AcpiTables::get_sdt).
+    pub unsafe fn get_sdts<T>(
+        &self,
+        signature: sdt::Signature,
+    ) -> impl Iterator<Item = Result<PhysicalMapping<H, T>, AcpiError>> + '_
+    where
+        T: AcpiTable,
+    {
+        self.listed_tables().filter(move |table| table.signature == signature).map(move |table| {
+            validate_table_length::<T>(signature, table.length)?;
+            let mapping =
+                unsafe { self.handler.map_physical_region::<T>(table.physical_address, table.length as usize) };
+            mapping.header().validate(signature)?;
+            Ok(mapping)
+        })
+    }
+
+    /// Map the DSDT, to get at its AML bytecode. The DSDT is found through the FADT, rather than being listed in
+    /// the RSDT/XSDT, so this fails with `AcpiError::TableMissing` if the platform doesn't have a FADT.
+    pub fn dsdt(&self) -> Result<AmlStream<H>, AcpiError> {
+        let dsdt = self.dsdt_table()?.ok_or(AcpiError::TableMissing(Signature::DSDT))?;
+        AmlStream::map(&self.handler, &dsdt, Signature::DSDT)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn dsdt_table(&self) -> Result<Option<AmlTable>, AcpiError> {
+        Ok(self.dsdt.as_ref().map(|dsdt| AmlTable { address: dsdt.address, length: dsdt.length }))
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    fn dsdt_table(&self) -> Result<Option<AmlTable>, AcpiError> {
+        let fadt = match unsafe { self.get_sdt::<fadt::Fadt>(Signature::FADT)? } {
+            Some(fadt) => fadt,
             None => return Ok(None),
         };
-        let mapping = unsafe { self.handler.map_physical_region::<T>(sdt.physical_address, sdt.length as usize) };
+        let dsdt_address = fadt.dsdt_address()?;
+        let dsdt_header = sdt::peek_at_sdt_header(&self.handler, dsdt_address);
+        Ok(Some(AmlTable::new(dsdt_address, dsdt_header.length, Signature::DSDT)?))
+    }
 
-        if !sdt.validated {
-            mapping.header().validate(signature)?;
-        }
+    /// Map each of the SSDTs, to get at their AML bytecode. They are produced in the order they were found in the
+    /// RSDT/XSDT, which is the order they should be loaded in.
+    #[cfg(feature = "alloc")]
+    pub fn ssdts(&self) -> impl Iterator<Item = Result<AmlStream<H>, AcpiError>> + '_ {
+        self.ssdts.iter().map(move |ssdt| AmlStream::map(&self.handler, ssdt, Signature::SSDT))
+    }
+
+    /// Map each of the SSDTs, to get at their AML bytecode. They are produced in the order they were found in the
+    /// RSDT/XSDT, which is the order they should be loaded in.
+    #[cfg(not(feature = "alloc"))]
+    pub fn ssdts(&self) -> impl Iterator<Item = Result<AmlStream<H>, AcpiError>> + '_ {
+        self.listed_tables().filter(|table| table.signature == Signature::SSDT).map(move |table| {
+            let ssdt = AmlTable::new(table.physical_address, table.length, Signature::SSDT)?;
+            AmlStream::map(&self.handler, &ssdt, Signature::SSDT)
+        })
+    }
 
-        Ok(Some(mapping))
+    /// Map the DSDT and each of the SSDTs, in the order they should be loaded into the AML namespace: the DSDT
+    /// first, and then the SSDTs, in the order they were found in the RSDT/XSDT. The header of each stream gives
+    /// the table's signature and OEM table ID, for diagnostics.
+    ///
+    /// If any of the tables can't be mapped, the whole call fails. In particular, a platform without a FADT or DSDT
+    /// produces `AcpiError::TableMissing(Signature::DSDT)`, even if it has SSDTs; use [`AcpiTables::ssdts`] to map
+    /// those on their own.
+    #[cfg(feature = "alloc")]
+    pub fn aml_tables(&self) -> Result<Vec<AmlStream<H>>, AcpiError> {
+        core::iter::once(self.dsdt()).chain(self.ssdts()).collect()
     }
 
     /// Convenience method for contructing a [`PlatformInfo`](crate::platform::PlatformInfo). This is one of the
     /// first things you should usually do with an `AcpiTables`, and allows to collect helpful information about
     /// the platform from the ACPI tables.
+    ///
+    /// ```
+    /// use acpi::{AcpiError, AcpiHandler, AcpiTables};
+    ///
+    /// fn print_topology<H: AcpiHandler>(tables: &AcpiTables<H>) -> Result<(), AcpiError> {
+    ///     let platform_info = tables.platform_info()?;
+    ///     let numa_info = tables.numa_info()?;
+    ///
+    ///     if let Some(processor_info) = platform_info.processor_info {
+    ///         for (processor, domain) in numa_info.processor_domains(&processor_info) {
+    ///             println!("Processor {} is in domain {:?}", processor.processor_uid, domain);
+    ///         }
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "alloc")]
     pub fn platform_info(&self) -> Result<PlatformInfo, AcpiError> {
         PlatformInfo::new(self)
     }
+
+    /// Convenience method for constructing a [`NumaInfo`](crate::platform::NumaInfo), which describes the NUMA
+    /// topology of the platform. This fails with `AcpiError::TableMissing` if the platform doesn't have an SRAT.
+    #[cfg(feature = "alloc")]
+    pub fn numa_info(&self) -> Result<NumaInfo, AcpiError> {
+        NumaInfo::new(self)
+    }
+}
+
+/// Read the physical addresses of the tables listed by a mapped RSDT or XSDT (if `is_xsdt`).
+fn root_table_entries<H>(mapping: PhysicalMapping<H, SdtHeader>, is_xsdt: bool) -> impl Iterator<Item = usize>
+where
+    H: AcpiHandler,
+{
+    let entry_size = if is_xsdt { mem::size_of::<u64>() } else { mem::size_of::<u32>() };
+    let num_entries = (mapping.length as usize).saturating_sub(mem::size_of::<SdtHeader>()) / entry_size;
+
+    (0..num_entries).map(move |i| {
+        let entry = unsafe {
+            (mapping.virtual_start().as_ptr() as *const u8).add(mem::size_of::<SdtHeader>() + i * entry_size)
+        };
+        if entry_size == mem::size_of::<u32>() {
+            unsafe { (entry as *const u32).read_unaligned() as usize }
+        } else {
+            unsafe { (entry as *const u64).read_unaligned() as usize }
+        }
+    })
+}
+
+/// A table listed by the RSDT/XSDT, along with the signature and length from its header.
+#[derive(Clone, Copy, Debug)]
+struct ListedTable {
+    signature: Signature,
+    physical_address: usize,
+    length: u32,
 }
 
 pub struct Sdt {
@@ -292,6 +840,30 @@ pub struct Sdt {
 /// All types representing ACPI tables should implement this trait.
 pub trait AcpiTable {
     fn header(&self) -> &sdt::SdtHeader;
+
+    /// The length of the shortest valid version of the table. Tables that are shorter than this are rejected with
+    /// `AcpiError::TableMalformed` when they're mapped, so the fields before this point can always be accessed.
+    /// This is the size of the type by default, which is correct for tables that grow by appending variable-length
+    /// entries, but types that represent later versions of tables with extra fields at the end (like the FADT,
+    /// GTDT and SPCR) should override it.
+    fn minimum_length() -> usize
+    where
+        Self: Sized,
+    {
+        mem::size_of::<Self>()
+    }
+}
+
+/// Check that a table that claims to be `length` bytes long can be treated as a `T`.
+fn validate_table_length<T>(signature: Signature, length: u32) -> Result<(), AcpiError>
+where
+    T: AcpiTable,
+{
+    if (length as usize) < usize::max(T::minimum_length(), mem::size_of::<SdtHeader>()) {
+        return Err(AcpiError::TableMalformed(signature));
+    }
+
+    Ok(())
 }
 
 #[derive(Debug)]
@@ -303,11 +875,467 @@ pub struct AmlTable {
 }
 
 impl AmlTable {
-    /// Create an `AmlTable` from the address and length of the table **including the SDT header**.
-    pub(crate) fn new(address: usize, length: u32) -> AmlTable {
-        AmlTable {
-            address: address + mem::size_of::<SdtHeader>(),
-            length: length - mem::size_of::<SdtHeader>() as u32,
+    /// Create an `AmlTable` from the address and length of the table **including the SDT header**. Fails with
+    /// `AcpiError::TableMalformed` if the table claims to be too short to hold its header.
+    pub(crate) fn new(address: usize, length: u32, signature: Signature) -> Result<AmlTable, AcpiError> {
+        let length =
+            length.checked_sub(mem::size_of::<SdtHeader>() as u32).ok_or(AcpiError::TableMalformed(signature))?;
+        Ok(AmlTable { address: address + mem::size_of::<SdtHeader>(), length })
+    }
+}
+
+/// A mapped definition block (the DSDT or an SSDT), which contains AML bytecode to be parsed by the `aml` crate.
+/// The table stays mapped for as long as the `AmlStream` exists.
+pub struct AmlStream<H>
+where
+    H: AcpiHandler,
+{
+    mapping: PhysicalMapping<H, SdtHeader>,
+}
+
+impl<H> AmlStream<H>
+where
+    H: AcpiHandler,
+{
+    fn map(handler: &H, table: &AmlTable, signature: Signature) -> Result<AmlStream<H>, AcpiError> {
+        let mapping = unsafe {
+            handler.map_physical_region::<SdtHeader>(
+                table.address - mem::size_of::<SdtHeader>(),
+                table.length as usize + mem::size_of::<SdtHeader>(),
+            )
+        };
+        mapping.validate(signature)?;
+
+        Ok(AmlStream { mapping })
+    }
+
+    /// The header of the definition block. Its revision decides whether the AML uses 32-bit or 64-bit integers.
+    pub fn header(&self) -> &SdtHeader {
+        &self.mapping
+    }
+
+    /// The AML bytecode of the definition block, which follows its header.
+    pub fn bytecode(&self) -> &[u8] {
+        let length = (self.mapping.length as usize).saturating_sub(mem::size_of::<SdtHeader>());
+        unsafe {
+            slice::from_raw_parts(
+                (self.mapping.virtual_start().as_ptr() as *const u8).add(mem::size_of::<SdtHeader>()),
+                length,
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{make_rsdp, make_rsdt, make_sdt, make_xsdt, TestHandler, TestMemory};
+    use alloc::vec::Vec;
+    use core::cell::Cell;
+
+    /// Build a FADT that points to a DSDT at `dsdt_address`.
+    fn make_fadt(dsdt_address: usize) -> Vec<u8> {
+        let mut body = [0; 240];
+        body[4..8].copy_from_slice(&(dsdt_address as u32).to_le_bytes());
+        make_sdt(b"FACP", 6, &body)
+    }
+
+    #[test]
+    fn test_aml_streams() {
+        let mut memory = TestMemory::new(0x7ff0_0000);
+        // Scope (\_SB) { }
+        let dsdt = memory.add(&make_sdt(b"DSDT", 2, &[0x10, 0x06, b'\\', b'_', b'S', b'B', b'_']));
+        let fadt = memory.add(&make_fadt(dsdt));
+        let ssdt_1 = memory.add(&make_sdt(b"SSDT", 2, &[0x10, 0x06, b'\\', b'_', b'P', b'R', b'_']));
+        let ssdt_2 = memory.add(&make_sdt(b"SSDT", 1, &[0x08, b'F', b'O', b'O', b'_', 0x0a, 0x2a]));
+        let xsdt = memory.add(&make_xsdt(&[ssdt_1, fadt, ssdt_2]));
+
+        let tables = unsafe { AcpiTables::from_rsdt(memory.handler(), 2, xsdt) }.unwrap();
+
+        let dsdt = tables.dsdt().unwrap();
+        assert_eq!(dsdt.header().signature, Signature::DSDT);
+        assert_eq!(dsdt.header().revision, 2);
+        assert_eq!(dsdt.bytecode()[0], 0x10); // ScopeOp
+        assert_eq!(dsdt.bytecode().len(), 7);
+
+        let ssdts: Vec<AmlStream<TestHandler>> = tables.ssdts().collect::<Result<_, _>>().unwrap();
+        assert_eq!(ssdts.len(), 2);
+        assert_eq!(ssdts[0].bytecode(), &[0x10, 0x06, b'\\', b'_', b'P', b'R', b'_']);
+        assert_eq!(ssdts[1].header().revision, 1);
+        assert_eq!(ssdts[1].bytecode()[0], 0x08); // NameOp
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_aml_tables() {
+        let with_oem_table_id = |signature, oem_table_id: &[u8; 8]| {
+            let mut table = make_sdt(signature, 2, &[0x10, 0x06, b'\\', b'_', b'S', b'B', b'_']);
+            table[16..24].copy_from_slice(oem_table_id);
+            SdtHeader::finalize(&mut table);
+            table
+        };
+
+        let mut memory = TestMemory::new(0x7ff0_0000);
+        let dsdt = memory.add(&with_oem_table_id(b"DSDT", b"DSDTDSDT"));
+        let fadt = memory.add(&make_fadt(dsdt));
+        let ssdt_1 = memory.add(&with_oem_table_id(b"SSDT", b"CPUSSDT "));
+        let waet = memory.add(&make_sdt(b"WAET", 1, &0u32.to_le_bytes()));
+        let ssdt_2 = memory.add(&with_oem_table_id(b"SSDT", b"PCIESSDT"));
+        // The SSDTs should be loaded in the order the XSDT lists them, after the DSDT
+        let xsdt = memory.add(&make_xsdt(&[ssdt_2, fadt, waet, ssdt_1]));
+        let tables = unsafe { AcpiTables::from_rsdt(memory.handler(), 2, xsdt) }.unwrap();
+
+        let streams = tables.aml_tables().unwrap();
+        let order: Vec<(Signature, &str)> =
+            streams.iter().map(|stream| (stream.header().signature, stream.header().oem_table_id())).collect();
+        assert_eq!(
+            order,
+            [(Signature::DSDT, "DSDTDSDT"), (Signature::SSDT, "PCIESSDT"), (Signature::SSDT, "CPUSSDT ")]
+        );
+        assert!(streams.iter().all(|stream| stream.bytecode().len() == 7));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_aml_tables_without_dsdt() {
+        let mut memory = TestMemory::new(0x7ff0_0000);
+        let ssdt = memory.add(&make_sdt(b"SSDT", 2, &[0x10, 0x06, b'\\', b'_', b'S', b'B', b'_']));
+        // Without a FADT, there's no way to find the DSDT
+        let xsdt = memory.add(&make_xsdt(&[ssdt]));
+        let tables = unsafe { AcpiTables::from_rsdt(memory.handler(), 2, xsdt) }.unwrap();
+
+        assert!(matches!(tables.aml_tables(), Err(AcpiError::TableMissing(Signature::DSDT))));
+        let ssdts = tables.ssdts().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(ssdts.len(), 1);
+        assert_eq!(ssdts[0].header().signature, Signature::SSDT);
+    }
+
+    #[test]
+    fn test_short_aml_table() {
+        let mut memory = TestMemory::new(0x7ff0_0000);
+        // An SSDT that claims to be too short to hold its own header
+        let mut ssdt = make_sdt(b"SSDT", 2, &[]);
+        ssdt[4..8].copy_from_slice(&20u32.to_le_bytes());
+        ssdt[9] = ssdt[9].wrapping_add(36 - 20);
+        let ssdt = memory.add(&ssdt);
+        let xsdt = memory.add(&make_xsdt(&[ssdt]));
+
+        let result = unsafe { AcpiTables::from_rsdt(memory.handler(), 2, xsdt) }
+            .and_then(|tables| tables.ssdts().collect::<Result<Vec<_>, _>>().map(|_| ()));
+        assert!(matches!(result, Err(AcpiError::TableMalformed(Signature::SSDT))));
+    }
+
+    #[test]
+    fn test_from_xsdt() {
+        let mut memory = TestMemory::new(0x7ff0_0000);
+        let dsdt = memory.add(&make_sdt(b"DSDT", 2, &[0x10, 0x06, b'\\', b'_', b'S', b'B', b'_']));
+        let fadt = memory.add(&make_fadt(dsdt));
+        let xsdt = memory.add(&make_xsdt(&[fadt]));
+        let rsdt = memory.add(&make_rsdt(&[fadt]));
+
+        let tables = unsafe { AcpiTables::from_xsdt(memory.handler(), xsdt) }.unwrap();
+        assert!(tables.uses_xsdt());
+        assert_eq!(tables.revision(), 2);
+        let fadt_mapping = unsafe { tables.get_sdt::<fadt::Fadt>(Signature::FADT) }.unwrap().unwrap();
+        assert_eq!(fadt_mapping.physical_start(), fadt);
+        assert_eq!(fadt_mapping.dsdt_address().unwrap(), dsdt);
+
+        assert!(matches!(
+            unsafe { AcpiTables::from_xsdt(memory.handler(), rsdt) },
+            Err(AcpiError::SdtInvalidSignature(Signature::XSDT))
+        ));
+    }
+
+    #[test]
+    fn test_offset_mapper() {
+        let mut memory = TestMemory::new(0x7ff0_0000);
+        let dsdt = memory.add(&make_sdt(b"DSDT", 2, &[0x10, 0x06, b'\\', b'_', b'S', b'B', b'_']));
+        let fadt = memory.add(&make_fadt(dsdt));
+        let waet = memory.add(&make_sdt(b"WAET", 1, &0b10u32.to_le_bytes()));
+        let xsdt = memory.add(&make_xsdt(&[fadt, waet]));
+        let rsdp = memory.add(&make_rsdp(2, 0, xsdt as u64));
+
+        let handler = unsafe { OffsetMapper::new(memory.virtual_offset()) };
+        let tables = unsafe { AcpiTables::from_rsdp(handler, rsdp) }.unwrap();
+        let waet_mapping = unsafe { tables.get_sdt::<waet::Waet>(Signature::WAET) }.unwrap().unwrap();
+        assert_eq!(waet_mapping.physical_start(), waet);
+        assert_eq!(waet_mapping.virtual_start().as_ptr() as usize, waet.wrapping_add(memory.virtual_offset()));
+        assert!(waet_mapping.pm_timer_good());
+        assert_eq!(tables.dsdt().unwrap().bytecode().len(), 7);
+    }
+
+    #[test]
+    fn test_validate_all() {
+        let mut memory = TestMemory::new(0x7ff0_0000);
+        let dsdt = memory.add(&make_sdt(b"DSDT", 2, &[0x10, 0x06, b'\\', b'_', b'S', b'B', b'_']));
+        let fadt = memory.add(&make_fadt(dsdt));
+        let waet = memory.add(&make_sdt(b"WAET", 1, &0b10u32.to_le_bytes()));
+        let mut hpet = make_sdt(b"HPET", 1, &[0; 20]);
+        hpet[40] = 0x01;
+        let hpet = memory.add(&hpet);
+        let ssdt = memory.add(&make_sdt(b"SSDT", 2, &[0x10, 0x06, b'\\', b'_', b'P', b'R', b'_']));
+
+        let xsdt = memory.add(&make_xsdt(&[fadt, waet, ssdt]));
+        let tables = unsafe { AcpiTables::from_rsdt(memory.handler(), 2, xsdt) }.unwrap();
+        tables.validate_all().unwrap();
+
+        // The HPET's checksum doesn't account for the byte we changed, so it should be reported
+        let xsdt = memory.add(&make_xsdt(&[fadt, waet, hpet, ssdt]));
+        let tables = unsafe { AcpiTables::from_rsdt(memory.handler(), 2, xsdt) }.unwrap();
+        assert!(matches!(tables.validate_all(), Err(AcpiError::SdtInvalidChecksum(Signature::HPET))));
+    }
+
+    #[test]
+    fn test_headers() {
+        let mut memory = TestMemory::new(0x7ff0_0000);
+        let dsdt = memory.add(&make_sdt(b"DSDT", 2, &[0x10, 0x06, b'\\', b'_', b'S', b'B', b'_']));
+        let fadt = memory.add(&make_fadt(dsdt));
+        let waet = memory.add(&make_sdt(b"WAET", 1, &0b10u32.to_le_bytes()));
+        let ssdt = memory.add(&make_sdt(b"SSDT", 2, &[0x10, 0x06, b'\\', b'_', b'P', b'R', b'_']));
+        let xsdt = memory.add(&make_xsdt(&[fadt, waet, ssdt]));
+        let rsdt = memory.add(&make_rsdt(&[ssdt, fadt]));
+
+        let tables = unsafe { AcpiTables::from_rsdt(memory.handler(), 2, xsdt) }.unwrap();
+        let headers: Vec<SdtHeader> = tables.headers().collect();
+        assert_eq!(headers.len(), 3);
+        assert_eq!(headers[0].signature, Signature::FADT);
+        assert_eq!({ headers[0].length }, 276);
+        assert_eq!(headers[0].revision, 6);
+        assert_eq!(headers[1].signature, Signature::WAET);
+        assert_eq!(headers[1].oem_table_id(), "OEMTABLE");
+        assert_eq!(headers[2].signature, Signature::SSDT);
+
+        let tables = unsafe { AcpiTables::from_rsdt(memory.handler(), 0, rsdt) }.unwrap();
+        let signatures: Vec<Signature> = tables.headers().map(|header| header.signature).collect();
+        assert_eq!(signatures, [Signature::SSDT, Signature::FADT]);
+    }
+
+    #[repr(C, packed)]
+    struct Ssdt {
+        header: SdtHeader,
+    }
+
+    impl AcpiTable for Ssdt {
+        fn header(&self) -> &SdtHeader {
+            &self.header
+        }
+    }
+
+    #[test]
+    fn test_get_sdts() {
+        let mut memory = TestMemory::new(0x7ff0_0000);
+        let dsdt = memory.add(&make_sdt(b"DSDT", 2, &[0x10, 0x06, b'\\', b'_', b'S', b'B', b'_']));
+        let fadt = memory.add(&make_fadt(dsdt));
+        let ssdt_1 = memory.add(&make_sdt(b"SSDT", 2, &[0x10, 0x06, b'\\', b'_', b'P', b'R', b'_']));
+        let waet_1 = memory.add(&make_sdt(b"WAET", 1, &0b10u32.to_le_bytes()));
+        let ssdt_2 = memory.add(&make_sdt(b"SSDT", 1, &[0x08, b'F', b'O', b'O', b'_', 0x0a, 0x2a]));
+        let waet_2 = memory.add(&make_sdt(b"WAET", 1, &0b11u32.to_le_bytes()));
+        let xsdt = memory.add(&make_xsdt(&[ssdt_1, fadt, waet_1, ssdt_2, waet_2]));
+        let tables = unsafe { AcpiTables::from_rsdt(memory.handler(), 2, xsdt) }.unwrap();
+
+        let ssdts: Vec<PhysicalMapping<TestHandler, Ssdt>> =
+            unsafe { tables.get_sdts::<Ssdt>(Signature::SSDT) }.collect::<Result<_, _>>().unwrap();
+        assert_eq!(ssdts.len(), 2);
+        assert_eq!(ssdts[0].physical_start(), ssdt_1);
+        assert_eq!(ssdts[0].header().revision, 2);
+        assert_eq!(ssdts[1].physical_start(), ssdt_2);
+        assert_eq!(ssdts[1].header().revision, 1);
+
+        // `get_sdt` should find the first of the repeated tables
+        let waet = unsafe { tables.get_sdt::<waet::Waet>(Signature::WAET) }.unwrap().unwrap();
+        assert_eq!(waet.physical_start(), waet_1);
+        assert!(!waet.rtc_good());
+        let waets: Vec<usize> = unsafe { tables.get_sdts::<waet::Waet>(Signature::WAET) }
+            .map(|waet| waet.unwrap().physical_start())
+            .collect();
+        assert_eq!(waets, [waet_1, waet_2]);
+        assert_eq!(unsafe { tables.get_sdts::<Ssdt>(Signature::HPET) }.count(), 0);
+    }
+
+    /// Counts the mappings that are made, and unmapped, through it.
+    #[derive(Clone)]
+    struct CountingHandler<'a> {
+        inner: TestHandler,
+        mappings: &'a Cell<usize>,
+        unmappings: &'a Cell<usize>,
+    }
+
+    impl AcpiHandler for CountingHandler<'_> {
+        unsafe fn map_physical_region<T>(&self, physical_address: usize, size: usize) -> PhysicalMapping<Self, T> {
+            self.mappings.set(self.mappings.get() + 1);
+            let mapping = unsafe { self.inner.map_physical_region::<T>(physical_address, size) };
+            unsafe { PhysicalMapping::new(physical_address, mapping.virtual_start(), size, size, self.clone()) }
+        }
+
+        fn unmap_physical_region<T>(region: &PhysicalMapping<Self, T>) {
+            let unmappings = region.handler().unmappings;
+            unmappings.set(unmappings.get() + 1);
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_lookups_use_index() {
+        let mut memory = TestMemory::new(0x7ff0_0000);
+        let dsdt = memory.add(&make_sdt(b"DSDT", 2, &[0x10, 0x06, b'\\', b'_', b'S', b'B', b'_']));
+        let mut entries = alloc::vec![memory.add(&make_fadt(dsdt))];
+        for i in 0..4 {
+            entries.push(memory.add(&make_sdt(b"WAET", 1, &(i as u32).to_le_bytes())));
+            entries.push(memory.add(&make_sdt(b"SSDT", 2, &[0x10, 0x06, b'\\', b'_', b'P', b'R', b'_'])));
+        }
+        let xsdt = memory.add(&make_xsdt(&entries));
+
+        let (mappings, unmappings) = (Cell::new(0), Cell::new(0));
+        let handler = CountingHandler { inner: memory.handler(), mappings: &mappings, unmappings: &unmappings };
+        let tables = unsafe { AcpiTables::from_rsdt(handler, 2, xsdt) }.unwrap();
+
+        /*
+         * Each table's header is mapped to build the index. After that, lookups should only map the tables they
+         * find, however many tables are listed.
+         */
+        mappings.set(0);
+        for _ in 0..10 {
+            assert_eq!(unsafe { tables.get_sdts::<waet::Waet>(Signature::WAET) }.count(), 4);
+            assert_eq!(unsafe { tables.get_sdts::<waet::Waet>(Signature::HPET) }.count(), 0);
+            assert!(unsafe { tables.get_sdt::<waet::Waet>(Signature::WAET) }.unwrap().is_some());
+        }
+        assert_eq!(mappings.get(), 10 * (4 + 1));
+    }
+
+    #[test]
+    fn test_signature_at() {
+        let mut memory = TestMemory::new(0x7ff0_0000);
+        let waet = memory.add(&make_sdt(b"WAET", 1, &0u32.to_le_bytes()));
+        let oem = memory.add(&make_sdt(b"OEM_", 1, &[]));
+        let junk = memory.add(&[0xde, 0xad, 0xbe, 0xef]);
+
+        let (mappings, unmappings) = (Cell::new(0), Cell::new(0));
+        let handler = CountingHandler { inner: memory.handler(), mappings: &mappings, unmappings: &unmappings };
+        unsafe {
+            assert_eq!(AcpiTables::signature_at(&handler, waet), Some(Signature::WAET));
+            assert_eq!(AcpiTables::signature_at(&handler, oem), Some(Signature::from_bytes(b"OEM_")));
+            assert_eq!(AcpiTables::signature_at(&handler, junk), None);
         }
+        assert_eq!(mappings.get(), 3);
+        assert_eq!(unmappings.get(), 3);
+    }
+
+    #[test]
+    fn test_missing_dsdt() {
+        let mut memory = TestMemory::new(0x7ff0_0000);
+        let xsdt = memory.add(&make_xsdt(&[]));
+
+        let tables = unsafe { AcpiTables::from_rsdt(memory.handler(), 2, xsdt) }.unwrap();
+        assert!(matches!(tables.dsdt(), Err(AcpiError::TableMissing(Signature::DSDT))));
+        assert_eq!(tables.ssdts().count(), 0);
+    }
+
+    #[test]
+    fn test_xsdt_preferred() {
+        /*
+         * The memory starts just below 4GiB, so that the RSDT (and the WAET it lists) can be placed below 4GiB, and
+         * the HPET and XSDT above it.
+         */
+        let mut memory = TestMemory::new(0xffff_0000);
+        let waet = memory.add(&make_sdt(b"WAET", 1, &0u32.to_le_bytes()));
+        let rsdt = memory.add(&make_rsdt(&[waet]));
+        memory.add(&[0; 0x1_0000]);
+        let hpet = memory.add(&make_sdt(b"HPET", 1, &[0; 20]));
+        assert!(hpet > u32::MAX as usize);
+        let xsdt = memory.add(&make_xsdt(&[hpet]));
+        let rsdp_both = memory.add(&make_rsdp(2, rsdt as u32, xsdt as u64));
+        let rsdp_rsdt_only = memory.add(&make_rsdp(2, rsdt as u32, 0));
+        let rsdp_v1 = memory.add(&make_rsdp(0, rsdt as u32, 0));
+
+        let tables = unsafe { AcpiTables::from_rsdp(memory.handler(), rsdp_both) }.unwrap();
+        assert!(tables.uses_xsdt());
+        assert_eq!(tables.revision(), 2);
+        let hpet_mapping = unsafe { tables.get_sdt::<hpet::HpetTable>(Signature::HPET) }.unwrap().unwrap();
+        assert_eq!(hpet_mapping.physical_start(), hpet);
+        assert!(unsafe { tables.get_sdt::<waet::Waet>(Signature::WAET) }.unwrap().is_none());
+
+        // Without a XSDT, only the tables below 4GiB can be found, through the RSDT
+        let tables = unsafe { AcpiTables::from_rsdp(memory.handler(), rsdp_rsdt_only) }.unwrap();
+        assert!(!tables.uses_xsdt());
+        assert_eq!(tables.revision(), 2);
+        assert!(unsafe { tables.get_sdt::<hpet::HpetTable>(Signature::HPET) }.unwrap().is_none());
+        assert!(unsafe { tables.get_sdt::<waet::Waet>(Signature::WAET) }.unwrap().is_some());
+
+        let tables = unsafe { AcpiTables::from_rsdp(memory.handler(), rsdp_v1) }.unwrap();
+        assert!(!tables.uses_xsdt());
+        assert_eq!(tables.revision(), 0);
+        assert_eq!(tables.headers().count(), 1);
+    }
+
+    #[test]
+    fn test_short_table() {
+        let mut memory = TestMemory::new(0x7ff0_0000);
+        let dsdt = memory.add(&make_sdt(b"DSDT", 2, &[0x10, 0x06, b'\\', b'_', b'S', b'B', b'_']));
+        let fadt = memory.add(&make_fadt(dsdt));
+        // A FADT that's been cut off before the end of the fields present in ACPI Version 1.0
+        let mut short_fadt = make_fadt(dsdt);
+        short_fadt.truncate(100);
+        SdtHeader::finalize(&mut short_fadt);
+        let short_fadt = memory.add(&short_fadt);
+        // An HPET that's cut off before its base address
+        let short_hpet = memory.add(&make_sdt(b"HPET", 1, &[0; 8]));
+
+        let xsdt = memory.add(&make_xsdt(&[fadt, short_hpet]));
+        let tables = unsafe { AcpiTables::from_rsdt(memory.handler(), 2, xsdt) }.unwrap();
+        assert!(unsafe { tables.get_sdt::<fadt::Fadt>(Signature::FADT) }.unwrap().is_some());
+        assert!(matches!(
+            unsafe { tables.get_sdt::<hpet::HpetTable>(Signature::HPET) },
+            Err(AcpiError::TableMalformed(Signature::HPET))
+        ));
+        let mut hpets = unsafe { tables.get_sdts::<hpet::HpetTable>(Signature::HPET) };
+        assert!(matches!(hpets.next(), Some(Err(AcpiError::TableMalformed(Signature::HPET)))));
+        assert!(hpets.next().is_none());
+
+        // Revision 2 GTDTs and SPCRs are shorter than the types that represent them, but can still be mapped
+        let gtdt = memory.add(&make_sdt(b"GTDT", 2, &[0; 60]));
+        let spcr = memory.add(&make_sdt(b"SPCR", 2, &[0; 44]));
+        let xsdt = memory.add(&make_xsdt(&[gtdt, spcr]));
+        let tables = unsafe { AcpiTables::from_rsdt(memory.handler(), 2, xsdt) }.unwrap();
+        assert!(unsafe { tables.get_sdt::<gtdt::Gtdt>(Signature::GTDT) }.unwrap().is_some());
+        assert!(unsafe { tables.get_sdt::<spcr::Spcr>(Signature::SPCR) }.unwrap().is_some());
+
+        let short_gtdt = memory.add(&make_sdt(b"GTDT", 2, &[0; 56]));
+        let short_spcr = memory.add(&make_sdt(b"SPCR", 2, &[0; 40]));
+        let xsdt = memory.add(&make_xsdt(&[short_gtdt, short_spcr]));
+        let tables = unsafe { AcpiTables::from_rsdt(memory.handler(), 2, xsdt) }.unwrap();
+        assert!(matches!(
+            unsafe { tables.get_sdt::<gtdt::Gtdt>(Signature::GTDT) },
+            Err(AcpiError::TableMalformed(Signature::GTDT))
+        ));
+        assert!(matches!(
+            unsafe { tables.get_sdt::<spcr::Spcr>(Signature::SPCR) },
+            Err(AcpiError::TableMalformed(Signature::SPCR))
+        ));
+
+        let xsdt = memory.add(&make_xsdt(&[short_fadt]));
+        let result = unsafe { AcpiTables::from_rsdt(memory.handler(), 2, xsdt) }
+            .and_then(|tables| unsafe { tables.get_sdt::<fadt::Fadt>(Signature::FADT) }.map(|_| ()));
+        assert!(matches!(result, Err(AcpiError::TableMalformed(Signature::FADT))));
+    }
+
+    #[test]
+    fn test_error_messages() {
+        use crate::madt::MadtError;
+        use core::error::Error;
+
+        assert_eq!(format!("{}", AcpiError::TableMissing(Signature::SRAT)), "couldn't find a SRAT table");
+        assert_eq!(
+            format!("{}", AcpiError::SdtInvalidChecksum(Signature::FADT)),
+            "FACP table has an invalid checksum"
+        );
+        assert_eq!(
+            format!("{}", AcpiError::InvalidMadt(MadtError::MpsIntiInvalidPolarity)),
+            "invalid MADT: MPS INTI flags have an invalid polarity"
+        );
+
+        let err = AcpiError::Rsdp(RsdpError::InvalidExtendedChecksum);
+        assert_eq!(format!("{}", err), "RSDP has an invalid extended checksum");
+        assert_eq!(format!("{}", err.source().unwrap()), "RSDP has an invalid extended checksum");
+        assert!(AcpiError::BufferTooSmall.source().is_none());
     }
 }
@@ -0,0 +1,216 @@
+use crate::{
+    entry_as,
+    platform::address::{GenericAddress, RawGenericAddress},
+    sdt::{SdtHeader, Signature},
+    AcpiError,
+    AcpiTable,
+    EntryIter,
+};
+use bit_field::BitField;
+use core::{convert::TryFrom, mem};
+
+/// Represents the Low Power Idle Table (LPIT). This describes the platform's low-power idle states, how each is
+/// entered, and the counters that report how long the platform has spent in them.
+#[repr(C, packed)]
+pub struct Lpit {
+    header: SdtHeader,
+}
+
+impl AcpiTable for Lpit {
+    fn header(&self) -> &SdtHeader {
+        &self.header
+    }
+}
+
+impl Lpit {
+    pub fn states(&self) -> LpiStateIter<'_> {
+        LpiStateIter {
+            entries: unsafe {
+                EntryIter::new(
+                    Signature::LPIT,
+                    (self as *const Lpit as *const u8).add(mem::size_of::<Lpit>()),
+                    self.header.length.saturating_sub(mem::size_of::<Lpit>() as u32),
+                    entry_minimum_length,
+                )
+            },
+        }
+    }
+}
+
+pub struct LpiStateIter<'a> {
+    entries: EntryIter<'a, LpiStructureHeader>,
+}
+
+impl<'a> Iterator for LpiStateIter<'a> {
+    type Item = &'a NativeCStateLpi;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (entry_type, entry) in &mut self.entries {
+            match entry_type {
+                0 => return Some(unsafe { entry_as::<NativeCStateLpi>(entry) }),
+
+                /*
+                 * Other structure types are reserved by the ACPI standard. We skip them.
+                 */
+                _ => continue,
+            }
+        }
+
+        None
+    }
+}
+
+#[derive(Clone, Copy)]
+#[repr(C, packed)]
+struct LpiStructureHeader {
+    structure_type: u32,
+    length: u32,
+}
+
+impl crate::EntryHeader for LpiStructureHeader {
+    fn entry_type(&self) -> u16 {
+        // No structure types beyond `u16::MAX` are defined, so this doesn't alias any that are
+        u16::try_from(self.structure_type).unwrap_or(u16::MAX)
+    }
+
+    fn length(&self) -> u32 {
+        self.length
+    }
+}
+
+fn entry_minimum_length(header: &LpiStructureHeader) -> usize {
+    match header.structure_type {
+        0 => mem::size_of::<NativeCStateLpi>(),
+        _ => mem::size_of::<LpiStructureHeader>(),
+    }
+}
+
+/// Describes a low-power idle state that is entered through a native C-state instruction (e.g. `MWAIT`).
+#[repr(C, packed)]
+pub struct NativeCStateLpi {
+    header: LpiStructureHeader,
+    unique_id: u16,
+    _reserved: u16,
+    flags: u32,
+    entry_trigger: RawGenericAddress,
+    residency: u32,
+    latency: u32,
+    residency_counter: RawGenericAddress,
+    residency_counter_frequency: u64,
+}
+
+impl NativeCStateLpi {
+    pub fn unique_id(&self) -> u16 {
+        self.unique_id
+    }
+
+    /// If true, the state is disabled, and should not be used.
+    pub fn is_disabled(&self) -> bool {
+        { self.flags }.get_bit(0)
+    }
+
+    /// If true, the state doesn't have a residency counter, and `residency_counter` should be ignored.
+    pub fn counter_not_available(&self) -> bool {
+        { self.flags }.get_bit(1)
+    }
+
+    /// The register used to enter the state. For native C-states, this is usually a Functional Fixed Hardware
+    /// address that gives the `MWAIT` hint.
+    pub fn entry_trigger(&self) -> Result<GenericAddress, AcpiError> {
+        GenericAddress::from_raw(self.entry_trigger)
+    }
+
+    /// The minimum time the platform must stay in the state for entering it to save power, in microseconds.
+    pub fn min_residency(&self) -> u32 {
+        self.residency
+    }
+
+    /// The worst-case time to leave the state, in microseconds.
+    pub fn wake_latency(&self) -> u32 {
+        self.latency
+    }
+
+    /// The register that counts the time spent in the state, in ticks of `residency_counter_frequency`. Returns
+    /// `None` if the state doesn't have one.
+    pub fn residency_counter(&self) -> Option<Result<GenericAddress, AcpiError>> {
+        if self.counter_not_available() {
+            None
+        } else {
+            Some(GenericAddress::from_raw(self.residency_counter))
+        }
+    }
+
+    /// The frequency of the residency counter, in Hz. Returns `None` if the counter runs at the frequency of the
+    /// TSC.
+    pub fn residency_counter_frequency(&self) -> Option<u64> {
+        match self.residency_counter_frequency {
+            0 => None,
+            frequency => Some(frequency),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        platform::address::AddressSpace,
+        test_utils::{make_sdt, table_from_bytes},
+    };
+    use alloc::vec::Vec;
+
+    fn state_bytes(unique_id: u16, flags: u32, counter: (u8, u8, u64), frequency: u64) -> Vec<u8> {
+        let mut state = Vec::new();
+        state.extend_from_slice(&0u32.to_le_bytes());
+        state.extend_from_slice(&56u32.to_le_bytes());
+        state.extend_from_slice(&unique_id.to_le_bytes());
+        state.extend_from_slice(&0u16.to_le_bytes());
+        state.extend_from_slice(&flags.to_le_bytes());
+        state.extend_from_slice(&[0x7f, 1, 2, 3]);
+        state.extend_from_slice(&0x60u64.to_le_bytes());
+        state.extend_from_slice(&30_000u32.to_le_bytes());
+        state.extend_from_slice(&3_000u32.to_le_bytes());
+        state.extend_from_slice(&[counter.0, counter.1, 0, 0]);
+        state.extend_from_slice(&counter.2.to_le_bytes());
+        state.extend_from_slice(&frequency.to_le_bytes());
+        state
+    }
+
+    #[test]
+    fn test_states() {
+        let mut body = Vec::new();
+        body.extend(state_bytes(0, 0, (0x7f, 64, 0x632), 0));
+        // A reserved structure type, which should be skipped
+        body.extend_from_slice(&5u32.to_le_bytes());
+        body.extend_from_slice(&12u32.to_le_bytes());
+        body.extend_from_slice(&[0; 4]);
+        body.extend(state_bytes(1, 0, (0x00, 32, 0xfe00_0a00), 32_768));
+        body.extend(state_bytes(2, 0b11, (0x00, 0, 0), 0));
+
+        let table = make_sdt(b"LPIT", 1, &body);
+        let lpit = table_from_bytes::<Lpit>(&table);
+        let states: Vec<&NativeCStateLpi> = lpit.states().collect();
+        assert_eq!(states.len(), 3);
+
+        assert_eq!(states[0].unique_id(), 0);
+        assert!(!states[0].is_disabled());
+        assert_eq!(states[0].entry_trigger().unwrap().address_space, AddressSpace::FunctionalFixedHardware);
+        assert_eq!(states[0].min_residency(), 30_000);
+        assert_eq!(states[0].wake_latency(), 3_000);
+        let counter = states[0].residency_counter().unwrap().unwrap();
+        assert_eq!(counter.address_space, AddressSpace::FunctionalFixedHardware);
+        assert_eq!(counter.address, 0x632);
+        assert_eq!(states[0].residency_counter_frequency(), None);
+
+        assert_eq!(states[1].unique_id(), 1);
+        let counter = states[1].residency_counter().unwrap().unwrap();
+        assert_eq!(counter.address_space, AddressSpace::SystemMemory);
+        assert_eq!(counter.bit_width, 32);
+        assert_eq!(counter.address, 0xfe00_0a00);
+        assert_eq!(states[1].residency_counter_frequency(), Some(32_768));
+
+        assert!(states[2].is_disabled());
+        assert!(states[2].counter_not_available());
+        assert!(states[2].residency_counter().is_none());
+    }
+}
@@ -1,28 +1,47 @@
-use crate::{
-    platform::{
-        interrupt::{
-            Apic,
-            InterruptModel,
-            InterruptSourceOverride,
-            IoApic,
-            LocalInterruptLine,
-            NmiLine,
-            NmiProcessor,
-            NmiSource,
-            Polarity,
-            TriggerMode,
-        },
-        Processor,
-        ProcessorInfo,
-        ProcessorState,
+#[cfg(feature = "alloc")]
+use crate::platform::{
+    interrupt::{
+        Apic,
+        Gic,
+        GicIts,
+        GicMsiFrame,
+        GicRedistributorRegion,
+        GicVersion,
+        InterruptModel,
+        InterruptSourceOverride,
+        IoApic,
+        LocalInterruptLine,
+        NmiLine,
+        NmiProcessor,
+        NmiSource,
+        Polarity,
+        TriggerMode,
     },
-    sdt::SdtHeader,
+    Processor,
+    ProcessorInfo,
+    ProcessorState,
+};
+use crate::{
+    entry_as,
+    parse_warning,
+    sdt::{SdtHeader, Signature},
     AcpiError,
+    AcpiHandler,
     AcpiTable,
+    EntryIter,
+    ParseWarning,
+    PhysicalMapping,
 };
+#[cfg(feature = "alloc")]
 use alloc::vec::Vec;
 use bit_field::BitField;
-use core::{marker::PhantomData, mem};
+use core::{
+    fmt,
+    hint,
+    mem,
+    ptr,
+    sync::atomic::{AtomicU16, Ordering},
+};
 
 #[derive(Debug)]
 pub enum MadtError {
@@ -31,8 +50,43 @@ pub enum MadtError {
     InvalidLocalNmiLine,
     MpsIntiInvalidPolarity,
     MpsIntiInvalidTriggerMode,
+    /// The MADT describes a GIC, but doesn't have a GICD entry giving the address of its Distributor.
+    NoGicDistributor,
+    /// The firmware didn't acknowledge a command sent through the Multiprocessor Wakeup mailbox in time.
+    WakeupApsTimeout,
+    /// The MADT doesn't describe a Multiprocessor Wakeup mailbox.
+    WakeupMailboxUnsupported,
+    /// The firmware didn't finish handling the previous command sent through the Multiprocessor Wakeup mailbox in
+    /// time, so another command couldn't be sent.
+    WakeupMailboxBusy,
+    /// The firmware replaced a command sent through the Multiprocessor Wakeup mailbox with another value, instead
+    /// of clearing it, to show that it won't carry it out. The value it wrote is contained.
+    WakeupCommandRejected(u16),
+}
+
+impl fmt::Display for MadtError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MadtError::UnexpectedEntry => write!(f, "unexpected entry"),
+            MadtError::InterruptOverrideEntryHasInvalidBus => {
+                write!(f, "interrupt source override entry has a bus other than ISA")
+            }
+            MadtError::InvalidLocalNmiLine => write!(f, "local APIC NMI entry has an invalid LINT line"),
+            MadtError::MpsIntiInvalidPolarity => write!(f, "MPS INTI flags have an invalid polarity"),
+            MadtError::MpsIntiInvalidTriggerMode => write!(f, "MPS INTI flags have an invalid trigger mode"),
+            MadtError::NoGicDistributor => write!(f, "GIC is described without a GICD entry"),
+            MadtError::WakeupApsTimeout => write!(f, "timed out waiting for a wakeup mailbox command to complete"),
+            MadtError::WakeupMailboxUnsupported => write!(f, "no multiprocessor wakeup mailbox is described"),
+            MadtError::WakeupMailboxBusy => write!(f, "wakeup mailbox is still busy with the previous command"),
+            MadtError::WakeupCommandRejected(value) => {
+                write!(f, "firmware rejected a wakeup mailbox command with value {:#x}", value)
+            }
+        }
+    }
 }
 
+impl core::error::Error for MadtError {}
+
 /// Represents the MADT - this contains the MADT header fields. You can then iterate over a `Madt`
 /// to read each entry from it.
 ///
@@ -55,6 +109,7 @@ impl AcpiTable for Madt {
 }
 
 impl Madt {
+    #[cfg(feature = "alloc")]
     pub fn parse_interrupt_model(&self) -> Result<(InterruptModel, Option<ProcessorInfo>), AcpiError> {
         /*
          * We first do a pass through the MADT to determine which interrupt model is being used.
@@ -66,7 +121,9 @@ impl Madt {
             MadtEntry::InterruptSourceOverride(_) |
             MadtEntry::NmiSource(_) |   // TODO: is this one used by more than one model?
             MadtEntry::LocalApicNmi(_) |
-            MadtEntry::LocalApicAddressOverride(_) => {
+            MadtEntry::LocalApicAddressOverride(_) |
+            MadtEntry::LocalX2Apic(_) |
+            MadtEntry::X2ApicNmi(_) => {
                 return self.parse_apic_model();
             }
 
@@ -76,26 +133,23 @@ impl Madt {
                 unimplemented!();
             }
 
-            MadtEntry::LocalX2Apic(_) |
-            MadtEntry::X2ApicNmi(_) => {
-                unimplemented!();
-            }
-
             MadtEntry::Gicc(_) |
             MadtEntry::Gicd(_) |
             MadtEntry::GicMsiFrame(_) |
             MadtEntry::GicRedistributor(_) |
             MadtEntry::GicInterruptTranslationService(_) => {
-                unimplemented!();
+                return self.parse_gic_model();
             }
 
-            MadtEntry::MultiprocessorWakeup(_) => ()
+            MadtEntry::MultiprocessorWakeup(_) |
+            MadtEntry::RiscvIntc(_) => ()
         }
         }
 
         Ok((InterruptModel::Unknown, None))
     }
 
+    #[cfg(feature = "alloc")]
     fn parse_apic_model(&self) -> Result<(InterruptModel, Option<ProcessorInfo>), AcpiError> {
         let mut local_apic_address = self.local_apic_address as u64;
         let mut io_apic_count = 0;
@@ -110,8 +164,8 @@ impl Madt {
                 MadtEntry::IoApic(_) => io_apic_count += 1,
                 MadtEntry::InterruptSourceOverride(_) => iso_count += 1,
                 MadtEntry::NmiSource(_) => nmi_source_count += 1,
-                MadtEntry::LocalApicNmi(_) => local_nmi_line_count += 1,
-                MadtEntry::LocalApic(_) => processor_count += 1,
+                MadtEntry::LocalApicNmi(_) | MadtEntry::X2ApicNmi(_) => local_nmi_line_count += 1,
+                MadtEntry::LocalApic(_) | MadtEntry::LocalX2Apic(_) => processor_count += 1,
                 _ => (),
             }
         }
@@ -123,36 +177,54 @@ impl Madt {
         let mut boot_processor = None;
         let mut application_processors = Vec::with_capacity(processor_count.saturating_sub(1)); // Subtract one for the BSP
 
-        for entry in self.entries() {
-            match entry {
-                MadtEntry::LocalApic(entry) => {
-                    /*
-                     * The first processor is the BSP. Subsequent ones are APs. If we haven't found
-                     * the BSP yet, this must be it.
-                     */
-                    let is_ap = boot_processor.is_some();
-                    let is_disabled = !{ entry.flags }.get_bit(0);
+        /*
+         * Processors with APIC IDs that fit in 8 bits are described by Local APIC entries, and the others by Local
+         * x2APIC entries, so a MADT can contain both. The first processor, of either type, is the BSP, and
+         * subsequent ones are APs. Some firmware describes the same processor with both types of entry, so each
+         * APIC ID is only added once.
+         */
+        let mut add_processor = |processor_uid: u32, local_apic_id: u32, flags: u32| {
+            let already_added = boot_processor
+                .iter()
+                .chain(application_processors.iter())
+                .any(|processor: &Processor| processor.local_apic_id == local_apic_id);
+            if already_added {
+                return;
+            }
 
-                    let state = match (is_ap, is_disabled) {
-                        (_, true) => ProcessorState::Disabled,
-                        (true, false) => ProcessorState::WaitingForSipi,
-                        (false, false) => ProcessorState::Running,
-                    };
+            let is_ap = boot_processor.is_some();
+            let is_disabled = !flags.get_bit(0);
 
-                    let processor = Processor {
-                        processor_uid: entry.processor_id,
-                        local_apic_id: entry.apic_id,
-                        state,
-                        is_ap,
-                    };
+            let state = match (is_ap, is_disabled) {
+                (_, true) => ProcessorState::Disabled,
+                (true, false) => ProcessorState::WaitingForSipi,
+                (false, false) => ProcessorState::Running,
+            };
 
-                    if is_ap {
-                        application_processors.push(processor);
-                    } else {
-                        boot_processor = Some(processor);
-                    }
+            let processor = Processor {
+                processor_uid,
+                local_apic_id,
+                state,
+                is_ap,
+                online_capable: is_disabled && flags.get_bit(1),
+                mpidr: None,
+            };
+
+            if is_ap {
+                application_processors.push(processor);
+            } else {
+                boot_processor = Some(processor);
+            }
+        };
+
+        for entry in self.entries() {
+            match entry {
+                MadtEntry::LocalApic(entry) => {
+                    add_processor(entry.processor_id as u32, entry.apic_id as u32, entry.flags)
                 }
 
+                MadtEntry::LocalX2Apic(entry) => add_processor(entry.processor_uid, entry.x2apic_id, entry.flags),
+
                 MadtEntry::IoApic(entry) => {
                     io_apics.push(IoApic {
                         id: entry.io_apic_id,
@@ -199,10 +271,28 @@ impl Madt {
                     },
                 }),
 
+                MadtEntry::X2ApicNmi(entry) => local_apic_nmi_lines.push(NmiLine {
+                    processor: if entry.processor_uid == 0xffff_ffff {
+                        NmiProcessor::All
+                    } else {
+                        NmiProcessor::ProcessorUid(entry.processor_uid)
+                    },
+                    line: match entry.nmi_line {
+                        0 => LocalInterruptLine::Lint0,
+                        1 => LocalInterruptLine::Lint1,
+                        _ => return Err(AcpiError::InvalidMadt(MadtError::InvalidLocalNmiLine)),
+                    },
+                }),
+
                 MadtEntry::LocalApicAddressOverride(entry) => {
                     local_apic_address = entry.local_apic_address;
                 }
 
+                /*
+                 * The Multiprocessor Wakeup mailbox isn't part of the interrupt model.
+                 */
+                MadtEntry::MultiprocessorWakeup(_) => (),
+
                 _ => {
                     return Err(AcpiError::InvalidMadt(MadtError::UnexpectedEntry));
                 }
@@ -222,27 +312,136 @@ impl Madt {
         ))
     }
 
-    pub fn entries(&self) -> MadtEntryIter {
+    #[cfg(feature = "alloc")]
+    fn parse_gic_model(&self) -> Result<(InterruptModel, Option<ProcessorInfo>), AcpiError> {
+        let mut distributor = None;
+        let mut redistributor_regions = Vec::new();
+        let mut msi_frames = Vec::new();
+        let mut its_blocks = Vec::new();
+        let mut boot_processor = None;
+        let mut application_processors = Vec::new();
+
+        for entry in self.entries() {
+            match entry {
+                MadtEntry::Gicc(entry) => {
+                    /*
+                     * As with the APIC model, the first processor is taken to be the one we're running on, and
+                     * the others are APs, which must be started (usually with PSCI's `CPU_ON`).
+                     */
+                    let is_ap = boot_processor.is_some();
+                    let is_disabled = !{ entry.flags }.get_bit(0);
+
+                    let state = match (is_ap, is_disabled) {
+                        (_, true) => ProcessorState::Disabled,
+                        (true, false) => ProcessorState::WaitingForSipi,
+                        (false, false) => ProcessorState::Running,
+                    };
+
+                    let processor = Processor {
+                        processor_uid: entry.processor_uid,
+                        local_apic_id: entry.cpu_interface_number,
+                        state,
+                        is_ap,
+                        online_capable: is_disabled && { entry.flags }.get_bit(3),
+                        mpidr: Some(entry.mpidr),
+                    };
+
+                    if is_ap {
+                        application_processors.push(processor);
+                    } else {
+                        boot_processor = Some(processor);
+                    }
+                }
+
+                MadtEntry::Gicd(entry) => {
+                    let version = match entry.gic_version {
+                        0x00 => GicVersion::Unspecified,
+                        0x01 => GicVersion::V1,
+                        0x02 => GicVersion::V2,
+                        0x03 => GicVersion::V3,
+                        0x04 => GicVersion::V4,
+                        other => GicVersion::Reserved(other),
+                    };
+                    distributor = Some((version, entry.physical_base_address));
+                }
+
+                MadtEntry::GicMsiFrame(entry) => msi_frames.push(GicMsiFrame {
+                    id: entry.frame_id,
+                    base_address: entry.physical_base_address,
+                    spis: if { entry.flags }.get_bit(0) { Some((entry.spi_base, entry.spi_count)) } else { None },
+                }),
+
+                MadtEntry::GicRedistributor(entry) => redistributor_regions.push(GicRedistributorRegion {
+                    base_address: entry.discovery_range_base_address,
+                    length: entry.discovery_range_length,
+                }),
+
+                MadtEntry::GicInterruptTranslationService(entry) => {
+                    its_blocks.push(GicIts { id: entry.id, base_address: entry.physical_base_address })
+                }
+
+                /*
+                 * The Multiprocessor Wakeup mailbox can also be used to start processors on ARM platforms. It's
+                 * not part of the interrupt model, so we skip it.
+                 */
+                MadtEntry::MultiprocessorWakeup(_) => (),
+
+                _ => {
+                    return Err(AcpiError::InvalidMadt(MadtError::UnexpectedEntry));
+                }
+            }
+        }
+
+        let (version, distributor_address) =
+            distributor.ok_or(AcpiError::InvalidMadt(MadtError::NoGicDistributor))?;
+
+        Ok((
+            InterruptModel::Gic(Gic {
+                version,
+                distributor_address,
+                redistributor_regions,
+                msi_frames,
+                its_blocks,
+            }),
+            boot_processor.map(|boot_processor| ProcessorInfo { boot_processor, application_processors }),
+        ))
+    }
+
+    pub fn entries(&self) -> MadtEntryIter<'_> {
         MadtEntryIter {
-            pointer: unsafe { (self as *const Madt as *const u8).add(mem::size_of::<Madt>()) },
-            remaining_length: self.header.length - mem::size_of::<Madt>() as u32,
-            _phantom: PhantomData,
+            entries: unsafe {
+                EntryIter::new(
+                    Signature::MADT,
+                    (self as *const Madt as *const u8).add(mem::size_of::<Madt>()),
+                    self.header.length.saturating_sub(mem::size_of::<Madt>() as u32),
+                    entry_minimum_length,
+                )
+            },
         }
     }
 
     pub fn supports_8259(&self) -> bool {
         { self.flags }.get_bit(0)
     }
+
+    /// Get the physical address of the Multiprocessor Wakeup mailbox, which is used to start application
+    /// processors on platforms that don't support the usual INIT-SIPI sequence (e.g. guests using Intel TDX).
+    pub fn get_mpwk_mailbox_addr(&self) -> Result<u64, AcpiError> {
+        for entry in self.entries() {
+            if let MadtEntry::MultiprocessorWakeup(entry) = entry {
+                if entry.mailbox_address == 0 {
+                    break;
+                }
+                return Ok(entry.mailbox_address);
+            }
+        }
+
+        Err(AcpiError::InvalidMadt(MadtError::WakeupMailboxUnsupported))
+    }
 }
 
 pub struct MadtEntryIter<'a> {
-    pointer: *const u8,
-    /*
-     * The iterator can only have at most `u32::MAX` remaining bytes, because the length of the
-     * whole SDT can only be at most `u32::MAX`.
-     */
-    remaining_length: u32,
-    _phantom: PhantomData<&'a ()>,
+    entries: EntryIter<'a, EntryHeader>,
 }
 
 pub enum MadtEntry<'a> {
@@ -263,52 +462,51 @@ pub enum MadtEntry<'a> {
     GicRedistributor(&'a GicRedistributorEntry),
     GicInterruptTranslationService(&'a GicInterruptTranslationServiceEntry),
     MultiprocessorWakeup(&'a MultiprocessorWakeupEntry),
+    RiscvIntc(&'a RiscvIntcEntry),
 }
 
 impl<'a> Iterator for MadtEntryIter<'a> {
     type Item = MadtEntry<'a>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        while self.remaining_length > 0 {
-            let entry_pointer = self.pointer;
-            let header = unsafe { *(self.pointer as *const EntryHeader) };
-
-            self.pointer = unsafe { self.pointer.offset(header.length as isize) };
-            self.remaining_length -= header.length as u32;
-
+        for (entry_type, entry) in &mut self.entries {
             macro_rules! construct_entry {
                 ($entry_type:expr,
-                 $entry_pointer:expr,
+                 $entry:expr,
                  $(($value:expr => $variant:path as $type:ty)),*
                 ) => {
                     match $entry_type {
                         $(
                             $value => {
-                                return Some($variant(unsafe {
-                                    &*($entry_pointer as *const $type)
-                                }))
+                                return Some($variant(unsafe { entry_as::<$type>($entry) }))
                             }
                          )*
 
                         /*
-                         * These entry types are reserved by the ACPI standard. We should skip them
-                         * if they appear in a real MADT.
+                         * These entry types are reserved by the ACPI standard, or describe interrupt controllers
+                         * we don't support yet (e.g. those used by LoongArch). We skip them.
                          */
-                        0x11..=0x7f => {}
+                        0x11..=0x17 | 0x19..=0x7f => parse_warning(ParseWarning::UnknownEntryType {
+                            signature: Signature::MADT,
+                            entry_type,
+                        }),
 
                         /*
                          * These entry types are reserved for OEM use. Atm, we just skip them too.
                          * TODO: work out if we should ever do anything else here
                          */
-                        0x80..=0xff => {}
+                        0x80..=0xff => parse_warning(ParseWarning::UnknownEntryType {
+                            signature: Signature::MADT,
+                            entry_type,
+                        }),
                     }
                 }
             }
 
             #[rustfmt::skip]
             construct_entry!(
-                header.entry_type,
-                entry_pointer,
+                entry_type as u8,
+                entry,
                 (0x0 => MadtEntry::LocalApic as LocalApicEntry),
                 (0x1 => MadtEntry::IoApic as IoApicEntry),
                 (0x2 => MadtEntry::InterruptSourceOverride as InterruptSourceOverrideEntry),
@@ -325,7 +523,8 @@ impl<'a> Iterator for MadtEntryIter<'a> {
                 (0xd => MadtEntry::GicMsiFrame as GicMsiFrameEntry),
                 (0xe => MadtEntry::GicRedistributor as GicRedistributorEntry),
                 (0xf => MadtEntry::GicInterruptTranslationService as GicInterruptTranslationServiceEntry),
-                (0x10 => MadtEntry::MultiprocessorWakeup as MultiprocessorWakeupEntry)
+                (0x10 => MadtEntry::MultiprocessorWakeup as MultiprocessorWakeupEntry),
+                (0x18 => MadtEntry::RiscvIntc as RiscvIntcEntry)
             );
         }
 
@@ -340,6 +539,41 @@ pub struct EntryHeader {
     length: u8,
 }
 
+impl crate::EntryHeader for EntryHeader {
+    fn entry_type(&self) -> u16 {
+        self.entry_type.into()
+    }
+
+    fn length(&self) -> u32 {
+        self.length.into()
+    }
+}
+
+fn entry_minimum_length(header: &EntryHeader) -> usize {
+    match header.entry_type {
+        0x0 => mem::size_of::<LocalApicEntry>(),
+        0x1 => mem::size_of::<IoApicEntry>(),
+        0x2 => mem::size_of::<InterruptSourceOverrideEntry>(),
+        0x3 => mem::size_of::<NmiSourceEntry>(),
+        0x4 => mem::size_of::<LocalApicNmiEntry>(),
+        0x5 => mem::size_of::<LocalApicAddressOverrideEntry>(),
+        0x6 => mem::size_of::<IoSapicEntry>(),
+        0x7 => mem::size_of::<LocalSapicEntry>(),
+        0x8 => mem::size_of::<PlatformInterruptSourceEntry>(),
+        0x9 => mem::size_of::<LocalX2ApicEntry>(),
+        0xa => mem::size_of::<X2ApicNmiEntry>(),
+        // GICC entries have grown since ACPI 5.1, so we accept the original layout, and check for the newer fields
+        0xb => GICC_ACPI_5_1_LENGTH,
+        0xc => mem::size_of::<GicdEntry>(),
+        0xd => mem::size_of::<GicMsiFrameEntry>(),
+        0xe => mem::size_of::<GicRedistributorEntry>(),
+        0xf => mem::size_of::<GicInterruptTranslationServiceEntry>(),
+        0x10 => mem::size_of::<MultiprocessorWakeupEntry>(),
+        0x18 => mem::size_of::<RiscvIntcEntry>(),
+        _ => mem::size_of::<EntryHeader>(),
+    }
+}
+
 #[repr(C, packed)]
 pub struct LocalApicEntry {
     header: EntryHeader,
@@ -460,12 +694,65 @@ pub struct GiccEntry {
     performance_interrupt_gsiv: u32,
     parked_address: u64,
     gic_registers_address: u64,
+    gic_virtual_registers_address: u64,
     gic_control_block_address: u64,
     vgic_maintenance_interrupt: u32,
     gicr_base_address: u64,
     mpidr: u64,
     processor_power_efficiency_class: u8,
-    _reserved2: [u8; 3],
+    _reserved2: u8,
+    spe_overflow_interrupt: u16,
+    trbe_interrupt: u16,
+}
+
+/// The length of a GICC entry in ACPI 5.1, before any of the optional fields were added.
+const GICC_ACPI_5_1_LENGTH: usize = 76;
+/// The length of a GICC entry from ACPI 6.0, which added the processor power efficiency class. ACPI 6.3 added the
+/// SPE overflow interrupt in what were reserved bytes, without changing the length.
+const GICC_ACPI_6_0_LENGTH: usize = 80;
+/// The length of a GICC entry from ACPI 6.5, which added the TRBE interrupt.
+const GICC_ACPI_6_5_LENGTH: usize = 82;
+
+impl GiccEntry {
+    fn has_length(&self, length: usize) -> bool {
+        self.header.length as usize >= length
+    }
+
+    /// The power efficiency class of the processor, which can be used to tell apart processors with different
+    /// power and performance characteristics. Returns `None` if the entry predates ACPI 6.0.
+    pub fn processor_power_efficiency_class(&self) -> Option<u8> {
+        if !self.has_length(GICC_ACPI_6_0_LENGTH) {
+            return None;
+        }
+
+        Some(self.processor_power_efficiency_class)
+    }
+
+    /// The GSIV of the Statistical Profiling Extension's buffer overflow interrupt. Returns `None` if the entry
+    /// predates ACPI 6.3, or if the processor doesn't support it.
+    pub fn spe_overflow_interrupt(&self) -> Option<u16> {
+        if !self.has_length(GICC_ACPI_6_0_LENGTH) {
+            return None;
+        }
+
+        match self.spe_overflow_interrupt {
+            0 => None,
+            gsiv => Some(gsiv),
+        }
+    }
+
+    /// The GSIV of the Trace Buffer Extension interrupt. Returns `None` if the entry predates ACPI 6.5, or if the
+    /// processor doesn't support it.
+    pub fn trbe_interrupt(&self) -> Option<u16> {
+        if !self.has_length(GICC_ACPI_6_5_LENGTH) {
+            return None;
+        }
+
+        match self.trbe_interrupt {
+            0 => None,
+            gsiv => Some(gsiv),
+        }
+    }
 }
 
 #[repr(C, packed)]
@@ -523,6 +810,157 @@ pub struct MultiprocessorWakeupEntry {
     mailbox_address: u64,
 }
 
+/// Describes the local interrupt controller of a RISC-V hart (RINTC).
+#[repr(C, packed)]
+pub struct RiscvIntcEntry {
+    header: EntryHeader,
+    version: u8,
+    _reserved: u8,
+    flags: u32,
+    hart_id: u64,
+    processor_uid: u32,
+    external_interrupt_controller_id: u32,
+    imsic_base_address: u64,
+    imsic_size: u32,
+}
+
+impl RiscvIntcEntry {
+    pub fn is_enabled(&self) -> bool {
+        { self.flags }.get_bit(0)
+    }
+
+    pub fn hart_id(&self) -> u64 {
+        self.hart_id
+    }
+
+    pub fn processor_uid(&self) -> u32 {
+        self.processor_uid
+    }
+
+    /// The ID of the external interrupt controller (a PLIC or APLIC) that the hart's external interrupts come from.
+    pub fn external_interrupt_controller_id(&self) -> u32 {
+        self.external_interrupt_controller_id
+    }
+
+    /// The address and size of the hart's Incoming MSI Controller (IMSIC) interrupt file, if it has one.
+    pub fn imsic(&self) -> Option<(u64, u32)> {
+        match self.imsic_base_address {
+            0 => None,
+            base => Some((base, self.imsic_size)),
+        }
+    }
+}
+
+impl MultiprocessorWakeupEntry {
+    pub fn mailbox_version(&self) -> u16 {
+        self.mailbox_version
+    }
+
+    pub fn mailbox_address(&self) -> u64 {
+        self.mailbox_address
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u16)]
+pub enum MpProtectedModeWakeupCommand {
+    Noop = 0,
+    Wakeup = 1,
+}
+
+/// The layout of the Multiprocessor Wakeup mailbox. This is shared between the OS and the firmware: the OS writes
+/// a command, and the firmware clears the `command` field once it has read it.
+#[repr(C)]
+pub struct MultiprocessorWakeupMailbox {
+    pub command: AtomicU16,
+    _reserved: u16,
+    /// The processor to start. On x86, this is the processor's local APIC ID. On RISC-V, it's the hart ID.
+    pub apic_id: u32,
+    pub wakeup_vector: u64,
+    pub reserved_for_os: [u8; 2032],
+    reserved_for_firmware: [u8; 2048],
+}
+
+/// A mapping of the Multiprocessor Wakeup mailbox, through which commands can be sent to the firmware.
+pub struct MpwkMailbox<H>
+where
+    H: AcpiHandler,
+{
+    mapping: PhysicalMapping<H, MultiprocessorWakeupMailbox>,
+}
+
+impl<H> MpwkMailbox<H>
+where
+    H: AcpiHandler,
+{
+    /// Map the mailbox at the given physical address (usually found with [`Madt::get_mpwk_mailbox_addr`]).
+    ///
+    /// ### Safety
+    /// `address` must be the address of the platform's Multiprocessor Wakeup mailbox.
+    pub unsafe fn new(handler: &H, address: u64) -> MpwkMailbox<H> {
+        let mapping = unsafe {
+            handler.map_physical_region::<MultiprocessorWakeupMailbox>(
+                address as usize,
+                mem::size_of::<MultiprocessorWakeupMailbox>(),
+            )
+        };
+        MpwkMailbox { mapping }
+    }
+
+    /// Send `command` to the firmware, for the processor `processor_id`, and wait for the firmware to acknowledge
+    /// it. The meaning of `processor_id` and `wakeup_vector`, and the state the processor is started in, depend on
+    /// the architecture: on x86, the processor with the given APIC ID is started in 64-bit mode at
+    /// `wakeup_vector` when sent [`MpProtectedModeWakeupCommand::Wakeup`]. This checks whether the firmware has
+    /// acknowledged the command up to `timeout_loops` times.
+    ///
+    /// The firmware acknowledges a command by clearing it. If it instead replaces it with another value, this
+    /// fails with [`MadtError::WakeupCommandRejected`], and if it doesn't respond in time, with
+    /// [`MadtError::WakeupApsTimeout`]. The mailbox can only hold one command at a time, so this first waits (for
+    /// up to `timeout_loops` checks) for the firmware to clear any previous command, and fails with
+    /// [`MadtError::WakeupMailboxBusy`] if it doesn't.
+    pub fn send(
+        &self,
+        processor_id: u32,
+        wakeup_vector: u64,
+        command: u16,
+        timeout_loops: u64,
+    ) -> Result<(), AcpiError> {
+        let mailbox = self.mapping.virtual_start().as_ptr();
+
+        let mut is_free = false;
+        for _ in 0..timeout_loops {
+            if self.mapping.command.load(Ordering::Acquire) == MpProtectedModeWakeupCommand::Noop as u16 {
+                is_free = true;
+                break;
+            }
+            hint::spin_loop();
+        }
+        if !is_free {
+            return Err(AcpiError::InvalidMadt(MadtError::WakeupMailboxBusy));
+        }
+
+        /*
+         * The other fields must be written before the command, as the firmware may act as soon as it sees it.
+         */
+        unsafe {
+            ptr::write_volatile(ptr::addr_of_mut!((*mailbox).apic_id), processor_id);
+            ptr::write_volatile(ptr::addr_of_mut!((*mailbox).wakeup_vector), wakeup_vector);
+        }
+        self.mapping.command.store(command, Ordering::Release);
+
+        for _ in 0..timeout_loops {
+            match self.mapping.command.load(Ordering::Acquire) {
+                value if value == MpProtectedModeWakeupCommand::Noop as u16 => return Ok(()),
+                value if value == command => hint::spin_loop(),
+                value => return Err(AcpiError::InvalidMadt(MadtError::WakeupCommandRejected(value))),
+            }
+        }
+
+        Err(AcpiError::InvalidMadt(MadtError::WakeupApsTimeout))
+    }
+}
+
+#[cfg(feature = "alloc")]
 fn parse_mps_inti_flags(flags: u16) -> Result<(Polarity, TriggerMode), AcpiError> {
     let polarity = match flags.get_bits(0..2) {
         0b00 => Polarity::SameAsBus,
@@ -540,3 +978,541 @@ fn parse_mps_inti_flags(flags: u16) -> Result<(Polarity, TriggerMode), AcpiError
 
     Ok((polarity, trigger_mode))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_utils::{collect_parse_warnings, make_sdt, table_from_bytes, TestHandler},
+        ParseWarning,
+    };
+    use alloc::vec::Vec;
+
+    #[cfg(feature = "alloc")]
+    fn gicc(cpu_interface_number: u32, processor_uid: u32, flags: u32, mpidr: u64) -> Vec<u8> {
+        let mut entry = alloc::vec![0; 80];
+        entry[0] = 0x0b;
+        entry[1] = 80;
+        entry[4..8].copy_from_slice(&cpu_interface_number.to_le_bytes());
+        entry[8..12].copy_from_slice(&processor_uid.to_le_bytes());
+        entry[12..16].copy_from_slice(&flags.to_le_bytes());
+        entry[20..24].copy_from_slice(&23u32.to_le_bytes());
+        entry[56..60].copy_from_slice(&25u32.to_le_bytes());
+        entry[68..76].copy_from_slice(&mpidr.to_le_bytes());
+        entry
+    }
+
+    /// Build a MADT in the form produced by QEMU's `virt` machine with a GICv3 and an ITS.
+    #[cfg(feature = "alloc")]
+    fn make_gic_madt(with_gicd: bool) -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body.extend_from_slice(&0u32.to_le_bytes());
+
+        if with_gicd {
+            body.extend_from_slice(&[0x0c, 24, 0, 0]);
+            body.extend_from_slice(&0u32.to_le_bytes());
+            body.extend_from_slice(&0x0800_0000u64.to_le_bytes());
+            body.extend_from_slice(&0u32.to_le_bytes());
+            body.extend_from_slice(&[3, 0, 0, 0]);
+        }
+
+        body.extend(gicc(0, 0, 0b1, 0x0));
+        body.extend(gicc(1, 1, 0b1, 0x1));
+        body.extend(gicc(2, 2, 0b1000, 0x100));
+
+        body.extend_from_slice(&[0x0e, 16, 0, 0]);
+        body.extend_from_slice(&0x080a_0000u64.to_le_bytes());
+        body.extend_from_slice(&0x00f6_0000u32.to_le_bytes());
+
+        body.extend_from_slice(&[0x0f, 20, 0, 0]);
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body.extend_from_slice(&0x0808_0000u64.to_le_bytes());
+        body.extend_from_slice(&0u32.to_le_bytes());
+
+        body.extend_from_slice(&[0x0d, 24, 0, 0]);
+        body.extend_from_slice(&1u32.to_le_bytes());
+        body.extend_from_slice(&0x0802_0000u64.to_le_bytes());
+        body.extend_from_slice(&1u32.to_le_bytes());
+        body.extend_from_slice(&64u16.to_le_bytes());
+        body.extend_from_slice(&80u16.to_le_bytes());
+
+        make_sdt(b"APIC", 4, &body)
+    }
+
+    fn local_apic(processor_id: u8, apic_id: u8, flags: u32) -> Vec<u8> {
+        let mut entry = alloc::vec![0x00, 8, processor_id, apic_id];
+        entry.extend_from_slice(&flags.to_le_bytes());
+        entry
+    }
+
+    fn io_apic(id: u8, address: u32, gsi_base: u32) -> Vec<u8> {
+        let mut entry = alloc::vec![0x01, 12, id, 0];
+        entry.extend_from_slice(&address.to_le_bytes());
+        entry.extend_from_slice(&gsi_base.to_le_bytes());
+        entry
+    }
+
+    fn interrupt_source_override(irq: u8, gsi: u32, flags: u16) -> Vec<u8> {
+        let mut entry = alloc::vec![0x02, 10, 0, irq];
+        entry.extend_from_slice(&gsi.to_le_bytes());
+        entry.extend_from_slice(&flags.to_le_bytes());
+        entry
+    }
+
+    /// Build a MADT in the form produced by QEMU's `q35` machine, with two processors and a single I/O APIC.
+    fn make_apic_madt() -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&0xfee0_0000u32.to_le_bytes());
+        body.extend_from_slice(&1u32.to_le_bytes());
+
+        body.extend(local_apic(0, 0, 1));
+        body.extend(local_apic(1, 1, 1));
+        body.extend(io_apic(0, 0xfec0_0000, 0));
+        body.extend(interrupt_source_override(0, 2, 0x0000));
+        body.extend(interrupt_source_override(5, 5, 0x000d));
+        body.extend(interrupt_source_override(9, 9, 0x000d));
+        body.extend(interrupt_source_override(10, 10, 0x000f));
+        body.extend(interrupt_source_override(11, 11, 0x000d));
+        // NMI source
+        body.extend_from_slice(&[0x03, 8, 0x01, 0x00]);
+        body.extend_from_slice(&23u32.to_le_bytes());
+        // Local APIC NMI, on LINT1 of all processors
+        body.extend_from_slice(&[0x04, 6, 0xff, 0x00, 0x00, 0x01]);
+
+        make_sdt(b"APIC", 1, &body)
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_apic_model() {
+        let table = make_apic_madt();
+        let madt = table_from_bytes::<Madt>(&table);
+        let (model, processor_info) = madt.parse_interrupt_model().unwrap();
+
+        let apic = match model {
+            InterruptModel::Apic(ref apic) => apic,
+            ref other => panic!("Expected an APIC interrupt model, got {:?}", other),
+        };
+        assert_eq!(apic.local_apic_address, 0xfee0_0000);
+        assert!(apic.also_has_legacy_pics);
+        assert_eq!(apic.io_apics.len(), 1);
+        assert_eq!(apic.interrupt_source_overrides.len(), 5);
+
+        let timer_override = &apic.interrupt_source_overrides[0];
+        assert_eq!(timer_override.isa_source, 0);
+        assert_eq!(timer_override.global_system_interrupt, 2);
+        assert_eq!(timer_override.polarity, Polarity::SameAsBus);
+        assert_eq!(timer_override.trigger_mode, TriggerMode::SameAsBus);
+
+        let sci_override = &apic.interrupt_source_overrides[2];
+        assert_eq!(sci_override.isa_source, 9);
+        assert_eq!(sci_override.polarity, Polarity::ActiveHigh);
+        assert_eq!(sci_override.trigger_mode, TriggerMode::Level);
+        assert_eq!(apic.interrupt_source_overrides[3].polarity, Polarity::ActiveLow);
+        assert_eq!(apic.interrupt_source_overrides[3].trigger_mode, TriggerMode::Level);
+
+        assert_eq!(model.gsi_for_isa_irq(0), 2);
+        assert_eq!(model.gsi_for_isa_irq(1), 1);
+        assert_eq!(model.gsi_for_isa_irq(10), 10);
+        assert_eq!(InterruptModel::Unknown.gsi_for_isa_irq(0), 0);
+
+        assert_eq!(apic.nmi_sources.len(), 1);
+        assert_eq!(apic.nmi_sources[0].global_system_interrupt, 23);
+        assert_eq!(apic.nmi_sources[0].polarity, Polarity::ActiveHigh);
+        assert_eq!(apic.nmi_sources[0].trigger_mode, TriggerMode::SameAsBus);
+        assert_eq!(apic.local_apic_nmi_lines.len(), 1);
+        assert_eq!(apic.local_apic_nmi_lines[0].processor, NmiProcessor::All);
+        assert_eq!(apic.local_apic_nmi_lines[0].line, LocalInterruptLine::Lint1);
+
+        let processor_info = processor_info.unwrap();
+        assert_eq!(processor_info.boot_processor.local_apic_id, 0);
+        assert_eq!(processor_info.application_processors.len(), 1);
+    }
+
+    const MAILBOX_ADDRESS: u64 = 0x7fff_0000;
+
+    #[repr(C, align(4096))]
+    struct MailboxBytes([u8; 4096]);
+
+    #[test]
+    fn test_entries() {
+        let table = make_apic_madt();
+        let madt = table_from_bytes::<Madt>(&table);
+        let entries: Vec<MadtEntry> = madt.entries().collect();
+        assert_eq!(entries.len(), 10);
+        assert!(matches!(entries[0], MadtEntry::LocalApic(entry) if { entry.apic_id } == 0));
+        assert!(matches!(entries[1], MadtEntry::LocalApic(entry) if { entry.apic_id } == 1));
+        assert!(matches!(entries[2], MadtEntry::IoApic(entry) if { entry.io_apic_address } == 0xfec0_0000));
+        assert!(matches!(entries[3], MadtEntry::InterruptSourceOverride(entry) if { entry.irq } == 0));
+        assert!(matches!(entries[8], MadtEntry::NmiSource(_)));
+        assert!(matches!(entries[9], MadtEntry::LocalApicNmi(_)));
+    }
+
+    #[test]
+    fn test_skipped_and_riscv_entries() {
+        let mut body = Vec::new();
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body.extend_from_slice(&0u32.to_le_bytes());
+        // A LoongArch Core Programmable Interrupt Controller, which should be skipped
+        body.extend_from_slice(&[0x11, 15, 1, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0]);
+        // An OEM entry, which should also be skipped
+        body.extend_from_slice(&[0x80, 4, 0xaa, 0xbb]);
+
+        let mut rintc = alloc::vec![0; 36];
+        rintc[0] = 0x18;
+        rintc[1] = 36;
+        rintc[2] = 1;
+        rintc[4..8].copy_from_slice(&1u32.to_le_bytes());
+        rintc[8..16].copy_from_slice(&3u64.to_le_bytes());
+        rintc[16..20].copy_from_slice(&4u32.to_le_bytes());
+        rintc[20..24].copy_from_slice(&0u32.to_le_bytes());
+        rintc[24..32].copy_from_slice(&0x2800_3000u64.to_le_bytes());
+        rintc[32..36].copy_from_slice(&0x1000u32.to_le_bytes());
+        body.extend(rintc);
+
+        let table = make_sdt(b"APIC", 6, &body);
+        let madt = table_from_bytes::<Madt>(&table);
+        let (entries, warnings) = collect_parse_warnings(|| madt.entries().collect::<Vec<MadtEntry>>());
+        assert_eq!(
+            warnings,
+            [
+                ParseWarning::UnknownEntryType { signature: Signature::MADT, entry_type: 0x11 },
+                ParseWarning::UnknownEntryType { signature: Signature::MADT, entry_type: 0x80 },
+            ]
+        );
+        assert_eq!(entries.len(), 1);
+        match entries[0] {
+            MadtEntry::RiscvIntc(rintc) => {
+                assert!(rintc.is_enabled());
+                assert_eq!(rintc.hart_id(), 3);
+                assert_eq!(rintc.processor_uid(), 4);
+                assert_eq!(rintc.external_interrupt_controller_id(), 0);
+                assert_eq!(rintc.imsic(), Some((0x2800_3000, 0x1000)));
+            }
+            _ => panic!("Expected a RINTC entry"),
+        }
+    }
+
+    #[test]
+    fn test_invalid_entry_lengths() {
+        // A zero-length entry would otherwise be walked forever
+        let mut body = make_apic_madt()[36..].to_vec();
+        body.extend_from_slice(&[0x00, 0, 0, 0]);
+        body.extend(local_apic(2, 2, 1));
+        let table = make_sdt(b"APIC", 1, &body);
+        let madt = table_from_bytes::<Madt>(&table);
+        assert_eq!(madt.entries().count(), 10);
+
+        // A GICC that's too short for its type
+        let mut body = Vec::new();
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body.extend(local_apic(0, 0, 1));
+        body.extend_from_slice(&[0x0b, 8, 0, 0, 0, 0, 0, 0]);
+        let table = make_sdt(b"APIC", 1, &body);
+        let madt = table_from_bytes::<Madt>(&table);
+        assert_eq!(madt.entries().count(), 1);
+
+        // An entry that claims to extend past the end of the table
+        let mut body = Vec::new();
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body.extend(local_apic(0, 0, 1));
+        body.extend_from_slice(&[0x01, 12, 0, 0]);
+        let table = make_sdt(b"APIC", 1, &body);
+        let madt = table_from_bytes::<Madt>(&table);
+        assert_eq!(madt.entries().count(), 1);
+    }
+
+    #[test]
+    fn test_gicc_lengths() {
+        // An ACPI 5.1 GICC, which doesn't have any of the newer fields, followed by a GICD
+        let mut body = Vec::new();
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body.extend_from_slice(&0u32.to_le_bytes());
+        let mut gicc = [0; 76];
+        gicc[0] = 0x0b;
+        gicc[1] = 76;
+        body.extend_from_slice(&gicc);
+        body.extend_from_slice(&[0x0c, 24, 0, 0]);
+        body.extend_from_slice(&[0; 20]);
+        let table = make_sdt(b"APIC", 3, &body);
+        let madt = table_from_bytes::<Madt>(&table);
+
+        let mut entries = madt.entries();
+        match entries.next() {
+            Some(MadtEntry::Gicc(gicc)) => {
+                assert_eq!(gicc.processor_power_efficiency_class(), None);
+                assert_eq!(gicc.spe_overflow_interrupt(), None);
+                assert_eq!(gicc.trbe_interrupt(), None);
+            }
+            _ => panic!("Expected a GICC entry"),
+        }
+        assert!(matches!(entries.next(), Some(MadtEntry::Gicd(_))));
+        assert!(entries.next().is_none());
+
+        // An ACPI 6.5 GICC, which has all of them
+        let mut body = Vec::new();
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body.extend_from_slice(&0u32.to_le_bytes());
+        let mut gicc = [0; 82];
+        gicc[0] = 0x0b;
+        gicc[1] = 82;
+        gicc[76] = 1;
+        gicc[78..80].copy_from_slice(&21u16.to_le_bytes());
+        gicc[80..82].copy_from_slice(&22u16.to_le_bytes());
+        body.extend_from_slice(&gicc);
+        let table = make_sdt(b"APIC", 6, &body);
+        let madt = table_from_bytes::<Madt>(&table);
+
+        match madt.entries().next() {
+            Some(MadtEntry::Gicc(gicc)) => {
+                assert_eq!(gicc.processor_power_efficiency_class(), Some(1));
+                assert_eq!(gicc.spe_overflow_interrupt(), Some(21));
+                assert_eq!(gicc.trbe_interrupt(), Some(22));
+            }
+            _ => panic!("Expected a GICC entry"),
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_mpwk_mailbox_address() {
+        let table = make_apic_madt();
+        assert!(matches!(
+            table_from_bytes::<Madt>(&table).get_mpwk_mailbox_addr(),
+            Err(AcpiError::InvalidMadt(MadtError::WakeupMailboxUnsupported))
+        ));
+
+        let mut table = make_apic_madt();
+        table.extend_from_slice(&[0x10, 16, 0, 0, 0, 0, 0, 0]);
+        table.extend_from_slice(&MAILBOX_ADDRESS.to_le_bytes());
+        let table = make_sdt(b"APIC", 1, &table[36..]);
+        let madt = table_from_bytes::<Madt>(&table);
+        assert_eq!(madt.get_mpwk_mailbox_addr().unwrap(), MAILBOX_ADDRESS);
+        assert!(matches!(madt.parse_interrupt_model(), Ok((InterruptModel::Apic(_), Some(_)))));
+    }
+
+    #[test]
+    fn test_mpwk_mailbox_timeout() {
+        let mut bytes = MailboxBytes([0; 4096]);
+        let handler = TestHandler::with_mapping(MAILBOX_ADDRESS as usize, bytes.0.as_mut_ptr());
+
+        let mailbox = unsafe { MpwkMailbox::new(&handler, MAILBOX_ADDRESS) };
+        assert!(matches!(
+            mailbox.send(3, 0x8000, MpProtectedModeWakeupCommand::Wakeup as u16, 100),
+            Err(AcpiError::InvalidMadt(MadtError::WakeupApsTimeout))
+        ));
+        drop(mailbox);
+
+        assert_eq!(&bytes.0[0..2], &1u16.to_le_bytes());
+        assert_eq!(&bytes.0[4..8], &3u32.to_le_bytes());
+        assert_eq!(&bytes.0[8..16], &0x8000u64.to_le_bytes());
+    }
+
+    #[test]
+    fn test_mpwk_mailbox_busy() {
+        let mut bytes = MailboxBytes([0; 4096]);
+        bytes.0[0..2].copy_from_slice(&(MpProtectedModeWakeupCommand::Wakeup as u16).to_le_bytes());
+        let handler = TestHandler::with_mapping(MAILBOX_ADDRESS as usize, bytes.0.as_mut_ptr());
+
+        // The firmware never clears the previous command, so the mailbox must be left alone
+        let mailbox = unsafe { MpwkMailbox::new(&handler, MAILBOX_ADDRESS) };
+        assert!(matches!(
+            mailbox.send(3, 0x8000, MpProtectedModeWakeupCommand::Wakeup as u16, 100),
+            Err(AcpiError::InvalidMadt(MadtError::WakeupMailboxBusy))
+        ));
+        drop(mailbox);
+
+        assert_eq!(&bytes.0[0..2], &1u16.to_le_bytes());
+        assert_eq!(&bytes.0[4..16], &[0; 12]);
+    }
+
+    /// Send the wakeup command through a mailbox, while pretending to be the firmware: wait for the command to
+    /// arrive, and then respond to it by replacing it with `response`.
+    fn send_with_response(response: u16) -> Result<(), AcpiError> {
+        let mut bytes = MailboxBytes([0; 4096]);
+        let handler = TestHandler::with_mapping(MAILBOX_ADDRESS as usize, bytes.0.as_mut_ptr());
+
+        let command_address = bytes.0.as_ptr() as usize;
+        let firmware = std::thread::spawn(move || {
+            let command = unsafe { &*(command_address as *const AtomicU16) };
+            while command.load(Ordering::Acquire) != MpProtectedModeWakeupCommand::Wakeup as u16 {
+                hint::spin_loop();
+            }
+            command.store(response, Ordering::Release);
+        });
+
+        let mailbox = unsafe { MpwkMailbox::new(&handler, MAILBOX_ADDRESS) };
+        let result = mailbox.send(1, 0x8000, MpProtectedModeWakeupCommand::Wakeup as u16, u64::MAX);
+        firmware.join().unwrap();
+        result
+    }
+
+    #[test]
+    fn test_mpwk_mailbox_acknowledged() {
+        assert!(send_with_response(MpProtectedModeWakeupCommand::Noop as u16).is_ok());
+    }
+
+    #[test]
+    fn test_mpwk_mailbox_rejected() {
+        assert!(matches!(
+            send_with_response(0xffff),
+            Err(AcpiError::InvalidMadt(MadtError::WakeupCommandRejected(0xffff)))
+        ));
+    }
+
+    #[cfg(feature = "alloc")]
+    fn local_x2apic(processor_uid: u32, x2apic_id: u32, flags: u32) -> Vec<u8> {
+        let mut entry = alloc::vec![0x09, 16, 0, 0];
+        entry.extend_from_slice(&x2apic_id.to_le_bytes());
+        entry.extend_from_slice(&flags.to_le_bytes());
+        entry.extend_from_slice(&processor_uid.to_le_bytes());
+        entry
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_mixed_local_apic_and_x2apic() {
+        /*
+         * Processors with APIC IDs up to 254 are described by Local APIC entries, and the rest by Local x2APIC
+         * entries, like on a large server. The firmware also describes one of the low processors with both types
+         * of entry.
+         */
+        let mut body = Vec::new();
+        body.extend_from_slice(&0xfee0_0000u32.to_le_bytes());
+        body.extend_from_slice(&0u32.to_le_bytes());
+        for apic_id in 0..255u8 {
+            body.extend(local_apic(apic_id, apic_id, 1));
+        }
+        for apic_id in 255..260u32 {
+            body.extend(local_x2apic(apic_id, apic_id, 1));
+        }
+        body.extend(local_x2apic(3, 3, 1));
+        // An x2APIC NMI on LINT1 of all processors
+        body.extend_from_slice(&[0x0a, 12, 0, 0]);
+        body.extend_from_slice(&0xffff_ffffu32.to_le_bytes());
+        body.extend_from_slice(&[1, 0, 0, 0]);
+        let table = make_sdt(b"APIC", 4, &body);
+        let madt = table_from_bytes::<Madt>(&table);
+
+        let (model, processor_info) = madt.parse_interrupt_model().unwrap();
+        let apic = match model {
+            InterruptModel::Apic(apic) => apic,
+            other => panic!("Expected an APIC interrupt model, got {:?}", other),
+        };
+        assert_eq!(apic.local_apic_nmi_lines.len(), 1);
+        assert_eq!(apic.local_apic_nmi_lines[0].processor, NmiProcessor::All);
+        assert_eq!(apic.local_apic_nmi_lines[0].line, LocalInterruptLine::Lint1);
+
+        let processor_info = processor_info.unwrap();
+        assert_eq!(processor_info.boot_processor.local_apic_id, 0);
+        assert_eq!(processor_info.boot_processor.state, ProcessorState::Running);
+        assert_eq!(processor_info.application_processors.len(), 259);
+        assert!(processor_info
+            .application_processors
+            .iter()
+            .all(|processor| processor.is_ap && processor.state == ProcessorState::WaitingForSipi));
+
+        let apic_ids: Vec<u32> = processor_info.all().map(|processor| processor.local_apic_id).collect();
+        assert_eq!(apic_ids, (0..260).collect::<Vec<u32>>());
+        assert_eq!(processor_info.find_by_apic_id(259).unwrap().processor_uid, 259);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_local_apic_address_override() {
+        let mut body = make_apic_madt()[36..].to_vec();
+        body.extend_from_slice(&[0x05, 12, 0, 0]);
+        body.extend_from_slice(&0x0000_0010_fee0_0000u64.to_le_bytes());
+        let table = make_sdt(b"APIC", 1, &body);
+        let madt = table_from_bytes::<Madt>(&table);
+
+        let apic = match madt.parse_interrupt_model().unwrap().0 {
+            InterruptModel::Apic(apic) => apic,
+            other => panic!("Expected an APIC interrupt model, got {:?}", other),
+        };
+        assert_eq!(apic.local_apic_address, 0x0000_0010_fee0_0000);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_gic_model() {
+        let table = make_gic_madt(true);
+        let madt = table_from_bytes::<Madt>(&table);
+        let (model, processor_info) = madt.parse_interrupt_model().unwrap();
+
+        let gic = match model {
+            InterruptModel::Gic(gic) => gic,
+            other => panic!("Expected a GIC interrupt model, got {:?}", other),
+        };
+        assert_eq!(gic.version, GicVersion::V3);
+        assert_eq!(gic.distributor_address, 0x0800_0000);
+        assert_eq!(gic.redistributor_regions.len(), 1);
+        assert_eq!(gic.redistributor_regions[0].base_address, 0x080a_0000);
+        assert_eq!(gic.redistributor_regions[0].length, 0x00f6_0000);
+        assert_eq!(gic.its_blocks.len(), 1);
+        assert_eq!(gic.its_blocks[0].base_address, 0x0808_0000);
+        assert_eq!(gic.msi_frames.len(), 1);
+        assert_eq!(gic.msi_frames[0].id, 1);
+        assert_eq!(gic.msi_frames[0].spis, Some((80, 64)));
+
+        let processor_info = processor_info.unwrap();
+        assert_eq!(processor_info.boot_processor.processor_uid, 0);
+        assert_eq!(processor_info.boot_processor.state, ProcessorState::Running);
+        assert_eq!(processor_info.application_processors.len(), 2);
+        assert_eq!(processor_info.application_processors[0].local_apic_id, 1);
+        assert_eq!(processor_info.application_processors[0].state, ProcessorState::WaitingForSipi);
+        assert_eq!(processor_info.application_processors[1].state, ProcessorState::Disabled);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_gic_processor_mpidrs() {
+        let table = make_gic_madt(true);
+        let madt = table_from_bytes::<Madt>(&table);
+        let processor_info = madt.parse_interrupt_model().unwrap().1.unwrap();
+
+        assert!(!processor_info.boot_processor.is_ap);
+        assert_eq!(processor_info.boot_processor.mpidr, Some(0x0));
+        let processors: Vec<(u32, Option<u64>, ProcessorState, bool)> = processor_info
+            .application_processors
+            .iter()
+            .map(|processor| (processor.processor_uid, processor.mpidr, processor.state, processor.online_capable))
+            .collect();
+        assert_eq!(
+            processors,
+            [
+                (1, Some(0x1), ProcessorState::WaitingForSipi, false),
+                (2, Some(0x100), ProcessorState::Disabled, true)
+            ]
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_apic_processors_have_no_mpidr() {
+        let mut body = make_apic_madt()[36..].to_vec();
+        // A disabled processor that can be hot-plugged
+        body.extend(local_apic(2, 2, 0b10));
+        let table = make_sdt(b"APIC", 1, &body);
+        let madt = table_from_bytes::<Madt>(&table);
+        let processor_info = madt.parse_interrupt_model().unwrap().1.unwrap();
+
+        assert_eq!(processor_info.boot_processor.mpidr, None);
+        assert!(!processor_info.boot_processor.online_capable);
+        assert_eq!(processor_info.application_processors.len(), 2);
+        assert_eq!(processor_info.application_processors[0].mpidr, None);
+        assert_eq!(processor_info.application_processors[1].state, ProcessorState::Disabled);
+        assert!(processor_info.application_processors[1].online_capable);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_gic_model_without_distributor() {
+        let table = make_gic_madt(false);
+        let madt = table_from_bytes::<Madt>(&table);
+        assert!(matches!(madt.parse_interrupt_model(), Err(AcpiError::InvalidMadt(MadtError::NoGicDistributor))));
+    }
+}
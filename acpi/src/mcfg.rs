@@ -1,5 +1,10 @@
-use crate::{sdt::SdtHeader, AcpiError, AcpiHandler, AcpiTable, AcpiTables};
+use crate::{sdt::SdtHeader, AcpiTable};
+#[cfg(feature = "alloc")]
+use crate::{AcpiError, AcpiHandler, AcpiTables};
+#[cfg(feature = "alloc")]
 use alloc::vec::Vec;
+#[cfg(feature = "alloc")]
+use core::ops::RangeInclusive;
 use core::{mem, slice};
 
 /// Describes a set of regions of physical memory used to access the PCIe configuration space. A
@@ -7,11 +12,13 @@ use core::{mem, slice};
 /// function of a PCIe device, the `physical_address` method on this will give you the physical
 /// address of the start of that device function's configuration space (each function has 4096
 /// bytes of configuration space in PCIe).
+#[cfg(feature = "alloc")]
 #[derive(Clone, Debug)]
 pub struct PciConfigRegions {
     regions: Vec<McfgEntry>,
 }
 
+#[cfg(feature = "alloc")]
 impl PciConfigRegions {
     pub fn new<H>(tables: &AcpiTables<H>) -> Result<PciConfigRegions, AcpiError>
     where
@@ -22,12 +29,21 @@ impl PciConfigRegions {
                 .get_sdt::<Mcfg>(crate::sdt::Signature::MCFG)?
                 .ok_or(AcpiError::TableMissing(crate::sdt::Signature::MCFG))?
         };
-        Ok(PciConfigRegions { regions: mcfg.entries().iter().copied().collect() })
+        Ok(PciConfigRegions::from_mcfg(&mcfg))
+    }
+
+    pub fn from_mcfg(mcfg: &Mcfg) -> PciConfigRegions {
+        PciConfigRegions { regions: mcfg.entries().to_vec() }
     }
 
     /// Get the physical address of the start of the configuration space for a given PCIe device
-    /// function. Returns `None` if there isn't an entry in the MCFG that manages that device.
+    /// function. Returns `None` if there isn't an entry in the MCFG that manages that device, or
+    /// if `device` or `function` is out of range.
     pub fn physical_address(&self, segment_group_no: u16, bus: u8, device: u8, function: u8) -> Option<u64> {
+        if device > 31 || function > 7 {
+            return None;
+        }
+
         // First, find the memory region that handles this segment and bus. This method is fine
         // because there should only be one region that handles each segment group + bus
         // combination.
@@ -43,6 +59,36 @@ impl PciConfigRegions {
                     | (u64::from(function) << 12)),
         )
     }
+
+    /// Iterate over the PCI segment groups that have configuration space regions. Each segment
+    /// group is only yielded once, even if it's split across several regions.
+    pub fn segments(&self) -> impl Iterator<Item = u16> + '_ {
+        self.regions.iter().enumerate().filter_map(move |(i, region)| {
+            let segment = region.pci_segment_group;
+            if self.regions[..i].iter().any(|earlier| earlier.pci_segment_group == segment) {
+                None
+            } else {
+                Some(segment)
+            }
+        })
+    }
+
+    /// Get the range of buses of `segment_group_no` that have configuration space. If the segment
+    /// group is split across several regions, this covers every bus between the first and last
+    /// of them, which may include buses that aren't covered by any region.
+    pub fn bus_range(&self, segment_group_no: u16) -> Option<RangeInclusive<u8>> {
+        let mut regions = self.regions.iter().filter(|region| region.pci_segment_group == segment_group_no);
+        let first = regions.next()?;
+        let (start, end) = regions.fold((first.bus_number_start, first.bus_number_end), |(start, end), region| {
+            (u8::min(start, region.bus_number_start), u8::max(end, region.bus_number_end))
+        });
+        Some(start..=end)
+    }
+
+    /// Iterate over the MCFG entries the regions were built from.
+    pub fn iter(&self) -> impl Iterator<Item = McfgEntry> + '_ {
+        self.regions.iter().copied()
+    }
 }
 
 #[repr(C, packed)]
@@ -59,8 +105,8 @@ impl AcpiTable for Mcfg {
 }
 
 impl Mcfg {
-    fn entries(&self) -> &[McfgEntry] {
-        let length = self.header.length as usize - mem::size_of::<Mcfg>();
+    pub fn entries(&self) -> &[McfgEntry] {
+        let length = (self.header.length as usize).saturating_sub(mem::size_of::<Mcfg>());
 
         // Intentionally round down in case length isn't an exact multiple of McfgEntry size
         // (see rust-osdev/acpi#58)
@@ -73,6 +119,8 @@ impl Mcfg {
     }
 }
 
+/// Describes the region of physical memory used to access the configuration space of the buses
+/// `bus_number_start..=bus_number_end` of a PCI segment group.
 #[derive(Clone, Copy, Debug)]
 #[repr(C, packed)]
 pub struct McfgEntry {
@@ -82,3 +130,94 @@ pub struct McfgEntry {
     bus_number_end: u8,
     _reserved: u32,
 }
+
+impl McfgEntry {
+    /// The physical address of the start of the region.
+    pub fn base_address(&self) -> u64 {
+        self.base_address
+    }
+
+    pub fn pci_segment_group(&self) -> u16 {
+        self.pci_segment_group
+    }
+
+    pub fn bus_number_start(&self) -> u8 {
+        self.bus_number_start
+    }
+
+    pub fn bus_number_end(&self) -> u8 {
+        self.bus_number_end
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::test_utils::{make_sdt, table_from_bytes};
+
+    /// Build an MCFG with regions for two segment groups, with the second split into two
+    /// regions.
+    fn make_mcfg() -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&[0; 8]);
+        for &(base_address, segment, start, end) in
+            [(0xb000_0000u64, 0u16, 0u8, 0xffu8), (0x3_0000_0000, 1, 0, 0x7f), (0x3_0000_0000, 1, 0x80, 0xbf)]
+                .iter()
+        {
+            body.extend_from_slice(&base_address.to_le_bytes());
+            body.extend_from_slice(&segment.to_le_bytes());
+            body.extend_from_slice(&[start, end]);
+            body.extend_from_slice(&[0; 4]);
+        }
+        make_sdt(b"MCFG", 1, &body)
+    }
+
+    #[test]
+    fn test_entries() {
+        let table = make_mcfg();
+        let mcfg = table_from_bytes::<Mcfg>(&table);
+        let regions = PciConfigRegions::from_mcfg(mcfg);
+
+        assert_eq!(mcfg.entries().len(), 3);
+        assert_eq!(regions.iter().count(), 3);
+        for (entry, region) in mcfg.entries().iter().zip(regions.iter()) {
+            assert_eq!(entry.base_address(), region.base_address());
+            assert_eq!(entry.pci_segment_group(), region.pci_segment_group());
+            assert_eq!(entry.bus_number_start(), region.bus_number_start());
+            assert_eq!(entry.bus_number_end(), region.bus_number_end());
+
+            // The lookup should agree with the entry for the first function on its first bus
+            assert_eq!(
+                regions.physical_address(entry.pci_segment_group(), entry.bus_number_start(), 0, 0),
+                Some(entry.base_address())
+            );
+        }
+
+        assert_eq!(regions.physical_address(0, 3, 2, 1), Some(0xb000_0000 + (3 << 20) + (2 << 15) + (1 << 12)));
+        assert_eq!(regions.physical_address(1, 0x81, 0, 0), Some(0x3_0000_0000 + (1 << 20)));
+        assert_eq!(regions.physical_address(2, 0, 0, 0), None);
+    }
+
+    #[test]
+    fn test_segments_and_bus_ranges() {
+        let table = make_mcfg();
+        let regions = PciConfigRegions::from_mcfg(table_from_bytes::<Mcfg>(&table));
+
+        assert_eq!(regions.segments().collect::<Vec<u16>>(), [0, 1]);
+        assert_eq!(regions.bus_range(0), Some(0..=0xff));
+        assert_eq!(regions.bus_range(1), Some(0..=0xbf));
+        assert_eq!(regions.bus_range(2), None);
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        let table = make_mcfg();
+        let regions = PciConfigRegions::from_mcfg(table_from_bytes::<Mcfg>(&table));
+
+        assert!(regions.physical_address(1, 0xbf, 31, 7).is_some());
+        assert_eq!(regions.physical_address(1, 0xc0, 0, 0), None);
+        assert_eq!(regions.physical_address(1, 0xff, 0, 0), None);
+        assert_eq!(regions.physical_address(0, 0, 32, 0), None);
+        assert_eq!(regions.physical_address(0, 0, 0, 8), None);
+    }
+}
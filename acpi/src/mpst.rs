@@ -0,0 +1,368 @@
+use crate::{
+    entry_as,
+    pcct::{Pcct, Subspace},
+    sdt::{SdtHeader, Signature},
+    AcpiTable,
+    EntryIter,
+};
+use bit_field::BitField;
+use core::{mem, ptr, slice};
+
+/// Represents the Memory Power State Table (MPST). This describes the memory power nodes of the platform, which are
+/// ranges of memory that can be moved between power states as a unit, and the characteristics of each of those
+/// power states. The OS requests power state transitions through a Platform Communications Channel subspace.
+///
+/// The table is made up of a list of memory power nodes, followed by a list of power state characteristics, which
+/// the power states supported by each node refer to by index.
+#[repr(C, packed)]
+pub struct Mpst {
+    header: SdtHeader,
+    pcc_id: u8,
+    _reserved0: [u8; 3],
+    num_power_nodes: u16,
+    _reserved1: u16,
+    // Followed by `num_power_nodes` memory power nodes, and then the power state characteristics
+}
+
+impl AcpiTable for Mpst {
+    fn header(&self) -> &SdtHeader {
+        &self.header
+    }
+}
+
+impl Mpst {
+    /// The index of the MPST's subspace in the PCCT.
+    pub fn pcc_subspace_index(&self) -> u8 {
+        self.pcc_id
+    }
+
+    /// Find the MPST's subspace in `pcct`. Returns `None` if the PCCT doesn't have a subspace with the right
+    /// index, or if it's of an unsupported type.
+    pub fn pcc_channel_in<'a>(&self, pcct: &'a Pcct) -> Option<Subspace<'a>> {
+        pcct.subspace(self.pcc_id)
+    }
+
+    pub fn power_nodes(&self) -> MemoryPowerNodeIter<'_> {
+        MemoryPowerNodeIter {
+            entries: unsafe {
+                EntryIter::new(
+                    Signature::MPST,
+                    (self as *const Mpst as *const u8).add(mem::size_of::<Mpst>()),
+                    self.header.length.saturating_sub(mem::size_of::<Mpst>() as u32),
+                    node_minimum_length,
+                )
+            },
+            remaining_nodes: self.num_power_nodes,
+        }
+    }
+
+    /// Get the characteristics of each power state. If the list of memory power nodes is malformed, the
+    /// characteristics can't be found, and this returns an empty slice. If the table is too short to hold the
+    /// number of characteristics structures it claims to, only those that fit within it are returned.
+    pub fn power_state_characteristics(&self) -> &[PowerStateCharacteristics] {
+        let mut nodes = self.power_nodes();
+        nodes.by_ref().for_each(drop);
+        let rest = nodes.entries.rest();
+        if nodes.remaining_nodes != 0 || rest.len() < mem::size_of::<u32>() {
+            return &[];
+        }
+
+        let count = unsafe { ptr::read_unaligned(rest.as_ptr() as *const u16) } as usize;
+        let available = (rest.len() - mem::size_of::<u32>()) / mem::size_of::<PowerStateCharacteristics>();
+        unsafe {
+            slice::from_raw_parts(
+                rest.as_ptr().add(mem::size_of::<u32>()) as *const PowerStateCharacteristics,
+                usize::min(count, available),
+            )
+        }
+    }
+
+    /// Get the characteristics of a power state supported by one of the memory power nodes. Returns `None` if the
+    /// table doesn't contain the characteristics it refers to.
+    pub fn characteristics_of(&self, state: &MemoryPowerState) -> Option<&PowerStateCharacteristics> {
+        self.power_state_characteristics().get(state.information_index as usize)
+    }
+}
+
+pub struct MemoryPowerNodeIter<'a> {
+    entries: EntryIter<'a, MemoryPowerNode>,
+    remaining_nodes: u16,
+}
+
+impl<'a> Iterator for MemoryPowerNodeIter<'a> {
+    type Item = &'a MemoryPowerNode;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining_nodes == 0 {
+            return None;
+        }
+
+        let (_, node) = self.entries.next()?;
+        self.remaining_nodes -= 1;
+        Some(unsafe { entry_as(node) })
+    }
+}
+
+/// A range of memory that can be moved between power states as a unit.
+#[repr(C, packed)]
+pub struct MemoryPowerNode {
+    flags: u8,
+    _reserved: u8,
+    node_id: u16,
+    length: u32,
+    base_address_low: u32,
+    base_address_high: u32,
+    range_length_low: u32,
+    range_length_high: u32,
+    num_power_states: u32,
+    num_physical_components: u32,
+    /*
+     * Followed by `num_power_states` power states, each with format `MemoryPowerState`, and then
+     * `num_physical_components` 16-bit physical component IDs.
+     */
+}
+
+impl crate::EntryHeader for MemoryPowerNode {
+    fn entry_type(&self) -> u16 {
+        // Memory power nodes don't have a type
+        0
+    }
+
+    fn length(&self) -> u32 {
+        self.length
+    }
+}
+
+fn node_minimum_length(node: &MemoryPowerNode) -> usize {
+    mem::size_of::<MemoryPowerNode>()
+        .saturating_add(({ node.num_power_states } as usize).saturating_mul(mem::size_of::<MemoryPowerState>()))
+        .saturating_add(({ node.num_physical_components } as usize).saturating_mul(mem::size_of::<u16>()))
+}
+
+impl MemoryPowerNode {
+    pub fn node_id(&self) -> u16 {
+        self.node_id
+    }
+
+    /// If false, the node should be ignored.
+    pub fn is_enabled(&self) -> bool {
+        self.flags.get_bit(0)
+    }
+
+    /// If true, the node can be moved between power states by the OS.
+    pub fn is_power_managed(&self) -> bool {
+        self.flags.get_bit(1)
+    }
+
+    pub fn is_hot_pluggable(&self) -> bool {
+        self.flags.get_bit(2)
+    }
+
+    /// The physical address of the start of the memory covered by the node. The range covered by each node is
+    /// contained within one of the memory ranges described by the SRAT, which gives its proximity domain.
+    pub fn base_address(&self) -> u64 {
+        let mut address = self.base_address_low as u64;
+        address.set_bits(32..64, self.base_address_high as u64);
+        address
+    }
+
+    /// The length of the memory covered by the node, in bytes.
+    pub fn length(&self) -> u64 {
+        let mut length = self.range_length_low as u64;
+        length.set_bits(32..64, self.range_length_high as u64);
+        length
+    }
+
+    /// The power states supported by the node. Use `Mpst::characteristics_of` to find the characteristics of each.
+    pub fn power_states(&self) -> &[MemoryPowerState] {
+        unsafe {
+            slice::from_raw_parts(
+                (self as *const MemoryPowerNode as *const u8).add(mem::size_of::<MemoryPowerNode>())
+                    as *const MemoryPowerState,
+                self.num_power_states as usize,
+            )
+        }
+    }
+
+    /// The IDs of the physical components (e.g. DIMMs) that make up the node. These match the IDs used by the PMTT.
+    pub fn physical_component_ids(&self) -> impl Iterator<Item = u16> + '_ {
+        let base = unsafe {
+            (self as *const MemoryPowerNode as *const u8)
+                .add(mem::size_of::<MemoryPowerNode>())
+                .add(self.num_power_states as usize * mem::size_of::<MemoryPowerState>())
+        };
+        (0..self.num_physical_components as usize)
+            .map(move |i| unsafe { ptr::read_unaligned((base as *const u16).add(i)) })
+    }
+}
+
+/// A power state supported by a memory power node.
+#[derive(Clone, Copy, Debug)]
+#[repr(C, packed)]
+pub struct MemoryPowerState {
+    value: u8,
+    information_index: u8,
+}
+
+impl MemoryPowerState {
+    /// The value to send through the PCC subspace to move the node into this power state.
+    pub fn value(&self) -> u8 {
+        self.value
+    }
+
+    /// The index of the characteristics of this power state, in `Mpst::power_state_characteristics`.
+    pub fn information_index(&self) -> u8 {
+        self.information_index
+    }
+}
+
+/// Describes a memory power state, which may be supported by several memory power nodes.
+#[repr(C, packed)]
+pub struct PowerStateCharacteristics {
+    structure_id: u8,
+    flags: u8,
+    _reserved0: u16,
+    average_power: u32,
+    relative_power_saving: u32,
+    exit_latency: u64,
+    _reserved1: u64,
+}
+
+impl PowerStateCharacteristics {
+    /// The ID of the power state. This is the `x` in `MPSx`, where `MPS0` is the state in which memory is fully
+    /// powered.
+    pub fn power_state_id(&self) -> u8 {
+        self.structure_id.get_bits(0..6)
+    }
+
+    /// If true, the contents of memory are preserved while in this state.
+    pub fn preserves_memory(&self) -> bool {
+        self.flags.get_bit(0)
+    }
+
+    /// If true, the platform can move a node into this state without a request from the OS.
+    pub fn autonomous_entry(&self) -> bool {
+        self.flags.get_bit(1)
+    }
+
+    /// If true, the platform moves a node out of this state on its own when its memory is accessed.
+    pub fn autonomous_exit(&self) -> bool {
+        self.flags.get_bit(2)
+    }
+
+    /// The average power consumed by a node in this state, in milliwatts.
+    pub fn average_power(&self) -> u32 {
+        self.average_power
+    }
+
+    /// The power saved in this state, relative to `MPS0`.
+    pub fn relative_power_saving(&self) -> u32 {
+        self.relative_power_saving
+    }
+
+    /// The time taken to move a node from this state back to `MPS0`.
+    pub fn exit_latency(&self) -> u64 {
+        self.exit_latency
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{make_sdt, table_from_bytes};
+    use alloc::vec::Vec;
+
+    fn characteristics(id: u8, flags: u8, average_power: u32, saving: u32, exit_latency: u64) -> Vec<u8> {
+        let mut structure = alloc::vec![id | (1 << 6), flags, 0, 0];
+        structure.extend_from_slice(&average_power.to_le_bytes());
+        structure.extend_from_slice(&saving.to_le_bytes());
+        structure.extend_from_slice(&exit_latency.to_le_bytes());
+        structure.extend_from_slice(&0u64.to_le_bytes());
+        structure
+    }
+
+    #[test]
+    fn test_power_nodes() {
+        let mut body = Vec::new();
+        body.extend_from_slice(&[3, 0, 0, 0]);
+        body.extend_from_slice(&1u16.to_le_bytes());
+        body.extend_from_slice(&0u16.to_le_bytes());
+
+        // A node covering 8GiB from 4GiB, with two power states, made up of two DIMMs
+        body.extend_from_slice(&[0b011, 0]);
+        body.extend_from_slice(&7u16.to_le_bytes());
+        body.extend_from_slice(&40u32.to_le_bytes());
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body.extend_from_slice(&1u32.to_le_bytes());
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body.extend_from_slice(&2u32.to_le_bytes());
+        body.extend_from_slice(&2u32.to_le_bytes());
+        body.extend_from_slice(&2u32.to_le_bytes());
+        body.extend_from_slice(&[0, 0, 0x20, 1]);
+        body.extend_from_slice(&0x10u16.to_le_bytes());
+        body.extend_from_slice(&0x11u16.to_le_bytes());
+
+        body.extend_from_slice(&2u16.to_le_bytes());
+        body.extend_from_slice(&0u16.to_le_bytes());
+        body.extend(characteristics(0, 0b001, 3000, 0, 0));
+        body.extend(characteristics(1, 0b101, 400, 80, 50_000));
+
+        let table = make_sdt(b"MPST", 1, &body);
+        let mpst = table_from_bytes::<Mpst>(&table);
+        assert_eq!(mpst.pcc_subspace_index(), 3);
+
+        let nodes: Vec<&MemoryPowerNode> = mpst.power_nodes().collect();
+        assert_eq!(nodes.len(), 1);
+        let node = nodes[0];
+        assert_eq!(node.node_id(), 7);
+        assert!(node.is_enabled());
+        assert!(node.is_power_managed());
+        assert!(!node.is_hot_pluggable());
+        assert_eq!(node.base_address(), 0x1_0000_0000);
+        assert_eq!(node.length(), 0x2_0000_0000);
+        assert_eq!(node.physical_component_ids().collect::<Vec<u16>>(), [0x10, 0x11]);
+
+        let states = node.power_states();
+        assert_eq!(states.len(), 2);
+        assert_eq!(states[1].value(), 0x20);
+        assert_eq!(states[1].information_index(), 1);
+
+        let normal = mpst.characteristics_of(&states[0]).unwrap();
+        assert_eq!(normal.power_state_id(), 0);
+        assert!(normal.preserves_memory());
+        assert_eq!(normal.average_power(), 3000);
+
+        let self_refresh = mpst.characteristics_of(&states[1]).unwrap();
+        assert_eq!(self_refresh.power_state_id(), 1);
+        assert!(self_refresh.preserves_memory());
+        assert!(!self_refresh.autonomous_entry());
+        assert!(self_refresh.autonomous_exit());
+        assert_eq!(self_refresh.average_power(), 400);
+        assert_eq!(self_refresh.relative_power_saving(), 80);
+        assert_eq!(self_refresh.exit_latency(), 50_000);
+    }
+
+    #[test]
+    fn test_truncated_power_node() {
+        let mut body = Vec::new();
+        body.extend_from_slice(&[0, 0, 0, 0]);
+        body.extend_from_slice(&1u16.to_le_bytes());
+        body.extend_from_slice(&0u16.to_le_bytes());
+        // The node claims to have more power states than fit in its length
+        body.extend_from_slice(&[0b001, 0]);
+        body.extend_from_slice(&0u16.to_le_bytes());
+        body.extend_from_slice(&34u32.to_le_bytes());
+        body.extend_from_slice(&[0; 16]);
+        body.extend_from_slice(&2u32.to_le_bytes());
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body.extend_from_slice(&[0, 0]);
+        body.extend_from_slice(&1u16.to_le_bytes());
+        body.extend_from_slice(&0u16.to_le_bytes());
+        body.extend(characteristics(0, 0b001, 3000, 0, 0));
+
+        let table = make_sdt(b"MPST", 1, &body);
+        let mpst = table_from_bytes::<Mpst>(&table);
+        assert_eq!(mpst.power_nodes().count(), 0);
+        assert!(mpst.power_state_characteristics().is_empty());
+    }
+}
@@ -0,0 +1,167 @@
+use crate::{
+    entry_as,
+    sdt::{SdtHeader, Signature},
+    AcpiTable,
+    EntryIter,
+};
+use core::mem;
+
+/// Represents the Maximum System Characteristics Table (MSCT). This describes the largest configuration that the
+/// platform can grow to through hot-plug, so that the OS can size its data structures for the processors and
+/// memory that might be added, rather than just those that are present at boot.
+#[repr(C, packed)]
+pub struct Msct {
+    header: SdtHeader,
+    proximity_domain_info_offset: u32,
+    max_proximity_domain: u32,
+    max_clock_domain: u32,
+    max_physical_address: u64,
+}
+
+impl AcpiTable for Msct {
+    fn header(&self) -> &SdtHeader {
+        &self.header
+    }
+}
+
+impl Msct {
+    /// The maximum number of proximity domains that the platform can have. (The table itself encodes the number
+    /// minus one.)
+    pub fn max_proximity_domains(&self) -> u64 {
+        u64::from(self.max_proximity_domain) + 1
+    }
+
+    /// The maximum number of clock domains that the platform can have. (The table itself encodes the number
+    /// minus one.)
+    pub fn max_clock_domains(&self) -> u64 {
+        u64::from(self.max_clock_domain) + 1
+    }
+
+    /// The highest physical address that the platform can have memory at.
+    pub fn max_physical_address(&self) -> u64 {
+        self.max_physical_address
+    }
+
+    pub fn proximity_domain_info(&self) -> ProximityDomainInfoIter<'_> {
+        let offset = self.proximity_domain_info_offset;
+        let remaining_length =
+            if (offset as usize) < mem::size_of::<Msct>() { 0 } else { self.header.length.saturating_sub(offset) };
+
+        ProximityDomainInfoIter {
+            entries: unsafe {
+                EntryIter::new(
+                    Signature::MSCT,
+                    (self as *const Msct as *const u8).add(offset as usize),
+                    remaining_length,
+                    |_| mem::size_of::<ProximityDomainInfo>(),
+                )
+            },
+        }
+    }
+}
+
+pub struct ProximityDomainInfoIter<'a> {
+    entries: EntryIter<'a, ProximityDomainInfoHeader>,
+}
+
+impl<'a> Iterator for ProximityDomainInfoIter<'a> {
+    type Item = &'a ProximityDomainInfo;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (_, info) = self.entries.next()?;
+        Some(unsafe { entry_as(info) })
+    }
+}
+
+/// The fields shared by every revision of the proximity domain information structure.
+#[repr(C, packed)]
+struct ProximityDomainInfoHeader {
+    revision: u8,
+    length: u8,
+}
+
+impl crate::EntryHeader for ProximityDomainInfoHeader {
+    fn entry_type(&self) -> u16 {
+        self.revision.into()
+    }
+
+    fn length(&self) -> u32 {
+        self.length.into()
+    }
+}
+
+/// Describes the maximum capacity of a range of proximity domains.
+#[repr(C, packed)]
+pub struct ProximityDomainInfo {
+    revision: u8,
+    length: u8,
+    proximity_domain_low: u32,
+    proximity_domain_high: u32,
+    max_processor_capacity: u32,
+    max_memory_capacity: u64,
+}
+
+impl ProximityDomainInfo {
+    /// The first proximity domain that this structure describes.
+    pub fn proximity_domain_low(&self) -> u32 {
+        self.proximity_domain_low
+    }
+
+    /// The last proximity domain that this structure describes (inclusive).
+    pub fn proximity_domain_high(&self) -> u32 {
+        self.proximity_domain_high
+    }
+
+    /// The maximum number of processors that each of the proximity domains can have.
+    pub fn max_processor_capacity(&self) -> u32 {
+        self.max_processor_capacity
+    }
+
+    /// The maximum amount of memory that each of the proximity domains can have, in bytes.
+    pub fn max_memory_capacity(&self) -> u64 {
+        self.max_memory_capacity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{make_sdt, table_from_bytes};
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_msct() {
+        let mut body = Vec::new();
+        body.extend_from_slice(&56u32.to_le_bytes());
+        body.extend_from_slice(&7u32.to_le_bytes());
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body.extend_from_slice(&0x3f_ffff_ffffu64.to_le_bytes());
+        for &(low, high, processors, memory) in
+            &[(0u32, 3u32, 56u32, 0x40_0000_0000u64), (4, 7, 0, 0x100_0000_0000)]
+        {
+            body.extend_from_slice(&[1, 22]);
+            body.extend_from_slice(&low.to_le_bytes());
+            body.extend_from_slice(&high.to_le_bytes());
+            body.extend_from_slice(&processors.to_le_bytes());
+            body.extend_from_slice(&memory.to_le_bytes());
+        }
+
+        let table = make_sdt(b"MSCT", 1, &body);
+        let msct = table_from_bytes::<Msct>(&table);
+        assert_eq!(msct.max_proximity_domains(), 8);
+        assert_eq!(msct.max_clock_domains(), 1);
+        assert_eq!(msct.max_physical_address(), 0x3f_ffff_ffff);
+
+        let info: Vec<&ProximityDomainInfo> = msct.proximity_domain_info().collect();
+        assert_eq!(info.len(), 2);
+        assert_eq!(info[0].proximity_domain_low(), 0);
+        assert_eq!(info[0].proximity_domain_high(), 3);
+        assert_eq!(info[0].max_processor_capacity(), 56);
+        assert_eq!(info[0].max_memory_capacity(), 0x40_0000_0000);
+        // A range of domains that only has memory, such as memory expanders
+        assert_eq!(info[1].proximity_domain_low(), 4);
+        assert_eq!(info[1].proximity_domain_high(), 7);
+        assert_eq!(info[1].max_processor_capacity(), 0);
+        assert_eq!(info[1].max_memory_capacity(), 0x100_0000_0000);
+    }
+}
@@ -0,0 +1,808 @@
+use crate::{
+    entry_as,
+    sdt::{SdtHeader, Signature},
+    AcpiTable,
+    EntryIter,
+};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use bit_field::BitField;
+use core::{mem, ptr, slice};
+
+/// Represents the NVDIMM Firmware Interface Table (NFIT). This describes the NVDIMMs on the platform: the ranges of
+/// the system physical address (SPA) space they are mapped into, how those ranges are interleaved across NVDIMMs,
+/// and the control regions (and optional block data windows) used to manage each NVDIMM.
+///
+/// Structures refer to each other by indices (e.g. an SPA range is referred to by its SPA range structure index),
+/// and to NVDIMMs by their NFIT device handle.
+#[repr(C, packed)]
+pub struct Nfit {
+    header: SdtHeader,
+    _reserved: u32,
+}
+
+impl AcpiTable for Nfit {
+    fn header(&self) -> &SdtHeader {
+        &self.header
+    }
+}
+
+impl Nfit {
+    pub fn structures(&self) -> NfitStructureIter<'_> {
+        NfitStructureIter {
+            entries: unsafe {
+                EntryIter::new(
+                    Signature::NFIT,
+                    (self as *const Nfit as *const u8).add(mem::size_of::<Nfit>()),
+                    self.header.length.saturating_sub(mem::size_of::<Nfit>() as u32),
+                    entry_minimum_length,
+                )
+            },
+        }
+    }
+
+    /// Gather the structures that describe the NVDIMM with the given device handle: its region mappings, the SPA
+    /// ranges and control regions they refer to, the block data windows of those control regions, and its flush
+    /// hint addresses.
+    #[cfg(feature = "alloc")]
+    pub fn nvdimm(&self, device_handle: NfitDeviceHandle) -> NvdimmStructures<'_> {
+        let mut nvdimm = NvdimmStructures {
+            region_mappings: Vec::new(),
+            spa_ranges: Vec::new(),
+            control_regions: Vec::new(),
+            block_data_windows: Vec::new(),
+            flush_hint_address: None,
+        };
+
+        for structure in self.structures() {
+            match structure {
+                NfitStructure::RegionMapping(mapping) if mapping.device_handle() == device_handle => {
+                    nvdimm.region_mappings.push(mapping)
+                }
+                NfitStructure::FlushHintAddress(flush_hint) if flush_hint.device_handle() == device_handle => {
+                    nvdimm.flush_hint_address = Some(flush_hint)
+                }
+                _ => (),
+            }
+        }
+
+        for structure in self.structures() {
+            let mappings = &nvdimm.region_mappings;
+            match structure {
+                NfitStructure::SpaRange(range)
+                    if mappings.iter().any(|mapping| mapping.spa_range_index() == range.index()) =>
+                {
+                    nvdimm.spa_ranges.push(range)
+                }
+                NfitStructure::ControlRegion(region)
+                    if mappings.iter().any(|mapping| mapping.control_region_index() == region.index()) =>
+                {
+                    nvdimm.control_regions.push(region)
+                }
+                NfitStructure::BlockDataWindowRegion(window)
+                    if mappings
+                        .iter()
+                        .any(|mapping| mapping.control_region_index() == window.control_region_index()) =>
+                {
+                    nvdimm.block_data_windows.push(window)
+                }
+                _ => (),
+            }
+        }
+
+        nvdimm
+    }
+}
+
+/// The structures that describe a single NVDIMM. See `Nfit::nvdimm`.
+#[cfg(feature = "alloc")]
+pub struct NvdimmStructures<'a> {
+    pub region_mappings: Vec<&'a RegionMapping>,
+    pub spa_ranges: Vec<&'a SpaRange>,
+    pub control_regions: Vec<&'a ControlRegion>,
+    pub block_data_windows: Vec<&'a BlockDataWindowRegion>,
+    pub flush_hint_address: Option<&'a FlushHintAddress>,
+}
+
+pub struct NfitStructureIter<'a> {
+    entries: EntryIter<'a, NfitStructureHeader>,
+}
+
+pub enum NfitStructure<'a> {
+    SpaRange(&'a SpaRange),
+    RegionMapping(&'a RegionMapping),
+    Interleave(&'a Interleave),
+    SmbiosManagementInformation(&'a SmbiosManagementInformation),
+    ControlRegion(&'a ControlRegion),
+    BlockDataWindowRegion(&'a BlockDataWindowRegion),
+    FlushHintAddress(&'a FlushHintAddress),
+    PlatformCapabilities(&'a PlatformCapabilities),
+}
+
+impl<'a> Iterator for NfitStructureIter<'a> {
+    type Item = NfitStructure<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (entry_type, entry) in &mut self.entries {
+            let structure = unsafe {
+                match entry_type {
+                    0x0 => NfitStructure::SpaRange(entry_as(entry)),
+                    0x1 => NfitStructure::RegionMapping(entry_as(entry)),
+                    0x2 => NfitStructure::Interleave(entry_as(entry)),
+                    0x3 => NfitStructure::SmbiosManagementInformation(entry_as(entry)),
+                    0x4 => NfitStructure::ControlRegion(entry_as(entry)),
+                    0x5 => NfitStructure::BlockDataWindowRegion(entry_as(entry)),
+                    0x6 => NfitStructure::FlushHintAddress(entry_as(entry)),
+                    0x7 => NfitStructure::PlatformCapabilities(entry_as(entry)),
+
+                    /*
+                     * Other structure types are reserved by the ACPI standard. We skip them.
+                     */
+                    _ => continue,
+                }
+            };
+            return Some(structure);
+        }
+
+        None
+    }
+}
+
+#[derive(Clone, Copy)]
+#[repr(C, packed)]
+struct NfitStructureHeader {
+    structure_type: u16,
+    length: u16,
+}
+
+impl crate::EntryHeader for NfitStructureHeader {
+    fn entry_type(&self) -> u16 {
+        self.structure_type
+    }
+
+    fn length(&self) -> u32 {
+        self.length.into()
+    }
+}
+
+fn entry_minimum_length(header: &NfitStructureHeader) -> usize {
+    match header.structure_type {
+        0x0 => mem::size_of::<SpaRange>(),
+        0x1 => mem::size_of::<RegionMapping>(),
+        0x2 => mem::size_of::<Interleave>(),
+        0x3 => mem::size_of::<SmbiosManagementInformation>(),
+        0x4 => mem::size_of::<ControlRegion>(),
+        0x5 => mem::size_of::<BlockDataWindowRegion>(),
+        0x6 => mem::size_of::<FlushHintAddress>(),
+        0x7 => mem::size_of::<PlatformCapabilities>(),
+        _ => mem::size_of::<NfitStructureHeader>(),
+    }
+}
+
+/// Identifies an NVDIMM by its location in the memory subsystem.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct NfitDeviceHandle(pub u32);
+
+impl NfitDeviceHandle {
+    pub fn dimm_number(&self) -> u8 {
+        self.0.get_bits(0..4) as u8
+    }
+
+    pub fn memory_channel_number(&self) -> u8 {
+        self.0.get_bits(4..8) as u8
+    }
+
+    pub fn memory_controller_id(&self) -> u8 {
+        self.0.get_bits(8..12) as u8
+    }
+
+    pub fn socket_id(&self) -> u8 {
+        self.0.get_bits(12..16) as u8
+    }
+
+    pub fn node_controller_id(&self) -> u16 {
+        self.0.get_bits(16..28) as u16
+    }
+}
+
+/// Builds the in-memory form of a GUID (which stores its first three fields in little-endian byte order).
+const fn guid(a: u32, b: u16, c: u16, d: [u8; 8]) -> [u8; 16] {
+    let a = a.to_le_bytes();
+    let b = b.to_le_bytes();
+    let c = c.to_le_bytes();
+    [a[0], a[1], a[2], a[3], b[0], b[1], c[0], c[1], d[0], d[1], d[2], d[3], d[4], d[5], d[6], d[7]]
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SpaRangeType {
+    VolatileMemory,
+    PersistentMemory,
+    ControlRegion,
+    BlockDataWindow,
+    RamDiskVirtualDisk,
+    RamDiskVirtualCd,
+    RamDiskPersistentVirtualDisk,
+    RamDiskPersistentVirtualCd,
+    Unknown([u8; 16]),
+}
+
+impl SpaRangeType {
+    const VOLATILE_MEMORY: [u8; 16] =
+        guid(0x7305944f, 0xfdda, 0x44e3, [0xb1, 0x6c, 0x3f, 0x22, 0xd2, 0x52, 0xe5, 0xd0]);
+    const PERSISTENT_MEMORY: [u8; 16] =
+        guid(0x66f0d379, 0xb4f3, 0x4074, [0xac, 0x43, 0x0d, 0x33, 0x18, 0xb7, 0x8c, 0xdb]);
+    const CONTROL_REGION: [u8; 16] =
+        guid(0x92f701f6, 0x13b4, 0x405d, [0x91, 0x0b, 0x29, 0x93, 0x67, 0xe8, 0x23, 0x4c]);
+    const BLOCK_DATA_WINDOW: [u8; 16] =
+        guid(0x91af0530, 0x5d86, 0x470e, [0xa6, 0xb0, 0x0a, 0x2d, 0xb9, 0x40, 0x82, 0x49]);
+    const RAM_DISK_VIRTUAL_DISK: [u8; 16] =
+        guid(0x77ab535a, 0x45fc, 0x624b, [0x55, 0x60, 0xf7, 0xb2, 0x81, 0xd1, 0xf9, 0x6e]);
+    const RAM_DISK_VIRTUAL_CD: [u8; 16] =
+        guid(0x3d5abd30, 0x4175, 0x87ce, [0x6d, 0x64, 0xd2, 0xad, 0xe5, 0x23, 0xc4, 0xbb]);
+    const RAM_DISK_PERSISTENT_VIRTUAL_DISK: [u8; 16] =
+        guid(0x5cea02c9, 0x4d07, 0x69d3, [0x26, 0x9f, 0x44, 0x96, 0xfb, 0xe0, 0x96, 0xf9]);
+    const RAM_DISK_PERSISTENT_VIRTUAL_CD: [u8; 16] =
+        guid(0x08018188, 0x42cd, 0xbb48, [0x10, 0x0f, 0x53, 0x87, 0xd5, 0x3d, 0xed, 0x3d]);
+
+    fn from_guid(guid: [u8; 16]) -> SpaRangeType {
+        match guid {
+            SpaRangeType::VOLATILE_MEMORY => SpaRangeType::VolatileMemory,
+            SpaRangeType::PERSISTENT_MEMORY => SpaRangeType::PersistentMemory,
+            SpaRangeType::CONTROL_REGION => SpaRangeType::ControlRegion,
+            SpaRangeType::BLOCK_DATA_WINDOW => SpaRangeType::BlockDataWindow,
+            SpaRangeType::RAM_DISK_VIRTUAL_DISK => SpaRangeType::RamDiskVirtualDisk,
+            SpaRangeType::RAM_DISK_VIRTUAL_CD => SpaRangeType::RamDiskVirtualCd,
+            SpaRangeType::RAM_DISK_PERSISTENT_VIRTUAL_DISK => SpaRangeType::RamDiskPersistentVirtualDisk,
+            SpaRangeType::RAM_DISK_PERSISTENT_VIRTUAL_CD => SpaRangeType::RamDiskPersistentVirtualCd,
+            other => SpaRangeType::Unknown(other),
+        }
+    }
+}
+
+/// The attributes of a range of memory, using the encoding of the `EFI_MEMORY_*` attributes of the UEFI memory
+/// map.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MemoryMappingAttributes(pub u64);
+
+impl MemoryMappingAttributes {
+    pub fn uncacheable(&self) -> bool {
+        self.0.get_bit(0)
+    }
+
+    pub fn write_combining(&self) -> bool {
+        self.0.get_bit(1)
+    }
+
+    pub fn write_through(&self) -> bool {
+        self.0.get_bit(2)
+    }
+
+    pub fn write_back(&self) -> bool {
+        self.0.get_bit(3)
+    }
+
+    pub fn uncacheable_exported(&self) -> bool {
+        self.0.get_bit(4)
+    }
+
+    pub fn write_protected(&self) -> bool {
+        self.0.get_bit(12)
+    }
+
+    pub fn read_protected(&self) -> bool {
+        self.0.get_bit(13)
+    }
+
+    pub fn execute_protected(&self) -> bool {
+        self.0.get_bit(14)
+    }
+
+    pub fn non_volatile(&self) -> bool {
+        self.0.get_bit(15)
+    }
+
+    pub fn more_reliable(&self) -> bool {
+        self.0.get_bit(16)
+    }
+
+    pub fn read_only(&self) -> bool {
+        self.0.get_bit(17)
+    }
+
+    pub fn specific_purpose(&self) -> bool {
+        self.0.get_bit(18)
+    }
+}
+
+/// A System Physical Address (SPA) Range structure, which describes a range of the physical address space that
+/// NVDIMMs (or their control regions) are mapped into.
+#[repr(C, packed)]
+pub struct SpaRange {
+    header: NfitStructureHeader,
+    index: u16,
+    flags: u16,
+    _reserved: u32,
+    proximity_domain: u32,
+    range_type_guid: [u8; 16],
+    base_address: u64,
+    length: u64,
+    memory_mapping_attributes: u64,
+}
+
+impl SpaRange {
+    /// The index used by other structures to refer to this range. This is never `0`.
+    pub fn index(&self) -> u16 {
+        self.index
+    }
+
+    pub fn range_type(&self) -> SpaRangeType {
+        SpaRangeType::from_guid(self.range_type_guid)
+    }
+
+    pub fn range_type_guid(&self) -> [u8; 16] {
+        self.range_type_guid
+    }
+
+    /// If true, the range is only used for management while an NVDIMM is hot-added or brought online, and should
+    /// not be used otherwise.
+    pub fn is_for_hot_add_only(&self) -> bool {
+        { self.flags }.get_bit(0)
+    }
+
+    pub fn proximity_domain(&self) -> Option<u32> {
+        if { self.flags }.get_bit(1) {
+            Some(self.proximity_domain)
+        } else {
+            None
+        }
+    }
+
+    pub fn base_address(&self) -> u64 {
+        self.base_address
+    }
+
+    pub fn length(&self) -> u64 {
+        self.length
+    }
+
+    pub fn memory_mapping_attributes(&self) -> MemoryMappingAttributes {
+        MemoryMappingAttributes(self.memory_mapping_attributes)
+    }
+}
+
+/// Describes how a region of an NVDIMM is mapped into an SPA range.
+#[repr(C, packed)]
+pub struct RegionMapping {
+    header: NfitStructureHeader,
+    device_handle: u32,
+    physical_id: u16,
+    region_id: u16,
+    spa_range_index: u16,
+    control_region_index: u16,
+    region_size: u64,
+    region_offset: u64,
+    physical_address_region_base: u64,
+    interleave_index: u16,
+    interleave_ways: u16,
+    state_flags: u16,
+    _reserved: u16,
+}
+
+impl RegionMapping {
+    pub fn device_handle(&self) -> NfitDeviceHandle {
+        NfitDeviceHandle(self.device_handle)
+    }
+
+    /// The handle of the NVDIMM in the SMBIOS tables.
+    pub fn physical_id(&self) -> u16 {
+        self.physical_id
+    }
+
+    pub fn region_id(&self) -> u16 {
+        self.region_id
+    }
+
+    /// The index of the SPA range the region is mapped into, or `0` if it isn't mapped into an SPA range.
+    pub fn spa_range_index(&self) -> u16 {
+        self.spa_range_index
+    }
+
+    pub fn control_region_index(&self) -> u16 {
+        self.control_region_index
+    }
+
+    pub fn region_size(&self) -> u64 {
+        self.region_size
+    }
+
+    /// The offset of the start of the region from the base of its SPA range.
+    pub fn region_offset(&self) -> u64 {
+        self.region_offset
+    }
+
+    pub fn physical_address_region_base(&self) -> u64 {
+        self.physical_address_region_base
+    }
+
+    /// The index of the interleave structure describing how the region is interleaved, or `0` if it isn't.
+    pub fn interleave_index(&self) -> u16 {
+        self.interleave_index
+    }
+
+    pub fn interleave_ways(&self) -> u16 {
+        self.interleave_ways
+    }
+
+    /// The raw state flags of the NVDIMM, which report e.g. failed saves or restores of its contents.
+    pub fn state_flags(&self) -> u16 {
+        self.state_flags
+    }
+}
+
+/// Describes how the lines of an interleaved region are laid out in an NVDIMM.
+#[repr(C, packed)]
+pub struct Interleave {
+    header: NfitStructureHeader,
+    index: u16,
+    _reserved: u16,
+    num_lines: u32,
+    line_size: u32,
+    // Followed by `num_lines` line offsets
+}
+
+impl Interleave {
+    pub fn index(&self) -> u16 {
+        self.index
+    }
+
+    pub fn line_size(&self) -> u32 {
+        self.line_size
+    }
+
+    /// The offsets of each line, in units of `line_size`.
+    pub fn line_offsets(&self) -> impl Iterator<Item = u32> + '_ {
+        let available = (self.header.length as usize - mem::size_of::<Interleave>()) / mem::size_of::<u32>();
+        let offsets =
+            unsafe { (self as *const Interleave as *const u8).add(mem::size_of::<Interleave>()) as *const u32 };
+        (0..usize::min(self.num_lines as usize, available))
+            .map(move |i| unsafe { ptr::read_unaligned(offsets.add(i)) })
+    }
+}
+
+/// Contains SMBIOS Management Information for the NVDIMMs, in the format of the SMBIOS tables.
+#[repr(C, packed)]
+pub struct SmbiosManagementInformation {
+    header: NfitStructureHeader,
+    _reserved: u32,
+}
+
+impl SmbiosManagementInformation {
+    pub fn data(&self) -> &[u8] {
+        unsafe {
+            slice::from_raw_parts(
+                (self as *const SmbiosManagementInformation as *const u8)
+                    .add(mem::size_of::<SmbiosManagementInformation>()),
+                self.header.length as usize - mem::size_of::<SmbiosManagementInformation>(),
+            )
+        }
+    }
+}
+
+/// Describes the control region of an NVDIMM, which is used to manage it. If the NVDIMM has block control windows,
+/// the structure also describes the layout of the command and status registers.
+#[repr(C, packed)]
+pub struct ControlRegion {
+    header: NfitStructureHeader,
+    index: u16,
+    vendor_id: u16,
+    device_id: u16,
+    revision_id: u16,
+    subsystem_vendor_id: u16,
+    subsystem_device_id: u16,
+    subsystem_revision_id: u16,
+    valid_fields: u8,
+    manufacturing_location: u8,
+    manufacturing_date: u16,
+    _reserved: u16,
+    serial_number: u32,
+    region_format_interface_code: u16,
+    num_block_control_windows: u16,
+}
+
+/// The layout of the block control windows of an NVDIMM's control region.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(C, packed)]
+pub struct BlockControlWindows {
+    pub size: u64,
+    pub command_register_offset: u64,
+    pub command_register_size: u64,
+    pub status_register_offset: u64,
+    pub status_register_size: u64,
+    pub flags: u16,
+    _reserved: [u8; 6],
+}
+
+impl ControlRegion {
+    pub fn index(&self) -> u16 {
+        self.index
+    }
+
+    pub fn vendor_id(&self) -> u16 {
+        self.vendor_id
+    }
+
+    pub fn device_id(&self) -> u16 {
+        self.device_id
+    }
+
+    pub fn revision_id(&self) -> u16 {
+        self.revision_id
+    }
+
+    pub fn subsystem_vendor_id(&self) -> u16 {
+        self.subsystem_vendor_id
+    }
+
+    pub fn subsystem_device_id(&self) -> u16 {
+        self.subsystem_device_id
+    }
+
+    pub fn subsystem_revision_id(&self) -> u16 {
+        self.subsystem_revision_id
+    }
+
+    /// The manufacturing location and date of the NVDIMM, if they're provided.
+    pub fn manufacturing_info(&self) -> Option<(u8, u16)> {
+        if self.valid_fields.get_bit(0) {
+            Some((self.manufacturing_location, self.manufacturing_date))
+        } else {
+            None
+        }
+    }
+
+    pub fn serial_number(&self) -> u32 {
+        self.serial_number
+    }
+
+    pub fn region_format_interface_code(&self) -> u16 {
+        self.region_format_interface_code
+    }
+
+    pub fn num_block_control_windows(&self) -> u16 {
+        self.num_block_control_windows
+    }
+
+    /// The layout of the NVDIMM's block control windows. Returns `None` if it doesn't have any.
+    pub fn block_control_windows(&self) -> Option<BlockControlWindows> {
+        if self.num_block_control_windows == 0
+            || (self.header.length as usize)
+                < mem::size_of::<ControlRegion>() + mem::size_of::<BlockControlWindows>()
+        {
+            return None;
+        }
+
+        Some(unsafe {
+            ptr::read_unaligned((self as *const ControlRegion as *const u8).add(mem::size_of::<ControlRegion>())
+                as *const BlockControlWindows)
+        })
+    }
+}
+
+/// Describes the block data windows of an NVDIMM's control region.
+#[repr(C, packed)]
+pub struct BlockDataWindowRegion {
+    header: NfitStructureHeader,
+    control_region_index: u16,
+    num_block_data_windows: u16,
+    start_offset: u64,
+    window_size: u64,
+    accessible_capacity: u64,
+    first_block_address: u64,
+}
+
+impl BlockDataWindowRegion {
+    pub fn control_region_index(&self) -> u16 {
+        self.control_region_index
+    }
+
+    pub fn num_block_data_windows(&self) -> u16 {
+        self.num_block_data_windows
+    }
+
+    /// The offset of the first block data window from the start of the control region.
+    pub fn start_offset(&self) -> u64 {
+        self.start_offset
+    }
+
+    pub fn window_size(&self) -> u64 {
+        self.window_size
+    }
+
+    /// The capacity of the NVDIMM that is accessible through the block data windows, in bytes.
+    pub fn accessible_capacity(&self) -> u64 {
+        self.accessible_capacity
+    }
+
+    pub fn first_block_address(&self) -> u64 {
+        self.first_block_address
+    }
+}
+
+/// Describes the addresses that must be written to flush writes to an NVDIMM into its persistence domain.
+#[repr(C, packed)]
+pub struct FlushHintAddress {
+    header: NfitStructureHeader,
+    device_handle: u32,
+    num_addresses: u16,
+    _reserved: [u8; 6],
+    // Followed by `num_addresses` addresses
+}
+
+impl FlushHintAddress {
+    pub fn device_handle(&self) -> NfitDeviceHandle {
+        NfitDeviceHandle(self.device_handle)
+    }
+
+    pub fn addresses(&self) -> impl Iterator<Item = u64> + '_ {
+        let available = (self.header.length as usize - mem::size_of::<FlushHintAddress>()) / mem::size_of::<u64>();
+        let addresses = unsafe {
+            (self as *const FlushHintAddress as *const u8).add(mem::size_of::<FlushHintAddress>()) as *const u64
+        };
+        (0..usize::min(self.num_addresses as usize, available))
+            .map(move |i| unsafe { ptr::read_unaligned(addresses.add(i)) })
+    }
+}
+
+/// Describes the persistence capabilities of the platform.
+#[repr(C, packed)]
+pub struct PlatformCapabilities {
+    header: NfitStructureHeader,
+    highest_valid_capability: u8,
+    _reserved1: [u8; 3],
+    capabilities: u32,
+    _reserved2: u32,
+}
+
+impl PlatformCapabilities {
+    fn capability(&self, bit: u8) -> bool {
+        bit <= self.highest_valid_capability && { self.capabilities }.get_bit(bit as usize)
+    }
+
+    /// If true, the platform flushes the CPU caches to the persistence domain on power loss.
+    pub fn cpu_cache_flush_to_nvdimm(&self) -> bool {
+        self.capability(0)
+    }
+
+    /// If true, the platform flushes the memory controller's buffers to the persistence domain on power loss.
+    pub fn memory_controller_flush_to_nvdimm(&self) -> bool {
+        self.capability(1)
+    }
+
+    pub fn hardware_mirroring(&self) -> bool {
+        self.capability(2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{make_sdt, table_from_bytes};
+    use alloc::vec::Vec;
+
+    /// Builds an NFIT in the layout QEMU uses for a single 1GiB `nvdimm` device in slot 0, which gives it device
+    /// handle `1`, SPA range index `2` and control region index `3`.
+    fn make_qemu_nfit() -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&[0; 4]);
+
+        // SPA range
+        body.extend_from_slice(&[0x00, 0x00, 56, 0x00]);
+        body.extend_from_slice(&2u16.to_le_bytes());
+        body.extend_from_slice(&0b11u16.to_le_bytes());
+        body.extend_from_slice(&[0; 4]);
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body.extend_from_slice(&SpaRangeType::PERSISTENT_MEMORY);
+        body.extend_from_slice(&0x1_4000_0000u64.to_le_bytes());
+        body.extend_from_slice(&0x4000_0000u64.to_le_bytes());
+        body.extend_from_slice(&0x8008u64.to_le_bytes());
+
+        // Region mapping
+        body.extend_from_slice(&[0x01, 0x00, 48, 0x00]);
+        body.extend_from_slice(&1u32.to_le_bytes());
+        for field in [0u16, 0, 2, 3].iter() {
+            body.extend_from_slice(&field.to_le_bytes());
+        }
+        body.extend_from_slice(&0x4000_0000u64.to_le_bytes());
+        body.extend_from_slice(&0u64.to_le_bytes());
+        body.extend_from_slice(&0u64.to_le_bytes());
+        for field in [0u16, 1, 0, 0].iter() {
+            body.extend_from_slice(&field.to_le_bytes());
+        }
+
+        // Control region, with no block control windows
+        body.extend_from_slice(&[0x04, 0x00, 80, 0x00]);
+        for field in [3u16, 0x8086, 0x0001, 0x0001, 0, 0, 0].iter() {
+            body.extend_from_slice(&field.to_le_bytes());
+        }
+        body.extend_from_slice(&[0, 0, 0, 0, 0, 0]);
+        body.extend_from_slice(&0x0012_3456u32.to_le_bytes());
+        body.extend_from_slice(&0x0301u16.to_le_bytes());
+        body.extend_from_slice(&0u16.to_le_bytes());
+        body.extend_from_slice(&[0; 48]);
+
+        // Flush hint addresses
+        body.extend_from_slice(&[0x06, 0x00, 24, 0x00]);
+        body.extend_from_slice(&1u32.to_le_bytes());
+        body.extend_from_slice(&1u16.to_le_bytes());
+        body.extend_from_slice(&[0; 6]);
+        body.extend_from_slice(&0xfed0_0000u64.to_le_bytes());
+
+        // Platform capabilities
+        body.extend_from_slice(&[0x07, 0x00, 16, 0x00, 1, 0, 0, 0]);
+        body.extend_from_slice(&0b11u32.to_le_bytes());
+        body.extend_from_slice(&[0; 4]);
+
+        make_sdt(b"NFIT", 1, &body)
+    }
+
+    #[test]
+    fn test_spa_range() {
+        let table = make_qemu_nfit();
+        let nfit = table_from_bytes::<Nfit>(&table);
+        assert_eq!(nfit.structures().count(), 5);
+
+        let range = match nfit.structures().next() {
+            Some(NfitStructure::SpaRange(range)) => range,
+            _ => panic!("Expected an SPA range"),
+        };
+        assert_eq!(range.index(), 2);
+        assert_eq!(range.range_type(), SpaRangeType::PersistentMemory);
+        assert_eq!(range.proximity_domain(), Some(0));
+        assert_eq!(range.base_address(), 0x1_4000_0000);
+        assert_eq!(range.length(), 0x4000_0000);
+        assert!(range.memory_mapping_attributes().write_back());
+        assert!(range.memory_mapping_attributes().non_volatile());
+        assert!(!range.memory_mapping_attributes().uncacheable());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_nvdimm() {
+        let table = make_qemu_nfit();
+        let nfit = table_from_bytes::<Nfit>(&table);
+        let nvdimm = nfit.nvdimm(NfitDeviceHandle(1));
+
+        assert_eq!(nvdimm.region_mappings.len(), 1);
+        assert_eq!(nvdimm.region_mappings[0].region_size(), 0x4000_0000);
+        assert_eq!(nvdimm.spa_ranges.len(), 1);
+        assert_eq!(nvdimm.spa_ranges[0].index(), 2);
+        assert_eq!(nvdimm.control_regions.len(), 1);
+        assert_eq!(nvdimm.control_regions[0].vendor_id(), 0x8086);
+        assert_eq!(nvdimm.control_regions[0].serial_number(), 0x0012_3456);
+        assert_eq!(nvdimm.control_regions[0].block_control_windows(), None);
+        assert!(nvdimm.block_data_windows.is_empty());
+        assert_eq!(nvdimm.flush_hint_address.unwrap().addresses().collect::<Vec<u64>>(), [0xfed0_0000]);
+
+        let other = nfit.nvdimm(NfitDeviceHandle(2));
+        assert!(other.region_mappings.is_empty());
+        assert!(other.spa_ranges.is_empty());
+        assert!(other.flush_hint_address.is_none());
+    }
+
+    #[test]
+    fn test_platform_capabilities() {
+        let table = make_qemu_nfit();
+        let nfit = table_from_bytes::<Nfit>(&table);
+        let capabilities = nfit
+            .structures()
+            .find_map(|structure| match structure {
+                NfitStructure::PlatformCapabilities(capabilities) => Some(capabilities),
+                _ => None,
+            })
+            .unwrap();
+
+        assert!(capabilities.cpu_cache_flush_to_nvdimm());
+        assert!(capabilities.memory_controller_flush_to_nvdimm());
+        assert!(!capabilities.hardware_mirroring());
+    }
+}
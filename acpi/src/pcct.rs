@@ -0,0 +1,492 @@
+use crate::{
+    entry_as,
+    platform::address::{GenericAddress, RawGenericAddress},
+    sdt::{SdtHeader, Signature},
+    AcpiError,
+    AcpiTable,
+    EntryIter,
+};
+use bit_field::BitField;
+use core::mem;
+
+/// Represents the Platform Communications Channel Table (PCCT). This describes the communication subspaces used by
+/// the OS to talk to entities elsewhere in the platform (e.g. a management controller that implements CPPC). Each
+/// subspace is a region of shared memory, with a doorbell register that is written to tell the platform that a
+/// command has been placed in it.
+#[repr(C, packed)]
+pub struct Pcct {
+    header: SdtHeader,
+    flags: u32,
+    _reserved: u64,
+}
+
+impl AcpiTable for Pcct {
+    fn header(&self) -> &SdtHeader {
+        &self.header
+    }
+}
+
+impl Pcct {
+    /// If true, the platform can raise an interrupt to signal that a command has completed, so the OS doesn't need
+    /// to poll the subspaces.
+    pub fn platform_interrupt(&self) -> bool {
+        { self.flags }.get_bit(0)
+    }
+
+    pub fn subspaces(&self) -> SubspaceIter<'_> {
+        SubspaceIter {
+            entries: unsafe {
+                EntryIter::new(
+                    Signature::PCCT,
+                    (self as *const Pcct as *const u8).add(mem::size_of::<Pcct>()),
+                    self.header.length.saturating_sub(mem::size_of::<Pcct>() as u32),
+                    entry_minimum_length,
+                )
+            },
+            next_index: 0,
+        }
+    }
+
+    /// Get the subspace with the given index, which is how other tables (and AML) refer to subspaces. Subspaces
+    /// are numbered by their position in the table, including those of types that aren't supported. Returns
+    /// `None` if the PCCT doesn't have a subspace with that index, or if it's of an unsupported type.
+    pub fn subspace(&self, index: u8) -> Option<Subspace<'_>> {
+        let mut subspaces = self.subspaces();
+        while let Some((subspace_index, subspace)) = subspaces.next_with_index() {
+            if subspace_index == index as usize {
+                return Some(subspace);
+            }
+        }
+        None
+    }
+}
+
+pub struct SubspaceIter<'a> {
+    entries: EntryIter<'a, SubspaceHeader>,
+    /// The index of the next subspace structure in the table, including those of reserved types.
+    next_index: usize,
+}
+
+pub enum Subspace<'a> {
+    Generic(&'a GenericSubspace),
+    HwReduced(&'a HwReducedSubspace),
+    HwReducedType2(&'a HwReducedType2Subspace),
+    /// An Extended PCC subspace. These are used for both directions of communication, with type 3 subspaces
+    /// being used by the OS to send commands to the platform, and type 4 subspaces used by the platform to send
+    /// notifications to the OS.
+    Extended(&'a ExtendedSubspace),
+}
+
+impl<'a> Iterator for SubspaceIter<'a> {
+    type Item = Subspace<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_with_index().map(|(_, subspace)| subspace)
+    }
+}
+
+impl<'a> SubspaceIter<'a> {
+    fn next_with_index(&mut self) -> Option<(usize, Subspace<'a>)> {
+        for (entry_type, entry) in &mut self.entries {
+            let index = self.next_index;
+            self.next_index += 1;
+
+            match entry_type {
+                0 => return Some((index, Subspace::Generic(unsafe { entry_as(entry) }))),
+                1 => return Some((index, Subspace::HwReduced(unsafe { entry_as(entry) }))),
+                2 => return Some((index, Subspace::HwReducedType2(unsafe { entry_as(entry) }))),
+                3 | 4 => return Some((index, Subspace::Extended(unsafe { entry_as(entry) }))),
+
+                /*
+                 * Other subspace types are reserved by the ACPI standard, or describe subspaces we don't support
+                 * yet. We skip them.
+                 */
+                _ => continue,
+            }
+        }
+
+        None
+    }
+}
+
+#[derive(Clone, Copy)]
+#[repr(C, packed)]
+struct SubspaceHeader {
+    subspace_type: u8,
+    length: u8,
+}
+
+impl crate::EntryHeader for SubspaceHeader {
+    fn entry_type(&self) -> u16 {
+        self.subspace_type.into()
+    }
+
+    fn length(&self) -> u32 {
+        self.length.into()
+    }
+}
+
+fn entry_minimum_length(header: &SubspaceHeader) -> usize {
+    match header.subspace_type {
+        0 => mem::size_of::<GenericSubspace>(),
+        1 => mem::size_of::<HwReducedSubspace>(),
+        2 => mem::size_of::<HwReducedType2Subspace>(),
+        3 | 4 => mem::size_of::<ExtendedSubspace>(),
+        _ => mem::size_of::<SubspaceHeader>(),
+    }
+}
+
+/// A subspace that signals the completion of commands through the SCI, or not at all.
+#[repr(C, packed)]
+pub struct GenericSubspace {
+    header: SubspaceHeader,
+    _reserved: [u8; 6],
+    base_address: u64,
+    memory_range_length: u64,
+    doorbell_register: RawGenericAddress,
+    doorbell_preserve: u64,
+    doorbell_write: u64,
+    nominal_latency: u32,
+    max_periodic_access_rate: u32,
+    min_request_turnaround_time: u16,
+}
+
+impl GenericSubspace {
+    /// The physical address of the subspace's shared memory.
+    pub fn base_address(&self) -> u64 {
+        self.base_address
+    }
+
+    pub fn memory_range_length(&self) -> u64 {
+        self.memory_range_length
+    }
+
+    /// The register that is written to tell the platform that a command is ready. The register should be read,
+    /// ANDed with `doorbell_preserve`, ORed with `doorbell_write`, and then written back.
+    pub fn doorbell_register(&self) -> Result<GenericAddress, AcpiError> {
+        GenericAddress::from_raw(self.doorbell_register)
+    }
+
+    pub fn doorbell_preserve(&self) -> u64 {
+        self.doorbell_preserve
+    }
+
+    pub fn doorbell_write(&self) -> u64 {
+        self.doorbell_write
+    }
+
+    /// The expected latency to process a command, in microseconds.
+    pub fn nominal_latency(&self) -> u32 {
+        self.nominal_latency
+    }
+
+    /// The maximum number of periodic requests that the subspace can handle each minute. Returns `None` if there
+    /// is no limit.
+    pub fn max_periodic_access_rate(&self) -> Option<u32> {
+        match self.max_periodic_access_rate {
+            0 => None,
+            rate => Some(rate),
+        }
+    }
+
+    /// The minimum time that the OS must wait after one command completes before sending another, in
+    /// microseconds.
+    pub fn min_request_turnaround_time(&self) -> u16 {
+        self.min_request_turnaround_time
+    }
+}
+
+/// A subspace for hardware-reduced platforms, which signals the completion of commands through its own interrupt.
+#[repr(C, packed)]
+pub struct HwReducedSubspace {
+    header: SubspaceHeader,
+    platform_interrupt: u32,
+    platform_interrupt_flags: u8,
+    _reserved: u8,
+    base_address: u64,
+    memory_range_length: u64,
+    doorbell_register: RawGenericAddress,
+    doorbell_preserve: u64,
+    doorbell_write: u64,
+    nominal_latency: u32,
+    max_periodic_access_rate: u32,
+    min_request_turnaround_time: u16,
+}
+
+impl HwReducedSubspace {
+    /// The GSI of the interrupt that the platform raises when a command completes.
+    pub fn platform_interrupt(&self) -> u32 {
+        self.platform_interrupt
+    }
+
+    /// If true, the platform interrupt is active-low. Otherwise, it's active-high.
+    pub fn platform_interrupt_is_active_low(&self) -> bool {
+        self.platform_interrupt_flags.get_bit(0)
+    }
+
+    /// If true, the platform interrupt is edge-triggered. Otherwise, it's level-triggered.
+    pub fn platform_interrupt_is_edge_triggered(&self) -> bool {
+        self.platform_interrupt_flags.get_bit(1)
+    }
+
+    /// The physical address of the subspace's shared memory.
+    pub fn base_address(&self) -> u64 {
+        self.base_address
+    }
+
+    pub fn memory_range_length(&self) -> u64 {
+        self.memory_range_length
+    }
+
+    /// The register that is written to tell the platform that a command is ready. The register should be read,
+    /// ANDed with `doorbell_preserve`, ORed with `doorbell_write`, and then written back.
+    pub fn doorbell_register(&self) -> Result<GenericAddress, AcpiError> {
+        GenericAddress::from_raw(self.doorbell_register)
+    }
+
+    pub fn doorbell_preserve(&self) -> u64 {
+        self.doorbell_preserve
+    }
+
+    pub fn doorbell_write(&self) -> u64 {
+        self.doorbell_write
+    }
+
+    /// The expected latency to process a command, in microseconds.
+    pub fn nominal_latency(&self) -> u32 {
+        self.nominal_latency
+    }
+
+    /// The maximum number of periodic requests that the subspace can handle each minute. Returns `None` if there
+    /// is no limit.
+    pub fn max_periodic_access_rate(&self) -> Option<u32> {
+        match self.max_periodic_access_rate {
+            0 => None,
+            rate => Some(rate),
+        }
+    }
+
+    /// The minimum time that the OS must wait after one command completes before sending another, in
+    /// microseconds.
+    pub fn min_request_turnaround_time(&self) -> u16 {
+        self.min_request_turnaround_time
+    }
+}
+
+/// A hardware-reduced subspace whose platform interrupt must be acknowledged by writing to a register. The fields
+/// it shares with type 1 subspaces can be accessed through `common`.
+#[repr(C, packed)]
+pub struct HwReducedType2Subspace {
+    common: HwReducedSubspace,
+    platform_interrupt_ack_register: RawGenericAddress,
+    platform_interrupt_ack_preserve: u64,
+    platform_interrupt_ack_write: u64,
+}
+
+impl HwReducedType2Subspace {
+    pub fn common(&self) -> &HwReducedSubspace {
+        &self.common
+    }
+
+    /// The register that is written to acknowledge the platform interrupt. Like the doorbell, it should be read,
+    /// ANDed with `platform_interrupt_ack_preserve`, ORed with `platform_interrupt_ack_write`, and then written
+    /// back.
+    pub fn platform_interrupt_ack_register(&self) -> Result<GenericAddress, AcpiError> {
+        GenericAddress::from_raw(self.platform_interrupt_ack_register)
+    }
+
+    pub fn platform_interrupt_ack_preserve(&self) -> u64 {
+        self.platform_interrupt_ack_preserve
+    }
+
+    pub fn platform_interrupt_ack_write(&self) -> u64 {
+        self.platform_interrupt_ack_write
+    }
+}
+
+#[repr(C, packed)]
+pub struct ExtendedSubspace {
+    header: SubspaceHeader,
+    platform_interrupt: u32,
+    platform_interrupt_flags: u8,
+    _reserved1: u8,
+    base_address: u64,
+    memory_range_length: u32,
+    doorbell_register: RawGenericAddress,
+    doorbell_preserve: u64,
+    doorbell_write: u64,
+    nominal_latency: u32,
+    max_periodic_access_rate: u32,
+    min_request_turnaround_time: u32,
+    platform_interrupt_ack_register: RawGenericAddress,
+    platform_interrupt_ack_preserve: u64,
+    platform_interrupt_ack_set: u64,
+    _reserved2: u64,
+    command_complete_check_register: RawGenericAddress,
+    command_complete_check_mask: u64,
+    command_complete_update_register: RawGenericAddress,
+    command_complete_update_preserve: u64,
+    command_complete_update_set: u64,
+    error_status_register: RawGenericAddress,
+    error_status_mask: u64,
+}
+
+impl ExtendedSubspace {
+    /// If true, this is a type 4 subspace, used by the platform to send notifications to the OS. Otherwise, it's
+    /// a type 3 subspace, used by the OS to send commands to the platform.
+    pub fn is_responder(&self) -> bool {
+        self.header.subspace_type == 4
+    }
+
+    /// The GSI of the interrupt that the platform raises when a command completes (or a notification is ready).
+    pub fn platform_interrupt(&self) -> u32 {
+        self.platform_interrupt
+    }
+
+    /// The physical address of the subspace's shared memory.
+    pub fn base_address(&self) -> u64 {
+        self.base_address
+    }
+
+    pub fn memory_range_length(&self) -> u32 {
+        self.memory_range_length
+    }
+
+    pub fn doorbell_register(&self) -> Result<GenericAddress, AcpiError> {
+        GenericAddress::from_raw(self.doorbell_register)
+    }
+
+    /// The expected latency to process a command, in microseconds.
+    pub fn nominal_latency(&self) -> u32 {
+        self.nominal_latency
+    }
+
+    /// The maximum number of periodic requests that the subspace can handle each minute. Returns `None` if there
+    /// is no limit.
+    pub fn max_periodic_access_rate(&self) -> Option<u32> {
+        match self.max_periodic_access_rate {
+            0 => None,
+            rate => Some(rate),
+        }
+    }
+
+    /// The minimum time that the OS must wait after one command completes before sending another, in
+    /// microseconds.
+    pub fn min_request_turnaround_time(&self) -> u32 {
+        self.min_request_turnaround_time
+    }
+
+    pub fn platform_interrupt_ack_register(&self) -> Result<GenericAddress, AcpiError> {
+        GenericAddress::from_raw(self.platform_interrupt_ack_register)
+    }
+
+    /// The register that indicates that a command has completed, when ANDed with `command_complete_check_mask`.
+    pub fn command_complete_check_register(&self) -> Result<GenericAddress, AcpiError> {
+        GenericAddress::from_raw(self.command_complete_check_register)
+    }
+
+    pub fn command_complete_check_mask(&self) -> u64 {
+        self.command_complete_check_mask
+    }
+
+    pub fn error_status_register(&self) -> Result<GenericAddress, AcpiError> {
+        GenericAddress::from_raw(self.error_status_register)
+    }
+
+    pub fn error_status_mask(&self) -> u64 {
+        self.error_status_mask
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        platform::address::AddressSpace,
+        test_utils::{make_sdt, table_from_bytes},
+    };
+    use alloc::vec::Vec;
+
+    fn gas(address_space: u8, bit_width: u8, address: u64) -> Vec<u8> {
+        let mut gas = alloc::vec![address_space, bit_width, 0, 4];
+        gas.extend_from_slice(&address.to_le_bytes());
+        gas
+    }
+
+    /// Build a type 2 subspace in the form used by many Arm servers to talk to their System Control Processor.
+    fn type_2_subspace() -> Vec<u8> {
+        let mut subspace = Vec::new();
+        subspace.extend_from_slice(&[2, 90]);
+        subspace.extend_from_slice(&(32u32 + 63).to_le_bytes());
+        subspace.extend_from_slice(&[0b10, 0]);
+        subspace.extend_from_slice(&0x5000_0000u64.to_le_bytes());
+        subspace.extend_from_slice(&0x4000u64.to_le_bytes());
+        subspace.extend(gas(0, 32, 0x2a_0080_0000));
+        subspace.extend_from_slice(&0u64.to_le_bytes());
+        subspace.extend_from_slice(&1u64.to_le_bytes());
+        subspace.extend_from_slice(&1000u32.to_le_bytes());
+        subspace.extend_from_slice(&0u32.to_le_bytes());
+        subspace.extend_from_slice(&100u16.to_le_bytes());
+        subspace.extend(gas(0, 32, 0x2a_0080_0010));
+        subspace.extend_from_slice(&0xffff_fffeu64.to_le_bytes());
+        subspace.extend_from_slice(&1u64.to_le_bytes());
+        subspace
+    }
+
+    #[test]
+    fn test_type_2_subspace() {
+        let mut body = Vec::new();
+        body.extend_from_slice(&1u32.to_le_bytes());
+        body.extend_from_slice(&[0; 8]);
+        body.extend(type_2_subspace());
+        // A reserved subspace type, which should be skipped
+        body.extend_from_slice(&[0x7f, 4, 0, 0]);
+
+        let table = make_sdt(b"PCCT", 2, &body);
+        let pcct = table_from_bytes::<Pcct>(&table);
+        assert!(pcct.platform_interrupt());
+
+        let subspaces: Vec<Subspace> = pcct.subspaces().collect();
+        assert_eq!(subspaces.len(), 1);
+        let subspace = match subspaces[0] {
+            Subspace::HwReducedType2(subspace) => subspace,
+            _ => panic!("Unexpected PCCT subspace"),
+        };
+
+        let common = subspace.common();
+        assert_eq!(common.platform_interrupt(), 95);
+        assert!(!common.platform_interrupt_is_active_low());
+        assert!(common.platform_interrupt_is_edge_triggered());
+        assert_eq!(common.base_address(), 0x5000_0000);
+        assert_eq!(common.memory_range_length(), 0x4000);
+        let doorbell = common.doorbell_register().unwrap();
+        assert_eq!(doorbell.address_space, AddressSpace::SystemMemory);
+        assert_eq!(doorbell.bit_width, 32);
+        assert_eq!(doorbell.address, 0x2a_0080_0000);
+        assert_eq!(common.doorbell_preserve(), 0);
+        assert_eq!(common.doorbell_write(), 1);
+        assert_eq!(common.nominal_latency(), 1000);
+        assert_eq!(common.max_periodic_access_rate(), None);
+        assert_eq!(common.min_request_turnaround_time(), 100);
+
+        assert_eq!(subspace.platform_interrupt_ack_register().unwrap().address, 0x2a_0080_0010);
+        assert_eq!(subspace.platform_interrupt_ack_preserve(), 0xffff_fffe);
+        assert_eq!(subspace.platform_interrupt_ack_write(), 1);
+    }
+
+    #[test]
+    fn test_truncated_subspace() {
+        let mut body = Vec::new();
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body.extend_from_slice(&[0; 8]);
+        let mut subspace = type_2_subspace();
+        // Claim to be a type 2 subspace, but with the length of a type 1 subspace
+        subspace[1] = 62;
+        subspace.truncate(62);
+        body.extend(subspace);
+
+        let table = make_sdt(b"PCCT", 2, &body);
+        let pcct = table_from_bytes::<Pcct>(&table);
+        assert_eq!(pcct.subspaces().count(), 0);
+    }
+}
@@ -0,0 +1,299 @@
+use crate::{
+    entry_as,
+    sdt::{SdtHeader, Signature},
+    AcpiTable,
+    EntryIter,
+};
+use core::{char, mem, slice};
+
+/// Represents the Platform Health Assessment Table (PHAT). This contains records that describe the versions of the
+/// firmware components on the platform, and their health, so that firmware update tools and telemetry can check
+/// for problems.
+#[repr(C, packed)]
+pub struct Phat {
+    header: SdtHeader,
+}
+
+impl AcpiTable for Phat {
+    fn header(&self) -> &SdtHeader {
+        &self.header
+    }
+}
+
+impl Phat {
+    pub fn records(&self) -> PhatRecordIter<'_> {
+        PhatRecordIter {
+            entries: unsafe {
+                EntryIter::new(
+                    Signature::PHAT,
+                    (self as *const Phat as *const u8).add(mem::size_of::<Phat>()),
+                    self.header.length.saturating_sub(mem::size_of::<Phat>() as u32),
+                    entry_minimum_length,
+                )
+            },
+        }
+    }
+}
+
+pub struct PhatRecordIter<'a> {
+    entries: EntryIter<'a, RecordHeader>,
+}
+
+pub enum PhatRecord<'a> {
+    FirmwareVersion(&'a FirmwareVersionRecord),
+    FirmwareHealth(&'a FirmwareHealthRecord),
+}
+
+impl<'a> Iterator for PhatRecordIter<'a> {
+    type Item = PhatRecord<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (entry_type, entry) in &mut self.entries {
+            match entry_type {
+                0 => return Some(PhatRecord::FirmwareVersion(unsafe { entry_as(entry) })),
+                1 => return Some(PhatRecord::FirmwareHealth(unsafe { entry_as(entry) })),
+
+                /*
+                 * Other record types are reserved by the ACPI standard. We skip them.
+                 */
+                _ => continue,
+            }
+        }
+
+        None
+    }
+}
+
+#[derive(Clone, Copy)]
+#[repr(C, packed)]
+struct RecordHeader {
+    record_type: u16,
+    length: u16,
+    revision: u8,
+}
+
+impl crate::EntryHeader for RecordHeader {
+    fn entry_type(&self) -> u16 {
+        self.record_type
+    }
+
+    fn length(&self) -> u32 {
+        self.length.into()
+    }
+}
+
+fn entry_minimum_length(header: &RecordHeader) -> usize {
+    match header.record_type {
+        0 => mem::size_of::<FirmwareVersionRecord>(),
+        1 => mem::size_of::<FirmwareHealthRecord>(),
+        _ => mem::size_of::<RecordHeader>(),
+    }
+}
+
+/// Lists the versions of a set of the platform's firmware components.
+#[repr(C, packed)]
+pub struct FirmwareVersionRecord {
+    header: RecordHeader,
+    _reserved: [u8; 3],
+    element_count: u32,
+    // Followed by `element_count` elements with format `FirmwareVersionElement`
+}
+
+impl FirmwareVersionRecord {
+    /// Get the version of each component. If the record is too short to hold the number of elements it claims to,
+    /// only the elements that fit within it are returned.
+    pub fn elements(&self) -> &[FirmwareVersionElement] {
+        let available = (self.header.length as usize - mem::size_of::<FirmwareVersionRecord>())
+            / mem::size_of::<FirmwareVersionElement>();
+        unsafe {
+            slice::from_raw_parts(
+                (self as *const FirmwareVersionRecord as *const u8).add(mem::size_of::<FirmwareVersionRecord>())
+                    as *const FirmwareVersionElement,
+                usize::min(self.element_count as usize, available),
+            )
+        }
+    }
+}
+
+#[repr(C, packed)]
+pub struct FirmwareVersionElement {
+    component_id: [u8; 16],
+    version: u64,
+    producer_id: u32,
+}
+
+impl FirmwareVersionElement {
+    /// The GUID identifying the firmware component, in its in-memory form.
+    pub fn component_id(&self) -> [u8; 16] {
+        self.component_id
+    }
+
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// The ID of the producer of the component, which is usually one of the vendor IDs used in ACPI table
+    /// headers.
+    pub fn producer_id(&self) -> u32 {
+        self.producer_id
+    }
+}
+
+/// Describes the health of one of the platform's devices, as assessed by the firmware.
+#[repr(C, packed)]
+pub struct FirmwareHealthRecord {
+    header: RecordHeader,
+    _reserved: [u8; 2],
+    am_healthy: u8,
+    device_signature: [u8; 16],
+    device_specific_data_offset: u32,
+    /*
+     * Followed by the device's path, as a null-terminated UCS-2 string, and then any device-specific data, at
+     * `device_specific_data_offset` from the start of this structure.
+     */
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HealthStatus {
+    ErrorsFound,
+    NoErrorsFound,
+    Unknown,
+    /// No errors were found, but there is more information about the device's health in its device-specific
+    /// data.
+    Advisory,
+    Reserved(u8),
+}
+
+impl FirmwareHealthRecord {
+    pub fn am_healthy(&self) -> HealthStatus {
+        match self.am_healthy {
+            0 => HealthStatus::ErrorsFound,
+            1 => HealthStatus::NoErrorsFound,
+            2 => HealthStatus::Unknown,
+            3 => HealthStatus::Advisory,
+            other => HealthStatus::Reserved(other),
+        }
+    }
+
+    /// The GUID identifying the format of the device-specific data, in its in-memory form.
+    pub fn device_signature(&self) -> [u8; 16] {
+        self.device_signature
+    }
+
+    /// The path of the device, as a UEFI device path in text form. Characters that can't be decoded are replaced
+    /// with `U+FFFD`.
+    pub fn device_path(&self) -> impl Iterator<Item = char> + '_ {
+        let bytes = &self.bytes()[mem::size_of::<FirmwareHealthRecord>()..self.device_specific_data_start()];
+        let units =
+            bytes.chunks_exact(2).map(|unit| u16::from_le_bytes([unit[0], unit[1]])).take_while(|&unit| unit != 0);
+        char::decode_utf16(units).map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+    }
+
+    /// Data about the device's health, in a format identified by `device_signature`. Returns an empty slice if the
+    /// record doesn't have any.
+    pub fn device_specific_data(&self) -> &[u8] {
+        &self.bytes()[self.device_specific_data_start()..]
+    }
+
+    /// The offset of the device-specific data within the record. If there isn't any, or the offset is outside the
+    /// record, this is the end of the record.
+    fn device_specific_data_start(&self) -> usize {
+        let offset = self.device_specific_data_offset as usize;
+        let length = self.header.length as usize;
+        if offset >= mem::size_of::<FirmwareHealthRecord>() && offset <= length {
+            offset
+        } else {
+            length
+        }
+    }
+
+    fn bytes(&self) -> &[u8] {
+        unsafe {
+            slice::from_raw_parts(self as *const FirmwareHealthRecord as *const u8, self.header.length as usize)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{make_sdt, table_from_bytes};
+    use alloc::{string::String, vec::Vec};
+
+    #[test]
+    fn test_records() {
+        let mut body = Vec::new();
+        body.extend_from_slice(&0u16.to_le_bytes());
+        body.extend_from_slice(&(12u16 + 2 * 28).to_le_bytes());
+        body.extend_from_slice(&[1, 0, 0, 0]);
+        body.extend_from_slice(&2u32.to_le_bytes());
+        for (id, version) in [(0x11, 0x0001_0002_0003_0004u64), (0x22, 7)].iter() {
+            body.extend_from_slice(&[*id; 16]);
+            body.extend_from_slice(&version.to_le_bytes());
+            body.extend_from_slice(b"INTL");
+        }
+
+        let path: Vec<u8> =
+            "PciRoot(0x0)/Pci(0x1F,0x0)\0".encode_utf16().flat_map(|unit| unit.to_le_bytes()).collect();
+        let data_offset = 28 + path.len();
+        body.extend_from_slice(&1u16.to_le_bytes());
+        body.extend_from_slice(&((data_offset + 4) as u16).to_le_bytes());
+        body.extend_from_slice(&[1, 0, 0, 3]);
+        body.extend_from_slice(&[0x33; 16]);
+        body.extend_from_slice(&(data_offset as u32).to_le_bytes());
+        body.extend_from_slice(&path);
+        body.extend_from_slice(&[0xde, 0xad, 0xbe, 0xef]);
+
+        // A reserved record type, which should be skipped
+        body.extend_from_slice(&[0xff, 0x00, 5, 0, 1]);
+
+        let table = make_sdt(b"PHAT", 1, &body);
+        let phat = table_from_bytes::<Phat>(&table);
+        let records: Vec<PhatRecord> = phat.records().collect();
+        assert_eq!(records.len(), 2);
+
+        let versions = match records[0] {
+            PhatRecord::FirmwareVersion(record) => record.elements(),
+            _ => panic!("Expected a firmware version record"),
+        };
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions[0].component_id(), [0x11; 16]);
+        assert_eq!(versions[0].version(), 0x0001_0002_0003_0004);
+        assert_eq!(versions[0].producer_id(), u32::from_le_bytes(*b"INTL"));
+        assert_eq!(versions[1].component_id(), [0x22; 16]);
+        assert_eq!(versions[1].version(), 7);
+
+        let health = match records[1] {
+            PhatRecord::FirmwareHealth(record) => record,
+            _ => panic!("Expected a firmware health record"),
+        };
+        assert_eq!(health.am_healthy(), HealthStatus::Advisory);
+        assert_eq!(health.device_signature(), [0x33; 16]);
+        assert_eq!(health.device_path().collect::<String>(), "PciRoot(0x0)/Pci(0x1F,0x0)");
+        assert_eq!(health.device_specific_data(), [0xde, 0xad, 0xbe, 0xef]);
+    }
+
+    #[test]
+    fn test_truncated_version_record() {
+        // The record claims to have three elements, but is only long enough for one
+        let mut body = Vec::new();
+        body.extend_from_slice(&0u16.to_le_bytes());
+        body.extend_from_slice(&(12u16 + 28).to_le_bytes());
+        body.extend_from_slice(&[1, 0, 0, 0]);
+        body.extend_from_slice(&3u32.to_le_bytes());
+        body.extend_from_slice(&[0x11; 28]);
+        // A health record whose length doesn't cover its fixed fields
+        body.extend_from_slice(&1u16.to_le_bytes());
+        body.extend_from_slice(&20u16.to_le_bytes());
+        body.extend_from_slice(&[0; 16]);
+
+        let table = make_sdt(b"PHAT", 1, &body);
+        let phat = table_from_bytes::<Phat>(&table);
+        let mut records = phat.records();
+        match records.next() {
+            Some(PhatRecord::FirmwareVersion(record)) => assert_eq!(record.elements().len(), 1),
+            _ => panic!("Expected a firmware version record"),
+        }
+        assert!(records.next().is_none());
+    }
+}
@@ -1,8 +1,8 @@
 //! ACPI defines a Generic Address Structure (GAS), which provides a versatile way to describe register locations
 //! in a wide range of address spaces.
 
-use crate::AcpiError;
-use core::convert::TryFrom;
+use crate::{AcpiError, AcpiHandler, PortIo};
+use core::{convert::TryFrom, fmt, ptr};
 
 /// This is the raw form of a Generic Address Structure, and follows the layout found in the ACPI tables. It does
 /// not form part of the public API, and should be turned into a `GenericAddress` for most use-cases.
@@ -41,6 +41,26 @@ pub enum AddressSpace {
     OemDefined(u8),
 }
 
+impl fmt::Display for AddressSpace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AddressSpace::SystemMemory => write!(f, "SystemMemory"),
+            AddressSpace::SystemIo => write!(f, "SystemIo"),
+            AddressSpace::PciConfigSpace => write!(f, "PciConfigSpace"),
+            AddressSpace::EmbeddedController => write!(f, "EmbeddedController"),
+            AddressSpace::SMBus => write!(f, "SMBus"),
+            AddressSpace::SystemCmos => write!(f, "SystemCmos"),
+            AddressSpace::PciBarTarget => write!(f, "PciBarTarget"),
+            AddressSpace::Ipmi => write!(f, "Ipmi"),
+            AddressSpace::GeneralIo => write!(f, "GeneralIo"),
+            AddressSpace::GenericSerialBus => write!(f, "GenericSerialBus"),
+            AddressSpace::PlatformCommunicationsChannel => write!(f, "PlatformCommunicationsChannel"),
+            AddressSpace::FunctionalFixedHardware => write!(f, "FunctionalFixedHardware"),
+            AddressSpace::OemDefined(id) => write!(f, "OemDefined({:#x})", id),
+        }
+    }
+}
+
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub enum AccessSize {
     Undefined,
@@ -102,4 +122,314 @@ impl GenericAddress {
             address: raw.address,
         })
     }
+
+    /// The address space the register is in.
+    pub fn space(&self) -> AddressSpace {
+        self.address_space
+    }
+
+    pub fn is_system_memory(&self) -> bool {
+        self.address_space == AddressSpace::SystemMemory
+    }
+
+    pub fn is_io_port(&self) -> bool {
+        self.address_space == AddressSpace::SystemIo
+    }
+
+    /// Read from the register described by this address, using `handler` to map it. The register is accessed
+    /// with the access size it specifies (or the smallest size that covers it, if it doesn't specify one), using
+    /// multiple accesses if it's wider than that. The value is shifted down by `bit_offset`, and masked to
+    /// `bit_width`.
+    ///
+    /// Registers in system memory can be accessed, as can those in the system I/O space if `handler` provides
+    /// [`PortIo`](crate::PortIo). Other address spaces produce `AcpiError::UnsupportedAddressSpace`. I/O ports
+    /// can't be accessed with 64-bit accesses.
+    ///
+    /// ### Safety
+    /// Reading some registers can have side-effects. The address must describe a valid register.
+    pub unsafe fn read<H>(&self, handler: &H) -> Result<u64, AcpiError>
+    where
+        H: AcpiHandler,
+    {
+        let (access_bits, num_accesses) = self.accesses()?;
+
+        let mut raw = 0u64;
+        for i in 0..num_accesses {
+            let value = unsafe { self.read_access(handler, i, access_bits) }?;
+            raw |= value << (i * access_bits);
+        }
+
+        Ok((raw >> self.bit_offset) & self.mask())
+    }
+
+    /// Write `value` to the register described by this address, using `handler` to map it. `value` is masked to
+    /// `bit_width` and shifted up by `bit_offset`, and the register is accessed in the same way as in
+    /// [`GenericAddress::read`]. Bits of the accessed area that lie outside the register are written as zero,
+    /// rather than being preserved, as registers may have side-effects on read.
+    ///
+    /// ### Safety
+    /// Writing to registers can have arbitrary effects on the platform. The address must describe a valid register.
+    pub unsafe fn write<H>(&self, handler: &H, value: u64) -> Result<(), AcpiError>
+    where
+        H: AcpiHandler,
+    {
+        let (access_bits, num_accesses) = self.accesses()?;
+
+        let raw = (value & self.mask()) << self.bit_offset;
+        for i in 0..num_accesses {
+            let value = (raw >> (i * access_bits)) & (u64::MAX >> (64 - access_bits));
+            unsafe { self.write_access(handler, i, access_bits, value) }?;
+        }
+
+        Ok(())
+    }
+
+    /// The number of bits in the register. A `bit_width` of `0` is treated as covering the whole access.
+    fn register_bits(&self) -> u32 {
+        if self.bit_width == 0 {
+            match self.access_size {
+                AccessSize::Undefined | AccessSize::QWordAccess => 64,
+                AccessSize::ByteAccess => 8,
+                AccessSize::WordAccess => 16,
+                AccessSize::DWordAccess => 32,
+            }
+        } else {
+            self.bit_width as u32
+        }
+    }
+
+    fn mask(&self) -> u64 {
+        u64::MAX >> (64 - self.register_bits().min(64))
+    }
+
+    /// Work out the size of each access to the register, in bits, and how many accesses are needed to cover it.
+    fn accesses(&self) -> Result<(u32, u32), AcpiError> {
+        let total_bits = self.bit_offset as u32 + self.register_bits();
+        if total_bits > 64 {
+            return Err(AcpiError::InvalidGenericAddress);
+        }
+
+        let access_bits = match self.access_size {
+            AccessSize::Undefined => total_bits.next_power_of_two().max(8),
+            AccessSize::ByteAccess => 8,
+            AccessSize::WordAccess => 16,
+            AccessSize::DWordAccess => 32,
+            AccessSize::QWordAccess => 64,
+        };
+        if !self.address.is_multiple_of(access_bits as u64 / 8) {
+            return Err(AcpiError::InvalidGenericAddress);
+        }
+
+        Ok((access_bits, total_bits.div_ceil(access_bits)))
+    }
+
+    unsafe fn read_access<H>(&self, handler: &H, index: u32, access_bits: u32) -> Result<u64, AcpiError>
+    where
+        H: AcpiHandler,
+    {
+        let address = self.address as usize + (index * access_bits / 8) as usize;
+        match self.address_space {
+            AddressSpace::SystemMemory => {
+                let mapping = unsafe { handler.map_physical_region::<u8>(address, access_bits as usize / 8) };
+                let pointer = mapping.virtual_start().as_ptr();
+                Ok(unsafe {
+                    match access_bits {
+                        8 => ptr::read_volatile(pointer) as u64,
+                        16 => ptr::read_volatile(pointer as *const u16) as u64,
+                        32 => ptr::read_volatile(pointer as *const u32) as u64,
+                        _ => ptr::read_volatile(pointer as *const u64),
+                    }
+                })
+            }
+            AddressSpace::SystemIo => {
+                let (port_io, port) = Self::io_port(handler, address)?;
+                Ok(unsafe {
+                    match access_bits {
+                        8 => port_io.read_u8(port) as u64,
+                        16 => port_io.read_u16(port) as u64,
+                        32 => port_io.read_u32(port) as u64,
+                        _ => return Err(AcpiError::InvalidGenericAddress),
+                    }
+                })
+            }
+            space => Err(AcpiError::UnsupportedAddressSpace(space)),
+        }
+    }
+
+    unsafe fn write_access<H>(
+        &self,
+        handler: &H,
+        index: u32,
+        access_bits: u32,
+        value: u64,
+    ) -> Result<(), AcpiError>
+    where
+        H: AcpiHandler,
+    {
+        let address = self.address as usize + (index * access_bits / 8) as usize;
+        match self.address_space {
+            AddressSpace::SystemMemory => {
+                let mapping = unsafe { handler.map_physical_region::<u8>(address, access_bits as usize / 8) };
+                let pointer = mapping.virtual_start().as_ptr();
+                unsafe {
+                    match access_bits {
+                        8 => ptr::write_volatile(pointer, value as u8),
+                        16 => ptr::write_volatile(pointer as *mut u16, value as u16),
+                        32 => ptr::write_volatile(pointer as *mut u32, value as u32),
+                        _ => ptr::write_volatile(pointer as *mut u64, value),
+                    }
+                }
+                Ok(())
+            }
+            AddressSpace::SystemIo => {
+                let (port_io, port) = Self::io_port(handler, address)?;
+                unsafe {
+                    match access_bits {
+                        8 => port_io.write_u8(port, value as u8),
+                        16 => port_io.write_u16(port, value as u16),
+                        32 => port_io.write_u32(port, value as u32),
+                        _ => return Err(AcpiError::InvalidGenericAddress),
+                    }
+                }
+                Ok(())
+            }
+            space => Err(AcpiError::UnsupportedAddressSpace(space)),
+        }
+    }
+
+    fn io_port<H>(handler: &H, address: usize) -> Result<(&dyn PortIo, u16), AcpiError>
+    where
+        H: AcpiHandler,
+    {
+        let port_io = handler.port_io().ok_or(AcpiError::UnsupportedAddressSpace(AddressSpace::SystemIo))?;
+        let port = u16::try_from(address).map_err(|_| AcpiError::InvalidGenericAddress)?;
+        Ok((port_io, port))
+    }
+}
+
+/// Formats the address like `SystemMemory@0xFED00000 (64-bit)`, with the bit offset of the register if it isn't
+/// `0`.
+impl fmt::Display for GenericAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}@{:#X} ({}-bit", self.address_space, self.address, self.register_bits())?;
+        if self.bit_offset != 0 {
+            write!(f, ", offset {}", self.bit_offset)?;
+        }
+        write!(f, ")")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestHandler;
+
+    const REGISTER_ADDRESS: u64 = 0xfed0_0000;
+
+    fn address(bit_width: u8, bit_offset: u8, access_size: AccessSize) -> GenericAddress {
+        GenericAddress {
+            address_space: AddressSpace::SystemMemory,
+            bit_width,
+            bit_offset,
+            access_size,
+            address: REGISTER_ADDRESS,
+        }
+    }
+
+    #[test]
+    fn test_read() {
+        let registers = [0x1122_3344_5566_7788u64, 0];
+        let handler = TestHandler::with_mapping(REGISTER_ADDRESS as usize, registers.as_ptr() as *const u8);
+
+        unsafe {
+            assert_eq!(address(64, 0, AccessSize::QWordAccess).read(&handler).unwrap(), 0x1122_3344_5566_7788);
+            assert_eq!(address(32, 0, AccessSize::DWordAccess).read(&handler).unwrap(), 0x5566_7788);
+            assert_eq!(address(8, 8, AccessSize::Undefined).read(&handler).unwrap(), 0x77);
+            assert_eq!(address(4, 12, AccessSize::WordAccess).read(&handler).unwrap(), 0x7);
+            // A 64-bit register read with byte accesses
+            assert_eq!(address(64, 0, AccessSize::ByteAccess).read(&handler).unwrap(), 0x1122_3344_5566_7788);
+            assert_eq!(address(16, 24, AccessSize::ByteAccess).read(&handler).unwrap(), 0x4455);
+            assert_eq!(address(0, 0, AccessSize::WordAccess).read(&handler).unwrap(), 0x7788);
+        }
+    }
+
+    #[test]
+    fn test_write() {
+        let mut registers = [0u64; 2];
+        let handler = TestHandler::with_mapping(REGISTER_ADDRESS as usize, registers.as_mut_ptr() as *const u8);
+
+        unsafe {
+            address(32, 0, AccessSize::DWordAccess).write(&handler, 0xdead_beef_cafe_f00d).unwrap();
+            assert_eq!(address(64, 0, AccessSize::QWordAccess).read(&handler).unwrap(), 0xcafe_f00d);
+            address(4, 4, AccessSize::ByteAccess).write(&handler, 0xf5).unwrap();
+            assert_eq!(address(64, 0, AccessSize::QWordAccess).read(&handler).unwrap(), 0xcafe_f050);
+            address(64, 0, AccessSize::WordAccess).write(&handler, 0x0102_0304_0506_0708).unwrap();
+        }
+        assert_eq!(registers[0], 0x0102_0304_0506_0708);
+    }
+
+    #[test]
+    fn test_invalid_accesses() {
+        let registers = [0u64; 2];
+        let handler = TestHandler::with_mapping(REGISTER_ADDRESS as usize, registers.as_ptr() as *const u8);
+
+        let io_port =
+            GenericAddress { address_space: AddressSpace::SystemIo, ..address(8, 0, AccessSize::ByteAccess) };
+        let too_wide = address(64, 8, AccessSize::QWordAccess);
+        let misaligned =
+            GenericAddress { address: REGISTER_ADDRESS + 2, ..address(32, 0, AccessSize::DWordAccess) };
+        unsafe {
+            assert!(matches!(
+                io_port.read(&handler),
+                Err(AcpiError::UnsupportedAddressSpace(AddressSpace::SystemIo))
+            ));
+            assert!(matches!(too_wide.read(&handler), Err(AcpiError::InvalidGenericAddress)));
+            assert!(matches!(misaligned.write(&handler, 0), Err(AcpiError::InvalidGenericAddress)));
+        }
+    }
+
+    #[test]
+    fn test_address_spaces() {
+        let raw = |address_space| RawGenericAddress {
+            address_space,
+            bit_width: 8,
+            bit_offset: 0,
+            access_size: 1,
+            address: 0x70,
+        };
+
+        let cmos = GenericAddress::from_raw(raw(0x05)).unwrap();
+        assert_eq!(cmos.space(), AddressSpace::SystemCmos);
+        assert!(!cmos.is_system_memory() && !cmos.is_io_port());
+        assert_eq!(format!("{}", cmos), "SystemCmos@0x70 (8-bit)");
+
+        let io_port = GenericAddress::from_raw(raw(0x01)).unwrap();
+        assert!(io_port.is_io_port());
+        assert_eq!(format!("{}", io_port), "SystemIo@0x70 (8-bit)");
+
+        assert_eq!(
+            GenericAddress::from_raw(raw(0x0a)).unwrap().address_space,
+            AddressSpace::PlatformCommunicationsChannel
+        );
+        assert_eq!(
+            format!("{}", GenericAddress::from_raw(raw(0x7f)).unwrap().address_space),
+            "FunctionalFixedHardware"
+        );
+        let oem = GenericAddress::from_raw(raw(0xc2)).unwrap();
+        assert_eq!(oem.space(), AddressSpace::OemDefined(0xc2));
+        assert!(!oem.is_system_memory() && !oem.is_io_port());
+        assert_eq!(format!("{}", oem.space()), "OemDefined(0xc2)");
+        assert_eq!(GenericAddress::from_raw(raw(0xff)).unwrap().space(), AddressSpace::OemDefined(0xff));
+        // Reserved address spaces
+        assert!(matches!(GenericAddress::from_raw(raw(0x0b)), Err(AcpiError::InvalidGenericAddress)));
+        assert!(matches!(GenericAddress::from_raw(raw(0x80)), Err(AcpiError::InvalidGenericAddress)));
+
+        let hpet = address(64, 0, AccessSize::QWordAccess);
+        assert!(hpet.is_system_memory());
+        assert_eq!(format!("{}", hpet), "SystemMemory@0xFED00000 (64-bit)");
+        assert_eq!(
+            format!("{}", address(4, 12, AccessSize::WordAccess)),
+            "SystemMemory@0xFED00000 (4-bit, offset 12)"
+        );
+    }
 }
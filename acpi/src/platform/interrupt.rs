@@ -1,4 +1,8 @@
+use crate::AcpiHandler;
+#[cfg(feature = "alloc")]
 use alloc::vec::Vec;
+use bit_field::BitField;
+use core::{mem, ops::RangeInclusive, ptr};
 
 #[derive(Debug)]
 pub struct IoApic {
@@ -7,19 +11,62 @@ pub struct IoApic {
     pub global_system_interrupt_base: u32,
 }
 
+impl IoApic {
+    /*
+     * The I/O APIC is accessed indirectly: the index of a register is written to `IOREGSEL`, and the register can
+     * then be accessed through `IOWIN`.
+     */
+    const IOREGSEL_OFFSET: usize = 0x00;
+    const IOWIN_OFFSET: usize = 0x10;
+    const IOAPICVER_INDEX: u32 = 0x01;
+
+    /// Read the I/O APIC's version register to find the index of its highest redirection entry. The MADT does not
+    /// tell us how many redirection entries (and so interrupt inputs) each I/O APIC has, so this is needed to work
+    /// out which Global System Interrupts it handles. The I/O APIC has `max_redirection_entries + 1` inputs.
+    ///
+    /// ### Safety
+    /// This maps the I/O APIC's register block using `handler`, and accesses it. The caller must ensure nothing
+    /// else is accessing the same I/O APIC at the same time, as selecting a register is not atomic with accessing
+    /// it.
+    pub unsafe fn max_redirection_entries<H>(&self, handler: &H) -> u8
+    where
+        H: AcpiHandler,
+    {
+        let mapping = unsafe {
+            handler.map_physical_region::<u8>(self.address as usize, Self::IOWIN_OFFSET + mem::size_of::<u32>())
+        };
+        let base = mapping.virtual_start().as_ptr();
+
+        let version = unsafe {
+            ptr::write_volatile(base.add(Self::IOREGSEL_OFFSET) as *mut u32, Self::IOAPICVER_INDEX);
+            ptr::read_volatile(base.add(Self::IOWIN_OFFSET) as *const u32)
+        };
+
+        version.get_bits(16..24) as u8
+    }
+
+    /// The range of Global System Interrupts handled by this I/O APIC. The MADT doesn't give the number of
+    /// interrupt inputs each I/O APIC has, so `max_redirection_entries` must be read from the I/O APIC, with
+    /// [`IoApic::max_redirection_entries`].
+    pub fn gsi_range(&self, max_redirection_entries: u8) -> RangeInclusive<u32> {
+        self.global_system_interrupt_base
+            ..=self.global_system_interrupt_base.saturating_add(max_redirection_entries as u32)
+    }
+}
+
 #[derive(Debug)]
 pub struct NmiLine {
     pub processor: NmiProcessor,
     pub line: LocalInterruptLine,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum LocalInterruptLine {
     Lint0,
     Lint1,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum NmiProcessor {
     All,
     /// Refers to a processor with the given UID. This is stored as a `u32`, but should be casted to `u8` when the
@@ -27,14 +74,14 @@ pub enum NmiProcessor {
     ProcessorUid(u32),
 }
 
-#[derive(Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum Polarity {
     SameAsBus,
     ActiveHigh,
     ActiveLow,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum TriggerMode {
     SameAsBus,
     Edge,
@@ -62,6 +109,7 @@ pub struct NmiSource {
     pub trigger_mode: TriggerMode,
 }
 
+#[cfg(feature = "alloc")]
 #[derive(Debug)]
 pub struct Apic {
     pub local_apic_address: u64,
@@ -76,6 +124,55 @@ pub struct Apic {
     pub also_has_legacy_pics: bool,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GicVersion {
+    /// The MADT doesn't give the version of the GIC, so it should be found from the hardware.
+    Unspecified,
+    V1,
+    V2,
+    V3,
+    V4,
+    Reserved(u8),
+}
+
+/// A region of physical memory that contains the register frames of one or more GIC Redistributors. These are only
+/// present on GICv3 and later.
+#[derive(Debug)]
+pub struct GicRedistributorRegion {
+    pub base_address: u64,
+    pub length: u32,
+}
+
+/// A GICv2m MSI frame, which turns writes to its doorbell register into SPIs.
+#[derive(Debug)]
+pub struct GicMsiFrame {
+    pub id: u32,
+    pub base_address: u64,
+    /// The first SPI, and the number of SPIs, that the frame produces. If this is `None`, they should instead be
+    /// read from the frame's `MSI_TYPER` register.
+    pub spis: Option<(u16, u16)>,
+}
+
+/// A GIC Interrupt Translation Service (ITS), which translates MSIs into LPIs on GICv3 and later.
+#[derive(Debug)]
+pub struct GicIts {
+    pub id: u32,
+    pub base_address: u64,
+}
+
+#[cfg(feature = "alloc")]
+#[derive(Debug)]
+pub struct Gic {
+    pub version: GicVersion,
+    pub distributor_address: u64,
+    /// The regions containing the GIC Redistributors. If this is empty on a GICv3 or later system, the address of
+    /// each processor's Redistributor is instead given by its GICC entry in the MADT.
+    pub redistributor_regions: Vec<GicRedistributorRegion>,
+    pub msi_frames: Vec<GicMsiFrame>,
+    pub its_blocks: Vec<GicIts>,
+}
+
+#[cfg(feature = "alloc")]
 #[derive(Debug)]
 #[non_exhaustive]
 pub enum InterruptModel {
@@ -87,4 +184,150 @@ pub enum InterruptModel {
     /// Controllers. These are likely to be found on x86 and x86_64 systems and are made up of a
     /// Local APIC for each core and one or more I/O APICs to handle external interrupts.
     Apic(Apic),
+
+    /// Describes an interrupt controller based around ARM's Generic Interrupt Controller. This is made up of a
+    /// Distributor that handles shared interrupts, a CPU interface (and, on GICv3 and later, a Redistributor) for
+    /// each core, and optionally blocks that handle MSIs.
+    Gic(Gic),
+}
+
+#[cfg(feature = "alloc")]
+impl InterruptModel {
+    /// The physical address of the local APIC of each processor. Returns `None` if the interrupt model doesn't use
+    /// the APIC.
+    pub fn local_apic_address(&self) -> Option<u64> {
+        match self {
+            InterruptModel::Apic(apic) => Some(apic.local_apic_address),
+            _ => None,
+        }
+    }
+
+    /// The I/O APICs of the platform. Returns an empty slice if the interrupt model doesn't use the APIC.
+    pub fn io_apics(&self) -> &[IoApic] {
+        match self {
+            InterruptModel::Apic(apic) => &apic.io_apics,
+            _ => &[],
+        }
+    }
+
+    /// The LINT lines of the local APICs that are connected to NMIs. Returns an empty slice if the interrupt model
+    /// doesn't use the APIC.
+    pub fn local_apic_nmi_lines(&self) -> &[NmiLine] {
+        match self {
+            InterruptModel::Apic(apic) => &apic.local_apic_nmi_lines,
+            _ => &[],
+        }
+    }
+
+    /// Find the Global System Interrupt that an ISA IRQ is connected to. ISA IRQs are identity-mapped to GSIs,
+    /// unless the MADT has an Interrupt Source Override entry for them, so this is also the case for platforms
+    /// that don't use the APIC.
+    pub fn gsi_for_isa_irq(&self, irq: u8) -> u32 {
+        match self {
+            InterruptModel::Apic(apic) => apic
+                .interrupt_source_overrides
+                .iter()
+                .find(|entry| entry.isa_source == irq)
+                .map(|entry| entry.global_system_interrupt)
+                .unwrap_or(irq as u32),
+            _ => irq as u32,
+        }
+    }
+
+    /// Find the I/O APIC that handles the given Global System Interrupt. The range of GSIs each I/O APIC handles
+    /// depends on how many redirection entries it has, which isn't given by the MADT, so the caller must supply
+    /// `max_redirection_entries` to find it for each I/O APIC (usually by calling
+    /// [`IoApic::max_redirection_entries`]). Returns `None` if no I/O APIC handles the GSI, or if the interrupt
+    /// model doesn't use the APIC.
+    pub fn ioapic_for_gsi<F>(&self, gsi: u32, mut max_redirection_entries: F) -> Option<&IoApic>
+    where
+        F: FnMut(&IoApic) -> u8,
+    {
+        match self {
+            InterruptModel::Apic(apic) => apic
+                .io_apics
+                .iter()
+                .filter(|io_apic| io_apic.global_system_interrupt_base <= gsi)
+                .find(|io_apic| io_apic.gsi_range(max_redirection_entries(io_apic)).contains(&gsi)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestHandler;
+    #[cfg(feature = "alloc")]
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_max_redirection_entries() {
+        /*
+         * A fake I/O APIC register block. As nothing actually responds to the write to `IOREGSEL`, `IOWIN` holds
+         * the value of the version register all the time: a version of `0x20` with 24 redirection entries.
+         */
+        let mut registers = [0u32; 5];
+        registers[4] = 0x0017_0020;
+
+        let handler = TestHandler::with_mapping(0xfec0_0000, registers.as_mut_ptr() as *const u8);
+
+        let io_apic = IoApic { id: 0, address: 0xfec0_0000, global_system_interrupt_base: 0 };
+        assert_eq!(unsafe { io_apic.max_redirection_entries(&handler) }, 23);
+        assert_eq!(registers[0], 0x01);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_ioapic_for_gsi() {
+        let model = InterruptModel::Apic(Apic {
+            local_apic_address: 0xfee0_0000,
+            io_apics: alloc::vec![
+                IoApic { id: 0, address: 0xfec0_0000, global_system_interrupt_base: 0 },
+                IoApic { id: 1, address: 0xfec0_1000, global_system_interrupt_base: 24 },
+            ],
+            local_apic_nmi_lines: Vec::new(),
+            interrupt_source_overrides: Vec::new(),
+            nmi_sources: Vec::new(),
+            also_has_legacy_pics: false,
+        });
+        let max_redirection_entries = |io_apic: &IoApic| if io_apic.id == 0 { 23 } else { 31 };
+
+        let io_apics = model.io_apics();
+        assert_eq!(io_apics[0].gsi_range(23), 0..=23);
+        assert_eq!(io_apics[1].gsi_range(31), 24..=55);
+
+        assert_eq!(model.ioapic_for_gsi(0, max_redirection_entries).unwrap().id, 0);
+        assert_eq!(model.ioapic_for_gsi(23, max_redirection_entries).unwrap().id, 0);
+        assert_eq!(model.ioapic_for_gsi(24, max_redirection_entries).unwrap().id, 1);
+        assert_eq!(model.ioapic_for_gsi(55, max_redirection_entries).unwrap().id, 1);
+        assert!(model.ioapic_for_gsi(56, max_redirection_entries).is_none());
+        assert!(InterruptModel::Unknown.ioapic_for_gsi(0, max_redirection_entries).is_none());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_accessors() {
+        let model = InterruptModel::Apic(Apic {
+            local_apic_address: 0xfee0_0000,
+            io_apics: alloc::vec![IoApic { id: 2, address: 0xfec0_0000, global_system_interrupt_base: 0 }],
+            local_apic_nmi_lines: alloc::vec![NmiLine {
+                processor: NmiProcessor::All,
+                line: LocalInterruptLine::Lint1
+            }],
+            interrupt_source_overrides: Vec::new(),
+            nmi_sources: Vec::new(),
+            also_has_legacy_pics: true,
+        });
+        assert_eq!(model.local_apic_address(), Some(0xfee0_0000));
+        assert_eq!(model.io_apics().len(), 1);
+        assert_eq!(model.io_apics()[0].id, 2);
+        assert_eq!(model.local_apic_nmi_lines().len(), 1);
+        assert_eq!(model.local_apic_nmi_lines()[0].line, LocalInterruptLine::Lint1);
+
+        let model = InterruptModel::Unknown;
+        assert_eq!(model.local_apic_address(), None);
+        assert!(model.io_apics().is_empty());
+        assert!(model.local_apic_nmi_lines().is_empty());
+    }
 }
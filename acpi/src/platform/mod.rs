@@ -3,14 +3,21 @@ pub mod interrupt;
 
 use crate::{
     fadt::Fadt,
-    madt::Madt,
+    madt::{Madt, MpProtectedModeWakeupCommand, MpwkMailbox},
+    waet::Waet,
     AcpiError,
     AcpiHandler,
     AcpiTables,
+};
+#[cfg(feature = "alloc")]
+use crate::{
+    srat::{GenericInitiatorAffinity, GicItsAffinity, MemoryAffinity, Srat, SratEntry},
     PowerProfile,
 };
 use address::GenericAddress;
+#[cfg(feature = "alloc")]
 use alloc::vec::Vec;
+#[cfg(feature = "alloc")]
 use interrupt::InterruptModel;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -28,8 +35,11 @@ pub enum ProcessorState {
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Processor {
-    pub processor_uid: u8,
-    pub local_apic_id: u8,
+    /// The ACPI Processor UID, which matches the `_UID` of the processor's object in the namespace.
+    pub processor_uid: u32,
+    /// The ID of the processor's local APIC. On platforms that use the GIC, this is instead the number of the
+    /// processor's GIC CPU interface.
+    pub local_apic_id: u32,
 
     /// The state of this processor. Always check that the processor is not `Disabled` before
     /// attempting to bring it up!
@@ -39,37 +49,133 @@ pub struct Processor {
     /// When the bootloader is entered, the BSP is the only processor running code. To run code on
     /// more than one processor, you need to "bring up" the APs.
     pub is_ap: bool,
+
+    /// If `true`, the processor is currently `Disabled`, but can be enabled while the OS is running (e.g. by
+    /// hot-plugging it). Processors that are `Disabled` and not online-capable can never be used.
+    pub online_capable: bool,
+
+    /// On platforms that use the GIC, the affinity fields of the processor's `MPIDR_EL1` register. This is how
+    /// the processor is identified to PSCI (e.g. as the target of `CPU_ON`). This is `None` on platforms that use
+    /// the APIC.
+    pub mpidr: Option<u64>,
 }
 
+#[cfg(feature = "alloc")]
 pub struct ProcessorInfo {
     pub boot_processor: Processor,
     /// Application processors should be brought up in the order they're defined in this list.
     pub application_processors: Vec<Processor>,
 }
 
+#[cfg(feature = "alloc")]
+impl ProcessorInfo {
+    /// Iterate over all of the processors, starting with the boot processor, followed by the application
+    /// processors in the order they should be brought up.
+    pub fn all(&self) -> impl Iterator<Item = &Processor> {
+        core::iter::once(&self.boot_processor).chain(self.application_processors.iter())
+    }
+
+    /// Find the processor with the given ACPI Processor UID.
+    pub fn find_by_uid(&self, uid: u32) -> Option<&Processor> {
+        self.all().find(|processor| processor.processor_uid == uid)
+    }
+
+    /// Find the processor with the given local APIC ID (or, on platforms that use the GIC, GIC CPU interface
+    /// number).
+    pub fn find_by_apic_id(&self, apic_id: u32) -> Option<&Processor> {
+        self.all().find(|processor| processor.local_apic_id == apic_id)
+    }
+}
+
+/// The frequency of the ACPI PM Timer, in Hz.
+pub const PM_TIMER_FREQUENCY: u64 = 3_579_545;
+
+/// The number of times [`PmTimer::read`] tries to get three reads of the timer that are in order.
+pub const PM_TIMER_READ_ATTEMPTS: usize = 16;
+
 /// Information about the ACPI Power Management Timer (ACPI PM Timer).
 pub struct PmTimer {
     /// A generic address to the register block of ACPI PM Timer.
     pub base: GenericAddress,
     /// This field is `true` if the hardware supports 32-bit timer, and `false` if the hardware supports 24-bit timer.
     pub supports_32bit: bool,
+    /// If this is `true`, a single read of the timer may return an incorrect value while the counter is changing,
+    /// so [`PmTimer::read`] checks each value against the reads either side of it. This is `false` only if the WAET
+    /// says the timer is reliable.
+    pub needs_read_verification: bool,
 }
 
 impl PmTimer {
-    pub fn new(fadt: &Fadt) -> Result<Option<PmTimer>, AcpiError> {
+    pub fn new(fadt: &Fadt, waet: Option<&Waet>) -> Result<Option<PmTimer>, AcpiError> {
         match fadt.pm_timer_block()? {
             Some(base) => Ok(Some(PmTimer {
                 base,
                 supports_32bit: {fadt.flags}.pm_timer_is_32_bit(),
+                needs_read_verification: !matches!(waet, Some(waet) if waet.pm_timer_good()),
             })),
             None => Ok(None),
         }
     }
+
+    /// Read the timer's counter. This is masked to the width of the counter (24 or 32 bits). If
+    /// `needs_read_verification` is set, the counter is read three times, and the middle read is returned once
+    /// the three are in order, to avoid the bad values some chipsets return while the counter is changing. This is
+    /// tried up to `PM_TIMER_READ_ATTEMPTS` times, after which the middle read of the last attempt is returned
+    /// anyway, so a broken timer can't hang the caller.
+    ///
+    /// ### Safety
+    /// The timer's register block must be accessible with `handler`.
+    pub unsafe fn read<H>(&self, handler: &H) -> Result<u32, AcpiError>
+    where
+        H: AcpiHandler,
+    {
+        let read = || -> Result<u32, AcpiError> {
+            Ok((unsafe { self.base.read(handler) }? as u32) & self.counter_mask())
+        };
+
+        if !self.needs_read_verification {
+            return read();
+        }
+
+        let mut value = 0;
+        for _ in 0..PM_TIMER_READ_ATTEMPTS {
+            /*
+             * The reads are in order if they increase, allowing for the counter wrapping around between two of
+             * them. Going around the three reads (and back to the first), this means the value must only
+             * decrease once.
+             */
+            let (first, second, third) = (read()?, read()?, read()?);
+            let decreases = (first > second) as u8 + (second > third) as u8 + (third > first) as u8;
+            value = second;
+            if decreases <= 1 {
+                break;
+            }
+        }
+
+        Ok(value)
+    }
+
+    /// The time between two reads of the counter, in microseconds. This handles the counter wrapping around once
+    /// between the reads, but can't detect it wrapping more than once, which takes around 4.7 seconds for a 24-bit
+    /// counter.
+    pub fn elapsed_us(&self, start: u32, end: u32) -> u64 {
+        let ticks = end.wrapping_sub(start) & self.counter_mask();
+        ticks as u64 * 1_000_000 / PM_TIMER_FREQUENCY
+    }
+
+    fn counter_mask(&self) -> u32 {
+        if self.supports_32bit {
+            u32::MAX
+        } else {
+            0x00ff_ffff
+        }
+    }
 }
 
 /// `PlatformInfo` allows the collection of some basic information about the platform from some of the fixed-size
 /// tables in a nice way. It requires access to the `FADT` and `MADT`. It is the easiest way to get information
 /// about the processors and interrupt controllers on a platform.
+#[cfg(feature = "alloc")]
 pub struct PlatformInfo {
     pub power_profile: PowerProfile,
     pub interrupt_model: InterruptModel,
@@ -82,6 +188,7 @@ pub struct PlatformInfo {
      */
 }
 
+#[cfg(feature = "alloc")]
 impl PlatformInfo {
     pub fn new<H>(tables: &AcpiTables<H>) -> Result<PlatformInfo, AcpiError>
     where
@@ -99,8 +206,545 @@ impl PlatformInfo {
             Some(madt) => madt.parse_interrupt_model()?,
             None => (InterruptModel::Unknown, None),
         };
-        let pm_timer = PmTimer::new(&fadt)?;
+        let waet = unsafe { tables.get_sdt::<Waet>(crate::sdt::Signature::WAET)? };
+        let pm_timer = PmTimer::new(&fadt, waet.as_deref())?;
 
         Ok(PlatformInfo { power_profile, interrupt_model, processor_info, pm_timer })
     }
 }
+
+/// Identifies a processor in the `SRAT`. Processors with an APIC are identified by their local APIC (or x2APIC)
+/// ID, while processors with a GIC are identified by their ACPI processor UID, which matches the `processor_uid`
+/// of their GICC structure in the `MADT`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProcessorId {
+    LocalApic(u32),
+    AcpiProcessorUid(u32),
+}
+
+/// Associates a processor with the proximity domain it belongs to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProcessorAffinity {
+    pub processor: ProcessorId,
+    pub proximity_domain: u32,
+}
+
+/// `NumaInfo` collects the NUMA topology of the platform from the `SRAT`: which proximity domain each processor
+/// and range of memory belongs to. Only the entries that the firmware has marked as enabled are included.
+#[cfg(feature = "alloc")]
+pub struct NumaInfo {
+    pub processor_affinity: Vec<ProcessorAffinity>,
+    pub memory_affinity: Vec<MemoryAffinity>,
+    /// The proximity domains of devices other than processors that initiate memory transactions, such as GPUs.
+    pub generic_initiator_affinity: Vec<GenericInitiatorAffinity>,
+    /// The proximity domains of the GIC Interrupt Translation Services, on ARM platforms.
+    pub its_affinity: Vec<GicItsAffinity>,
+}
+
+#[cfg(feature = "alloc")]
+impl NumaInfo {
+    pub fn new<H>(tables: &AcpiTables<H>) -> Result<NumaInfo, AcpiError>
+    where
+        H: AcpiHandler,
+    {
+        let srat = unsafe {
+            tables
+                .get_sdt::<Srat>(crate::sdt::Signature::SRAT)?
+                .ok_or(AcpiError::TableMissing(crate::sdt::Signature::SRAT))?
+        };
+
+        let mut processor_affinity = Vec::new();
+        let mut memory_affinity = Vec::new();
+        let mut generic_initiator_affinity = Vec::new();
+        let mut its_affinity = Vec::new();
+        for entry in srat.entries() {
+            match entry {
+                SratEntry::LocalApicAffinity(entry) if entry.is_enabled() => {
+                    processor_affinity.push(ProcessorAffinity {
+                        processor: ProcessorId::LocalApic(entry.apic_id().into()),
+                        proximity_domain: entry.proximity_domain(),
+                    })
+                }
+                SratEntry::LocalX2ApicAffinity(entry) if entry.is_enabled() => {
+                    processor_affinity.push(ProcessorAffinity {
+                        processor: ProcessorId::LocalApic(entry.x2apic_id()),
+                        proximity_domain: entry.proximity_domain(),
+                    })
+                }
+                SratEntry::GiccAffinity(entry) if entry.is_enabled() => {
+                    processor_affinity.push(ProcessorAffinity {
+                        processor: ProcessorId::AcpiProcessorUid(entry.acpi_processor_uid()),
+                        proximity_domain: entry.proximity_domain(),
+                    })
+                }
+                SratEntry::GicItsAffinity(entry) => its_affinity.push(*entry),
+                SratEntry::MemoryAffinity(entry) if entry.is_enabled() => memory_affinity.push(*entry),
+                SratEntry::GenericInitiatorAffinity(entry) if entry.is_enabled() => {
+                    generic_initiator_affinity.push(*entry)
+                }
+                _ => (),
+            }
+        }
+
+        Ok(NumaInfo { processor_affinity, memory_affinity, generic_initiator_affinity, its_affinity })
+    }
+
+    /// The proximity domain of the processor with the local APIC (or x2APIC) ID `local_apic_id`. Returns `None` if
+    /// the `SRAT` doesn't have an enabled entry for it.
+    pub fn proximity_domain_of_apic(&self, local_apic_id: u32) -> Option<u32> {
+        self.proximity_domain_of(ProcessorId::LocalApic(local_apic_id))
+    }
+
+    /// The proximity domain of the processor with the ACPI processor UID `processor_uid`, on platforms with a GIC.
+    /// Returns `None` if the `SRAT` doesn't have an enabled entry for it.
+    pub fn proximity_domain_of_uid(&self, processor_uid: u32) -> Option<u32> {
+        self.proximity_domain_of(ProcessorId::AcpiProcessorUid(processor_uid))
+    }
+
+    fn proximity_domain_of(&self, processor: ProcessorId) -> Option<u32> {
+        self.processor_affinity
+            .iter()
+            .find(|affinity| affinity.processor == processor)
+            .map(|affinity| affinity.proximity_domain)
+    }
+
+    /// Pair each of the processors found in the `MADT` with its proximity domain, starting with the boot
+    /// processor. A processor that doesn't have an entry in the `SRAT` is paired with `None`.
+    pub fn processor_domains(&self, processor_info: &ProcessorInfo) -> Vec<(Processor, Option<u32>)> {
+        processor_info
+            .all()
+            .map(|&processor| {
+                /*
+                 * Only processors with a GIC have an MPIDR, and they're identified by their UID instead.
+                 */
+                let domain = match processor.mpidr {
+                    Some(_) => self.proximity_domain_of_uid(processor.processor_uid),
+                    None => self.proximity_domain_of_apic(processor.local_apic_id),
+                };
+                (processor, domain)
+            })
+            .collect()
+    }
+
+    /// Merge the ranges of memory in each proximity domain that are adjacent or overlap, as firmware often splits
+    /// a domain's memory into many entries. Produces `(proximity_domain, base_address, length)` for each merged
+    /// range, sorted by base address. Hot-pluggable ranges, which may not be present, are only included if
+    /// `include_hot_pluggable` is set.
+    pub fn coalesced_memory_ranges(&self, include_hot_pluggable: bool) -> Vec<(u32, u64, u64)> {
+        let mut ranges: Vec<(u32, u64, u64)> = self
+            .memory_affinity
+            .iter()
+            .filter(|affinity| include_hot_pluggable || !affinity.is_hot_pluggable())
+            .filter(|affinity| affinity.length() != 0)
+            .map(|affinity| (affinity.proximity_domain(), affinity.base_address(), affinity.length()))
+            .collect();
+        ranges.sort_unstable_by_key(|&(domain, base, _)| (domain, base));
+
+        let mut coalesced: Vec<(u32, u64, u64)> = Vec::with_capacity(ranges.len());
+        for (domain, base, length) in ranges {
+            if let Some((last_domain, last_base, last_length)) = coalesced.last_mut() {
+                let last_end = last_base.saturating_add(*last_length);
+                if *last_domain == domain && base <= last_end {
+                    *last_length = u64::max(last_end, base.saturating_add(length)) - *last_base;
+                    continue;
+                }
+            }
+            coalesced.push((domain, base, length));
+        }
+        coalesced.sort_unstable_by_key(|&(_, base, _)| base);
+        coalesced
+    }
+}
+
+/// Start an application processor through the Multiprocessor Wakeup mailbox described by the MADT. On x86, the
+/// processor with the local APIC ID `processor_id` is started in 64-bit mode, at `wakeup_vector`. Other
+/// architectures that use the mailbox (or need a different command) should use [`MpwkMailbox`] directly. The
+/// firmware is given `timeout_loops` checks of the mailbox to acknowledge the command.
+///
+/// ### Safety
+/// The processor will start executing at `wakeup_vector`, so the caller must make sure it's ready to do so.
+pub unsafe fn wakeup_aps<H>(
+    tables: &AcpiTables<H>,
+    handler: H,
+    processor_id: u32,
+    wakeup_vector: u64,
+    timeout_loops: u64,
+) -> Result<(), AcpiError>
+where
+    H: AcpiHandler,
+{
+    let madt = unsafe {
+        tables
+            .get_sdt::<Madt>(crate::sdt::Signature::MADT)?
+            .ok_or(AcpiError::TableMissing(crate::sdt::Signature::MADT))?
+    };
+    let mailbox = unsafe { MpwkMailbox::new(&handler, madt.get_mpwk_mailbox_addr()?) };
+    mailbox.send(processor_id, wakeup_vector, MpProtectedModeWakeupCommand::Wakeup as u16, timeout_loops)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "alloc")]
+    use crate::test_utils::{make_sdt, make_xsdt, TestMemory};
+    use crate::{
+        platform::address::{AccessSize, AddressSpace},
+        test_utils::TestHandler,
+    };
+
+    const PM_TIMER_ADDRESS: usize = 0xfed0_0808;
+
+    fn pm_timer(supports_32bit: bool) -> PmTimer {
+        PmTimer {
+            base: GenericAddress {
+                address_space: AddressSpace::SystemMemory,
+                bit_width: 32,
+                bit_offset: 0,
+                access_size: AccessSize::DWordAccess,
+                address: PM_TIMER_ADDRESS as u64,
+            },
+            supports_32bit,
+            needs_read_verification: true,
+        }
+    }
+
+    #[test]
+    fn test_read() {
+        let counter = [0xab12_3456u32];
+        let handler = TestHandler::with_mapping(PM_TIMER_ADDRESS, counter.as_ptr() as *const u8);
+
+        assert_eq!(unsafe { pm_timer(true).read(&handler) }.unwrap(), 0xab12_3456);
+        assert_eq!(unsafe { pm_timer(false).read(&handler) }.unwrap(), 0x12_3456);
+    }
+
+    /// A PM timer in the system I/O space whose counter goes backwards on every read, so that no three reads are
+    /// ever in order.
+    struct BrokenTimer {
+        counter: core::cell::Cell<u32>,
+        reads: core::cell::Cell<usize>,
+    }
+
+    impl crate::PortIo for BrokenTimer {
+        unsafe fn read_u8(&self, port: u16) -> u8 {
+            panic!("Unexpected 8-bit read from port {:#x}", port);
+        }
+
+        unsafe fn read_u16(&self, port: u16) -> u16 {
+            panic!("Unexpected 16-bit read from port {:#x}", port);
+        }
+
+        unsafe fn read_u32(&self, port: u16) -> u32 {
+            assert_eq!(port, 0x608);
+            self.reads.set(self.reads.get() + 1);
+            self.counter.set(self.counter.get() - 1);
+            self.counter.get()
+        }
+
+        unsafe fn write_u8(&self, port: u16, _value: u8) {
+            panic!("Unexpected 8-bit write to port {:#x}", port);
+        }
+
+        unsafe fn write_u16(&self, port: u16, _value: u16) {
+            panic!("Unexpected 16-bit write to port {:#x}", port);
+        }
+
+        unsafe fn write_u32(&self, port: u16, _value: u32) {
+            panic!("Unexpected 32-bit write to port {:#x}", port);
+        }
+    }
+
+    #[derive(Clone)]
+    struct BrokenTimerHandler<'a>(&'a BrokenTimer);
+
+    impl AcpiHandler for BrokenTimerHandler<'_> {
+        unsafe fn map_physical_region<T>(
+            &self,
+            physical_address: usize,
+            _size: usize,
+        ) -> crate::PhysicalMapping<Self, T> {
+            panic!("Unexpected mapping of {:#x}", physical_address);
+        }
+
+        fn unmap_physical_region<T>(_region: &crate::PhysicalMapping<Self, T>) {}
+
+        fn port_io(&self) -> Option<&dyn crate::PortIo> {
+            Some(self.0)
+        }
+    }
+
+    #[test]
+    fn test_read_gives_up_on_broken_timer() {
+        let timer = PmTimer {
+            base: GenericAddress {
+                address_space: AddressSpace::SystemIo,
+                bit_width: 32,
+                bit_offset: 0,
+                access_size: AccessSize::DWordAccess,
+                address: 0x608,
+            },
+            supports_32bit: true,
+            needs_read_verification: true,
+        };
+        let broken = BrokenTimer { counter: core::cell::Cell::new(1000), reads: core::cell::Cell::new(0) };
+
+        // The middle read of the last attempt is returned
+        let value = unsafe { timer.read(&BrokenTimerHandler(&broken)) }.unwrap();
+        assert_eq!(broken.reads.get(), 3 * PM_TIMER_READ_ATTEMPTS);
+        assert_eq!(value, 1000 - broken.reads.get() as u32 + 1);
+    }
+
+    #[test]
+    fn test_elapsed() {
+        let timer = pm_timer(true);
+        assert_eq!(timer.elapsed_us(1000, 1000), 0);
+        assert_eq!(timer.elapsed_us(0, 3_579_545), 1_000_000);
+        assert_eq!(timer.elapsed_us(0xffff_fff0, 0x0000_0010), 8);
+        assert_eq!(timer.elapsed_us(0, u32::MAX), 1_199_864_031);
+
+        let timer = pm_timer(false);
+        assert_eq!(timer.elapsed_us(100, 3_680), 1000);
+        // Wrapping around at 24 bits
+        assert_eq!(timer.elapsed_us(0x00ff_f000, 0x0000_0596), 1_543);
+        assert_eq!(timer.elapsed_us(0x00ff_ffff, 0x0000_0000), 0);
+    }
+
+    /// Build the tables of a small two-node machine: a FADT and DSDT, a MADT with a local APIC for each of three
+    /// processors, and a SRAT that puts the first two in domain 0 and the third (and a GPU) in domain 1. A fourth
+    /// SRAT processor entry, for a processor that isn't present, is marked as disabled.
+    #[cfg(feature = "alloc")]
+    fn make_numa_tables(memory: &mut TestMemory) -> usize {
+        let dsdt = memory.add(&make_sdt(b"DSDT", 2, &[]));
+        let mut fadt = [0; 240];
+        fadt[4..8].copy_from_slice(&(dsdt as u32).to_le_bytes());
+        fadt[9] = 4; // Enterprise server
+        let fadt = memory.add(&make_sdt(b"FACP", 6, &fadt));
+
+        let mut madt = Vec::new();
+        madt.extend_from_slice(&0xfee0_0000u32.to_le_bytes());
+        madt.extend_from_slice(&1u32.to_le_bytes());
+        for apic_id in 0..3 {
+            madt.extend_from_slice(&[0x0, 8, apic_id, apic_id]);
+            madt.extend_from_slice(&1u32.to_le_bytes());
+        }
+        let madt = memory.add(&make_sdt(b"APIC", 5, &madt));
+
+        let mut srat = Vec::new();
+        srat.extend_from_slice(&1u32.to_le_bytes());
+        srat.extend_from_slice(&[0; 8]);
+        for &(apic_id, proximity_domain, flags) in &[(0u8, 0u8, 1u32), (1, 0, 1), (2, 1, 1), (3, 1, 0)] {
+            srat.extend_from_slice(&[0x0, 16, proximity_domain, apic_id]);
+            srat.extend_from_slice(&flags.to_le_bytes());
+            srat.extend_from_slice(&[0; 8]);
+        }
+        for &(proximity_domain, base_address, length) in
+            &[(0u32, 0x0u64, 0x8000_0000u64), (1, 0x1_0000_0000, 0x8000_0000)]
+        {
+            srat.extend_from_slice(&[0x1, 40]);
+            srat.extend_from_slice(&proximity_domain.to_le_bytes());
+            srat.extend_from_slice(&[0; 2]);
+            srat.extend_from_slice(&base_address.to_le_bytes());
+            srat.extend_from_slice(&length.to_le_bytes());
+            srat.extend_from_slice(&[0; 4]);
+            srat.extend_from_slice(&1u32.to_le_bytes());
+            srat.extend_from_slice(&[0; 8]);
+        }
+        // A GPU at 0000:41:00.0, in domain 1
+        srat.extend_from_slice(&[0x5, 32, 0, 1]);
+        srat.extend_from_slice(&1u32.to_le_bytes());
+        srat.extend_from_slice(&[0, 0, 0x00, 0x41]);
+        srat.extend_from_slice(&[0; 12]);
+        srat.extend_from_slice(&1u32.to_le_bytes());
+        srat.extend_from_slice(&[0; 4]);
+        let srat = memory.add(&make_sdt(b"SRAT", 3, &srat));
+
+        memory.add(&make_xsdt(&[fadt, madt, srat]))
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_platform_and_numa_info() {
+        let mut memory = TestMemory::new(0x7fe0_0000);
+        let xsdt = make_numa_tables(&mut memory);
+        let tables = unsafe { AcpiTables::from_rsdt(memory.handler(), 2, xsdt) }.unwrap();
+
+        // Both can be built from the same tables, which are still usable afterwards
+        let platform_info = PlatformInfo::new(&tables).unwrap();
+        let numa_info = NumaInfo::new(&tables).unwrap();
+        assert!(unsafe { tables.get_sdt::<Srat>(crate::sdt::Signature::SRAT) }.unwrap().is_some());
+
+        assert_eq!(platform_info.power_profile, PowerProfile::EnterpriseServer);
+        assert_eq!(platform_info.processor_info.unwrap().application_processors.len(), 2);
+
+        assert_eq!(
+            numa_info.processor_affinity,
+            [
+                ProcessorAffinity { processor: ProcessorId::LocalApic(0), proximity_domain: 0 },
+                ProcessorAffinity { processor: ProcessorId::LocalApic(1), proximity_domain: 0 },
+                ProcessorAffinity { processor: ProcessorId::LocalApic(2), proximity_domain: 1 },
+            ]
+        );
+        assert_eq!(numa_info.proximity_domain_of_apic(2), Some(1));
+        assert_eq!(numa_info.proximity_domain_of_apic(3), None);
+        assert_eq!(numa_info.memory_affinity.len(), 2);
+        assert_eq!(numa_info.memory_affinity[1].proximity_domain(), 1);
+        assert_eq!(numa_info.memory_affinity[1].base_address(), 0x1_0000_0000);
+        assert_eq!(numa_info.generic_initiator_affinity.len(), 1);
+        assert_eq!(numa_info.generic_initiator_affinity[0].proximity_domain(), 1);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_processor_domains() {
+        let mut memory = TestMemory::new(0x7fe0_0000);
+        let xsdt = make_numa_tables(&mut memory);
+        let tables = unsafe { AcpiTables::from_rsdt(memory.handler(), 2, xsdt) }.unwrap();
+        let platform_info = PlatformInfo::new(&tables).unwrap();
+        let mut processor_info = platform_info.processor_info.unwrap();
+        let numa_info = NumaInfo::new(&tables).unwrap();
+
+        let domains: Vec<(u32, Option<u32>)> = numa_info
+            .processor_domains(&processor_info)
+            .iter()
+            .map(|(processor, domain)| (processor.local_apic_id, *domain))
+            .collect();
+        assert_eq!(domains, [(0, Some(0)), (1, Some(0)), (2, Some(1))]);
+
+        // A processor in the MADT that the SRAT doesn't mention
+        processor_info.application_processors.push(Processor {
+            processor_uid: 7,
+            local_apic_id: 7,
+            state: ProcessorState::WaitingForSipi,
+            is_ap: true,
+            online_capable: false,
+            mpidr: None,
+        });
+        let domains = numa_info.processor_domains(&processor_info);
+        assert_eq!(domains.len(), 4);
+        assert_eq!(domains[3].0.processor_uid, 7);
+        assert_eq!(domains[3].1, None);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_arm_numa_info() {
+        let mut srat = Vec::new();
+        srat.extend_from_slice(&1u32.to_le_bytes());
+        srat.extend_from_slice(&[0; 8]);
+        for &(processor_uid, proximity_domain, flags) in &[(0u32, 0u32, 1u32), (1, 1, 1), (2, 1, 0)] {
+            srat.extend_from_slice(&[0x3, 18]);
+            srat.extend_from_slice(&proximity_domain.to_le_bytes());
+            srat.extend_from_slice(&processor_uid.to_le_bytes());
+            srat.extend_from_slice(&flags.to_le_bytes());
+            srat.extend_from_slice(&0u32.to_le_bytes());
+        }
+        srat.extend_from_slice(&[0x4, 12]);
+        srat.extend_from_slice(&1u32.to_le_bytes());
+        srat.extend_from_slice(&[0; 2]);
+        srat.extend_from_slice(&0x20u32.to_le_bytes());
+
+        let mut memory = TestMemory::new(0x7fe0_0000);
+        let srat = memory.add(&make_sdt(b"SRAT", 3, &srat));
+        let xsdt = memory.add(&make_xsdt(&[srat]));
+        let tables = unsafe { AcpiTables::from_rsdt(memory.handler(), 2, xsdt) }.unwrap();
+        let numa_info = NumaInfo::new(&tables).unwrap();
+
+        assert_eq!(
+            numa_info.processor_affinity,
+            [
+                ProcessorAffinity { processor: ProcessorId::AcpiProcessorUid(0), proximity_domain: 0 },
+                ProcessorAffinity { processor: ProcessorId::AcpiProcessorUid(1), proximity_domain: 1 },
+            ]
+        );
+        assert_eq!(numa_info.its_affinity.len(), 1);
+        assert_eq!(numa_info.its_affinity[0].its_id(), 0x20);
+        assert_eq!(numa_info.its_affinity[0].proximity_domain(), 1);
+
+        // The GIC processors' CPU interface numbers shouldn't be mistaken for APIC IDs
+        let processor = |processor_uid, local_apic_id, is_ap| Processor {
+            processor_uid,
+            local_apic_id,
+            state: if is_ap { ProcessorState::WaitingForSipi } else { ProcessorState::Running },
+            is_ap,
+            online_capable: false,
+            mpidr: Some(local_apic_id as u64),
+        };
+        let processor_info = ProcessorInfo {
+            boot_processor: processor(0, 1, false),
+            application_processors: alloc::vec![processor(1, 0, true)],
+        };
+        let domains: Vec<(u32, Option<u32>)> = numa_info
+            .processor_domains(&processor_info)
+            .iter()
+            .map(|(processor, domain)| (processor.processor_uid, *domain))
+            .collect();
+        assert_eq!(domains, [(0, Some(0)), (1, Some(1))]);
+        assert_eq!(numa_info.proximity_domain_of_apic(0), None);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_find_processor() {
+        let processor = |processor_uid, local_apic_id, is_ap| Processor {
+            processor_uid,
+            local_apic_id,
+            state: if is_ap { ProcessorState::WaitingForSipi } else { ProcessorState::Running },
+            is_ap,
+            online_capable: false,
+            mpidr: None,
+        };
+        let processor_info = ProcessorInfo {
+            boot_processor: processor(1, 0, false),
+            application_processors: alloc::vec![processor(2, 4, true), processor(3, 2, true)],
+        };
+
+        let uids: Vec<u32> = processor_info.all().map(|processor| processor.processor_uid).collect();
+        assert_eq!(uids, [1, 2, 3]);
+
+        assert_eq!(processor_info.find_by_uid(1), Some(&processor_info.boot_processor));
+        assert_eq!(processor_info.find_by_uid(3).unwrap().local_apic_id, 2);
+        assert_eq!(processor_info.find_by_uid(0), None);
+
+        assert_eq!(processor_info.find_by_apic_id(0), Some(&processor_info.boot_processor));
+        assert_eq!(processor_info.find_by_apic_id(4).unwrap().processor_uid, 2);
+        assert_eq!(processor_info.find_by_apic_id(1), None);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_coalesced_memory_ranges() {
+        let numa_info = NumaInfo {
+            processor_affinity: Vec::new(),
+            memory_affinity: alloc::vec![
+                MemoryAffinity::new(0, 0x10_0000, 0x7ff0_0000, true, false, false),
+                MemoryAffinity::new(1, 0x1_0000_0000, 0x8000_0000, true, false, false),
+                MemoryAffinity::new(0, 0x0, 0xa_0000, true, false, false),
+                MemoryAffinity::new(0, 0xa_0000, 0x6_0000, true, false, false),
+                // Adjacent to domain 0's memory, but in a different domain
+                MemoryAffinity::new(1, 0x8000_0000, 0x1000_0000, true, false, false),
+                MemoryAffinity::new(1, 0x1_8000_0000, 0x8000_0000, true, true, false),
+            ],
+            generic_initiator_affinity: Vec::new(),
+            its_affinity: Vec::new(),
+        };
+
+        assert_eq!(
+            numa_info.coalesced_memory_ranges(false),
+            [(0, 0x0, 0x8000_0000), (1, 0x8000_0000, 0x1000_0000), (1, 0x1_0000_0000, 0x8000_0000)]
+        );
+        assert_eq!(
+            numa_info.coalesced_memory_ranges(true),
+            [(0, 0x0, 0x8000_0000), (1, 0x8000_0000, 0x1000_0000), (1, 0x1_0000_0000, 0x1_0000_0000)]
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_numa_info_without_srat() {
+        let mut memory = TestMemory::new(0x7fe0_0000);
+        let waet = memory.add(&make_sdt(b"WAET", 1, &0u32.to_le_bytes()));
+        let xsdt = memory.add(&make_xsdt(&[waet]));
+        let tables = unsafe { AcpiTables::from_rsdt(memory.handler(), 2, xsdt) }.unwrap();
+
+        assert!(matches!(NumaInfo::new(&tables), Err(AcpiError::TableMissing(crate::sdt::Signature::SRAT))));
+    }
+}
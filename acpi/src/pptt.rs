@@ -0,0 +1,528 @@
+use crate::{
+    check_entry_length,
+    sdt::{SdtHeader, Signature},
+    AcpiTable,
+};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use bit_field::BitField;
+use core::{mem, ptr};
+
+/// Represents the Processor Properties Topology Table (PPTT). This describes the topology of the processors on the
+/// platform as a tree of processor hierarchy nodes (e.g. packages containing clusters containing cores containing
+/// threads), and the caches that are private to each level of the tree.
+///
+/// Structures refer to each other by their offset from the start of the table.
+#[repr(C, packed)]
+pub struct Pptt {
+    header: SdtHeader,
+}
+
+impl AcpiTable for Pptt {
+    fn header(&self) -> &SdtHeader {
+        &self.header
+    }
+}
+
+impl Pptt {
+    pub fn nodes(&self) -> PpttNodeIter<'_> {
+        PpttNodeIter { pptt: self, offset: mem::size_of::<Pptt>() as u32 }
+    }
+
+    /// Get the structure that starts `offset` bytes into the table.
+    pub fn node_at(&self, offset: u32) -> Option<PpttNode<'_>> {
+        let table_length = self.header.length;
+        if offset < mem::size_of::<Pptt>() as u32
+            || table_length.saturating_sub(offset) < mem::size_of::<PpttStructureHeader>() as u32
+        {
+            return None;
+        }
+
+        let pointer = unsafe { (self as *const Pptt as *const u8).add(offset as usize) };
+        let header = unsafe { *(pointer as *const PpttStructureHeader) };
+        let minimum_length = match header.structure_type {
+            PROCESSOR_HIERARCHY_NODE_TYPE => mem::size_of::<ProcessorHierarchyNode>(),
+            CACHE_TYPE => mem::size_of::<CacheType>(),
+            _ => mem::size_of::<PpttStructureHeader>(),
+        };
+        if !check_entry_length(
+            Signature::PPTT,
+            header.structure_type.into(),
+            header.length.into(),
+            minimum_length,
+            table_length - offset,
+        ) {
+            return None;
+        }
+
+        match header.structure_type {
+            PROCESSOR_HIERARCHY_NODE_TYPE => {
+                Some(PpttNode::Processor(unsafe { &*(pointer as *const ProcessorHierarchyNode) }))
+            }
+            CACHE_TYPE => Some(PpttNode::Cache(unsafe { &*(pointer as *const CacheType) })),
+            _ => None,
+        }
+    }
+
+    /// Find the topology of the processor with the given ACPI Processor UID (as found in its MADT entry), by
+    /// walking up the tree from its leaf node. Returns `None` if the processor can't be found, or if the tree is
+    /// malformed (e.g. it refers to structures that don't exist, or contains a cycle).
+    #[cfg(feature = "alloc")]
+    pub fn topology_for(&self, acpi_processor_uid: u32) -> Option<CpuTopology> {
+        let (leaf_offset, leaf) = self
+            .processor_nodes()
+            .find(|(_, node)| node.is_leaf() && node.acpi_processor_id() == Some(acpi_processor_uid))?;
+
+        /*
+         * Collect the chain of nodes from the leaf to the root of the tree. Each node in a well-formed tree can
+         * only appear once, so we can detect cycles by checking whether we've already visited a node.
+         */
+        let mut chain: Vec<(u32, &ProcessorHierarchyNode)> = Vec::new();
+        chain.push((leaf_offset, leaf));
+        while let Some(parent_offset) = chain.last().unwrap().1.parent() {
+            if chain.iter().any(|&(offset, _)| offset == parent_offset) {
+                return None;
+            }
+
+            match self.node_at(parent_offset) {
+                Some(PpttNode::Processor(parent)) => chain.push((parent_offset, parent)),
+                _ => return None,
+            }
+        }
+
+        let node_id = |(offset, node): (u32, &ProcessorHierarchyNode)| node.acpi_processor_id().unwrap_or(offset);
+
+        let (thread_id, core_index) =
+            if leaf.is_thread() && chain.len() > 1 { (Some(acpi_processor_uid), 1) } else { (None, 0) };
+        let package_index =
+            chain.iter().position(|(_, node)| node.is_physical_package()).unwrap_or(chain.len() - 1);
+        let package_index = usize::max(package_index, core_index);
+        let cluster_id = if package_index > core_index + 1 { Some(node_id(chain[core_index + 1])) } else { None };
+
+        Some(CpuTopology {
+            thread_id,
+            core_id: node_id(chain[core_index]),
+            cluster_id,
+            package_id: node_id(chain[package_index]),
+            caches: self.caches_for(&chain),
+        })
+    }
+
+    /// Collect the caches used by a processor, given the chain of nodes from its leaf node to the root of the tree.
+    /// The caches private to each node are found directly, and the caches beyond them by following each cache's
+    /// next level of cache. Caches that have already been seen end the walk, so cycles between caches can't cause
+    /// it to loop forever.
+    #[cfg(feature = "alloc")]
+    fn caches_for(&self, chain: &[(u32, &ProcessorHierarchyNode)]) -> Vec<CacheInfo> {
+        let mut caches: Vec<(u32, CacheInfo)> = Vec::new();
+
+        for (_, node) in chain {
+            /*
+             * The caches private to a node are the next level up from every cache seen so far.
+             */
+            let base_level = caches.iter().map(|(_, cache)| cache.level).max().unwrap_or(0);
+
+            for resource in node.private_resources() {
+                let mut next = Some(resource);
+                let mut level = base_level + 1;
+
+                while let Some(offset) = next {
+                    if caches.iter().any(|&(seen, _)| seen == offset) {
+                        break;
+                    }
+
+                    let cache = match self.node_at(offset) {
+                        Some(PpttNode::Cache(cache)) => cache,
+                        _ => break,
+                    };
+                    caches.push((offset, CacheInfo::new(cache, level)));
+                    next = cache.next_level_of_cache();
+                    level = level.saturating_add(1);
+                }
+            }
+        }
+
+        caches.into_iter().map(|(_, cache)| cache).collect()
+    }
+
+    #[cfg(feature = "alloc")]
+    fn processor_nodes(&self) -> impl Iterator<Item = (u32, &ProcessorHierarchyNode)> + '_ {
+        self.nodes().filter_map(move |node| match node {
+            PpttNode::Processor(node) => {
+                let offset = node as *const ProcessorHierarchyNode as usize - self as *const Pptt as usize;
+                Some((offset as u32, node))
+            }
+            _ => None,
+        })
+    }
+
+    /// Get the length of the structure at `offset`, if it fits in the table.
+    fn structure_length_at(&self, offset: u32) -> Option<u32> {
+        let table_length = self.header.length;
+        if table_length.saturating_sub(offset) < mem::size_of::<PpttStructureHeader>() as u32 {
+            return None;
+        }
+
+        let header =
+            unsafe { *((self as *const Pptt as *const u8).add(offset as usize) as *const PpttStructureHeader) };
+        if !check_entry_length(
+            Signature::PPTT,
+            header.structure_type.into(),
+            header.length.into(),
+            mem::size_of::<PpttStructureHeader>(),
+            table_length - offset,
+        ) {
+            return None;
+        }
+
+        Some(header.length.into())
+    }
+}
+
+const PROCESSOR_HIERARCHY_NODE_TYPE: u8 = 0;
+const CACHE_TYPE: u8 = 1;
+
+pub struct PpttNodeIter<'a> {
+    pptt: &'a Pptt,
+    offset: u32,
+}
+
+impl<'a> Iterator for PpttNodeIter<'a> {
+    type Item = PpttNode<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let offset = self.offset;
+            self.offset += self.pptt.structure_length_at(offset)?;
+            let node = self.pptt.node_at(offset);
+
+            /*
+             * Other structure types are reserved, or are the deprecated ID structure. We skip them.
+             */
+            if let Some(node) = node {
+                return Some(node);
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum PpttNode<'a> {
+    Processor(&'a ProcessorHierarchyNode),
+    Cache(&'a CacheType),
+}
+
+#[derive(Clone, Copy)]
+#[repr(C, packed)]
+struct PpttStructureHeader {
+    structure_type: u8,
+    length: u8,
+    _reserved: u16,
+}
+
+/// A node in the processor topology tree. Leaf nodes are the processors themselves (either cores, or threads of a
+/// core), and the other nodes are the groupings they belong to (e.g. clusters and packages).
+#[repr(C, packed)]
+pub struct ProcessorHierarchyNode {
+    header: PpttStructureHeader,
+    flags: u32,
+    parent: u32,
+    acpi_processor_id: u32,
+    num_private_resources: u32,
+    // Followed by `num_private_resources` offsets of the structures private to this node
+}
+
+impl ProcessorHierarchyNode {
+    pub fn is_physical_package(&self) -> bool {
+        { self.flags }.get_bit(0)
+    }
+
+    /// For leaf nodes, the ACPI Processor UID of the processor. For other nodes, an identifier that matches the
+    /// `_UID` of a processor container in the namespace. Returns `None` if the node doesn't have a valid ID.
+    pub fn acpi_processor_id(&self) -> Option<u32> {
+        if { self.flags }.get_bit(1) {
+            Some(self.acpi_processor_id)
+        } else {
+            None
+        }
+    }
+
+    pub fn is_thread(&self) -> bool {
+        { self.flags }.get_bit(2)
+    }
+
+    pub fn is_leaf(&self) -> bool {
+        { self.flags }.get_bit(3)
+    }
+
+    /// If true, all of the children of this node have an identical implementation.
+    pub fn has_identical_implementation(&self) -> bool {
+        { self.flags }.get_bit(4)
+    }
+
+    /// The offset of the parent of this node, or `None` if it is the root of its tree.
+    pub fn parent(&self) -> Option<u32> {
+        match self.parent {
+            0 => None,
+            parent => Some(parent),
+        }
+    }
+
+    /// Iterate over the offsets of the structures (e.g. caches) private to this node.
+    pub fn private_resources(&self) -> impl Iterator<Item = u32> + '_ {
+        let available =
+            (self.header.length as usize - mem::size_of::<ProcessorHierarchyNode>()) / mem::size_of::<u32>();
+        let resources = unsafe {
+            (self as *const ProcessorHierarchyNode as *const u8).add(mem::size_of::<ProcessorHierarchyNode>())
+                as *const u32
+        };
+
+        (0..usize::min(self.num_private_resources as usize, available))
+            .map(move |i| unsafe { ptr::read_unaligned(resources.add(i)) })
+    }
+}
+
+/// Describes a cache, which may be private to a processor hierarchy node or be the next level of cache from
+/// another cache. Each property of the cache is only reported if it is valid.
+#[repr(C, packed)]
+pub struct CacheType {
+    header: PpttStructureHeader,
+    flags: u32,
+    next_level_of_cache: u32,
+    size: u32,
+    number_of_sets: u32,
+    associativity: u8,
+    attributes: u8,
+    line_size: u16,
+    /*
+     * From revision 3 of the table, this is followed by a `u32` cache ID.
+     */
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CacheKind {
+    Data,
+    Instruction,
+    Unified,
+}
+
+impl CacheType {
+    /// The offset of the next level of cache, or `None` if this is the last level.
+    pub fn next_level_of_cache(&self) -> Option<u32> {
+        match self.next_level_of_cache {
+            0 => None,
+            offset => Some(offset),
+        }
+    }
+
+    /// The size of the cache, in bytes.
+    pub fn size(&self) -> Option<u32> {
+        self.valid_field(0, self.size)
+    }
+
+    pub fn number_of_sets(&self) -> Option<u32> {
+        self.valid_field(1, self.number_of_sets)
+    }
+
+    pub fn associativity(&self) -> Option<u8> {
+        self.valid_field(2, self.associativity)
+    }
+
+    pub fn kind(&self) -> Option<CacheKind> {
+        self.valid_field(4, self.attributes).map(|attributes| match attributes.get_bits(2..4) {
+            0b00 => CacheKind::Data,
+            0b01 => CacheKind::Instruction,
+            _ => CacheKind::Unified,
+        })
+    }
+
+    /// If true, the cache is write-through. If false, it is write-back.
+    pub fn is_write_through(&self) -> Option<bool> {
+        self.valid_field(5, self.attributes).map(|attributes| attributes.get_bit(4))
+    }
+
+    /// The size of each line of the cache, in bytes.
+    pub fn line_size(&self) -> Option<u16> {
+        self.valid_field(6, self.line_size)
+    }
+
+    /// A unique, non-zero identifier for the cache. This is only present from revision 3 of the table.
+    pub fn cache_id(&self) -> Option<u32> {
+        if (self.header.length as usize) < mem::size_of::<CacheType>() + mem::size_of::<u32>() {
+            return None;
+        }
+
+        let cache_id = unsafe {
+            ptr::read_unaligned(
+                (self as *const CacheType as *const u8).add(mem::size_of::<CacheType>()) as *const u32
+            )
+        };
+        self.valid_field(7, cache_id)
+    }
+
+    fn valid_field<T>(&self, flag: usize, value: T) -> Option<T> {
+        if { self.flags }.get_bit(flag) {
+            Some(value)
+        } else {
+            None
+        }
+    }
+}
+
+/// The position of a processor in the topology, and the caches it uses. IDs that aren't provided by the PPTT are
+/// replaced by the offset of their node in the table, which is unique but otherwise meaningless.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CpuTopology {
+    /// If the processor is a thread of a core, its ACPI Processor UID.
+    pub thread_id: Option<u32>,
+    pub core_id: u32,
+    /// The ID of the node between the core and the package, if there is one.
+    pub cluster_id: Option<u32>,
+    pub package_id: u32,
+    /// The caches used by the processor, starting from the caches closest to it.
+    pub caches: Vec<CacheInfo>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CacheInfo {
+    /// The level of the cache, with `1` being the level closest to the processor.
+    pub level: u8,
+    pub kind: Option<CacheKind>,
+    pub size: Option<u32>,
+    pub line_size: Option<u16>,
+    pub number_of_sets: Option<u32>,
+    pub associativity: Option<u8>,
+}
+
+impl CacheInfo {
+    #[cfg(feature = "alloc")]
+    fn new(cache: &CacheType, level: u8) -> CacheInfo {
+        CacheInfo {
+            level,
+            kind: cache.kind(),
+            size: cache.size(),
+            line_size: cache.line_size(),
+            number_of_sets: cache.number_of_sets(),
+            associativity: cache.associativity(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{make_sdt, table_from_bytes};
+    use alloc::vec::Vec;
+
+    const THREAD_FLAGS: u32 = 0b1110;
+    const DATA: u8 = 0b00 << 2;
+    const INSTRUCTION: u8 = 0b01 << 2;
+    const UNIFIED: u8 = 0b10 << 2;
+
+    fn processor_node(flags: u32, parent: u32, acpi_processor_id: u32, resources: &[u32]) -> Vec<u8> {
+        let mut node = Vec::new();
+        node.extend_from_slice(&[0, 20 + 4 * resources.len() as u8, 0, 0]);
+        for field in [flags, parent, acpi_processor_id, resources.len() as u32].iter().chain(resources) {
+            node.extend_from_slice(&field.to_le_bytes());
+        }
+        node
+    }
+
+    fn cache(next_level_of_cache: u32, size: u32, attributes: u8) -> Vec<u8> {
+        let mut cache = Vec::new();
+        cache.extend_from_slice(&[1, 24, 0, 0]);
+        cache.extend_from_slice(&0b0101_0111u32.to_le_bytes());
+        cache.extend_from_slice(&next_level_of_cache.to_le_bytes());
+        cache.extend_from_slice(&size.to_le_bytes());
+        cache.extend_from_slice(&(size / 64 / 8).to_le_bytes());
+        cache.extend_from_slice(&[8, attributes]);
+        cache.extend_from_slice(&64u16.to_le_bytes());
+        cache
+    }
+
+    /// Builds a PPTT for two packages, each with a shared L3, and two cores with private L1 and L2 caches and two
+    /// threads each. Returns the table, and the offset of each core node.
+    fn make_two_socket_pptt() -> (Vec<u8>, Vec<u32>) {
+        let mut body = Vec::new();
+        let mut core_offsets = Vec::new();
+        let offset = |body: &Vec<u8>| (mem::size_of::<SdtHeader>() + body.len()) as u32;
+
+        for package in 0..2 {
+            let l3 = offset(&body);
+            body.extend(cache(0, 32 * 1024 * 1024, UNIFIED));
+            let package_offset = offset(&body);
+            body.extend(processor_node(0b11, 0, package, &[l3]));
+
+            for core in 0..2 {
+                let l2 = offset(&body);
+                body.extend(cache(0, 1024 * 1024, UNIFIED));
+                let l1d = offset(&body);
+                body.extend(cache(l2, 32 * 1024, DATA));
+                let l1i = offset(&body);
+                body.extend(cache(l2, 32 * 1024, INSTRUCTION));
+
+                let core_offset = offset(&body);
+                core_offsets.push(core_offset);
+                body.extend(processor_node(0, package_offset, 0, &[l1d, l1i]));
+
+                for thread in 0..2 {
+                    body.extend(processor_node(THREAD_FLAGS, core_offset, package * 4 + core * 2 + thread, &[]));
+                }
+            }
+        }
+
+        (make_sdt(b"PPTT", 2, &body), core_offsets)
+    }
+
+    #[test]
+    fn test_nodes() {
+        let (table, _) = make_two_socket_pptt();
+        let pptt = table_from_bytes::<Pptt>(&table);
+
+        let processors = pptt.nodes().filter(|node| matches!(node, PpttNode::Processor(_))).count();
+        let caches = pptt.nodes().filter(|node| matches!(node, PpttNode::Cache(_))).count();
+        assert_eq!((processors, caches), (14, 14));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_topology_for() {
+        let (table, core_offsets) = make_two_socket_pptt();
+        let pptt = table_from_bytes::<Pptt>(&table);
+        let topology = pptt.topology_for(5).unwrap();
+
+        assert_eq!(topology.thread_id, Some(5));
+        assert_eq!(topology.core_id, core_offsets[2]);
+        assert_eq!(topology.cluster_id, None);
+        assert_eq!(topology.package_id, 1);
+
+        let caches: Vec<(u8, Option<CacheKind>, Option<u32>)> =
+            topology.caches.iter().map(|cache| (cache.level, cache.kind, cache.size)).collect();
+        assert_eq!(
+            caches,
+            [
+                (1, Some(CacheKind::Data), Some(32 * 1024)),
+                (2, Some(CacheKind::Unified), Some(1024 * 1024)),
+                (1, Some(CacheKind::Instruction), Some(32 * 1024)),
+                (3, Some(CacheKind::Unified), Some(32 * 1024 * 1024)),
+            ]
+        );
+        assert!(topology.caches.iter().all(|cache| cache.line_size == Some(64)));
+
+        assert_eq!(pptt.topology_for(8), None);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_parent_cycle() {
+        let mut body = Vec::new();
+        body.extend(processor_node(0, 56, 0, &[]));
+        body.extend(processor_node(0, 36, 0, &[]));
+        body.extend(processor_node(THREAD_FLAGS, 36, 0, &[]));
+        let table = make_sdt(b"PPTT", 2, &body);
+        let pptt = table_from_bytes::<Pptt>(&table);
+
+        assert_eq!(pptt.topology_for(0), None);
+    }
+}
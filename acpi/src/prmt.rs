@@ -0,0 +1,292 @@
+use crate::{
+    entry_as,
+    sdt::{SdtHeader, Signature},
+    AcpiTable,
+    EntryIter,
+};
+use core::mem;
+
+/// Represents the Platform Runtime Mechanism Table (PRMT). This describes the PRM modules provided by the firmware,
+/// each of which contains handlers that the OS can call at runtime (e.g. from AML, through a `PlatformRtMechanism`
+/// operation region) instead of entering System Management Mode.
+#[repr(C, packed)]
+pub struct Prmt {
+    header: SdtHeader,
+    platform_guid: [u8; 16],
+    module_info_offset: u32,
+    module_info_count: u32,
+}
+
+impl AcpiTable for Prmt {
+    fn header(&self) -> &SdtHeader {
+        &self.header
+    }
+}
+
+impl Prmt {
+    /// The GUID identifying the platform, in its in-memory form.
+    pub fn platform_guid(&self) -> [u8; 16] {
+        self.platform_guid
+    }
+
+    pub fn modules(&self) -> PrmModuleIter<'_> {
+        /*
+         * The modules can be anywhere in the table after the fixed fields, so an offset outside of that means the
+         * table is malformed. We treat it as if there are no modules.
+         */
+        let offset = self.module_info_offset;
+        let remaining_length = if offset as usize >= mem::size_of::<Prmt>() && offset <= self.header.length {
+            self.header.length - offset
+        } else {
+            0
+        };
+        PrmModuleIter {
+            entries: unsafe {
+                EntryIter::new(
+                    Signature::PRMT,
+                    (self as *const Prmt as *const u8).wrapping_add(offset as usize),
+                    remaining_length,
+                    |_| mem::size_of::<PrmModule>(),
+                )
+            },
+            remaining_modules: self.module_info_count,
+        }
+    }
+}
+
+pub struct PrmModuleIter<'a> {
+    entries: EntryIter<'a, PrmModule>,
+    remaining_modules: u32,
+}
+
+impl<'a> Iterator for PrmModuleIter<'a> {
+    type Item = &'a PrmModule;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining_modules == 0 {
+            return None;
+        }
+
+        let (_, module) = self.entries.next()?;
+        self.remaining_modules -= 1;
+        Some(unsafe { entry_as(module) })
+    }
+}
+
+/// Describes a PRM module, which is a collection of PRM handlers that are loaded and updated together.
+#[repr(C, packed)]
+pub struct PrmModule {
+    revision: u16,
+    length: u16,
+    module_guid: [u8; 16],
+    major_revision: u16,
+    minor_revision: u16,
+    handler_info_count: u16,
+    handler_info_offset: u32,
+    runtime_mmio_ranges: u64,
+    // The handler info structures follow, at `handler_info_offset` from the start of this structure
+}
+
+impl crate::EntryHeader for PrmModule {
+    fn entry_type(&self) -> u16 {
+        // Modules and handlers don't have a type, so we distinguish them by their position in the table
+        0
+    }
+
+    fn length(&self) -> u32 {
+        self.length.into()
+    }
+}
+
+impl PrmModule {
+    /// The GUID identifying the module, in its in-memory form.
+    pub fn module_guid(&self) -> [u8; 16] {
+        self.module_guid
+    }
+
+    /// The revision of the module, as `(major, minor)`.
+    pub fn module_revision(&self) -> (u16, u16) {
+        (self.major_revision, self.minor_revision)
+    }
+
+    /// The physical address of the structure describing the MMIO ranges that the module's handlers access, which
+    /// the OS must map before calling them. Returns `None` if the handlers don't access any MMIO ranges.
+    pub fn runtime_mmio_ranges_address(&self) -> Option<u64> {
+        match self.runtime_mmio_ranges {
+            0 => None,
+            address => Some(address),
+        }
+    }
+
+    pub fn handlers(&self) -> PrmHandlerIter<'_> {
+        let offset = self.handler_info_offset;
+        let remaining_length = if offset as usize >= mem::size_of::<PrmModule>() && offset <= self.length as u32 {
+            self.length as u32 - offset
+        } else {
+            0
+        };
+        PrmHandlerIter {
+            entries: unsafe {
+                EntryIter::new(
+                    Signature::PRMT,
+                    (self as *const PrmModule as *const u8).wrapping_add(offset as usize),
+                    remaining_length,
+                    |_| mem::size_of::<PrmHandler>(),
+                )
+            },
+            remaining_handlers: self.handler_info_count,
+        }
+    }
+}
+
+pub struct PrmHandlerIter<'a> {
+    entries: EntryIter<'a, PrmHandler>,
+    remaining_handlers: u16,
+}
+
+impl<'a> Iterator for PrmHandlerIter<'a> {
+    type Item = &'a PrmHandler;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining_handlers == 0 {
+            return None;
+        }
+
+        let (_, handler) = self.entries.next()?;
+        self.remaining_handlers -= 1;
+        Some(unsafe { entry_as(handler) })
+    }
+}
+
+/// Describes a PRM handler, which is a function within a PRM module that the OS can call.
+#[repr(C, packed)]
+pub struct PrmHandler {
+    revision: u16,
+    length: u16,
+    handler_guid: [u8; 16],
+    handler_address: u64,
+    static_data_buffer_address: u64,
+    acpi_parameter_buffer_address: u64,
+}
+
+impl crate::EntryHeader for PrmHandler {
+    fn entry_type(&self) -> u16 {
+        1
+    }
+
+    fn length(&self) -> u32 {
+        self.length.into()
+    }
+}
+
+impl PrmHandler {
+    /// The GUID identifying the handler, in its in-memory form. This is how AML refers to the handler.
+    pub fn handler_guid(&self) -> [u8; 16] {
+        self.handler_guid
+    }
+
+    /// The physical address of the handler's entry point.
+    pub fn handler_address(&self) -> u64 {
+        self.handler_address
+    }
+
+    /// The physical address of the static data buffer that is passed to the handler. Returns `None` if the handler
+    /// doesn't have one.
+    pub fn static_data_buffer_address(&self) -> Option<u64> {
+        match self.static_data_buffer_address {
+            0 => None,
+            address => Some(address),
+        }
+    }
+
+    /// The physical address of the buffer that AML uses to pass parameters to the handler. Returns `None` if the
+    /// handler doesn't have one.
+    pub fn acpi_parameter_buffer_address(&self) -> Option<u64> {
+        match self.acpi_parameter_buffer_address {
+            0 => None,
+            address => Some(address),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{make_sdt, table_from_bytes};
+    use alloc::vec::Vec;
+
+    fn handler(guid_byte: u8, address: u64, static_data: u64, acpi_parameters: u64) -> Vec<u8> {
+        let mut handler = Vec::new();
+        handler.extend_from_slice(&1u16.to_le_bytes());
+        handler.extend_from_slice(&44u16.to_le_bytes());
+        handler.extend_from_slice(&[guid_byte; 16]);
+        handler.extend_from_slice(&address.to_le_bytes());
+        handler.extend_from_slice(&static_data.to_le_bytes());
+        handler.extend_from_slice(&acpi_parameters.to_le_bytes());
+        handler
+    }
+
+    #[test]
+    fn test_modules() {
+        let mut body = Vec::new();
+        body.extend_from_slice(&[0xaa; 16]);
+        body.extend_from_slice(&60u32.to_le_bytes());
+        body.extend_from_slice(&1u32.to_le_bytes());
+
+        body.extend_from_slice(&1u16.to_le_bytes());
+        body.extend_from_slice(&(38u16 + 2 * 44).to_le_bytes());
+        body.extend_from_slice(&[0xbb; 16]);
+        body.extend_from_slice(&1u16.to_le_bytes());
+        body.extend_from_slice(&2u16.to_le_bytes());
+        body.extend_from_slice(&2u16.to_le_bytes());
+        body.extend_from_slice(&38u32.to_le_bytes());
+        body.extend_from_slice(&0x7f00_0000u64.to_le_bytes());
+        body.extend(handler(0x11, 0x7e00_1000, 0x7e00_8000, 0));
+        body.extend(handler(0x22, 0x7e00_2000, 0, 0x7e00_9000));
+
+        let table = make_sdt(b"PRMT", 0, &body);
+        let prmt = table_from_bytes::<Prmt>(&table);
+        assert_eq!(prmt.platform_guid(), [0xaa; 16]);
+
+        let modules: Vec<&PrmModule> = prmt.modules().collect();
+        assert_eq!(modules.len(), 1);
+        assert_eq!(modules[0].module_guid(), [0xbb; 16]);
+        assert_eq!(modules[0].module_revision(), (1, 2));
+        assert_eq!(modules[0].runtime_mmio_ranges_address(), Some(0x7f00_0000));
+
+        let handlers: Vec<&PrmHandler> = modules[0].handlers().collect();
+        assert_eq!(handlers.len(), 2);
+        assert_eq!(handlers[0].handler_guid(), [0x11; 16]);
+        assert_eq!(handlers[0].handler_address(), 0x7e00_1000);
+        assert_eq!(handlers[0].static_data_buffer_address(), Some(0x7e00_8000));
+        assert_eq!(handlers[0].acpi_parameter_buffer_address(), None);
+        assert_eq!(handlers[1].handler_guid(), [0x22; 16]);
+        assert_eq!(handlers[1].static_data_buffer_address(), None);
+        assert_eq!(handlers[1].acpi_parameter_buffer_address(), Some(0x7e00_9000));
+    }
+
+    #[test]
+    fn test_handlers_outside_module() {
+        let mut body = Vec::new();
+        body.extend_from_slice(&[0; 16]);
+        body.extend_from_slice(&60u32.to_le_bytes());
+        body.extend_from_slice(&1u32.to_le_bytes());
+
+        // The module claims two handlers, but its length only covers one
+        body.extend_from_slice(&1u16.to_le_bytes());
+        body.extend_from_slice(&(38u16 + 44).to_le_bytes());
+        body.extend_from_slice(&[0; 16]);
+        body.extend_from_slice(&[0; 4]);
+        body.extend_from_slice(&2u16.to_le_bytes());
+        body.extend_from_slice(&38u32.to_le_bytes());
+        body.extend_from_slice(&0u64.to_le_bytes());
+        body.extend(handler(0x11, 0x7e00_1000, 0, 0));
+        body.extend(handler(0x22, 0x7e00_2000, 0, 0));
+
+        let table = make_sdt(b"PRMT", 0, &body);
+        let prmt = table_from_bytes::<Prmt>(&table);
+        let module = prmt.modules().next().unwrap();
+        assert_eq!(module.runtime_mmio_ranges_address(), None);
+        assert_eq!(module.handlers().count(), 1);
+    }
+}
@@ -0,0 +1,91 @@
+use crate::{
+    pcct::{Pcct, Subspace},
+    sdt::SdtHeader,
+    AcpiTable,
+};
+
+/// Represents the RAS Feature Table (RASF). This identifies the Platform Communications Channel subspace that the
+/// OS uses to control the platform's RAS features, such as patrol scrubbing of memory. The commands themselves
+/// are sent through the shared memory of the subspace, which is described by the PCCT.
+#[repr(C, packed)]
+pub struct Rasf {
+    header: SdtHeader,
+    pcc_identifier: [u8; 12],
+}
+
+impl AcpiTable for Rasf {
+    fn header(&self) -> &SdtHeader {
+        &self.header
+    }
+}
+
+impl Rasf {
+    /// The raw identifier of the RASF's PCC subspace.
+    pub fn pcc_identifier(&self) -> [u8; 12] {
+        self.pcc_identifier
+    }
+
+    /// The index of the RASF's subspace in the PCCT. This is held in the first byte of the PCC identifier.
+    pub fn pcc_subspace_index(&self) -> u8 {
+        self.pcc_identifier[0]
+    }
+
+    /// Find the RASF's subspace in `pcct`. Returns `None` if the PCCT doesn't have a subspace with the right
+    /// index, or if it's of an unsupported type.
+    pub fn pcc_channel_in<'a>(&self, pcct: &'a Pcct) -> Option<Subspace<'a>> {
+        pcct.subspace(self.pcc_subspace_index())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{make_sdt, table_from_bytes};
+    use alloc::vec::Vec;
+
+    /// Build a PCCT that has a subspace of a reserved type, followed by a generic subspace.
+    fn make_pcct() -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body.extend_from_slice(&[0; 8]);
+        body.extend_from_slice(&[0x7f, 4, 0, 0]);
+
+        body.extend_from_slice(&[0, 62]);
+        body.extend_from_slice(&[0; 6]);
+        body.extend_from_slice(&0x7fff_0000u64.to_le_bytes());
+        body.extend_from_slice(&0x1000u64.to_le_bytes());
+        body.extend_from_slice(&[0x01, 8, 0, 1]);
+        body.extend_from_slice(&0xb2u64.to_le_bytes());
+        body.extend_from_slice(&0u64.to_le_bytes());
+        body.extend_from_slice(&0x9au64.to_le_bytes());
+        body.extend_from_slice(&500u32.to_le_bytes());
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body.extend_from_slice(&0u16.to_le_bytes());
+        make_sdt(b"PCCT", 2, &body)
+    }
+
+    #[test]
+    fn test_pcc_channel() {
+        let pcct = make_pcct();
+        let pcct = table_from_bytes::<Pcct>(&pcct);
+
+        let table = make_sdt(b"RASF", 1, &[1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        let rasf = table_from_bytes::<Rasf>(&table);
+        assert_eq!(rasf.pcc_identifier(), [1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(rasf.pcc_subspace_index(), 1);
+        match rasf.pcc_channel_in(pcct) {
+            Some(Subspace::Generic(subspace)) => {
+                assert_eq!(subspace.base_address(), 0x7fff_0000);
+                assert_eq!(subspace.doorbell_register().unwrap().address, 0xb2);
+                assert_eq!(subspace.doorbell_write(), 0x9a);
+            }
+            _ => panic!("Expected to find a generic subspace"),
+        }
+
+        // Subspace 0 is of a reserved type, and subspace 2 doesn't exist
+        let table = make_sdt(b"RASF", 1, &[0; 12]);
+        assert!(table_from_bytes::<Rasf>(&table).pcc_channel_in(pcct).is_none());
+        let table = make_sdt(b"RASF", 1, &[2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        assert!(table_from_bytes::<Rasf>(&table).pcc_channel_in(pcct).is_none());
+    }
+}
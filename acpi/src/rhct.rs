@@ -0,0 +1,307 @@
+use crate::{
+    parse_warning,
+    sdt::{SdtHeader, Signature},
+    AcpiTable,
+    ParseWarning,
+};
+use bit_field::BitField;
+use core::{mem, slice, str};
+
+/// Represents the RISC-V Hart Capabilities Table (RHCT). This describes the frequency of the platform's timer,
+/// and the capabilities of each of its harts (the ISA extensions they implement, and the features of their cache
+/// management operations and MMUs). The capabilities are described by nodes, which are referred to by the hart
+/// info node of each hart that has them.
+#[repr(C, packed)]
+pub struct Rhct {
+    header: SdtHeader,
+    flags: u32,
+    timebase_frequency: u64,
+    number_of_nodes: u32,
+    node_array_offset: u32,
+}
+
+impl AcpiTable for Rhct {
+    fn header(&self) -> &SdtHeader {
+        &self.header
+    }
+}
+
+impl Rhct {
+    /// If true, the timer interrupt can't wake up the hart from a suspend state.
+    pub fn timer_cannot_wake_cpu(&self) -> bool {
+        { self.flags }.get_bit(0)
+    }
+
+    /// The frequency of the `time` CSR, in Hz.
+    pub fn timebase_frequency(&self) -> u64 {
+        self.timebase_frequency
+    }
+
+    pub fn nodes(&self) -> RhctNodeIter<'_> {
+        RhctNodeIter { rhct: self, offset: self.node_array_offset as usize, remaining_nodes: self.number_of_nodes }
+    }
+
+    pub fn hart_info(&self) -> impl Iterator<Item = HartInfo<'_>> {
+        self.nodes().filter_map(|node| match node {
+            RhctNode::HartInfo(info) => Some(info),
+            _ => None,
+        })
+    }
+
+    /// Find the hart info node of the hart with the given ACPI processor UID.
+    pub fn find_hart(&self, acpi_processor_uid: u32) -> Option<HartInfo<'_>> {
+        self.hart_info().find(|info| info.acpi_processor_uid == acpi_processor_uid)
+    }
+
+    fn bytes(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self as *const Rhct as *const u8, self.header.length as usize) }
+    }
+
+    /// Parse the node at the given offset from the start of the table. Returns `Err` with the node's type and
+    /// length if it doesn't fit within the table, or is too short for its type.
+    fn node_at(&self, offset: usize) -> Result<RhctNode<'_>, (u16, u32)> {
+        let bytes = self.bytes();
+        let header =
+            bytes.get(offset..).and_then(|node| node.get(..mem::size_of::<NodeHeader>())).ok_or((0, 0))?;
+        let node_type = u16::from_le_bytes([header[0], header[1]]);
+        let length = u16::from_le_bytes([header[2], header[3]]);
+
+        let minimum_length = match node_type {
+            RHCT_ISA_STRING_NODE => 8,
+            RHCT_CMO_NODE => 10,
+            RHCT_MMU_NODE => 8,
+            RHCT_HART_INFO_NODE => 12,
+            _ => mem::size_of::<NodeHeader>(),
+        };
+        let node = match bytes.get(offset..offset + length as usize) {
+            Some(node) if node.len() >= minimum_length => node,
+            _ => return Err((node_type, length as u32)),
+        };
+
+        Ok(match node_type {
+            RHCT_ISA_STRING_NODE => {
+                let isa_length = u16::from_le_bytes([node[6], node[7]]) as usize;
+                let isa = node.get(8..8 + isa_length).ok_or((node_type, length as u32))?;
+                let isa = &isa[..isa.iter().position(|&byte| byte == 0).unwrap_or(isa.len())];
+                RhctNode::IsaString(str::from_utf8(isa).ok())
+            }
+            RHCT_CMO_NODE => RhctNode::Cmo {
+                cbom_block_size_log2: node[7],
+                cbop_block_size_log2: node[8],
+                cboz_block_size_log2: node[9],
+            },
+            RHCT_MMU_NODE => RhctNode::Mmu(match node[7] {
+                0 => MmuType::Sv39,
+                1 => MmuType::Sv48,
+                2 => MmuType::Sv57,
+                other => MmuType::Reserved(other),
+            }),
+            RHCT_HART_INFO_NODE => {
+                let num_offsets = u16::from_le_bytes([node[6], node[7]]) as usize;
+                let offsets = node.get(12..12 + num_offsets * 4).ok_or((node_type, length as u32))?;
+                RhctNode::HartInfo(HartInfo {
+                    rhct: self,
+                    acpi_processor_uid: u32::from_le_bytes([node[8], node[9], node[10], node[11]]),
+                    offsets,
+                })
+            }
+            other => RhctNode::Reserved(other),
+        })
+    }
+}
+
+const RHCT_ISA_STRING_NODE: u16 = 0;
+const RHCT_CMO_NODE: u16 = 1;
+const RHCT_MMU_NODE: u16 = 2;
+const RHCT_HART_INFO_NODE: u16 = 0xffff;
+
+#[repr(C, packed)]
+struct NodeHeader {
+    node_type: u16,
+    length: u16,
+    revision: u16,
+}
+
+pub struct RhctNodeIter<'a> {
+    rhct: &'a Rhct,
+    offset: usize,
+    remaining_nodes: u32,
+}
+
+impl<'a> Iterator for RhctNodeIter<'a> {
+    type Item = RhctNode<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining_nodes == 0 {
+            return None;
+        }
+
+        match self.rhct.node_at(self.offset) {
+            Ok(node) => {
+                // Safe to index because `node_at` checked the header is within the table
+                let bytes = self.rhct.bytes();
+                self.offset += u16::from_le_bytes([bytes[self.offset + 2], bytes[self.offset + 3]]) as usize;
+                self.remaining_nodes -= 1;
+                Some(node)
+            }
+            Err((node_type, length)) => {
+                parse_warning(ParseWarning::InvalidEntryLength {
+                    signature: Signature::RHCT,
+                    entry_type: node_type,
+                    length,
+                });
+                self.remaining_nodes = 0;
+                None
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MmuType {
+    Sv39,
+    Sv48,
+    Sv57,
+    Reserved(u8),
+}
+
+#[derive(Clone, Copy)]
+pub enum RhctNode<'a> {
+    /// The ISA string of the harts that refer to this node (e.g. `"rv64imafdc_zicsr_zifencei"`). This is `None`
+    /// if the string isn't valid UTF-8.
+    IsaString(Option<&'a str>),
+    /// The block sizes of the `Zicbom`, `Zicbop`, and `Zicboz` cache management operations, each given as a
+    /// power of two (e.g. a value of `6` means the block size is 64 bytes).
+    Cmo {
+        cbom_block_size_log2: u8,
+        cbop_block_size_log2: u8,
+        cboz_block_size_log2: u8,
+    },
+    Mmu(MmuType),
+    HartInfo(HartInfo<'a>),
+    Reserved(u16),
+}
+
+/// Describes a single hart, and refers to the nodes that describe its capabilities.
+#[derive(Clone, Copy)]
+pub struct HartInfo<'a> {
+    rhct: &'a Rhct,
+    pub acpi_processor_uid: u32,
+    offsets: &'a [u8],
+}
+
+impl<'a> HartInfo<'a> {
+    /// Iterate over the nodes that describe the capabilities of this hart. Offsets that don't refer to a valid
+    /// node are skipped.
+    pub fn nodes(&self) -> impl Iterator<Item = RhctNode<'a>> + 'a {
+        let rhct = self.rhct;
+        self.offsets.chunks_exact(4).filter_map(move |offset| {
+            let offset = u32::from_le_bytes([offset[0], offset[1], offset[2], offset[3]]);
+            rhct.node_at(offset as usize).ok()
+        })
+    }
+
+    pub fn isa_string(&self) -> Option<&'a str> {
+        self.nodes().find_map(|node| match node {
+            RhctNode::IsaString(isa) => isa,
+            _ => None,
+        })
+    }
+
+    pub fn mmu_type(&self) -> Option<MmuType> {
+        self.nodes().find_map(|node| match node {
+            RhctNode::Mmu(mmu_type) => Some(mmu_type),
+            _ => None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{make_sdt, table_from_bytes};
+    use alloc::vec::Vec;
+
+    fn isa_string_node(isa: &[u8]) -> Vec<u8> {
+        let mut node = Vec::new();
+        let padded_length = (isa.len() + 1) & !1;
+        node.extend_from_slice(&RHCT_ISA_STRING_NODE.to_le_bytes());
+        node.extend_from_slice(&(8 + padded_length as u16).to_le_bytes());
+        node.extend_from_slice(&1u16.to_le_bytes());
+        node.extend_from_slice(&(isa.len() as u16).to_le_bytes());
+        node.extend_from_slice(isa);
+        node.resize(8 + padded_length, 0);
+        node
+    }
+
+    fn hart_info_node(uid: u32, offsets: &[u32]) -> Vec<u8> {
+        let mut node = Vec::new();
+        node.extend_from_slice(&RHCT_HART_INFO_NODE.to_le_bytes());
+        node.extend_from_slice(&(12 + 4 * offsets.len() as u16).to_le_bytes());
+        node.extend_from_slice(&1u16.to_le_bytes());
+        node.extend_from_slice(&(offsets.len() as u16).to_le_bytes());
+        node.extend_from_slice(&uid.to_le_bytes());
+        for offset in offsets {
+            node.extend_from_slice(&offset.to_le_bytes());
+        }
+        node
+    }
+
+    /// Build an RHCT for two harts, in the form QEMU's `virt` machine produces. The harts share an ISA string node
+    /// and an MMU node.
+    fn make_rhct() -> Vec<u8> {
+        let isa_offset = 56u32;
+        let isa = isa_string_node(b"rv64imafdc\0");
+        let mmu_offset = isa_offset + isa.len() as u32;
+        let mut mmu = Vec::new();
+        mmu.extend_from_slice(&RHCT_MMU_NODE.to_le_bytes());
+        mmu.extend_from_slice(&8u16.to_le_bytes());
+        mmu.extend_from_slice(&1u16.to_le_bytes());
+        mmu.extend_from_slice(&[0, 1]);
+
+        let mut body = Vec::new();
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body.extend_from_slice(&10_000_000u64.to_le_bytes());
+        body.extend_from_slice(&4u32.to_le_bytes());
+        body.extend_from_slice(&isa_offset.to_le_bytes());
+        body.extend(isa);
+        body.extend(mmu);
+        body.extend(hart_info_node(0, &[isa_offset, mmu_offset]));
+        body.extend(hart_info_node(1, &[mmu_offset, isa_offset]));
+        make_sdt(b"RHCT", 1, &body)
+    }
+
+    #[test]
+    fn test_hart_isa_string() {
+        let table = make_rhct();
+        let rhct = table_from_bytes::<Rhct>(&table);
+        assert_eq!(rhct.timebase_frequency(), 10_000_000);
+        assert!(!rhct.timer_cannot_wake_cpu());
+        assert_eq!(rhct.nodes().count(), 4);
+        assert_eq!(rhct.hart_info().count(), 2);
+
+        let hart = rhct.find_hart(1).unwrap();
+        assert_eq!(hart.acpi_processor_uid, 1);
+        assert_eq!(hart.isa_string(), Some("rv64imafdc"));
+        assert_eq!(hart.mmu_type(), Some(MmuType::Sv48));
+        assert_eq!(rhct.find_hart(0).unwrap().isa_string(), Some("rv64imafdc"));
+        assert!(rhct.find_hart(2).is_none());
+    }
+
+    #[test]
+    fn test_invalid_node_offset() {
+        let mut body = Vec::new();
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body.extend_from_slice(&10_000_000u64.to_le_bytes());
+        body.extend_from_slice(&1u32.to_le_bytes());
+        body.extend_from_slice(&56u32.to_le_bytes());
+        // A hart info node that refers to a node past the end of the table
+        body.extend(hart_info_node(0, &[0x1000]));
+        let table = make_sdt(b"RHCT", 1, &body);
+        let rhct = table_from_bytes::<Rhct>(&table);
+
+        let hart = rhct.find_hart(0).unwrap();
+        assert_eq!(hart.nodes().count(), 0);
+        assert_eq!(hart.isa_string(), None);
+    }
+}
@@ -0,0 +1,67 @@
+use crate::{sdt::SdtHeader, AcpiTable};
+
+/// Represents the Smart Battery Specification Table (SBST). This is provided by platforms with Smart Batteries,
+/// and gives the energy levels at which the OS should warn the user that the battery is running out, put the
+/// platform to sleep, and shut it down. All of the levels are in mWh.
+#[repr(C, packed)]
+pub struct Sbst {
+    header: SdtHeader,
+    warning_energy_level: u32,
+    low_energy_level: u32,
+    critical_energy_level: u32,
+}
+
+impl AcpiTable for Sbst {
+    fn header(&self) -> &SdtHeader {
+        &self.header
+    }
+}
+
+impl Sbst {
+    /// The energy level at which the OS should warn the user that the battery is low.
+    pub fn warning_energy_level(&self) -> u32 {
+        self.warning_energy_level
+    }
+
+    /// The energy level at which the OS should put the platform into a sleep state.
+    pub fn low_energy_level(&self) -> u32 {
+        self.low_energy_level
+    }
+
+    /// The energy level at which the OS should shut the platform down.
+    pub fn critical_energy_level(&self) -> u32 {
+        self.critical_energy_level
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{make_sdt, table_from_bytes};
+    use alloc::vec::Vec;
+
+    fn make_sbst(warning: u32, low: u32, critical: u32) -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&warning.to_le_bytes());
+        body.extend_from_slice(&low.to_le_bytes());
+        body.extend_from_slice(&critical.to_le_bytes());
+        make_sdt(b"SBST", 1, &body)
+    }
+
+    #[test]
+    fn test_energy_levels() {
+        let table = make_sbst(5_000, 3_000, 1_500);
+        let sbst = table_from_bytes::<Sbst>(&table);
+        assert_eq!(sbst.warning_energy_level(), 5_000);
+        assert_eq!(sbst.low_energy_level(), 3_000);
+        assert_eq!(sbst.critical_energy_level(), 1_500);
+        assert!(sbst.warning_energy_level() >= sbst.low_energy_level());
+        assert!(sbst.low_energy_level() >= sbst.critical_energy_level());
+
+        // The levels aren't checked against each other, so firmware that gets them the wrong way round can be
+        // caught by the caller
+        let table = make_sbst(1_500, 3_000, 5_000);
+        let sbst = table_from_bytes::<Sbst>(&table);
+        assert!(sbst.warning_energy_level() < sbst.low_energy_level());
+    }
+}
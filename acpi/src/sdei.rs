@@ -0,0 +1,66 @@
+use crate::{sdt::SdtHeader, AcpiTable};
+
+/// Represents the Software Delegated Exception Interface table (SDEI). On ARM platforms, the presence of this table
+/// tells the OS that the firmware implements SDEI, which it can use to deliver events (e.g. for RAS) that can't be
+/// masked by the OS. The table has no contents other than its header. SDEI calls are made through the same conduit
+/// as PSCI calls, which is given by `Fadt::sdei_conduit`.
+#[repr(C, packed)]
+pub struct Sdei {
+    header: SdtHeader,
+}
+
+impl AcpiTable for Sdei {
+    fn header(&self) -> &SdtHeader {
+        &self.header
+    }
+}
+
+/// The instruction used to make calls to the firmware.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Conduit {
+    /// Calls are made with the `SMC` instruction, and handled by the Secure Monitor.
+    Smc,
+    /// Calls are made with the `HVC` instruction, and handled by a hypervisor.
+    Hvc,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fadt::Fadt,
+        sdt::Signature,
+        test_utils::{make_sdt, make_xsdt, table_from_bytes, TestMemory},
+        AcpiTables,
+    };
+
+    #[test]
+    fn test_sdei() {
+        let mut memory = TestMemory::new(0x7ff0_0000);
+        let sdei = memory.add(&make_sdt(b"SDEI", 1, &[]));
+        let xsdt = memory.add(&make_xsdt(&[sdei]));
+
+        let tables = unsafe { AcpiTables::from_rsdt(memory.handler(), 2, xsdt) }.unwrap();
+        let sdei = unsafe { tables.get_sdt::<Sdei>(Signature::SDEI) }.unwrap().unwrap();
+        sdei.header().validate(Signature::SDEI).unwrap();
+        assert_eq!({ sdei.header().length }, 36);
+    }
+
+    #[test]
+    fn test_conduit() {
+        // PSCI, and so SDEI, calls are made through HVC
+        let mut body = [0; 240];
+        body[93..95].copy_from_slice(&0b11u16.to_le_bytes());
+        let table = make_sdt(b"FACP", 6, &body);
+        assert_eq!(table_from_bytes::<Fadt>(&table).sdei_conduit(), Some(Conduit::Hvc));
+
+        body[93..95].copy_from_slice(&0b01u16.to_le_bytes());
+        let table = make_sdt(b"FACP", 6, &body);
+        assert_eq!(table_from_bytes::<Fadt>(&table).sdei_conduit(), Some(Conduit::Smc));
+
+        // PSCI isn't implemented, so there's no conduit
+        body[93..95].copy_from_slice(&0b10u16.to_le_bytes());
+        let table = make_sdt(b"FACP", 6, &body);
+        assert_eq!(table_from_bytes::<Fadt>(&table).sdei_conduit(), None);
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{AcpiError, AcpiHandler};
-use core::{fmt, mem, mem::MaybeUninit, str};
+use core::{fmt, mem, mem::MaybeUninit, slice, str};
 
 /// Represents a field which may or may not be present within an ACPI structure, depending on the version of ACPI
 /// that a system supports. If the field is not present, it is not safe to treat the data as initialised.
@@ -38,6 +38,7 @@ impl<T: Copy, const MIN_REVISION: u8> ExtendedField<T, MIN_REVISION> {
 /// * FPDT - Firmware Performance Data Table
 /// * GTDT - Generic Timer Description Table
 /// * HEST - Hardware Error Source Table
+/// * HMAT - Heterogeneous Memory Attribute Table
 /// * MSCT - Maximum System Characteristics Table
 /// * MPST - Memory Power StateTable
 /// * NFIT - NVDIMM Firmware Interface Table
@@ -45,8 +46,10 @@ impl<T: Copy, const MIN_REVISION: u8> ExtendedField<T, MIN_REVISION> {
 /// * PCCT - Platform Communications Channel Table
 /// * PHAT - Platform Health Assessment Table
 /// * PMTT - Platform Memory Topology Table
+/// * PPTT - Processor Properties Topology Table
 /// * PSDT - Persistent System Description Table
 /// * RASF - ACPI RAS Feature Table
+/// * RHCT - RISC-V Hart Capabilities Table
 /// * RSDT - Root System Description Table
 /// * SBST - Smart Battery Specification Table
 /// * SDEV - Secure DEVices Table
@@ -131,17 +134,37 @@ impl SdtHeader {
         }
 
         // Validate the checksum
-        let self_ptr = self as *const SdtHeader as *const u8;
-        let mut sum: u8 = 0;
-        for i in 0..self.length {
-            sum = sum.wrapping_add(unsafe { *(self_ptr.offset(i as isize)) } as u8);
-        }
+        let table = unsafe { slice::from_raw_parts(self as *const SdtHeader as *const u8, self.length as usize) };
+        self.validate_checksum(table)
+    }
+
+    /// Compute the 8-bit sum of `bytes`. The checksum of a table is correct if the sum of all of its bytes,
+    /// including the header's `checksum` field, is zero.
+    pub fn compute_checksum(bytes: &[u8]) -> u8 {
+        bytes.iter().fold(0u8, |sum, &byte| sum.wrapping_add(byte))
+    }
 
-        if sum > 0 {
-            return Err(AcpiError::SdtInvalidChecksum(signature));
+    /// Check the checksum of the table with this header, given the bytes of the whole table. The checksum covers
+    /// `length` bytes, so this fails with `AcpiError::SdtInvalidChecksum` if `full_table` is shorter than that.
+    pub fn validate_checksum(&self, full_table: &[u8]) -> Result<(), AcpiError> {
+        match full_table.get(..self.length as usize) {
+            Some(table) if SdtHeader::compute_checksum(table) == 0 => Ok(()),
+            _ => Err(AcpiError::SdtInvalidChecksum(self.signature)),
         }
+    }
 
-        Ok(())
+    /// Fill in the `length` and `checksum` fields of the header of the table in `table`, which must contain the
+    /// whole table: `length` is set to the length of `table`, and then `checksum` is set so that the table's bytes
+    /// sum to zero. This is useful when building tables, rather than parsing them.
+    ///
+    /// ### Panics
+    /// Panics if `table` is shorter than an `SdtHeader`.
+    pub fn finalize(table: &mut [u8]) {
+        assert!(table.len() >= mem::size_of::<SdtHeader>());
+        let length = table.len() as u32;
+        table[4..8].copy_from_slice(&length.to_le_bytes());
+        table[9] = 0;
+        table[9] = 0u8.wrapping_sub(SdtHeader::compute_checksum(table));
     }
 
     pub fn oem_id(&self) -> &str {
@@ -178,14 +201,17 @@ impl Signature {
     pub const FPDT: Signature = Signature(*b"FPDT");
     pub const GTDT: Signature = Signature(*b"GTDT");
     pub const HEST: Signature = Signature(*b"HEST");
+    pub const HMAT: Signature = Signature(*b"HMAT");
     pub const MSCT: Signature = Signature(*b"MSCT");
     pub const MPST: Signature = Signature(*b"MPST");
     pub const NFIT: Signature = Signature(*b"NFIT");
     pub const PCCT: Signature = Signature(*b"PCCT");
     pub const PHAT: Signature = Signature(*b"PHAT");
     pub const PMTT: Signature = Signature(*b"PMTT");
+    pub const PPTT: Signature = Signature(*b"PPTT");
     pub const PSDT: Signature = Signature(*b"PSDT");
     pub const RASF: Signature = Signature(*b"RASF");
+    pub const RHCT: Signature = Signature(*b"RHCT");
     pub const SBST: Signature = Signature(*b"SBST");
     pub const SDEV: Signature = Signature(*b"SDEV");
     pub const SLIT: Signature = Signature(*b"SLIT");
@@ -226,20 +252,42 @@ impl Signature {
     pub const WSMT: Signature = Signature(*b"WSMT");
     pub const XENV: Signature = Signature(*b"XENV");
 
+    /// Construct a signature from its four bytes. This can be used to find tables that don't have a constant
+    /// here, such as OEM-specific tables (`Signature::from_bytes(b"OEM1")`).
+    pub const fn from_bytes(bytes: &[u8; 4]) -> Signature {
+        Signature(*bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 4] {
+        &self.0
+    }
+
+    /// Get the signature as a string. Signatures read from corrupted tables may not be valid UTF-8, in which
+    /// case this returns `"????"` - the `Display` implementation escapes the invalid bytes instead.
     pub fn as_str(&self) -> &str {
-        str::from_utf8(&self.0).unwrap()
+        str::from_utf8(&self.0).unwrap_or("????")
     }
 }
 
 impl fmt::Display for Signature {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.as_str())
+        match str::from_utf8(&self.0) {
+            Ok(signature) => write!(f, "{}", signature),
+            Err(_) => {
+                for &byte in self.0.iter() {
+                    for escaped in core::ascii::escape_default(byte) {
+                        write!(f, "{}", escaped as char)?;
+                    }
+                }
+                Ok(())
+            }
+        }
     }
 }
 
 impl fmt::Debug for Signature {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "\"{}\"", self.as_str())
+        write!(f, "\"{}\"", self)
     }
 }
 
@@ -253,3 +301,69 @@ where
         unsafe { handler.map_physical_region::<SdtHeader>(physical_address, mem::size_of::<SdtHeader>()) };
     *mapping
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::table_from_bytes;
+    use alloc::vec::Vec;
+
+    /// Build a table with a header that has a bogus `length` and `checksum`, for `finalize` to fix up.
+    fn make_unfinished_table() -> Vec<u8> {
+        let mut table = Vec::new();
+        table.extend_from_slice(b"TEST");
+        table.extend_from_slice(&0xffff_ffffu32.to_le_bytes());
+        table.extend_from_slice(&[1, 0xaa]);
+        table.extend_from_slice(b"OEMID OEMTABLE");
+        table.extend_from_slice(&[0x12; 12]);
+        table.extend_from_slice(&[0xde, 0xad, 0xbe, 0xef, 0x01]);
+        table
+    }
+
+    #[test]
+    fn test_finalize_then_validate() {
+        let mut table = make_unfinished_table();
+        SdtHeader::finalize(&mut table);
+        assert_eq!(SdtHeader::compute_checksum(&table), 0);
+
+        let header = table_from_bytes::<SdtHeader>(&table);
+        assert_eq!({ header.length }, 41);
+        assert!(header.validate_checksum(&table).is_ok());
+        assert!(header.validate(Signature(*b"TEST")).is_ok());
+
+        // The checksum covers the body, not just the header
+        table[40] ^= 0x01;
+        let header = table_from_bytes::<SdtHeader>(&table);
+        assert!(matches!(header.validate_checksum(&table), Err(AcpiError::SdtInvalidChecksum(_))));
+        assert!(matches!(header.validate(Signature(*b"TEST")), Err(AcpiError::SdtInvalidChecksum(_))));
+    }
+
+    #[test]
+    fn test_validate_checksum_of_truncated_table() {
+        let mut table = make_unfinished_table();
+        SdtHeader::finalize(&mut table);
+        let header = *table_from_bytes::<SdtHeader>(&table);
+        assert!(matches!(header.validate_checksum(&table[..40]), Err(AcpiError::SdtInvalidChecksum(_))));
+        assert!(header.validate_checksum(&table).is_ok());
+    }
+
+    #[test]
+    fn test_signature_round_trip() {
+        let fadt = Signature::from_bytes(b"FACP");
+        assert_eq!(fadt, Signature::FADT);
+        assert_eq!(fadt.as_str(), "FACP");
+        assert_eq!(fadt.as_bytes(), b"FACP");
+        assert_eq!(format!("{}", fadt), "FACP");
+        assert_eq!(format!("{:?}", fadt), "\"FACP\"");
+
+        // Signatures without a constant are preserved as they are
+        let vbrt = Signature::from_bytes(b"VBRT");
+        assert_eq!(vbrt.as_str(), "VBRT");
+        assert_eq!(format!("{}", vbrt), "VBRT");
+        assert_ne!(vbrt, Signature::BGRT);
+
+        let corrupted = Signature::from_bytes(&[b'F', 0xff, b'C', b'P']);
+        assert_eq!(corrupted.as_str(), "????");
+        assert_eq!(format!("{}", corrupted), "F\\xffCP");
+    }
+}
@@ -0,0 +1,308 @@
+use crate::{
+    parse_warning,
+    sdt::{SdtHeader, Signature},
+    AcpiTable,
+    ParseWarning,
+};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::{convert::TryFrom, fmt, mem, slice};
+
+/// Represents the System Locality Distance Information Table (SLIT). This describes the relative distance (memory
+/// latency) between each pair of proximity domains (also called localities, or NUMA nodes) on the platform.
+///
+/// The table is followed by an `N x N` matrix of distances, where `N` is `num_proximity_domains`. The matrix is
+/// stored in row-major order, so the distance from domain `i` to domain `j` is at `matrix[i * N + j]`. Distances
+/// are normalized such that the distance from a domain to itself is `10`.
+#[repr(C, packed)]
+pub struct Slit {
+    header: SdtHeader,
+    num_proximity_domains: u64,
+}
+
+impl AcpiTable for Slit {
+    fn header(&self) -> &SdtHeader {
+        &self.header
+    }
+}
+
+impl Slit {
+    pub fn num_proximity_domains(&self) -> u64 {
+        self.num_proximity_domains
+    }
+
+    /// Get the distance from proximity domain `i` to proximity domain `j`. Returns `None` if either domain is out
+    /// of range.
+    pub fn entry(&self, i: u64, j: u64) -> Option<u8> {
+        let num_domains = self.num_proximity_domains;
+        if i < num_domains && j < num_domains {
+            let index = usize::try_from(i.checked_mul(num_domains)?.checked_add(j)?).ok()?;
+            self.matrix().get(index).copied()
+        } else {
+            None
+        }
+    }
+
+    /// Get the raw distance matrix. If the table is too short to hold the full `N x N` matrix, this produces a
+    /// `ParseWarning::TruncatedTable`, and returns an empty slice.
+    pub fn matrix(&self) -> &[u8] {
+        let num_entries = usize::try_from(self.num_proximity_domains)
+            .ok()
+            .and_then(|num_domains| num_domains.checked_mul(num_domains));
+        let available = (self.header.length as usize).saturating_sub(mem::size_of::<Slit>());
+
+        match num_entries {
+            Some(num_entries) if num_entries <= available => unsafe {
+                let pointer = (self as *const Slit as *const u8).add(mem::size_of::<Slit>());
+                slice::from_raw_parts(pointer, num_entries)
+            },
+            _ => {
+                let num_domains = self.num_proximity_domains;
+                parse_warning(ParseWarning::TruncatedTable {
+                    signature: Signature::SLIT,
+                    length: self.header.length,
+                    expected_length: num_domains
+                        .saturating_mul(num_domains)
+                        .saturating_add(mem::size_of::<Slit>() as u64),
+                });
+                &[]
+            }
+        }
+    }
+}
+
+/// The distance between proximity domains that are unreachable from each other.
+pub const UNREACHABLE_DISTANCE: u8 = 0xff;
+
+/// The distance from a proximity domain to itself. Distances are normalized so that no distance is shorter.
+pub const SELF_DISTANCE: u8 = 10;
+
+/// Describes how a distance matrix doesn't follow the rules the ACPI specification sets for it. See
+/// `DistanceMatrix::validate`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SlitError {
+    /// The table is too short to hold the full `N x N` matrix.
+    MatrixIncomplete,
+    /// The distance from proximity domain `i` to itself (at `(i, i)`) isn't `10`.
+    SelfDistanceNotNormalized { i: u64, j: u64, distance: u8 },
+    /// The distance from proximity domain `i` to `j` is less than `10`.
+    DistanceBelowMinimum { i: u64, j: u64, distance: u8 },
+}
+
+impl fmt::Display for SlitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SlitError::MatrixIncomplete => write!(f, "distance matrix is incomplete"),
+            SlitError::SelfDistanceNotNormalized { i, distance, .. } => {
+                write!(f, "distance from proximity domain {} to itself is {}, not 10", i, distance)
+            }
+            SlitError::DistanceBelowMinimum { i, j, distance } => {
+                write!(f, "distance from proximity domain {} to {} is {}, which is less than 10", i, j, distance)
+            }
+        }
+    }
+}
+
+impl core::error::Error for SlitError {}
+
+/// An owned copy of the distance matrix from the SLIT, which can outlive the mapping of the table.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug)]
+pub struct DistanceMatrix {
+    num_proximity_domains: u64,
+    distances: Vec<u8>,
+}
+
+#[cfg(feature = "alloc")]
+impl DistanceMatrix {
+    pub fn new(slit: &Slit) -> DistanceMatrix {
+        DistanceMatrix { num_proximity_domains: slit.num_proximity_domains(), distances: slit.matrix().to_vec() }
+    }
+
+    pub fn num_proximity_domains(&self) -> u64 {
+        self.num_proximity_domains
+    }
+
+    /// Get the raw distances, in row-major order.
+    pub fn distances(&self) -> &[u8] {
+        &self.distances
+    }
+
+    /// Iterate over the distances from proximity domain `from` to each proximity domain, in order. Returns `None`
+    /// if `from` is out of range, or if the matrix is malformed and doesn't contain the row.
+    pub fn row(&self, from: u64) -> Option<impl Iterator<Item = u8> + '_> {
+        if from >= self.num_proximity_domains {
+            return None;
+        }
+
+        let start = usize::try_from(from.checked_mul(self.num_proximity_domains)?).ok()?;
+        let end = start.checked_add(usize::try_from(self.num_proximity_domains).ok()?)?;
+        Some(self.distances.get(start..end)?.iter().copied())
+    }
+
+    /// Check that the matrix follows the rules of the ACPI specification: the distance from each domain to itself
+    /// must be `10`, and no distance can be less than `10`. Returns the first entry that doesn't, in row-major
+    /// order. Firmware that gets this wrong makes relative distances meaningless, so it may be best to treat all
+    /// domains as equally distant instead.
+    pub fn validate(&self) -> Result<(), SlitError> {
+        let num_domains = self.num_proximity_domains;
+        let num_entries = usize::try_from(num_domains).ok().and_then(|n| n.checked_mul(n));
+        if num_entries != Some(self.distances.len()) {
+            return Err(SlitError::MatrixIncomplete);
+        }
+
+        for (index, &distance) in (0..).zip(self.distances.iter()) {
+            let (i, j) = (index / num_domains, index % num_domains);
+            if i == j && distance != SELF_DISTANCE {
+                return Err(SlitError::SelfDistanceNotNormalized { i, j, distance });
+            }
+            if distance < SELF_DISTANCE {
+                return Err(SlitError::DistanceBelowMinimum { i, j, distance });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Find the closest proximity domain to `from`, other than `from` itself. Domains that are unreachable from
+    /// `from` are ignored, and if several domains are equally close, the one with the lowest number is returned.
+    /// Returns `None` if there are no other reachable domains, or if the row for `from` can't be found.
+    pub fn nearest(&self, from: u64) -> Option<u64> {
+        self.row(from)?
+            .zip(0..)
+            .filter(|&(distance, to)| to != from && distance != UNREACHABLE_DISTANCE)
+            .min_by_key(|&(distance, _)| distance)
+            .map(|(_, to)| to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{collect_parse_warnings, make_sdt, table_from_bytes};
+    use alloc::vec::Vec;
+
+    fn make_slit(num_domains: u64, distances: &[u8]) -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&num_domains.to_le_bytes());
+        body.extend_from_slice(distances);
+        make_sdt(b"SLIT", 1, &body)
+    }
+
+    #[test]
+    fn test_entry_bounds() {
+        let table = make_slit(2, &[10, 20, 21, 10]);
+        let slit = table_from_bytes::<Slit>(&table);
+
+        assert_eq!(slit.entry(1, 1), Some(10));
+        assert_eq!(slit.entry(2, 0), None);
+        assert_eq!(slit.entry(0, 2), None);
+    }
+
+    #[test]
+    fn test_entry_is_row_major() {
+        let table = make_slit(2, &[10, 20, 21, 10]);
+        let slit = table_from_bytes::<Slit>(&table);
+
+        assert_eq!(slit.entry(0, 1), Some(20));
+        assert_eq!(slit.entry(1, 0), Some(21));
+    }
+
+    #[test]
+    fn test_matrix_length() {
+        let table = make_slit(2, &[10, 20, 21, 10]);
+        assert_eq!(table_from_bytes::<Slit>(&table).matrix(), [10, 20, 21, 10]);
+
+        // Too short to hold the `3 x 3` matrix
+        let table = make_slit(3, &[10, 20, 21, 10, 10, 10, 10, 10]);
+        assert!(table_from_bytes::<Slit>(&table).matrix().is_empty());
+
+        // `N * N` overflows, which mustn't panic or wrap around to a small length
+        for &num_domains in [1 << 32, u64::MAX].iter() {
+            let table = make_slit(num_domains, &[10, 20, 21, 10]);
+            let slit = table_from_bytes::<Slit>(&table);
+            assert!(slit.matrix().is_empty());
+            assert_eq!(slit.entry(0, 0), None);
+            assert_eq!(slit.entry(num_domains - 1, num_domains - 1), None);
+        }
+    }
+
+    #[test]
+    fn test_truncated_matrix_warns() {
+        let table = make_slit(3, &[10, 20, 21, 10, 10, 10, 10, 10]);
+        let slit = table_from_bytes::<Slit>(&table);
+        let (matrix, warnings) = collect_parse_warnings(|| slit.matrix());
+        assert!(matrix.is_empty());
+        assert_eq!(
+            warnings,
+            [ParseWarning::TruncatedTable { signature: Signature::SLIT, length: 52, expected_length: 53 }]
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_distance_matrix_rows() {
+        #[rustfmt::skip]
+        let table = make_slit(3, &[
+            10, 32, 20,
+            21, 10, 40,
+            15, 15, 10,
+        ]);
+        let matrix = DistanceMatrix::new(table_from_bytes::<Slit>(&table));
+
+        assert_eq!(matrix.row(0).unwrap().collect::<Vec<u8>>(), [10, 32, 20]);
+        assert_eq!(matrix.row(1).unwrap().collect::<Vec<u8>>(), [21, 10, 40]);
+        assert_eq!(matrix.row(2).unwrap().collect::<Vec<u8>>(), [15, 15, 10]);
+        assert!(matrix.row(3).is_none());
+
+        assert_eq!(matrix.nearest(0), Some(2));
+        assert_eq!(matrix.nearest(1), Some(0));
+        assert_eq!(matrix.nearest(2), Some(0));
+        assert_eq!(matrix.nearest(3), None);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_malformed_distance_matrix() {
+        let table = make_slit(3, &[10, 20, 20, 10]);
+        let matrix = DistanceMatrix::new(table_from_bytes::<Slit>(&table));
+
+        assert!(matrix.row(0).is_none());
+        assert_eq!(matrix.nearest(0), None);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_validate() {
+        #[rustfmt::skip]
+        let table = make_slit(3, &[
+            10, 32, 20,
+            21, 10, 0xff,
+            15, 15, 10,
+        ]);
+        assert_eq!(DistanceMatrix::new(table_from_bytes::<Slit>(&table)).validate(), Ok(()));
+
+        #[rustfmt::skip]
+        let table = make_slit(3, &[
+            10, 32, 20,
+            21, 11, 40,
+            15, 15, 11,
+        ]);
+        assert_eq!(
+            DistanceMatrix::new(table_from_bytes::<Slit>(&table)).validate(),
+            Err(SlitError::SelfDistanceNotNormalized { i: 1, j: 1, distance: 11 })
+        );
+
+        let table = make_slit(2, &[10, 20, 9, 10]);
+        assert_eq!(
+            DistanceMatrix::new(table_from_bytes::<Slit>(&table)).validate(),
+            Err(SlitError::DistanceBelowMinimum { i: 1, j: 0, distance: 9 })
+        );
+
+        let table = make_slit(3, &[10, 20, 20, 10]);
+        assert_eq!(
+            DistanceMatrix::new(table_from_bytes::<Slit>(&table)).validate(),
+            Err(SlitError::MatrixIncomplete)
+        );
+    }
+}
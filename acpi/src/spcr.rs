@@ -0,0 +1,442 @@
+use crate::{
+    platform::address::{GenericAddress, RawGenericAddress},
+    sdt::SdtHeader,
+    AcpiError,
+    AcpiTable,
+};
+use bit_field::BitField;
+use core::{mem, slice, str};
+
+/// Represents the Serial Port Console Redirection (SPCR) table. This describes the serial port firmware uses as
+/// its console, and how it is configured, so that a bootloader or OS can continue to use it.
+///
+/// The table has been extended over time, and fields that were added after revision 2 are only returned if the
+/// table is new enough (and long enough) to contain them.
+#[repr(C, packed)]
+pub struct Spcr {
+    header: SdtHeader,
+    interface_type: u8,
+    _reserved1: [u8; 3],
+    base_address: RawGenericAddress,
+    interrupt_type: u8,
+    irq: u8,
+    global_system_interrupt: u32,
+    configured_baud_rate: u8,
+    parity: u8,
+    stop_bits: u8,
+    flow_control: u8,
+    terminal_type: u8,
+    /// Reserved before revision 4.
+    language: u8,
+    pci_device_id: u16,
+    pci_vendor_id: u16,
+    pci_bus: u8,
+    pci_device: u8,
+    pci_function: u8,
+    pci_flags: u32,
+    pci_segment: u8,
+    /// Reserved before revision 3.
+    uart_clock_frequency: u32,
+    /*
+     * The following fields were added in revision 4 of the table.
+     */
+    precise_baud_rate: u32,
+    namespace_string_length: u16,
+    namespace_string_offset: u16,
+}
+
+/// The length of the table before the fields added in revision 4.
+const REVISION_2_LENGTH: usize = 80;
+
+impl AcpiTable for Spcr {
+    fn header(&self) -> &SdtHeader {
+        &self.header
+    }
+
+    fn minimum_length() -> usize {
+        REVISION_2_LENGTH
+    }
+}
+
+impl Spcr {
+    pub fn interface_type(&self) -> SerialInterfaceType {
+        SerialInterfaceType::from(u16::from(self.interface_type))
+    }
+
+    /// The base address of the UART's registers.
+    pub fn base_address(&self) -> Result<GenericAddress, AcpiError> {
+        GenericAddress::from_raw(self.base_address)
+    }
+
+    pub fn interrupt_type(&self) -> SpcrInterruptType {
+        SpcrInterruptType(self.interrupt_type)
+    }
+
+    /// The legacy PC-AT IRQ used by the UART, if it's connected to the dual-8259 PIC.
+    pub fn irq(&self) -> Option<u8> {
+        if self.interrupt_type().pc_at() {
+            Some(self.irq)
+        } else {
+            None
+        }
+    }
+
+    /// The Global System Interrupt used by the UART, if it's connected to an I/O APIC, I/O SAPIC, GIC, or PLIC.
+    pub fn global_system_interrupt(&self) -> Option<u32> {
+        if self.interrupt_type.get_bits(1..8) != 0 {
+            Some(self.global_system_interrupt)
+        } else {
+            None
+        }
+    }
+
+    /// The baud rate the UART is configured with, or `None` if firmware uses the rate the UART is already
+    /// configured with (in which case the OS must not change it).
+    pub fn baud_rate(&self) -> Option<u32> {
+        if let Some(precise_baud_rate) = self.precise_baud_rate() {
+            return Some(precise_baud_rate);
+        }
+
+        match self.configured_baud_rate {
+            3 => Some(9600),
+            4 => Some(19200),
+            6 => Some(57600),
+            7 => Some(115200),
+            _ => None,
+        }
+    }
+
+    pub fn parity(&self) -> Parity {
+        match self.parity {
+            0 => Parity::NoParity,
+            other => Parity::Reserved(other),
+        }
+    }
+
+    pub fn stop_bits(&self) -> StopBits {
+        match self.stop_bits {
+            1 => StopBits::One,
+            other => StopBits::Reserved(other),
+        }
+    }
+
+    pub fn flow_control(&self) -> FlowControl {
+        FlowControl(self.flow_control)
+    }
+
+    pub fn terminal_type(&self) -> TerminalType {
+        match self.terminal_type {
+            0 => TerminalType::Vt100,
+            1 => TerminalType::ExtendedVt100,
+            2 => TerminalType::VtUtf8,
+            3 => TerminalType::Ansi,
+            other => TerminalType::Reserved(other),
+        }
+    }
+
+    /// If the UART is a PCI device, where it is found. Returns `None` if the UART isn't a PCI device.
+    pub fn pci_device(&self) -> Option<SpcrPciDevice> {
+        if self.pci_device_id == 0xffff {
+            return None;
+        }
+
+        Some(SpcrPciDevice {
+            segment: self.pci_segment,
+            bus: self.pci_bus,
+            device: self.pci_device,
+            function: self.pci_function,
+            vendor_id: self.pci_vendor_id,
+            device_id: self.pci_device_id,
+            do_not_suppress: !{ self.pci_flags }.get_bit(0),
+        })
+    }
+
+    /// The frequency of the UART's input clock, in Hz. This is only reported from revision 3 of the table, and is
+    /// `None` for older tables, or when the frequency is not known.
+    pub fn uart_clock_frequency(&self) -> Option<u32> {
+        match self.uart_clock_frequency {
+            0 => None,
+            _ if self.header.revision < 3 => None,
+            frequency => Some(frequency),
+        }
+    }
+
+    /// The UART's baud rate, if it's one that can't be expressed in the `configured_baud_rate` field. This is only
+    /// reported from revision 4 of the table.
+    fn precise_baud_rate(&self) -> Option<u32> {
+        if self.header.revision < 4 || (self.header.length as usize) < mem::size_of::<Spcr>() {
+            return None;
+        }
+
+        match self.precise_baud_rate {
+            0 => None,
+            baud_rate => Some(baud_rate),
+        }
+    }
+
+    /// The fully-qualified path to the UART in the ACPI namespace, or `"."` if it isn't in the namespace. This is
+    /// only reported from revision 4 of the table. Returns `None` for older tables, or if the path isn't valid
+    /// ASCII.
+    pub fn namespace_string(&self) -> Option<&str> {
+        if self.header.revision < 4 || (self.header.length as usize) < mem::size_of::<Spcr>() {
+            return None;
+        }
+
+        let offset = self.namespace_string_offset as usize;
+        let length = self.namespace_string_length as usize;
+        if offset < REVISION_2_LENGTH || offset.saturating_add(length) > self.header.length as usize {
+            return None;
+        }
+
+        let name = unsafe { slice::from_raw_parts((self as *const Spcr as *const u8).add(offset), length) };
+        let name = match name.iter().position(|&byte| byte == 0) {
+            Some(end) => &name[..end],
+            None => name,
+        };
+
+        if name.is_ascii() {
+            str::from_utf8(name).ok()
+        } else {
+            None
+        }
+    }
+}
+
+/// The type of a serial port, as described by the SPCR and DBG2 tables.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SerialInterfaceType {
+    /// A fully 16550-compatible UART.
+    Full16550,
+    /// A UART compatible with the subset of the 16550 described in the DBGP table.
+    Subset16550,
+    Max311xE,
+    ArmPl011,
+    Msm8x60,
+    Nvidia16550,
+    TiOmap,
+    Apm88xxxx,
+    Msm8974,
+    Sam5250,
+    IntelUsif,
+    Imx6,
+    /// An ARM SBSA Generic UART that only supports 32-bit accesses. This is deprecated in favour of
+    /// `ArmSbsaGeneric`.
+    ArmSbsa32BitGeneric,
+    ArmSbsaGeneric,
+    ArmDcc,
+    Bcm2835,
+    /// A Qualcomm SDM845 UART with a 1.8432MHz clock.
+    Sdm845,
+    /// A 16550-compatible UART, with the register width and access size described by its Generic Address
+    /// Structure.
+    Generic16550,
+    /// A Qualcomm SDM845 UART with a 7.372MHz clock.
+    Sdm845ExtendedClock,
+    IntelLpss,
+    RiscVSbi,
+    Reserved(u16),
+}
+
+impl From<u16> for SerialInterfaceType {
+    fn from(interface_type: u16) -> SerialInterfaceType {
+        match interface_type {
+            0x00 => SerialInterfaceType::Full16550,
+            0x01 => SerialInterfaceType::Subset16550,
+            0x02 => SerialInterfaceType::Max311xE,
+            0x03 => SerialInterfaceType::ArmPl011,
+            0x04 => SerialInterfaceType::Msm8x60,
+            0x05 => SerialInterfaceType::Nvidia16550,
+            0x06 => SerialInterfaceType::TiOmap,
+            0x08 => SerialInterfaceType::Apm88xxxx,
+            0x09 => SerialInterfaceType::Msm8974,
+            0x0a => SerialInterfaceType::Sam5250,
+            0x0b => SerialInterfaceType::IntelUsif,
+            0x0c => SerialInterfaceType::Imx6,
+            0x0d => SerialInterfaceType::ArmSbsa32BitGeneric,
+            0x0e => SerialInterfaceType::ArmSbsaGeneric,
+            0x0f => SerialInterfaceType::ArmDcc,
+            0x10 => SerialInterfaceType::Bcm2835,
+            0x11 => SerialInterfaceType::Sdm845,
+            0x12 => SerialInterfaceType::Generic16550,
+            0x13 => SerialInterfaceType::Sdm845ExtendedClock,
+            0x14 => SerialInterfaceType::IntelLpss,
+            0x15 => SerialInterfaceType::RiscVSbi,
+            other => SerialInterfaceType::Reserved(other),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SpcrInterruptType(u8);
+
+impl SpcrInterruptType {
+    /// The UART is connected to the dual-8259 PIC.
+    pub fn pc_at(&self) -> bool {
+        self.0.get_bit(0)
+    }
+
+    pub fn io_apic(&self) -> bool {
+        self.0.get_bit(1)
+    }
+
+    pub fn io_sapic(&self) -> bool {
+        self.0.get_bit(2)
+    }
+
+    pub fn arm_gic(&self) -> bool {
+        self.0.get_bit(3)
+    }
+
+    pub fn risc_v_plic(&self) -> bool {
+        self.0.get_bit(4)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Parity {
+    NoParity,
+    Reserved(u8),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StopBits {
+    One,
+    Reserved(u8),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FlowControl(u8);
+
+impl FlowControl {
+    /// DCD is required for transmission.
+    pub fn dcd(&self) -> bool {
+        self.0.get_bit(0)
+    }
+
+    pub fn rts_cts(&self) -> bool {
+        self.0.get_bit(1)
+    }
+
+    pub fn xon_xoff(&self) -> bool {
+        self.0.get_bit(2)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TerminalType {
+    Vt100,
+    ExtendedVt100,
+    VtUtf8,
+    Ansi,
+    Reserved(u8),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SpcrPciDevice {
+    pub segment: u8,
+    pub bus: u8,
+    pub device: u8,
+    pub function: u8,
+    pub vendor_id: u16,
+    pub device_id: u16,
+    /// If this is `false`, the OS should suppress Plug and Play enumeration of the device, and not disable it, so
+    /// that firmware can continue to use it.
+    pub do_not_suppress: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        platform::address::AddressSpace,
+        sdt::Signature,
+        test_utils::{make_sdt, make_xsdt, table_from_bytes, TestMemory},
+        AcpiTables,
+    };
+    use alloc::vec::Vec;
+
+    fn spcr_body(interface_type: u8, base_address: u64, baud_rate: u8) -> Vec<u8> {
+        let mut body = Vec::new();
+        body.push(interface_type);
+        body.extend_from_slice(&[0; 3]);
+        body.extend_from_slice(&[0x00, 32, 0, 3]);
+        body.extend_from_slice(&base_address.to_le_bytes());
+        body.push(0b1000);
+        body.push(0);
+        body.extend_from_slice(&33u32.to_le_bytes());
+        body.extend_from_slice(&[baud_rate, 0, 1, 0b10, 3, 0]);
+        body.extend_from_slice(&0xffffu16.to_le_bytes());
+        body.extend_from_slice(&0xffffu16.to_le_bytes());
+        body.extend_from_slice(&[0, 0, 0]);
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body.push(0);
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body
+    }
+
+    #[test]
+    fn test_qemu_spcr() {
+        /*
+         * This is the layout of the SPCR QEMU generates for its ARM `virt` machine, which describes the PL011 at
+         * `0x0900_0000`.
+         */
+        let mut table = make_sdt(b"SPCR", 2, &spcr_body(0x03, 0x0900_0000, 7));
+        // Pad the buffer so the fields added in revision 4 can be looked at without reading past its end
+        table.extend_from_slice(&[0xff; 8]);
+        let spcr = table_from_bytes::<Spcr>(&table);
+
+        assert_eq!(spcr.interface_type(), SerialInterfaceType::ArmPl011);
+        let base_address = spcr.base_address().unwrap();
+        assert_eq!(base_address.address_space, AddressSpace::SystemMemory);
+        assert_eq!(base_address.address, 0x0900_0000);
+        assert!(spcr.interrupt_type().arm_gic());
+        assert_eq!(spcr.irq(), None);
+        assert_eq!(spcr.global_system_interrupt(), Some(33));
+        assert_eq!(spcr.baud_rate(), Some(115200));
+        assert_eq!(spcr.parity(), Parity::NoParity);
+        assert_eq!(spcr.stop_bits(), StopBits::One);
+        assert!(spcr.flow_control().rts_cts());
+        assert_eq!(spcr.terminal_type(), TerminalType::Ansi);
+        assert_eq!(spcr.pci_device(), None);
+        assert_eq!(spcr.uart_clock_frequency(), None);
+        assert_eq!(spcr.namespace_string(), None);
+    }
+
+    #[test]
+    fn test_get_revision_2_table() {
+        // QEMU's SPCR is a revision 2 table, so it ends before the fields added in revision 4
+        let mut memory = TestMemory::new(0x7ff0_0000);
+        let spcr = memory.add(&make_sdt(b"SPCR", 2, &spcr_body(0x03, 0x0900_0000, 7)));
+        let xsdt = memory.add(&make_xsdt(&[spcr]));
+
+        let tables = unsafe { AcpiTables::from_rsdt(memory.handler(), 2, xsdt) }.unwrap();
+        let spcr = unsafe { tables.get_sdt::<Spcr>(Signature::SPCR) }.unwrap().unwrap();
+        assert_eq!({ spcr.header().length }, 80);
+        assert_eq!(spcr.interface_type(), SerialInterfaceType::ArmPl011);
+        assert_eq!(spcr.base_address().unwrap().address, 0x0900_0000);
+        assert_eq!(spcr.global_system_interrupt(), Some(33));
+        assert_eq!(spcr.baud_rate(), Some(115200));
+        assert_eq!(spcr.namespace_string(), None);
+    }
+
+    #[test]
+    fn test_revision_4_fields() {
+        let mut body = spcr_body(0x12, 0x3f8, 0);
+        body[40 - 36] = 0x01;
+        let clock_offset = 76 - 36;
+        body[clock_offset..clock_offset + 4].copy_from_slice(&1_843_200u32.to_le_bytes());
+        body.extend_from_slice(&1_500_000u32.to_le_bytes());
+        body.extend_from_slice(&10u16.to_le_bytes());
+        body.extend_from_slice(&88u16.to_le_bytes());
+        body.extend_from_slice(b"\\\\_SB.COM0\\0");
+
+        let table = make_sdt(b"SPCR", 4, &body);
+        let spcr = table_from_bytes::<Spcr>(&table);
+
+        assert_eq!(spcr.interface_type(), SerialInterfaceType::Generic16550);
+        assert_eq!(spcr.base_address().unwrap().address_space, AddressSpace::SystemIo);
+        assert_eq!(spcr.uart_clock_frequency(), Some(1_843_200));
+        assert_eq!(spcr.baud_rate(), Some(1_500_000));
+        assert_eq!(spcr.namespace_string(), Some("\\\\_SB.COM0"));
+    }
+}
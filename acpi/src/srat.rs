@@ -0,0 +1,712 @@
+use crate::{
+    entry_as,
+    parse_warning,
+    sdt::{SdtHeader, Signature},
+    AcpiError,
+    AcpiTable,
+    EntryIter,
+    ParseWarning,
+};
+use bit_field::BitField;
+use core::{mem, ptr};
+
+/// Represents the System Resource Affinity Table (SRAT). This associates processors and memory ranges with the
+/// proximity domains (NUMA nodes) they belong to. The fixed fields are followed by a list of variable-length
+/// affinity structures, which can be iterated with `entries`.
+#[repr(C, packed)]
+pub struct Srat {
+    header: SdtHeader,
+    _reserved1: u32,
+    _reserved2: u64,
+}
+
+impl AcpiTable for Srat {
+    fn header(&self) -> &SdtHeader {
+        &self.header
+    }
+}
+
+impl Srat {
+    pub fn entries(&self) -> SratEntryIter<'_> {
+        SratEntryIter {
+            entries: unsafe {
+                EntryIter::new(
+                    Signature::SRAT,
+                    (self as *const Srat as *const u8).add(mem::size_of::<Srat>()),
+                    self.header.length.saturating_sub(mem::size_of::<Srat>() as u32),
+                    entry_minimum_length,
+                )
+            },
+        }
+    }
+}
+
+/// Builds a SRAT in a buffer provided by the caller, for hypervisors (and tests) that need to produce one. Entries
+/// are written into the buffer as they're added, and `finish` fills in the header's `length` and `checksum`.
+pub struct SratBuilder<'a> {
+    buffer: &'a mut [u8],
+    length: usize,
+}
+
+impl<'a> SratBuilder<'a> {
+    /// Start building a SRAT in `buffer`. The revision and OEM fields of the table's header are taken from
+    /// `header`, while its signature, length and checksum are filled in by the builder. Fails with
+    /// `AcpiError::BufferTooSmall` if `buffer` can't hold even the fixed part of the table.
+    pub fn new(buffer: &'a mut [u8], header: SdtHeader) -> Result<SratBuilder<'a>, AcpiError> {
+        let mut builder = SratBuilder { buffer, length: 0 };
+        builder.push(Srat {
+            header: SdtHeader { signature: Signature::SRAT, length: 0, checksum: 0, ..header },
+            // This field must be `1`, for backwards-compatibility
+            _reserved1: 1,
+            _reserved2: 0,
+        })?;
+        Ok(builder)
+    }
+
+    pub fn add_local_apic_affinity(&mut self, entry: LocalApicAffinity) -> Result<&mut Self, AcpiError> {
+        self.push(entry)
+    }
+
+    pub fn add_memory_affinity(&mut self, entry: MemoryAffinity) -> Result<&mut Self, AcpiError> {
+        self.push(entry)
+    }
+
+    pub fn add_local_x2apic_affinity(&mut self, entry: LocalX2ApicAffinity) -> Result<&mut Self, AcpiError> {
+        self.push(entry)
+    }
+
+    /// Finish the table, returning the part of the buffer that it occupies.
+    pub fn finish(self) -> &'a [u8] {
+        let table = &mut self.buffer[..self.length];
+        SdtHeader::finalize(table);
+        table
+    }
+
+    fn push<T>(&mut self, value: T) -> Result<&mut Self, AcpiError> {
+        let size = mem::size_of::<T>();
+        if self.buffer.len() - self.length < size {
+            return Err(AcpiError::BufferTooSmall);
+        }
+
+        /*
+         * The tables and entries are all packed, so this doesn't need to care about alignment, and doesn't write
+         * any padding.
+         */
+        unsafe { ptr::write_unaligned(self.buffer[self.length..].as_mut_ptr() as *mut T, value) };
+        self.length += size;
+        Ok(self)
+    }
+}
+
+pub struct SratEntryIter<'a> {
+    entries: EntryIter<'a, EntryHeader>,
+}
+
+pub enum SratEntry<'a> {
+    LocalApicAffinity(&'a LocalApicAffinity),
+    MemoryAffinity(&'a MemoryAffinity),
+    LocalX2ApicAffinity(&'a LocalX2ApicAffinity),
+    GiccAffinity(&'a GiccAffinity),
+    GicItsAffinity(&'a GicItsAffinity),
+    GenericInitiatorAffinity(&'a GenericInitiatorAffinity),
+}
+
+impl<'a> Iterator for SratEntryIter<'a> {
+    type Item = SratEntry<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (entry_type, entry) in &mut self.entries {
+            match entry_type {
+                0x0 => return Some(SratEntry::LocalApicAffinity(unsafe { entry_as(entry) })),
+                0x1 => return Some(SratEntry::MemoryAffinity(unsafe { entry_as(entry) })),
+                0x2 => return Some(SratEntry::LocalX2ApicAffinity(unsafe { entry_as(entry) })),
+                0x3 => return Some(SratEntry::GiccAffinity(unsafe { entry_as(entry) })),
+                0x4 => return Some(SratEntry::GicItsAffinity(unsafe { entry_as(entry) })),
+                0x5 => return Some(SratEntry::GenericInitiatorAffinity(unsafe { entry_as(entry) })),
+
+                /*
+                 * Other entry types are reserved by the ACPI standard, or describe affinity structures we don't
+                 * support yet. We skip them.
+                 */
+                _ => parse_warning(ParseWarning::UnknownEntryType { signature: Signature::SRAT, entry_type }),
+            }
+        }
+
+        None
+    }
+}
+
+#[derive(Clone, Copy)]
+#[repr(C, packed)]
+pub struct EntryHeader {
+    entry_type: u8,
+    length: u8,
+}
+
+impl EntryHeader {
+    fn new<T>(entry_type: u8) -> EntryHeader {
+        EntryHeader { entry_type, length: mem::size_of::<T>() as u8 }
+    }
+}
+
+impl crate::EntryHeader for EntryHeader {
+    fn entry_type(&self) -> u16 {
+        self.entry_type.into()
+    }
+
+    fn length(&self) -> u32 {
+        self.length.into()
+    }
+}
+
+fn entry_minimum_length(header: &EntryHeader) -> usize {
+    match header.entry_type {
+        0x0 => mem::size_of::<LocalApicAffinity>(),
+        0x1 => mem::size_of::<MemoryAffinity>(),
+        0x2 => mem::size_of::<LocalX2ApicAffinity>(),
+        0x3 => mem::size_of::<GiccAffinity>(),
+        0x4 => mem::size_of::<GicItsAffinity>(),
+        0x5 => mem::size_of::<GenericInitiatorAffinity>(),
+        _ => mem::size_of::<EntryHeader>(),
+    }
+}
+
+/// Associates a processor, identified by its local APIC ID (or local SAPIC ID and EID), with a proximity domain.
+#[derive(Clone, Copy)]
+#[repr(C, packed)]
+pub struct LocalApicAffinity {
+    header: EntryHeader,
+    proximity_domain_low: u8,
+    apic_id: u8,
+    flags: u32,
+    local_sapic_eid: u8,
+    proximity_domain_high: [u8; 3],
+    clock_domain: u32,
+}
+
+impl LocalApicAffinity {
+    pub fn new(apic_id: u8, proximity_domain: u32, clock_domain: u32, enabled: bool) -> LocalApicAffinity {
+        let domain = proximity_domain.to_le_bytes();
+        LocalApicAffinity {
+            header: EntryHeader::new::<LocalApicAffinity>(0x0),
+            proximity_domain_low: domain[0],
+            apic_id,
+            flags: enabled as u32,
+            local_sapic_eid: 0,
+            proximity_domain_high: [domain[1], domain[2], domain[3]],
+            clock_domain,
+        }
+    }
+
+    pub fn proximity_domain(&self) -> u32 {
+        let high = self.proximity_domain_high;
+        u32::from_le_bytes([self.proximity_domain_low, high[0], high[1], high[2]])
+    }
+
+    pub fn apic_id(&self) -> u8 {
+        self.apic_id
+    }
+
+    pub fn local_sapic_eid(&self) -> u8 {
+        self.local_sapic_eid
+    }
+
+    pub fn clock_domain(&self) -> u32 {
+        self.clock_domain
+    }
+
+    /// If this is `false`, the entry should be ignored.
+    pub fn is_enabled(&self) -> bool {
+        { self.flags }.get_bit(0)
+    }
+}
+
+/// Associates a range of physical memory with a proximity domain.
+#[derive(Clone, Copy)]
+#[repr(C, packed)]
+pub struct MemoryAffinity {
+    header: EntryHeader,
+    proximity_domain: u32,
+    _reserved1: u16,
+    base_address_low: u32,
+    base_address_high: u32,
+    length_low: u32,
+    length_high: u32,
+    _reserved2: u32,
+    flags: u32,
+    _reserved3: u64,
+}
+
+impl MemoryAffinity {
+    pub fn new(
+        proximity_domain: u32,
+        base_address: u64,
+        length: u64,
+        enabled: bool,
+        hot_pluggable: bool,
+        non_volatile: bool,
+    ) -> MemoryAffinity {
+        let mut flags = 0;
+        flags.set_bit(0, enabled).set_bit(1, hot_pluggable).set_bit(2, non_volatile);
+        MemoryAffinity {
+            header: EntryHeader::new::<MemoryAffinity>(0x1),
+            proximity_domain,
+            _reserved1: 0,
+            base_address_low: base_address.get_bits(0..32) as u32,
+            base_address_high: base_address.get_bits(32..64) as u32,
+            length_low: length.get_bits(0..32) as u32,
+            length_high: length.get_bits(32..64) as u32,
+            _reserved2: 0,
+            flags,
+            _reserved3: 0,
+        }
+    }
+
+    pub fn proximity_domain(&self) -> u32 {
+        self.proximity_domain
+    }
+
+    pub fn base_address(&self) -> u64 {
+        let mut address = self.base_address_low as u64;
+        address.set_bits(32..64, self.base_address_high as u64);
+        address
+    }
+
+    pub fn length(&self) -> u64 {
+        let mut length = self.length_low as u64;
+        length.set_bits(32..64, self.length_high as u64);
+        length
+    }
+
+    /// If this is `false`, the entry should be ignored.
+    pub fn is_enabled(&self) -> bool {
+        { self.flags }.get_bit(0)
+    }
+
+    /// If this is `true`, the memory range may be hot-added or hot-removed at runtime.
+    pub fn is_hot_pluggable(&self) -> bool {
+        { self.flags }.get_bit(1)
+    }
+
+    pub fn is_non_volatile(&self) -> bool {
+        { self.flags }.get_bit(2)
+    }
+}
+
+/// Associates a processor, identified by its x2APIC ID, with a proximity domain.
+#[derive(Clone, Copy)]
+#[repr(C, packed)]
+pub struct LocalX2ApicAffinity {
+    header: EntryHeader,
+    _reserved1: u16,
+    proximity_domain: u32,
+    x2apic_id: u32,
+    flags: u32,
+    clock_domain: u32,
+    _reserved2: u32,
+}
+
+impl LocalX2ApicAffinity {
+    pub fn new(x2apic_id: u32, proximity_domain: u32, clock_domain: u32, enabled: bool) -> LocalX2ApicAffinity {
+        LocalX2ApicAffinity {
+            header: EntryHeader::new::<LocalX2ApicAffinity>(0x2),
+            _reserved1: 0,
+            proximity_domain,
+            x2apic_id,
+            flags: enabled as u32,
+            clock_domain,
+            _reserved2: 0,
+        }
+    }
+
+    pub fn proximity_domain(&self) -> u32 {
+        self.proximity_domain
+    }
+
+    pub fn x2apic_id(&self) -> u32 {
+        self.x2apic_id
+    }
+
+    pub fn clock_domain(&self) -> u32 {
+        self.clock_domain
+    }
+
+    /// If this is `false`, the entry should be ignored.
+    pub fn is_enabled(&self) -> bool {
+        { self.flags }.get_bit(0)
+    }
+}
+
+/// Associates a processor on an ARM system, identified by its ACPI Processor UID (as also found in its GICC
+/// structure in the MADT), with a proximity domain.
+#[derive(Clone, Copy)]
+#[repr(C, packed)]
+pub struct GiccAffinity {
+    header: EntryHeader,
+    proximity_domain: u32,
+    acpi_processor_uid: u32,
+    flags: u32,
+    clock_domain: u32,
+}
+
+impl GiccAffinity {
+    pub fn proximity_domain(&self) -> u32 {
+        self.proximity_domain
+    }
+
+    pub fn acpi_processor_uid(&self) -> u32 {
+        self.acpi_processor_uid
+    }
+
+    pub fn clock_domain(&self) -> u32 {
+        self.clock_domain
+    }
+
+    /// If this is `false`, the entry should be ignored.
+    pub fn is_enabled(&self) -> bool {
+        { self.flags }.get_bit(0)
+    }
+}
+
+/// Associates a GIC Interrupt Translation Service (ITS), identified by the ID in its MADT structure, with a
+/// proximity domain.
+#[derive(Clone, Copy)]
+#[repr(C, packed)]
+pub struct GicItsAffinity {
+    header: EntryHeader,
+    proximity_domain: u32,
+    _reserved: u16,
+    its_id: u32,
+}
+
+impl GicItsAffinity {
+    pub fn proximity_domain(&self) -> u32 {
+        self.proximity_domain
+    }
+
+    pub fn its_id(&self) -> u32 {
+        self.its_id
+    }
+}
+
+/// Associates a generic initiator - a device, such as a GPU or other accelerator, that initiates memory
+/// transactions but isn't a processor - with a proximity domain.
+#[derive(Clone, Copy)]
+#[repr(C, packed)]
+pub struct GenericInitiatorAffinity {
+    header: EntryHeader,
+    _reserved1: u8,
+    device_handle_type: u8,
+    proximity_domain: u32,
+    device_handle: [u8; 16],
+    flags: u32,
+    _reserved2: u32,
+}
+
+impl GenericInitiatorAffinity {
+    pub fn proximity_domain(&self) -> u32 {
+        self.proximity_domain
+    }
+
+    pub fn device_handle(&self) -> DeviceHandle {
+        let handle = self.device_handle;
+        match self.device_handle_type {
+            0 => DeviceHandle::Acpi {
+                hid: [handle[0], handle[1], handle[2], handle[3], handle[4], handle[5], handle[6], handle[7]],
+                uid: u32::from_le_bytes([handle[8], handle[9], handle[10], handle[11]]),
+            },
+            1 => {
+                let bdf = u16::from_le_bytes([handle[2], handle[3]]);
+                DeviceHandle::Pci {
+                    segment: u16::from_le_bytes([handle[0], handle[1]]),
+                    bus: bdf.get_bits(8..16) as u8,
+                    device: bdf.get_bits(3..8) as u8,
+                    function: bdf.get_bits(0..3) as u8,
+                }
+            }
+            other => DeviceHandle::Reserved(other),
+        }
+    }
+
+    /// If this is `false`, the entry should be ignored.
+    pub fn is_enabled(&self) -> bool {
+        { self.flags }.get_bit(0)
+    }
+
+    /// If this is `true`, the initiator can issue architectural transactions (e.g. it's cache-coherent with the
+    /// processors), rather than only ordinary memory accesses.
+    pub fn supports_architectural_transactions(&self) -> bool {
+        { self.flags }.get_bit(1)
+    }
+}
+
+/// Identifies the device that a `GenericInitiatorAffinity` describes.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DeviceHandle {
+    /// The device is identified by the `_HID` and `_UID` of its object in the namespace.
+    Acpi {
+        hid: [u8; 8],
+        uid: u32,
+    },
+    Pci {
+        segment: u16,
+        bus: u8,
+        device: u8,
+        function: u8,
+    },
+    Reserved(u8),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_utils::{collect_parse_warnings, make_sdt, table_from_bytes},
+        ParseWarning,
+    };
+    use alloc::vec::Vec;
+
+    fn memory_affinity_bytes(proximity_domain: u32, base_address: u64, length: u64, flags: u32) -> Vec<u8> {
+        let mut entry = Vec::new();
+        entry.extend_from_slice(&[0x1, 40]);
+        entry.extend_from_slice(&proximity_domain.to_le_bytes());
+        entry.extend_from_slice(&[0; 2]);
+        entry.extend_from_slice(&base_address.to_le_bytes());
+        entry.extend_from_slice(&length.to_le_bytes());
+        entry.extend_from_slice(&[0; 4]);
+        entry.extend_from_slice(&flags.to_le_bytes());
+        entry.extend_from_slice(&[0; 8]);
+        entry
+    }
+
+    #[test]
+    fn test_memory_affinity_address_and_length() {
+        let bytes = memory_affinity_bytes(1, 0x0000_0001_0000_0000, 0x0000_0003_8000_0000, 0b1);
+        let entry = table_from_bytes::<MemoryAffinity>(&bytes);
+
+        assert_eq!(entry.proximity_domain(), 1);
+        assert_eq!(entry.base_address(), 0x0000_0001_0000_0000);
+        assert_eq!(entry.length(), 0x0000_0003_8000_0000);
+        assert!(entry.is_enabled());
+        assert!(!entry.is_hot_pluggable());
+    }
+
+    #[test]
+    fn test_two_node_srat() {
+        /*
+         * Laid out like the SRAT QEMU generates for `-numa node,mem=2G -numa node,mem=2G` with a CPU in each
+         * node, except that the second node's memory starts above 4GiB so the high words of its base and length
+         * differ.
+         */
+        let mut body = Vec::new();
+        body.extend_from_slice(&1u32.to_le_bytes());
+        body.extend_from_slice(&[0; 8]);
+        for (apic_id, proximity_domain) in [(0u8, 0u8), (1, 1)].iter() {
+            body.extend_from_slice(&[0x0, 16, *proximity_domain, *apic_id]);
+            body.extend_from_slice(&1u32.to_le_bytes());
+            body.extend_from_slice(&[0; 8]);
+        }
+        body.extend(memory_affinity_bytes(0, 0x0, 0xa_0000, 0b1));
+        body.extend(memory_affinity_bytes(0, 0x10_0000, 0x7ff0_0000, 0b1));
+        body.extend(memory_affinity_bytes(1, 0x1_0000_0000, 0x8000_0000, 0b1));
+        body.extend(memory_affinity_bytes(1, 0x2_0000_0000, 0x4_0000_0000, 0b11));
+
+        let table = make_sdt(b"SRAT", 3, &body);
+        let srat = table_from_bytes::<Srat>(&table);
+
+        let mut processors = Vec::new();
+        let mut memory = Vec::new();
+        for entry in srat.entries() {
+            match entry {
+                SratEntry::LocalApicAffinity(entry) => {
+                    processors.push((entry.apic_id(), entry.proximity_domain()))
+                }
+                SratEntry::MemoryAffinity(entry) => {
+                    memory.push((entry.proximity_domain(), entry.base_address(), entry.length()))
+                }
+                _ => panic!("Unexpected SRAT entry"),
+            }
+        }
+
+        assert_eq!(processors, [(0, 0), (1, 1)]);
+        assert_eq!(
+            memory,
+            [
+                (0, 0x0, 0xa_0000),
+                (0, 0x10_0000, 0x7ff0_0000),
+                (1, 0x1_0000_0000, 0x8000_0000),
+                (1, 0x2_0000_0000, 0x4_0000_0000)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_zero_length_entry() {
+        let mut body = Vec::new();
+        body.extend_from_slice(&1u32.to_le_bytes());
+        body.extend_from_slice(&[0; 8]);
+        body.extend(memory_affinity_bytes(0, 0x0, 0xa_0000, 0b1));
+        // An entry of a reserved type with a length of zero, which would otherwise be walked forever
+        body.extend_from_slice(&[0x7f, 0]);
+        body.extend(memory_affinity_bytes(0, 0x10_0000, 0x7ff0_0000, 0b1));
+
+        let table = make_sdt(b"SRAT", 3, &body);
+        let srat = table_from_bytes::<Srat>(&table);
+        let mut entries = srat.entries();
+        assert!(matches!(entries.next(), Some(SratEntry::MemoryAffinity(entry)) if entry.length() == 0xa_0000));
+        assert!(entries.next().is_none());
+        assert!(entries.next().is_none());
+
+        // A zero-length entry of a known type is also rejected
+        let mut body = Vec::new();
+        body.extend_from_slice(&1u32.to_le_bytes());
+        body.extend_from_slice(&[0; 8]);
+        body.extend_from_slice(&[0x1, 0]);
+        body.extend_from_slice(&[0; 38]);
+        let table = make_sdt(b"SRAT", 3, &body);
+        let srat = table_from_bytes::<Srat>(&table);
+        assert_eq!(srat.entries().count(), 0);
+    }
+
+    #[test]
+    fn test_unknown_entry_type_warns() {
+        let mut body = Vec::new();
+        body.extend_from_slice(&1u32.to_le_bytes());
+        body.extend_from_slice(&[0; 8]);
+        // An entry of a reserved type, which should be skipped
+        body.extend_from_slice(&[0x7f, 4, 0, 0]);
+        body.extend(memory_affinity_bytes(0, 0x10_0000, 0x7ff0_0000, 0b1));
+
+        let table = make_sdt(b"SRAT", 3, &body);
+        let srat = table_from_bytes::<Srat>(&table);
+        let (count, warnings) = collect_parse_warnings(|| srat.entries().count());
+        assert_eq!(count, 1);
+        assert_eq!(warnings, [ParseWarning::UnknownEntryType { signature: Signature::SRAT, entry_type: 0x7f }]);
+    }
+
+    #[test]
+    fn test_generic_initiator_affinity() {
+        let mut body = Vec::new();
+        body.extend_from_slice(&1u32.to_le_bytes());
+        body.extend_from_slice(&[0; 8]);
+        // A GPU at 0000:41:00.0 in domain 2
+        body.extend_from_slice(&[0x5, 32, 0, 1]);
+        body.extend_from_slice(&2u32.to_le_bytes());
+        body.extend_from_slice(&0u16.to_le_bytes());
+        body.extend_from_slice(&0x4100u16.to_le_bytes());
+        body.extend_from_slice(&[0; 12]);
+        body.extend_from_slice(&0b11u32.to_le_bytes());
+        body.extend_from_slice(&[0; 4]);
+        // An accelerator identified by its namespace object, at 0001:00:1f.3, that's disabled
+        body.extend_from_slice(&[0x5, 32, 0, 0]);
+        body.extend_from_slice(&3u32.to_le_bytes());
+        body.extend_from_slice(b"ACPI0017");
+        body.extend_from_slice(&5u32.to_le_bytes());
+        body.extend_from_slice(&[0; 4]);
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body.extend_from_slice(&[0; 4]);
+
+        let table = make_sdt(b"SRAT", 3, &body);
+        let srat = table_from_bytes::<Srat>(&table);
+        let initiators: Vec<&GenericInitiatorAffinity> = srat
+            .entries()
+            .map(|entry| match entry {
+                SratEntry::GenericInitiatorAffinity(entry) => entry,
+                _ => panic!("Unexpected SRAT entry"),
+            })
+            .collect();
+        assert_eq!(initiators.len(), 2);
+
+        assert_eq!(initiators[0].proximity_domain(), 2);
+        assert_eq!(
+            initiators[0].device_handle(),
+            DeviceHandle::Pci { segment: 0, bus: 0x41, device: 0, function: 0 }
+        );
+        assert!(initiators[0].is_enabled());
+        assert!(initiators[0].supports_architectural_transactions());
+
+        assert_eq!(initiators[1].proximity_domain(), 3);
+        assert_eq!(initiators[1].device_handle(), DeviceHandle::Acpi { hid: *b"ACPI0017", uid: 5 });
+        assert!(!initiators[1].is_enabled());
+    }
+
+    #[test]
+    fn test_pci_device_handle() {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&[0x5, 32, 0, 1]);
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&((0x1f << 3) | 3u16).to_le_bytes());
+        bytes.extend_from_slice(&[0; 20]);
+        let entry = table_from_bytes::<GenericInitiatorAffinity>(&bytes);
+        assert_eq!(entry.device_handle(), DeviceHandle::Pci { segment: 1, bus: 0, device: 0x1f, function: 3 });
+    }
+
+    #[test]
+    fn test_builder_round_trip() {
+        let template = *table_from_bytes::<SdtHeader>(&make_sdt(b"SRAT", 3, &[]));
+        let mut buffer = [0u8; 256];
+        let mut builder = SratBuilder::new(&mut buffer, template).unwrap();
+        builder
+            .add_local_apic_affinity(LocalApicAffinity::new(0, 0, 0, true))
+            .unwrap()
+            .add_local_x2apic_affinity(LocalX2ApicAffinity::new(0x1_0000, 0x0102_0304, 1, true))
+            .unwrap()
+            .add_memory_affinity(MemoryAffinity::new(0, 0x0, 0x8000_0000, true, false, false))
+            .unwrap()
+            .add_memory_affinity(MemoryAffinity::new(0x0102_0304, 0x1_0000_0000, 0x2_4000_0000, true, true, true))
+            .unwrap();
+        let table = builder.finish();
+        assert_eq!(table.len(), 48 + 16 + 24 + 40 + 40);
+
+        let srat = table_from_bytes::<Srat>(table);
+        assert_eq!({ srat.header().length } as usize, table.len());
+        srat.header().validate(Signature::SRAT).unwrap();
+        assert_eq!(srat.header().revision, 3);
+
+        let entries: Vec<SratEntry> = srat.entries().collect();
+        assert_eq!(entries.len(), 4);
+        match entries[0] {
+            SratEntry::LocalApicAffinity(entry) => {
+                assert_eq!(entry.apic_id(), 0);
+                assert_eq!(entry.proximity_domain(), 0);
+                assert!(entry.is_enabled());
+            }
+            _ => panic!("Unexpected SRAT entry"),
+        }
+        match entries[1] {
+            SratEntry::LocalX2ApicAffinity(entry) => {
+                assert_eq!(entry.x2apic_id(), 0x1_0000);
+                assert_eq!(entry.proximity_domain(), 0x0102_0304);
+                assert_eq!(entry.clock_domain(), 1);
+                assert!(entry.is_enabled());
+            }
+            _ => panic!("Unexpected SRAT entry"),
+        }
+        match entries[3] {
+            SratEntry::MemoryAffinity(entry) => {
+                assert_eq!(entry.proximity_domain(), 0x0102_0304);
+                assert_eq!(entry.base_address(), 0x1_0000_0000);
+                assert_eq!(entry.length(), 0x2_4000_0000);
+                assert!(entry.is_enabled() && entry.is_hot_pluggable() && entry.is_non_volatile());
+            }
+            _ => panic!("Unexpected SRAT entry"),
+        }
+    }
+
+    #[test]
+    fn test_builder_buffer_too_small() {
+        let template = *table_from_bytes::<SdtHeader>(&make_sdt(b"SRAT", 3, &[]));
+        assert!(matches!(SratBuilder::new(&mut [0; 40], template), Err(AcpiError::BufferTooSmall)));
+
+        let mut buffer = [0u8; 60];
+        let mut builder = SratBuilder::new(&mut buffer, template).unwrap();
+        assert!(matches!(
+            builder.add_memory_affinity(MemoryAffinity::new(0, 0, 0x1000, true, false, false)),
+            Err(AcpiError::BufferTooSmall)
+        ));
+        assert!(builder.add_local_apic_affinity(LocalApicAffinity::new(0, 0, 0, true)).is_err());
+        assert!(builder.add_local_apic_affinity(LocalApicAffinity::new(0, 0, 0, false)).is_err());
+        assert_eq!(builder.finish().len(), 48);
+    }
+}
@@ -0,0 +1,97 @@
+pub(crate) use crate::testing::{make_rsdp, make_rsdt, make_sdt, make_xsdt};
+use crate::{AcpiHandler, ParseWarning, PhysicalMapping};
+use alloc::vec::Vec;
+use core::{cell::RefCell, mem, ptr::NonNull};
+use std::sync::Mutex;
+
+/// An `AcpiHandler` for tests, which "maps" physical memory by adding a fixed offset to the physical address. This
+/// allows tables and register blocks to be built in ordinary buffers, and then handed to the library at realistic
+/// physical addresses (which matters for fields that are narrower than a pointer).
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct TestHandler {
+    offset: usize,
+}
+
+impl TestHandler {
+    /// A handler that makes the memory at `virtual_address` appear at `physical_address`.
+    pub(crate) fn with_mapping(physical_address: usize, virtual_address: *const u8) -> TestHandler {
+        TestHandler { offset: (virtual_address as usize).wrapping_sub(physical_address) }
+    }
+}
+
+impl AcpiHandler for TestHandler {
+    unsafe fn map_physical_region<T>(&self, physical_address: usize, size: usize) -> PhysicalMapping<Self, T> {
+        let virtual_address = physical_address.wrapping_add(self.offset);
+        unsafe {
+            PhysicalMapping::new(
+                physical_address,
+                NonNull::new(virtual_address as *mut T).unwrap(),
+                size,
+                size,
+                *self,
+            )
+        }
+    }
+
+    fn unmap_physical_region<T>(_region: &PhysicalMapping<Self, T>) {}
+}
+
+/// Interpret a buffer built by a test as a table of type `T`.
+pub(crate) fn table_from_bytes<T>(bytes: &[u8]) -> &T {
+    assert!(bytes.len() >= mem::size_of::<T>());
+    assert_eq!(bytes.as_ptr() as usize % mem::align_of::<T>(), 0);
+    unsafe { &*(bytes.as_ptr() as *const T) }
+}
+
+/// A buffer that tests can lay out a set of tables in, so that they can refer to each other by physical address.
+/// Tables are placed one after another, each starting on a 16-byte boundary, from `base` upwards.
+pub(crate) struct TestMemory {
+    base: usize,
+    bytes: Vec<u8>,
+}
+
+impl TestMemory {
+    pub(crate) fn new(base: usize) -> TestMemory {
+        TestMemory { base, bytes: Vec::new() }
+    }
+
+    /// Copy `bytes` into the memory, returning the physical address they were placed at.
+    pub(crate) fn add(&mut self, bytes: &[u8]) -> usize {
+        let offset = (self.bytes.len() + 15) & !15;
+        self.bytes.resize(offset, 0);
+        self.bytes.extend_from_slice(bytes);
+        self.base + offset
+    }
+
+    /// The difference between the virtual address of the memory and the physical addresses returned by `add`.
+    pub(crate) fn virtual_offset(&self) -> usize {
+        (self.bytes.as_ptr() as usize).wrapping_sub(self.base)
+    }
+
+    /// A handler that maps the memory at the physical addresses returned by `add`. The memory must not be added to
+    /// while the handler is in use.
+    pub(crate) fn handler(&self) -> TestHandler {
+        TestHandler::with_mapping(self.base, self.bytes.as_ptr())
+    }
+}
+
+std::thread_local! {
+    static PARSE_WARNINGS: RefCell<Vec<ParseWarning>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Serializes the tests that install a parse warning handler, as the handler is global.
+static PARSE_WARNING_HANDLER_LOCK: Mutex<()> = Mutex::new(());
+
+/// Run `f`, and collect the parse warnings it produces on the current thread.
+pub(crate) fn collect_parse_warnings<R>(f: impl FnOnce() -> R) -> (R, Vec<ParseWarning>) {
+    let _guard = PARSE_WARNING_HANDLER_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    PARSE_WARNINGS.with(|warnings| warnings.borrow_mut().clear());
+
+    crate::set_parse_warning_handler(Some(|warning| {
+        PARSE_WARNINGS.with(|warnings| warnings.borrow_mut().push(warning.clone()))
+    }));
+    let result = f();
+    crate::set_parse_warning_handler(None);
+
+    (result, PARSE_WARNINGS.with(|warnings| warnings.take()))
+}
@@ -0,0 +1,197 @@
+//! Support for testing code that consumes ACPI tables, without needing real firmware. Tables are built in
+//! ordinary buffers, placed at chosen physical addresses in a `PhysicalMemory`, and then accessed through the
+//! `SliceHandler` it provides. This module is only available with the `testing` feature.
+//!
+//! ```
+//! use acpi::{sdt::Signature, testing::PhysicalMemory, waet::Waet, AcpiTables};
+//!
+//! let mut memory = PhysicalMemory::new();
+//! memory.add_table(0x7ff0_0000, acpi::testing::make_sdt(b"WAET", 1, &0b10u32.to_le_bytes()));
+//! let rsdp_address = memory.add_root_tables(0xe_0000, 0x7fe0_0000);
+//!
+//! let tables = unsafe { AcpiTables::from_rsdp(memory.handler(), rsdp_address) }.unwrap();
+//! let waet = unsafe { tables.get_sdt::<Waet>(Signature::WAET) }.unwrap().unwrap();
+//! assert!(waet.pm_timer_good());
+//! ```
+
+use crate::{sdt::SdtHeader, AcpiHandler, PhysicalMapping};
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::{mem, ptr::NonNull};
+
+/// A set of regions of simulated physical memory, each starting at a chosen physical address. Use
+/// `PhysicalMemory::handler` to access them through the library.
+#[derive(Default)]
+pub struct PhysicalMemory {
+    regions: BTreeMap<usize, Vec<u8>>,
+    /// The physical addresses of the tables added with `add_table`, in the order they were added.
+    tables: Vec<usize>,
+}
+
+impl PhysicalMemory {
+    pub fn new() -> PhysicalMemory {
+        PhysicalMemory::default()
+    }
+
+    /// Place `bytes` at `physical_address`. Panics if the region overlaps one that has already been added.
+    pub fn add_region(&mut self, physical_address: usize, bytes: Vec<u8>) {
+        let end = physical_address + bytes.len();
+        let overlaps = self
+            .regions
+            .range(..end)
+            .next_back()
+            .is_some_and(|(&start, region)| start + region.len() > physical_address);
+        assert!(!overlaps, "Region at {:#x} overlaps a region that has already been added", physical_address);
+        self.regions.insert(physical_address, bytes);
+    }
+
+    /// Place the table `bytes` at `physical_address`, and include it in the root table built by
+    /// `add_root_tables`.
+    pub fn add_table(&mut self, physical_address: usize, bytes: Vec<u8>) {
+        self.add_region(physical_address, bytes);
+        self.tables.push(physical_address);
+    }
+
+    /// Build an XSDT at `xsdt_address` that points to each of the tables added with `add_table`, and a revision 2
+    /// RSDP at `rsdp_address` that points to it. Returns `rsdp_address`, for passing to `AcpiTables::from_rsdp`.
+    pub fn add_root_tables(&mut self, rsdp_address: usize, xsdt_address: usize) -> usize {
+        let xsdt = make_xsdt(&self.tables);
+        self.add_region(xsdt_address, xsdt);
+        self.add_region(rsdp_address, make_rsdp(2, 0, xsdt_address as u64));
+        rsdp_address
+    }
+
+    /// A handler that maps the regions that have been added. The memory can't be added to while the handler
+    /// is in use.
+    pub fn handler(&self) -> SliceHandler<'_> {
+        SliceHandler { memory: self }
+    }
+}
+
+/// An `AcpiHandler` that maps the regions of a `PhysicalMemory`. Mapping memory that isn't entirely within a
+/// single region panics, which makes accesses to unexpected addresses easy to catch in tests. The mapped memory
+/// must only be read.
+#[derive(Clone, Copy)]
+pub struct SliceHandler<'a> {
+    memory: &'a PhysicalMemory,
+}
+
+impl AcpiHandler for SliceHandler<'_> {
+    unsafe fn map_physical_region<T>(&self, physical_address: usize, size: usize) -> PhysicalMapping<Self, T> {
+        let (&start, region) = self
+            .memory
+            .regions
+            .range(..=physical_address)
+            .next_back()
+            .filter(|(&start, region)| physical_address + size <= start + region.len())
+            .unwrap_or_else(|| {
+                panic!("Tried to map {:#x} bytes at {:#x}, which hasn't been added", size, physical_address)
+            });
+
+        let virtual_start = unsafe { region.as_ptr().add(physical_address - start) } as *mut T;
+        unsafe { PhysicalMapping::new(physical_address, NonNull::new(virtual_start).unwrap(), size, size, *self) }
+    }
+
+    fn unmap_physical_region<T>(_region: &PhysicalMapping<Self, T>) {}
+}
+
+/// Build the bytes of an SDT with the given signature and revision, and with `body` following the header. The
+/// header's `length` and `checksum` fields are filled in so that the table is valid.
+pub fn make_sdt(signature: &[u8; 4], revision: u8, body: &[u8]) -> Vec<u8> {
+    let mut table = Vec::with_capacity(mem::size_of::<SdtHeader>() + body.len());
+    table.extend_from_slice(signature);
+    table.extend_from_slice(&((mem::size_of::<SdtHeader>() + body.len()) as u32).to_le_bytes());
+    table.push(revision);
+    table.push(0); // Checksum, filled in below
+    table.extend_from_slice(b"OEMID ");
+    table.extend_from_slice(b"OEMTABLE");
+    table.extend_from_slice(&1u32.to_le_bytes()); // OEM revision
+    table.extend_from_slice(b"TEST"); // Creator ID
+    table.extend_from_slice(&1u32.to_le_bytes()); // Creator revision
+    table.extend_from_slice(body);
+
+    let sum = table.iter().fold(0u8, |sum, &byte| sum.wrapping_add(byte));
+    table[9] = 0u8.wrapping_sub(sum);
+    table
+}
+
+/// Build an RSDT that points to the tables at each of `addresses`, in order.
+pub fn make_rsdt(addresses: &[usize]) -> Vec<u8> {
+    let entries: Vec<u8> = addresses.iter().flat_map(|&address| (address as u32).to_le_bytes()).collect();
+    make_sdt(b"RSDT", 1, &entries)
+}
+
+/// Build an XSDT that points to the tables at each of `addresses`, in order.
+pub fn make_xsdt(addresses: &[usize]) -> Vec<u8> {
+    let entries: Vec<u8> = addresses.iter().flat_map(|&address| (address as u64).to_le_bytes()).collect();
+    make_sdt(b"XSDT", 1, &entries)
+}
+
+/// Build the bytes of a valid RSDP with the given revision and root table addresses. The extended fields are only
+/// included for revision 2 and greater.
+pub fn make_rsdp(revision: u8, rsdt_address: u32, xsdt_address: u64) -> Vec<u8> {
+    let mut rsdp = Vec::new();
+    rsdp.extend_from_slice(b"RSD PTR ");
+    rsdp.push(0); // Checksum, filled in below
+    rsdp.extend_from_slice(b"OEMID ");
+    rsdp.push(revision);
+    rsdp.extend_from_slice(&rsdt_address.to_le_bytes());
+    rsdp[8] = 0u8.wrapping_sub(rsdp.iter().fold(0u8, |sum, &byte| sum.wrapping_add(byte)));
+
+    if revision >= 2 {
+        rsdp.extend_from_slice(&36u32.to_le_bytes());
+        rsdp.extend_from_slice(&xsdt_address.to_le_bytes());
+        rsdp.extend_from_slice(&[0; 4]); // Extended checksum, filled in below
+        rsdp[32] = 0u8.wrapping_sub(rsdp.iter().fold(0u8, |sum, &byte| sum.wrapping_add(byte)));
+    }
+    rsdp
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{hpet::HpetTable, sdt::Signature, AcpiTables};
+
+    #[test]
+    fn test_from_rsdp() {
+        let mut memory = PhysicalMemory::new();
+        let mut dsdt_address = [0; 240];
+        dsdt_address[4..8].copy_from_slice(&0x7ff1_0000u32.to_le_bytes());
+        memory.add_table(0x7ff0_0000, make_sdt(b"FACP", 6, &dsdt_address));
+        memory.add_region(0x7ff1_0000, make_sdt(b"DSDT", 2, &[0x10, 0x06, b'\\', b'_', b'S', b'B', b'_']));
+        memory.add_table(0x7ff0_1000, make_sdt(b"HPET", 1, &[0; 20]));
+        let rsdp_address = memory.add_root_tables(0xe_0000, 0x7fe0_0000);
+
+        let tables = unsafe { AcpiTables::from_rsdp(memory.handler(), rsdp_address) }.unwrap();
+        assert_eq!(tables.revision(), 2);
+        assert!(tables.uses_xsdt());
+        assert_eq!(
+            tables.headers().map(|header| header.signature).collect::<Vec<_>>(),
+            [Signature::FADT, Signature::HPET]
+        );
+
+        let hpet = unsafe { tables.get_sdt::<HpetTable>(Signature::HPET) }.unwrap().unwrap();
+        assert_eq!(hpet.physical_start(), 0x7ff0_1000);
+        assert_eq!(tables.dsdt().unwrap().bytecode().len(), 7);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_unmapped_access() {
+        let mut memory = PhysicalMemory::new();
+        memory.add_table(0x7ff0_0000, make_sdt(b"HPET", 1, &[0; 20]));
+        // The XSDT's second entry points past the end of the only table
+        memory.add_region(0x7fe0_0000, make_xsdt(&[0x7ff0_0000, 0x7ff0_0038]));
+        memory.add_region(0xe_0000, make_rsdp(2, 0, 0x7fe0_0000));
+
+        let tables = unsafe { AcpiTables::from_rsdp(memory.handler(), 0xe_0000) }.unwrap();
+        tables.headers().for_each(drop);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_overlapping_regions() {
+        let mut memory = PhysicalMemory::new();
+        memory.add_region(0x1000, alloc::vec![0; 0x100]);
+        memory.add_region(0x10f0, alloc::vec![0; 0x100]);
+    }
+}
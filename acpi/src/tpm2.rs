@@ -0,0 +1,150 @@
+use crate::{sdt::SdtHeader, AcpiTable};
+use core::{mem, slice};
+
+/// Represents the Trusted Platform Module 2 table (TPM2), defined by the TCG ACPI Specification. This describes how
+/// to talk to the platform's TPM 2.0, and (in revision 4 tables) where the firmware's TCG event log is.
+#[repr(C, packed)]
+pub struct Tpm2 {
+    header: SdtHeader,
+    platform_class: u16,
+    _reserved: u16,
+    control_area_address: u64,
+    start_method: u32,
+    /*
+     * In revision 4 tables, this may be followed by:
+     *    - 12 bytes of parameters that are specific to the start method
+     *    - The minimum length of the log area (u32)
+     *    - The physical address of the log area (u64)
+     */
+}
+
+impl AcpiTable for Tpm2 {
+    fn header(&self) -> &SdtHeader {
+        &self.header
+    }
+}
+
+// The layout of the optional fields, relative to the end of the fixed part of the table
+const START_METHOD_PARAMETERS_LENGTH: usize = 12;
+const LOG_AREA_MINIMUM_LENGTH_OFFSET: usize = 12;
+const LOG_AREA_START_ADDRESS_OFFSET: usize = 16;
+const OPTIONAL_FIELDS_LENGTH: usize = 24;
+
+impl Tpm2 {
+    pub fn start_method(&self) -> StartMethod {
+        match self.start_method {
+            2 => StartMethod::Acpi,
+            6 => StartMethod::Tis,
+            7 => StartMethod::Crb,
+            8 => StartMethod::CrbWithAcpi,
+            11 => StartMethod::CrbWithArmSmc,
+            12 => StartMethod::FifoOverI2c,
+            other => StartMethod::Reserved(other),
+        }
+    }
+
+    /// The physical address of the control area. For the CRB start methods, this is the CRB's control area; for
+    /// others, it's the base of the TPM's registers, or is unused.
+    pub fn control_area_address(&self) -> u64 {
+        self.control_area_address
+    }
+
+    /// The parameters that are specific to the start method (e.g. the SMC function ID used by the Arm SMC start
+    /// method). Returns `None` if the table doesn't have them.
+    pub fn start_method_parameters(&self) -> Option<&[u8]> {
+        self.optional_fields().map(|fields| &fields[..START_METHOD_PARAMETERS_LENGTH])
+    }
+
+    /// The log area that the firmware records the TCG event log in, as its physical address and minimum length.
+    /// Returns `None` if the table doesn't describe a log area.
+    pub fn log_area(&self) -> Option<(u64, u32)> {
+        let fields = self.optional_fields()?;
+        let mut length = [0; 4];
+        length.copy_from_slice(&fields[LOG_AREA_MINIMUM_LENGTH_OFFSET..LOG_AREA_START_ADDRESS_OFFSET]);
+        let mut address = [0; 8];
+        address.copy_from_slice(&fields[LOG_AREA_START_ADDRESS_OFFSET..OPTIONAL_FIELDS_LENGTH]);
+        Some((u64::from_le_bytes(address), u32::from_le_bytes(length)))
+    }
+
+    /// The fields that follow the fixed part of the table, if revision 4 or later of the table is used and it's
+    /// long enough to contain them.
+    fn optional_fields(&self) -> Option<&[u8]> {
+        let length = (self.header.length as usize).checked_sub(mem::size_of::<Tpm2>())?;
+        if self.header.revision < 4 || length < OPTIONAL_FIELDS_LENGTH {
+            return None;
+        }
+
+        Some(unsafe {
+            slice::from_raw_parts(
+                (self as *const Tpm2 as *const u8).add(mem::size_of::<Tpm2>()),
+                OPTIONAL_FIELDS_LENGTH,
+            )
+        })
+    }
+}
+
+/// How the OS tells the TPM to start processing a command.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StartMethod {
+    /// Through the `_DSM` method of the TPM's object in the namespace.
+    Acpi,
+    /// Through the memory-mapped FIFO interface defined by the TCG PC Client Platform TPM Profile (the "TIS").
+    Tis,
+    /// Through the Command Response Buffer interface.
+    Crb,
+    /// Through the Command Response Buffer interface, and then the `_DSM` method.
+    CrbWithAcpi,
+    /// Through the Command Response Buffer interface, and then an Arm Secure Monitor Call.
+    CrbWithArmSmc,
+    FifoOverI2c,
+    Reserved(u32),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{make_sdt, table_from_bytes};
+    use alloc::vec::Vec;
+
+    /// Build a revision 4 TPM2 using the CRB start method, as found on many Intel machines with a firmware TPM.
+    fn make_crb_tpm2() -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&0u16.to_le_bytes());
+        body.extend_from_slice(&0u16.to_le_bytes());
+        body.extend_from_slice(&0xfed4_0040u64.to_le_bytes());
+        body.extend_from_slice(&7u32.to_le_bytes());
+        body.extend_from_slice(&[0; 12]);
+        body.extend_from_slice(&0x1_0000u32.to_le_bytes());
+        body.extend_from_slice(&0x7a5e_0000u64.to_le_bytes());
+        make_sdt(b"TPM2", 4, &body)
+    }
+
+    #[test]
+    fn test_crb_tpm2() {
+        let table = make_crb_tpm2();
+        let tpm2 = table_from_bytes::<Tpm2>(&table);
+        assert_eq!(tpm2.start_method(), StartMethod::Crb);
+        assert_eq!(tpm2.control_area_address(), 0xfed4_0040);
+        assert_eq!(tpm2.start_method_parameters(), Some(&[0; 12][..]));
+        assert_eq!(tpm2.log_area(), Some((0x7a5e_0000, 0x1_0000)));
+    }
+
+    #[test]
+    fn test_without_optional_fields() {
+        // A revision 3 table, which doesn't have the log area
+        let mut table = make_crb_tpm2();
+        table.truncate(52);
+        table[4..8].copy_from_slice(&52u32.to_le_bytes());
+        table[8] = 3;
+        let tpm2 = table_from_bytes::<Tpm2>(&table);
+        assert_eq!(tpm2.start_method(), StartMethod::Crb);
+        assert_eq!(tpm2.start_method_parameters(), None);
+        assert_eq!(tpm2.log_area(), None);
+
+        // A revision 4 table that's been cut short
+        let mut table = make_crb_tpm2();
+        table[4..8].copy_from_slice(&64u32.to_le_bytes());
+        let tpm2 = table_from_bytes::<Tpm2>(&table);
+        assert_eq!(tpm2.log_area(), None);
+    }
+}
@@ -0,0 +1,82 @@
+use crate::{sdt::SdtHeader, AcpiTable};
+use bit_field::BitField;
+
+/// Represents the Windows ACPI Emulated Devices Table (WAET). This is provided by hypervisors to say that some
+/// of the devices they emulate don't need the workarounds that real hardware does, which lets the OS avoid slow
+/// (and, under emulation, expensive) extra register accesses.
+#[repr(C, packed)]
+pub struct Waet {
+    header: SdtHeader,
+    emulated_device_flags: u32,
+}
+
+impl AcpiTable for Waet {
+    fn header(&self) -> &SdtHeader {
+        &self.header
+    }
+}
+
+impl Waet {
+    /// If true, the RTC doesn't need its status register checked for an update in progress around each read.
+    pub fn rtc_good(&self) -> bool {
+        { self.emulated_device_flags }.get_bit(0)
+    }
+
+    /// If true, a single read of the ACPI PM Timer returns a reliable value, and it doesn't need to be read
+    /// repeatedly to check it.
+    pub fn pm_timer_good(&self) -> bool {
+        { self.emulated_device_flags }.get_bit(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fadt::Fadt,
+        platform::PmTimer,
+        test_utils::{make_sdt, table_from_bytes},
+    };
+    use alloc::vec::Vec;
+
+    /// Build a FADT that has a 32-bit PM Timer at port `0x608`, as QEMU's does.
+    fn make_fadt() -> Vec<u8> {
+        let mut body = [0; 240];
+        body[40..44].copy_from_slice(&0x608u32.to_le_bytes());
+        body[55] = 4;
+        body[76..80].copy_from_slice(&(1u32 << 8).to_le_bytes());
+        make_sdt(b"FACP", 6, &body)
+    }
+
+    #[test]
+    fn test_flags() {
+        let table = make_sdt(b"WAET", 1, &0b10u32.to_le_bytes());
+        let waet = table_from_bytes::<Waet>(&table);
+        assert!(!waet.rtc_good());
+        assert!(waet.pm_timer_good());
+
+        let table = make_sdt(b"WAET", 1, &0b11u32.to_le_bytes());
+        let waet = table_from_bytes::<Waet>(&table);
+        assert!(waet.rtc_good());
+        assert!(waet.pm_timer_good());
+    }
+
+    #[test]
+    fn test_pm_timer_read_verification() {
+        let fadt = make_fadt();
+        let fadt = table_from_bytes::<Fadt>(&fadt);
+
+        let pm_timer = PmTimer::new(fadt, None).unwrap().unwrap();
+        assert_eq!(pm_timer.base.address, 0x608);
+        assert!(pm_timer.supports_32bit);
+        assert!(pm_timer.needs_read_verification);
+
+        let waet = make_sdt(b"WAET", 1, &0b10u32.to_le_bytes());
+        let pm_timer = PmTimer::new(fadt, Some(table_from_bytes::<Waet>(&waet))).unwrap().unwrap();
+        assert!(!pm_timer.needs_read_verification);
+
+        let waet = make_sdt(b"WAET", 1, &0b01u32.to_le_bytes());
+        let pm_timer = PmTimer::new(fadt, Some(table_from_bytes::<Waet>(&waet))).unwrap().unwrap();
+        assert!(pm_timer.needs_read_verification);
+    }
+}
@@ -0,0 +1,275 @@
+use crate::{
+    platform::address::{GenericAddress, RawGenericAddress},
+    sdt::SdtHeader,
+    AcpiError,
+    AcpiTable,
+};
+use bit_field::BitField;
+use core::{mem, slice};
+
+/// Represents the Watchdog Action Table (WDAT). This describes a hardware watchdog timer, and the actions that
+/// can be performed on it. Each action is made up of one or more instruction entries, which should be performed
+/// in the order they appear in the table. This means an OS can drive the watchdog without a specific driver for
+/// it.
+#[repr(C, packed)]
+pub struct Wdat {
+    header: SdtHeader,
+    watchdog_header_length: u32,
+    pci_segment: u16,
+    pci_bus: u8,
+    pci_device: u8,
+    pci_function: u8,
+    _reserved1: [u8; 3],
+    timer_period: u32,
+    max_count: u32,
+    min_count: u32,
+    watchdog_flags: u8,
+    _reserved2: [u8; 3],
+    number_of_entries: u32,
+    // Followed by `number_of_entries` entries with format `WatchdogInstructionEntry`
+}
+
+impl AcpiTable for Wdat {
+    fn header(&self) -> &SdtHeader {
+        &self.header
+    }
+}
+
+impl Wdat {
+    /// The location of the watchdog, if it's a PCI device, as `(segment, bus, device, function)`. Returns `None`
+    /// if the watchdog isn't a PCI device.
+    pub fn pci_location(&self) -> Option<(u16, u8, u8, u8)> {
+        if self.pci_segment == 0xff && self.pci_bus == 0xff && self.pci_device == 0xff && self.pci_function == 0xff
+        {
+            None
+        } else {
+            Some((self.pci_segment, self.pci_bus, self.pci_device, self.pci_function))
+        }
+    }
+
+    /// The length of each count of the watchdog, in milliseconds.
+    pub fn timer_period(&self) -> u32 {
+        self.timer_period
+    }
+
+    /// The maximum count that the watchdog can be set to, in units of `timer_period`.
+    pub fn max_count(&self) -> u32 {
+        self.max_count
+    }
+
+    /// The minimum count that the watchdog can be set to, in units of `timer_period`.
+    pub fn min_count(&self) -> u32 {
+        self.min_count
+    }
+
+    /// If true, the watchdog is enabled, and the OS can use it. Otherwise, the table should be ignored.
+    pub fn is_enabled(&self) -> bool {
+        self.watchdog_flags.get_bit(0)
+    }
+
+    /// If true, the watchdog is stopped while the platform is in a sleep state.
+    pub fn stopped_in_sleep_state(&self) -> bool {
+        self.watchdog_flags.get_bit(7)
+    }
+
+    /// Get the instruction entries. If the table is too short to hold the number of entries it claims to, only the
+    /// entries that fit within it are returned.
+    pub fn instruction_entries(&self) -> &[WatchdogInstructionEntry] {
+        let available = (self.header.length as usize).saturating_sub(mem::size_of::<Wdat>())
+            / mem::size_of::<WatchdogInstructionEntry>();
+        let num_entries = usize::min(self.number_of_entries as usize, available);
+
+        unsafe {
+            let pointer =
+                (self as *const Wdat as *const u8).add(mem::size_of::<Wdat>()) as *const WatchdogInstructionEntry;
+            slice::from_raw_parts(pointer, num_entries)
+        }
+    }
+
+    /// Iterate over the instruction entries that make up the given action, in the order they should be
+    /// performed.
+    pub fn action_entries(&self, action: WatchdogAction) -> impl Iterator<Item = &WatchdogInstructionEntry> {
+        self.instruction_entries().iter().filter(move |entry| entry.action() == action)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum WatchdogAction {
+    /// Restart the countdown of the watchdog, so that it does not expire.
+    Reset,
+    QueryCurrentCountdownPeriod,
+    QueryCountdownPeriod,
+    SetCountdownPeriod,
+    QueryRunningState,
+    SetRunningState,
+    QueryStoppedState,
+    SetStoppedState,
+    QueryReboot,
+    SetReboot,
+    QueryShutdown,
+    SetShutdown,
+    QueryWatchdogStatus,
+    SetWatchdogStatus,
+    Reserved(u8),
+}
+
+impl From<u8> for WatchdogAction {
+    fn from(action: u8) -> Self {
+        match action {
+            0x01 => WatchdogAction::Reset,
+            0x04 => WatchdogAction::QueryCurrentCountdownPeriod,
+            0x05 => WatchdogAction::QueryCountdownPeriod,
+            0x06 => WatchdogAction::SetCountdownPeriod,
+            0x08 => WatchdogAction::QueryRunningState,
+            0x09 => WatchdogAction::SetRunningState,
+            0x0a => WatchdogAction::QueryStoppedState,
+            0x0b => WatchdogAction::SetStoppedState,
+            0x0e => WatchdogAction::QueryReboot,
+            0x0f => WatchdogAction::SetReboot,
+            0x10 => WatchdogAction::QueryShutdown,
+            0x11 => WatchdogAction::SetShutdown,
+            0x20 => WatchdogAction::QueryWatchdogStatus,
+            0x21 => WatchdogAction::SetWatchdogStatus,
+            other => WatchdogAction::Reserved(other),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum WatchdogInstruction {
+    /// Read the register, and compare the (masked) result with `value`.
+    ReadValue,
+    /// Read the register, and return the (masked) result as the value of the countdown.
+    ReadCountdown,
+    /// Write `value` to the register.
+    WriteValue,
+    /// Write the countdown value given by the OS to the register.
+    WriteCountdown,
+    Reserved(u8),
+}
+
+#[derive(Clone, Copy, Debug)]
+#[repr(C, packed)]
+pub struct WatchdogInstructionEntry {
+    watchdog_action: u8,
+    instruction_flags: u8,
+    _reserved: u16,
+    register_region: RawGenericAddress,
+    value: u32,
+    mask: u32,
+}
+
+impl WatchdogInstructionEntry {
+    pub fn action(&self) -> WatchdogAction {
+        WatchdogAction::from(self.watchdog_action)
+    }
+
+    pub fn instruction(&self) -> WatchdogInstruction {
+        match self.instruction_flags.get_bits(0..7) {
+            0 => WatchdogInstruction::ReadValue,
+            1 => WatchdogInstruction::ReadCountdown,
+            2 => WatchdogInstruction::WriteValue,
+            3 => WatchdogInstruction::WriteCountdown,
+            other => WatchdogInstruction::Reserved(other),
+        }
+    }
+
+    /// If true, the bits of the register outside of `mask` must be preserved when it's written to. This means
+    /// the register needs to be read, and the new value merged into it, before being written back.
+    pub fn preserve_register(&self) -> bool {
+        self.instruction_flags.get_bit(7)
+    }
+
+    pub fn register(&self) -> Result<GenericAddress, AcpiError> {
+        GenericAddress::from_raw(self.register_region)
+    }
+
+    pub fn value(&self) -> u32 {
+        self.value
+    }
+
+    pub fn mask(&self) -> u32 {
+        self.mask
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        platform::address::AddressSpace,
+        test_utils::{make_sdt, table_from_bytes},
+    };
+    use alloc::vec::Vec;
+
+    fn entry_bytes(action: u8, instruction: u8, port: u16, value: u32, mask: u32) -> Vec<u8> {
+        let mut entry = Vec::new();
+        entry.extend_from_slice(&[action, instruction, 0, 0]);
+        entry.extend_from_slice(&[0x01, 16, 0, 2]);
+        entry.extend_from_slice(&(port as u64).to_le_bytes());
+        entry.extend_from_slice(&value.to_le_bytes());
+        entry.extend_from_slice(&mask.to_le_bytes());
+        entry
+    }
+
+    /// Build a WDAT for a TCO-style watchdog, with a period of 600ms per count.
+    fn make_wdat(number_of_entries: u32, entries: &[u8]) -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&32u32.to_le_bytes());
+        body.extend_from_slice(&0xffu16.to_le_bytes());
+        body.extend_from_slice(&[0xff, 0xff, 0xff, 0, 0, 0]);
+        body.extend_from_slice(&600u32.to_le_bytes());
+        body.extend_from_slice(&0x3ffu32.to_le_bytes());
+        body.extend_from_slice(&2u32.to_le_bytes());
+        body.extend_from_slice(&[0b1000_0001, 0, 0, 0]);
+        body.extend_from_slice(&number_of_entries.to_le_bytes());
+        body.extend_from_slice(entries);
+        make_sdt(b"WDAT", 1, &body)
+    }
+
+    #[test]
+    fn test_actions() {
+        let mut entries = Vec::new();
+        entries.extend(entry_bytes(0x06, 0x83, 0x412, 0, 0x3ff));
+        entries.extend(entry_bytes(0x01, 0x82, 0x400, 0x1, 0x1));
+        entries.extend(entry_bytes(0x06, 0x02, 0x404, 0x8, 0x8));
+        let table = make_wdat(3, &entries);
+        let wdat = table_from_bytes::<Wdat>(&table);
+
+        assert_eq!(wdat.pci_location(), None);
+        assert_eq!(wdat.timer_period(), 600);
+        assert_eq!(wdat.max_count(), 0x3ff);
+        assert_eq!(wdat.min_count(), 2);
+        assert!(wdat.is_enabled());
+        assert!(wdat.stopped_in_sleep_state());
+        assert_eq!(wdat.instruction_entries().len(), 3);
+
+        let set_countdown: Vec<&WatchdogInstructionEntry> =
+            wdat.action_entries(WatchdogAction::SetCountdownPeriod).collect();
+        assert_eq!(set_countdown.len(), 2);
+        assert_eq!(set_countdown[0].instruction(), WatchdogInstruction::WriteCountdown);
+        assert!(set_countdown[0].preserve_register());
+        let register = set_countdown[0].register().unwrap();
+        assert_eq!(register.address_space, AddressSpace::SystemIo);
+        assert_eq!(register.bit_width, 16);
+        assert_eq!(register.address, 0x412);
+        assert_eq!(set_countdown[0].mask(), 0x3ff);
+        assert_eq!(set_countdown[1].instruction(), WatchdogInstruction::WriteValue);
+        assert!(!set_countdown[1].preserve_register());
+        assert_eq!(set_countdown[1].value(), 0x8);
+
+        let reset: Vec<&WatchdogInstructionEntry> = wdat.action_entries(WatchdogAction::Reset).collect();
+        assert_eq!(reset.len(), 1);
+        assert_eq!(reset[0].instruction(), WatchdogInstruction::WriteValue);
+        assert_eq!(reset[0].register().unwrap().address, 0x400);
+        assert_eq!(reset[0].value(), 0x1);
+        assert_eq!(reset[0].mask(), 0x1);
+    }
+
+    #[test]
+    fn test_truncated_entries() {
+        let table = make_wdat(4, &entry_bytes(0x01, 0x82, 0x400, 0x1, 0x1));
+        let wdat = table_from_bytes::<Wdat>(&table);
+        assert_eq!(wdat.instruction_entries().len(), 1);
+    }
+}
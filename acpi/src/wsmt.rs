@@ -0,0 +1,54 @@
+use crate::{sdt::SdtHeader, AcpiTable};
+use bit_field::BitField;
+
+/// Represents the Windows SMM Security Mitigations Table (WSMT). This is used by firmware to say which of a set of
+/// protections against attacks on System Management Mode (SMM) it has implemented.
+#[repr(C, packed)]
+pub struct Wsmt {
+    header: SdtHeader,
+    protection_flags: u32,
+}
+
+impl AcpiTable for Wsmt {
+    fn header(&self) -> &SdtHeader {
+        &self.header
+    }
+}
+
+impl Wsmt {
+    /// If true, the buffers that SMI handlers use to communicate with the OS are at fixed locations, which are
+    /// reported to the OS as firmware-reserved memory, and the handlers check that the buffers they're given are
+    /// within them.
+    pub fn fixed_comm_buffers(&self) -> bool {
+        { self.protection_flags }.get_bit(0)
+    }
+
+    /// If true, SMI handlers also check that any pointers found within the communication buffers point into the
+    /// fixed buffers. This is only meaningful if `fixed_comm_buffers` is also set.
+    pub fn comm_buffer_nested_ptr_protection(&self) -> bool {
+        { self.protection_flags }.get_bit(1)
+    }
+
+    /// If true, the firmware has locked down the system resources (e.g. the I/O ports and MMIO regions that
+    /// control SMM) so that they can't be reconfigured by the OS.
+    pub fn system_resource_protection(&self) -> bool {
+        { self.protection_flags }.get_bit(2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{make_sdt, table_from_bytes};
+
+    #[test]
+    fn test_flags() {
+        for flags in 0..8u32 {
+            let table = make_sdt(b"WSMT", 1, &flags.to_le_bytes());
+            let wsmt = table_from_bytes::<Wsmt>(&table);
+            assert_eq!(wsmt.fixed_comm_buffers(), flags & 0b001 != 0);
+            assert_eq!(wsmt.comm_buffer_nested_ptr_protection(), flags & 0b010 != 0);
+            assert_eq!(wsmt.system_resource_protection(), flags & 0b100 != 0);
+        }
+    }
+}
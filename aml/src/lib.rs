@@ -76,6 +76,16 @@ use value::{AmlType, Args};
 /// what this is actually used for, but this is ours.
 pub const AML_INTERPRETER_REVISION: u64 = 0;
 
+/// The default maximum depth that control method invocations can be nested to. The ACPI spec does not bound this,
+/// but a buggy or malicious table that recurses forever would otherwise overflow the stack. This can be changed
+/// with [`AmlContext::set_max_method_depth`].
+///
+/// Each nested invocation uses roughly 4KiB of stack in a release build, and 20KiB in a debug build (on x86_64),
+/// plus more for methods with deeply nested terms. The default is chosen so the interpreter fits in a 256KiB
+/// stack in a release build, and a 1MiB stack in a debug build. If you run it on a smaller stack, lower the limit
+/// to match.
+pub const DEFAULT_MAX_METHOD_DEPTH: usize = 32;
+
 /// Describes how much debug information the parser should emit. Set the "maximum" expected verbosity in
 /// the context's `debug_verbosity` - everything will be printed that is less or equal in 'verbosity'.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
@@ -132,6 +142,13 @@ pub struct AmlContext {
 
     pub namespace: Namespace,
     method_context: Option<MethodContext>,
+    /// How many control methods are currently being executed, including ones that are waiting for an invocation
+    /// of another method to return.
+    method_depth: usize,
+    max_method_depth: usize,
+    /// The revision of the DSDT. Definition blocks with a revision below `2` use 32-bit integers, and
+    /// later ones use 64-bit integers.
+    dsdt_revision: u8,
 
     /*
      * These track the state of the context while it's parsing an AML table.
@@ -149,6 +166,9 @@ impl AmlContext {
             handler,
             namespace: Namespace::new(),
             method_context: None,
+            method_depth: 0,
+            max_method_depth: DEFAULT_MAX_METHOD_DEPTH,
+            dsdt_revision: 2,
 
             current_scope: AmlName::root(),
             scope_indent: 0,
@@ -159,6 +179,36 @@ impl AmlContext {
         context
     }
 
+    /// Set the maximum depth that control method invocations can be nested to before
+    /// `AmlError::RecursionLimitExceeded` is produced. Defaults to [`DEFAULT_MAX_METHOD_DEPTH`].
+    pub fn set_max_method_depth(&mut self, max_method_depth: usize) {
+        self.max_method_depth = max_method_depth;
+    }
+
+    /// Set the revision of the DSDT, which decides whether AML integers are 32 or 64 bits wide. This should be
+    /// set before any tables are parsed. Defaults to `2`, which uses 64-bit integers.
+    pub fn set_dsdt_revision(&mut self, revision: u8) {
+        self.dsdt_revision = revision;
+    }
+
+    /// The size of an AML integer, in bytes.
+    pub(crate) fn integer_size(&self) -> usize {
+        if self.dsdt_revision < 2 {
+            4
+        } else {
+            8
+        }
+    }
+
+    /// A mask of the bits of a `u64` that fit into an AML integer.
+    pub(crate) fn integer_mask(&self) -> u64 {
+        if self.dsdt_revision < 2 {
+            u32::MAX as u64
+        } else {
+            u64::MAX
+        }
+    }
+
     pub fn parse_table(&mut self, stream: &[u8]) -> Result<(), AmlError> {
         if stream.len() == 0 {
             return Err(AmlError::UnexpectedEndOfStream);
@@ -184,6 +234,11 @@ impl AmlContext {
 
         match self.namespace.get_by_path(path)?.clone() {
             AmlValue::Method { flags, code } => {
+                if self.method_depth >= self.max_method_depth {
+                    error!("Exceeded maximum method depth ({}) invoking {}", self.max_method_depth, path);
+                    return Err(AmlError::RecursionLimitExceeded);
+                }
+
                 /*
                  * First, set up the state we expect to enter the method with, but clearing local
                  * variables to "null" and setting the arguments. Save the current method state and scope, so if we're
@@ -191,6 +246,7 @@ impl AmlContext {
                  */
                 let old_context = mem::replace(&mut self.method_context, Some(MethodContext::new(args)));
                 let old_scope = mem::replace(&mut self.current_scope, path.clone());
+                self.method_depth += 1;
 
                 /*
                  * Create a namespace level to store local objects created by the invocation.
@@ -227,15 +283,20 @@ impl AmlContext {
                  */
                 // TODO: this should also remove objects created by the method outside the method's scope, if they
                 // weren't statically created. This is harder.
-                self.namespace.remove_level(path.clone())?;
+                let remove_result = self.namespace.remove_level(path.clone());
 
                 /*
-                 * Restore the old state.
+                 * Restore the old state. We do this before propagating any errors, so the context is left
+                 * consistent if an error unwinds through several nested invocations. An error produced by the
+                 * method itself is more useful than one produced tearing down its level, so it takes priority.
                  */
                 self.method_context = old_context;
                 self.current_scope = old_scope;
+                self.method_depth -= 1;
 
-                return_value
+                let return_value = return_value?;
+                remove_result?;
+                Ok(return_value)
             }
 
             /*
@@ -761,6 +822,9 @@ pub enum AmlError {
     InvalidArgAccess(ArgNum),
     /// Produced when a method accesses a local that it has not stored into.
     InvalidLocalAccess(LocalNum),
+    /// Produced when invoking a control method would nest method invocations deeper than the context's maximum
+    /// method depth. This usually means the AML recurses without bound.
+    RecursionLimitExceeded,
 
     /*
      * Errors produced parsing the PCI routing tables (_PRT objects).
@@ -802,4 +866,25 @@ mod tests {
         fn test_send_sync<T: Send + Sync>() {}
         test_send_sync::<AmlContext>();
     }
+
+    #[test]
+    fn test_recursion_limit() {
+        /*
+         * This uses the default limit, so it also checks that the limit is hit before the test thread's stack
+         * overflows.
+         */
+        let mut context = crate::test_utils::make_test_context();
+
+        /*
+         * Method(TST, 0) {
+         *     TST()
+         * }
+         */
+        context.parse_table(&[0x14, 0x0a, b'T', b'S', b'T', b'_', 0x00, b'T', b'S', b'T', b'_']).unwrap();
+
+        assert!(matches!(
+            context.invoke_method(&AmlName::from_str("\\TST").unwrap(), Args::default()),
+            Err(AmlError::RecursionLimitExceeded)
+        ));
+    }
 }
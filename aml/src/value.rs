@@ -249,7 +249,7 @@ impl AmlValue {
 
     pub fn as_integer(&self, context: &AmlContext) -> Result<u64, AmlError> {
         match self {
-            AmlValue::Integer(value) => Ok(*value),
+            AmlValue::Integer(value) => Ok(*value & context.integer_mask()),
 
             AmlValue::Buffer(ref bytes) => {
                 /*
@@ -257,9 +257,12 @@ impl AmlValue {
                  * byte as the least significant byte of the integer. A zero-length buffer is
                  * illegal." - §19.6.140
                  *
+                 * If integers are 32 bits wide, only the first 4 bytes are used.
+                 *
                  * XXX: Buffers with length `0` appear in real tables, so we return `0` for them.
                  */
-                let bytes = if bytes.len() > 8 { &bytes[0..8] } else { bytes };
+                let integer_size = context.integer_size();
+                let bytes = if bytes.len() > integer_size { &bytes[0..integer_size] } else { bytes };
 
                 Ok(bytes.iter().rev().fold(0: u64, |mut i, &popped| {
                     i <<= 8;
@@ -268,6 +271,19 @@ impl AmlValue {
                 }))
             }
 
+            AmlValue::String(ref string) => {
+                /*
+                 * The string is interpreted as a hexadecimal number, starting from its leftmost character and
+                 * stopping at the first character that isn't a hex digit. Only as many characters as fit in an
+                 * integer (16, or 8 for 32-bit integers) are converted. - §19.3.5.7
+                 */
+                Ok(string
+                    .chars()
+                    .map_while(|c| c.to_digit(16))
+                    .take(context.integer_size() * 2)
+                    .fold(0u64, |value, digit| (value << 4) | digit as u64))
+            }
+
             /*
              * Read from a field. This can return either a `Buffer` or an `Integer`, so we make sure to call
              * `as_integer` on the result.
@@ -281,7 +297,23 @@ impl AmlValue {
     pub fn as_buffer(&self, context: &AmlContext) -> Result<Vec<u8>, AmlError> {
         match self {
             AmlValue::Buffer(ref bytes) => Ok(bytes.clone()),
-            // TODO: implement conversion of String and Integer to Buffer
+            /*
+             * Integers are stored in little-endian order, in a buffer that is as long as an integer.
+             */
+            AmlValue::Integer(value) => Ok(value.to_le_bytes()[0..context.integer_size()].to_vec()),
+            /*
+             * The bytes of the string are copied, including the null terminator. An empty string is converted to
+             * an empty buffer, however. - §19.3.5.7
+             */
+            AmlValue::String(ref string) => {
+                if string.is_empty() {
+                    Ok(Vec::new())
+                } else {
+                    let mut bytes = string.as_bytes().to_vec();
+                    bytes.push(0x00);
+                    Ok(bytes)
+                }
+            }
             AmlValue::Field { .. } => self.read_field(context)?.as_buffer(context),
             _ => Err(AmlError::IncompatibleValueConversion { current: self.type_of(), target: AmlType::Buffer }),
         }
@@ -290,7 +322,19 @@ impl AmlValue {
     pub fn as_string(&self, context: &AmlContext) -> Result<String, AmlError> {
         match self {
             AmlValue::String(ref string) => Ok(string.clone()),
-            // TODO: implement conversion of Buffer to String
+            /*
+             * Integers are converted to hexadecimal, padded to the width of an integer.
+             */
+            AmlValue::Integer(value) => {
+                let value = value & context.integer_mask();
+                Ok(alloc::format!("{:01$X}", value, context.integer_size() * 2))
+            }
+            /*
+             * Each byte of the buffer is converted to two hexadecimal characters, separated by spaces. - §19.3.5.7
+             */
+            AmlValue::Buffer(ref bytes) => {
+                Ok(bytes.iter().map(|byte| alloc::format!("{:02X}", byte)).collect::<Vec<String>>().join(" "))
+            }
             AmlValue::Field { .. } => self.read_field(context)?.as_string(context),
             _ => Err(AmlError::IncompatibleValueConversion { current: self.type_of(), target: AmlType::String }),
         }
@@ -342,6 +386,8 @@ impl AmlValue {
         // TODO: implement all of the rules
         match desired_type {
             AmlType::Integer => self.as_integer(context).map(|value| AmlValue::Integer(value)),
+            AmlType::Buffer => self.as_buffer(context).map(|bytes| AmlValue::Buffer(bytes)),
+            AmlType::String => self.as_string(context).map(|string| AmlValue::String(string)),
             AmlType::FieldUnit => panic!(
                 "Can't implicitly convert to FieldUnit. This must be special-cased by the caller for now :("
             ),
@@ -533,4 +579,114 @@ mod tests {
 
         // TODO: test the other combinations too, as well as conversions to the correct types for the second operand
     }
+
+    #[test]
+    fn test_conversions() {
+        use alloc::string::ToString;
+
+        let mut context = make_test_context();
+        let integer_tests: &[(AmlValue, u64, u64)] = &[
+            (AmlValue::Integer(0xdead_beef_cafe_f00d), 0xdead_beef_cafe_f00d, 0xcafe_f00d),
+            (
+                AmlValue::Buffer(alloc::vec![0x0d, 0xf0, 0xfe, 0xca, 0xef, 0xbe, 0xad, 0xde, 0xff]),
+                0xdead_beef_cafe_f00d,
+                0xcafe_f00d,
+            ),
+            (AmlValue::Buffer(alloc::vec![0x34, 0x12]), 0x1234, 0x1234),
+            (AmlValue::Buffer(alloc::vec![]), 0, 0),
+            (AmlValue::String("1234abCD".to_string()), 0x1234_abcd, 0x1234_abcd),
+            (AmlValue::String("1234abcd5678".to_string()), 0x1234_abcd_5678, 0x1234_abcd),
+            (AmlValue::String("fffffffffffffffff1".to_string()), u64::MAX, u32::MAX as u64),
+            (AmlValue::String("12G4".to_string()), 0x12, 0x12),
+            (AmlValue::String("".to_string()), 0, 0),
+        ];
+        let buffer_tests: &[(AmlValue, &[u8], &[u8])] = &[
+            (
+                AmlValue::Integer(0x1122_3344_5566_7788),
+                &[0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11],
+                &[0x88, 0x77, 0x66, 0x55],
+            ),
+            (AmlValue::Buffer(alloc::vec![1, 2, 3]), &[1, 2, 3], &[1, 2, 3]),
+            (AmlValue::String("AB".to_string()), &[b'A', b'B', 0x00], &[b'A', b'B', 0x00]),
+            (AmlValue::String("".to_string()), &[], &[]),
+        ];
+        let string_tests: &[(AmlValue, &str, &str)] = &[
+            (AmlValue::Integer(0x1_0000_abcd), "000000010000ABCD", "0000ABCD"),
+            (AmlValue::Buffer(alloc::vec![0x0a, 0xff, 0x10]), "0A FF 10", "0A FF 10"),
+            (AmlValue::Buffer(alloc::vec![]), "", ""),
+            (AmlValue::String("_SB_".to_string()), "_SB_", "_SB_"),
+        ];
+
+        for &(revision, is_32bit) in &[(2, false), (1, true)] {
+            context.set_dsdt_revision(revision);
+
+            for (value, expected_64, expected_32) in integer_tests {
+                let expected = if is_32bit { *expected_32 } else { *expected_64 };
+                assert_eq!(value.as_integer(&context), Ok(expected), "{:?} to Integer (rev {})", value, revision);
+            }
+            for (value, expected_64, expected_32) in buffer_tests {
+                let expected = if is_32bit { *expected_32 } else { *expected_64 };
+                assert_eq!(
+                    value.as_buffer(&context).unwrap(),
+                    expected,
+                    "{:?} to Buffer (rev {})",
+                    value,
+                    revision
+                );
+            }
+            for (value, expected_64, expected_32) in string_tests {
+                let expected = if is_32bit { *expected_32 } else { *expected_64 };
+                assert_eq!(
+                    value.as_string(&context).unwrap(),
+                    expected,
+                    "{:?} to String (rev {})",
+                    value,
+                    revision
+                );
+            }
+        }
+
+        assert!(AmlValue::Package(alloc::vec![]).as_integer(&context).is_err());
+        assert!(AmlValue::Package(alloc::vec![]).as_buffer(&context).is_err());
+        assert!(AmlValue::Mutex { sync_level: 0 }.as_string(&context).is_err());
+    }
+
+    #[test]
+    fn test_nested_package() {
+        /*
+         * Package (3) { 0x01, "_HID", Package (2) { Buffer (2) { 0xaa, 0xbb }, Package (1) { 0x02 } } }
+         */
+        let package = AmlValue::Package(alloc::vec![
+            AmlValue::Integer(1),
+            AmlValue::String(String::from("_HID")),
+            AmlValue::Package(alloc::vec![
+                AmlValue::Buffer(alloc::vec![0xaa, 0xbb]),
+                AmlValue::Package(alloc::vec![AmlValue::Integer(2)]),
+            ]),
+        ]);
+
+        fn count_integers(value: &AmlValue) -> usize {
+            match value {
+                AmlValue::Integer(_) => 1,
+                AmlValue::Package(ref elements) => elements.iter().map(count_integers).sum(),
+                _ => 0,
+            }
+        }
+        assert_eq!(count_integers(&package), 2);
+
+        let inner = match package {
+            AmlValue::Package(ref elements) => match elements[2] {
+                AmlValue::Package(ref inner) => inner,
+                _ => panic!("Third element should be a package"),
+            },
+            _ => unreachable!(),
+        };
+        assert_eq!(inner.len(), 2);
+        assert!(crudely_cmp_values(&inner[0], &AmlValue::Buffer(alloc::vec![0xaa, 0xbb])));
+        assert!(crudely_cmp_values(&inner[1], &AmlValue::Package(alloc::vec![AmlValue::Integer(2)])));
+
+        let cloned = package.clone();
+        assert!(crudely_cmp_values(&cloned, &package));
+        assert_eq!(cloned.type_of(), AmlType::Package);
+    }
 }
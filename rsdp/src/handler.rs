@@ -102,6 +102,79 @@ pub trait AcpiHandler: Clone {
     ///
     /// Note: A reference to the handler used to construct `region` can be acquired by calling [`PhysicalMapping::handler`].
     fn unmap_physical_region<T>(region: &PhysicalMapping<Self, T>);
+
+    /// Get an implementation of `PortIo`, which allows `acpi` to access registers in the system I/O address space
+    /// (e.g. the PM1 control registers and SMI command port on x86). By default, this returns `None`, and
+    /// accesses to registers in that address space fail.
+    fn port_io(&self) -> Option<&dyn PortIo> {
+        None
+    }
+}
+
+/// Provides access to the I/O port address space, on platforms that have one. This can be provided to `acpi` by
+/// overriding `AcpiHandler::port_io`.
+pub trait PortIo {
+    /// ## Safety
+    ///
+    /// Reading from some ports has side-effects. `port` must be safe to read from.
+    unsafe fn read_u8(&self, port: u16) -> u8;
+    /// ## Safety
+    ///
+    /// Reading from some ports has side-effects. `port` must be safe to read from.
+    unsafe fn read_u16(&self, port: u16) -> u16;
+    /// ## Safety
+    ///
+    /// Reading from some ports has side-effects. `port` must be safe to read from.
+    unsafe fn read_u32(&self, port: u16) -> u32;
+
+    /// ## Safety
+    ///
+    /// Writing to ports can have arbitrary effects on the platform. `port` must be safe to write to.
+    unsafe fn write_u8(&self, port: u16, value: u8);
+    /// ## Safety
+    ///
+    /// Writing to ports can have arbitrary effects on the platform. `port` must be safe to write to.
+    unsafe fn write_u16(&self, port: u16, value: u16);
+    /// ## Safety
+    ///
+    /// Writing to ports can have arbitrary effects on the platform. `port` must be safe to write to.
+    unsafe fn write_u32(&self, port: u16, value: u32);
+}
+
+/// An `AcpiHandler` for environments that already have all of physical memory mapped at a fixed offset in the
+/// virtual address space (e.g. the direct map of a higher-half kernel). Mapping a region just adds the offset to
+/// its physical address, and unmapping it does nothing.
+#[derive(Clone, Copy, Debug)]
+pub struct OffsetMapper {
+    physical_memory_offset: usize,
+}
+
+impl OffsetMapper {
+    /// Construct an `OffsetMapper` for physical memory that is mapped starting at the virtual address
+    /// `physical_memory_offset`.
+    ///
+    /// ## Safety
+    ///
+    /// For every region the library asks to map, the virtual addresses `physical_memory_offset` above it must be
+    /// mapped to it, and must stay mapped for as long as the `OffsetMapper`, or any mapping it creates, exists.
+    pub unsafe fn new(physical_memory_offset: usize) -> OffsetMapper {
+        OffsetMapper { physical_memory_offset }
+    }
+
+    pub fn physical_memory_offset(&self) -> usize {
+        self.physical_memory_offset
+    }
+}
+
+impl AcpiHandler for OffsetMapper {
+    unsafe fn map_physical_region<T>(&self, physical_address: usize, size: usize) -> PhysicalMapping<Self, T> {
+        let virtual_address = physical_address.wrapping_add(self.physical_memory_offset);
+        let virtual_start =
+            NonNull::new(virtual_address as *mut T).expect("Physical region mapped to the null virtual address");
+        unsafe { PhysicalMapping::new(physical_address, virtual_start, size, size, *self) }
+    }
+
+    fn unmap_physical_region<T>(_region: &PhysicalMapping<Self, T>) {}
 }
 
 #[cfg(test)]
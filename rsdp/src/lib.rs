@@ -16,8 +16,12 @@ extern crate std;
 
 pub mod handler;
 
-use core::{mem, ops::Range, slice, str};
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use core::ops::Range;
+use core::{fmt, mem, slice, str};
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 use handler::{AcpiHandler, PhysicalMapping};
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 use log::warn;
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
@@ -25,9 +29,32 @@ pub enum RsdpError {
     NoValidRsdp,
     IncorrectSignature,
     InvalidOemId,
+    /// The checksum of the first 20 bytes (the fields present in all revisions of the RSDP) is incorrect.
     InvalidChecksum,
+    /// The RSDP claims to be for ACPI Version 2.0+, but its `length` is too short to include the extended fields.
+    InvalidRevision,
+    /// The extended checksum, which covers the whole of an ACPI Version 2.0+ RSDP, is incorrect.
+    InvalidExtendedChecksum,
+    /// The RSDP claims to be for ACPI Version 2.0+, but its `length` is longer than any revision of the RSDP.
+    InvalidLength,
 }
 
+impl fmt::Display for RsdpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RsdpError::NoValidRsdp => write!(f, "couldn't find a valid RSDP"),
+            RsdpError::IncorrectSignature => write!(f, "RSDP has an incorrect signature"),
+            RsdpError::InvalidOemId => write!(f, "RSDP has an OEM ID that isn't valid UTF-8"),
+            RsdpError::InvalidChecksum => write!(f, "RSDP has an invalid checksum"),
+            RsdpError::InvalidRevision => write!(f, "RSDP claims to be for ACPI 2.0+, but is too short"),
+            RsdpError::InvalidExtendedChecksum => write!(f, "RSDP has an invalid extended checksum"),
+            RsdpError::InvalidLength => write!(f, "RSDP claims to be longer than any revision of the RSDP"),
+        }
+    }
+}
+
+impl core::error::Error for RsdpError {}
+
 /// The first structure found in ACPI. It just tells us where the RSDT is.
 ///
 /// On BIOS systems, it is either found in the first 1KB of the Extended Bios Data Area, or between
@@ -75,6 +102,9 @@ impl Rsdp {
     ///     - ACPI v1.0 structures use `eb9d2d30-2d88-11d3-9a16-0090273fc14d`.
     ///     - ACPI v2.0 or later structures use `8868e871-e4f1-11d3-bc22-0080c73c8881`.
     /// You should search the entire table for the v2.0 GUID before searching for the v1.0 one.
+    ///
+    /// This is only available on x86 and x86_64, as other architectures don't have these BIOS memory areas.
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
     pub unsafe fn search_for_on_bios<H>(handler: H) -> Result<PhysicalMapping<H, Rsdp>, RsdpError>
     where
         H: AcpiHandler,
@@ -87,8 +117,7 @@ impl Rsdp {
                 let mapping = unsafe { handler.map_physical_region::<u8>(area.start, area.end - area.start) };
 
                 for address in area.clone().step_by(16) {
-                    let ptr_in_mapping =
-                        unsafe { mapping.virtual_start().as_ptr().add(address - area.start) };
+                    let ptr_in_mapping = unsafe { mapping.virtual_start().as_ptr().add(address - area.start) };
                     let signature = unsafe { *(ptr_in_mapping as *const [u8; 8]) };
 
                     if signature == RSDP_SIGNATURE {
@@ -117,10 +146,14 @@ impl Rsdp {
 
     /// Checks that:
     ///     1) The signature is correct
-    ///     2) The checksum is correct
-    ///     3) For Version 2.0+, that the extension checksum is correct
+    ///     2) The checksum of the first 20 bytes is correct
+    ///     3) For Version 2.0+ (`revision >= 2`), that `length` covers the extended fields, and that the extended
+    ///        checksum of the whole structure is correct
+    ///
+    /// The extended fields of a Version 1.0 RSDP (including `length`) aren't checked, as they may contain garbage.
     pub fn validate(&self) -> Result<(), RsdpError> {
         const RSDP_V1_LENGTH: usize = 20;
+        const RSDP_V2_LENGTH: usize = 36;
 
         // Check the signature
         if self.signature != RSDP_SIGNATURE {
@@ -132,22 +165,29 @@ impl Rsdp {
             return Err(RsdpError::InvalidOemId);
         }
 
+        // The first checksum only covers the fields from ACPI Version 1.0, and is present in all revisions
+        if checksum(self, RSDP_V1_LENGTH) != 0 {
+            return Err(RsdpError::InvalidChecksum);
+        }
+
         /*
-         * `self.length` doesn't exist on ACPI version 1.0, so we mustn't rely on it. Instead,
-         * check for version 1.0 and use a hard-coded length instead.
+         * `self.length` doesn't exist on ACPI version 1.0, so we mustn't rely on it. For Version 2.0+, the extended
+         * checksum covers the number of bytes specified by `length`.
          */
-        let length = if self.revision > 0 {
-            // For Version 2.0+, include the number of bytes specified by `length`
-            self.length as usize
-        } else {
-            RSDP_V1_LENGTH
-        };
+        if self.revision >= 2 {
+            let length = self.length as usize;
+            if length < RSDP_V2_LENGTH {
+                return Err(RsdpError::InvalidRevision);
+            }
 
-        let bytes = unsafe { slice::from_raw_parts(self as *const Rsdp as *const u8, length) };
-        let sum = bytes.iter().fold(0u8, |sum, &byte| sum.wrapping_add(byte));
+            // Only the RSDP itself is mapped, so the checksum mustn't cover any bytes past it
+            if length > mem::size_of::<Rsdp>() {
+                return Err(RsdpError::InvalidLength);
+            }
 
-        if sum != 0 {
-            return Err(RsdpError::InvalidChecksum);
+            if checksum(self, length) != 0 {
+                return Err(RsdpError::InvalidExtendedChecksum);
+            }
         }
 
         Ok(())
@@ -171,7 +211,14 @@ impl Rsdp {
     }
 }
 
+/// Sum the first `length` bytes of the RSDP. This is zero if they are covered by a correct checksum.
+fn checksum(rsdp: &Rsdp, length: usize) -> u8 {
+    let bytes = unsafe { slice::from_raw_parts(rsdp as *const Rsdp as *const u8, length) };
+    bytes.iter().fold(0u8, |sum, &byte| sum.wrapping_add(byte))
+}
+
 /// Find the areas we should search for the RSDP in.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 pub fn find_search_areas<H>(handler: H) -> [Range<usize>; 2]
 where
     H: AcpiHandler,
@@ -203,14 +250,164 @@ where
 }
 
 /// This (usually!) contains the base address of the EBDA (Extended Bios Data Area), shifted right by 4
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 const EBDA_START_SEGMENT_PTR: usize = 0x40e;
 /// The earliest (lowest) memory address an EBDA (Extended Bios Data Area) can start
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 const EBDA_EARLIEST_START: usize = 0x80000;
 /// The end of the EBDA (Extended Bios Data Area)
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 const EBDA_END: usize = 0x9ffff;
 /// The start of the main BIOS area below 1mb in which to search for the RSDP (Root System Description Pointer)
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 const RSDP_BIOS_AREA_START: usize = 0xe0000;
 /// The end of the main BIOS area below 1mb in which to search for the RSDP (Root System Description Pointer)
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 const RSDP_BIOS_AREA_END: usize = 0xfffff;
 /// The RSDP (Root System Description Pointer)'s signature, "RSD PTR " (note trailing space)
 const RSDP_SIGNATURE: [u8; 8] = *b"RSD PTR ";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    use core::ptr::NonNull;
+    use std::{vec, vec::Vec};
+
+    /// An `AcpiHandler` that maps a buffer standing in for the first 1MiB of physical memory.
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    #[derive(Clone, Copy)]
+    struct LowMemoryHandler {
+        memory: *const u8,
+        length: usize,
+    }
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    impl AcpiHandler for LowMemoryHandler {
+        unsafe fn map_physical_region<T>(&self, physical_address: usize, size: usize) -> PhysicalMapping<Self, T> {
+            assert!(physical_address + size <= self.length);
+            unsafe {
+                PhysicalMapping::new(
+                    physical_address,
+                    NonNull::new(self.memory.add(physical_address) as *mut T).unwrap(),
+                    size,
+                    size,
+                    *self,
+                )
+            }
+        }
+
+        fn unmap_physical_region<T>(_region: &PhysicalMapping<Self, T>) {}
+    }
+
+    /// Build the bytes of a valid RSDP with the given revision, pointing to an RSDT at `rsdt_address` and (for
+    /// revision 2+) an XSDT at `xsdt_address`.
+    fn make_rsdp(revision: u8, rsdt_address: u32, xsdt_address: u64) -> Vec<u8> {
+        let mut rsdp = Vec::new();
+        rsdp.extend_from_slice(&RSDP_SIGNATURE);
+        rsdp.push(0);
+        rsdp.extend_from_slice(b"OEMID ");
+        rsdp.push(revision);
+        rsdp.extend_from_slice(&rsdt_address.to_le_bytes());
+        rsdp[8] = 0u8.wrapping_sub(rsdp.iter().fold(0, |sum: u8, &byte| sum.wrapping_add(byte)));
+
+        if revision > 0 {
+            rsdp.extend_from_slice(&36u32.to_le_bytes());
+            rsdp.extend_from_slice(&xsdt_address.to_le_bytes());
+            rsdp.extend_from_slice(&[0; 4]);
+            rsdp[32] = 0u8.wrapping_sub(rsdp.iter().fold(0, |sum: u8, &byte| sum.wrapping_add(byte)));
+        }
+        rsdp
+    }
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    #[test]
+    fn test_search_for_on_bios() {
+        let mut memory = vec![0u8; 0x10_0000];
+        // The BDA's pointer to the EBDA is left as zero, so the whole of the possible EBDA is searched
+        let rsdp = make_rsdp(2, 0x7fe1_0000, 0x7fe1_0100);
+
+        // A signature that isn't on a 16-byte boundary, which should be ignored
+        memory[0xe_0008..0xe_0008 + 36].copy_from_slice(&rsdp);
+        // An RSDP with a bad checksum, which should be skipped
+        memory[0xe_0100..0xe_0100 + 36].copy_from_slice(&rsdp);
+        memory[0xe_0100 + 9] ^= 0xff;
+        memory[0xe_1230..0xe_1230 + 36].copy_from_slice(&rsdp);
+
+        let handler = LowMemoryHandler { memory: memory.as_ptr(), length: memory.len() };
+        let mapping = unsafe { Rsdp::search_for_on_bios(handler) }.unwrap();
+        assert_eq!(mapping.physical_start(), 0xe_1230);
+        assert_eq!(mapping.revision(), 2);
+        assert_eq!(mapping.rsdt_address(), 0x7fe1_0000);
+        assert_eq!(mapping.xsdt_address(), 0x7fe1_0100);
+    }
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    #[test]
+    fn test_search_for_on_bios_in_ebda() {
+        let mut memory = vec![0u8; 0x10_0000];
+        memory[0x40e..0x410].copy_from_slice(&0x9fc0u16.to_le_bytes());
+        memory[0x9_fc20..0x9_fc20 + 20].copy_from_slice(&make_rsdp(0, 0x7fe1_0000, 0));
+
+        let handler = LowMemoryHandler { memory: memory.as_ptr(), length: memory.len() };
+        let mapping = unsafe { Rsdp::search_for_on_bios(handler) }.unwrap();
+        assert_eq!(mapping.physical_start(), 0x9_fc20);
+        assert_eq!(mapping.revision(), 0);
+
+        memory[0x9_fc20] = 0;
+        let handler = LowMemoryHandler { memory: memory.as_ptr(), length: memory.len() };
+        assert!(matches!(unsafe { Rsdp::search_for_on_bios(handler) }, Err(RsdpError::NoValidRsdp)));
+    }
+
+    /// Interpret the bytes of an RSDP built by `make_rsdp`, which are padded to the size of the full structure.
+    fn rsdp_from_bytes(mut bytes: Vec<u8>) -> Rsdp {
+        bytes.resize(core::mem::size_of::<Rsdp>(), 0);
+        unsafe { (bytes.as_ptr() as *const Rsdp).read_unaligned() }
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(rsdp_from_bytes(make_rsdp(0, 0x7fe1_0000, 0)).validate(), Ok(()));
+        assert_eq!(rsdp_from_bytes(make_rsdp(2, 0x7fe1_0000, 0x7fe1_0100)).validate(), Ok(()));
+
+        let mut bytes = make_rsdp(2, 0x7fe1_0000, 0x7fe1_0100);
+        bytes[0] = b'X';
+        assert_eq!(rsdp_from_bytes(bytes).validate(), Err(RsdpError::IncorrectSignature));
+
+        let mut bytes = make_rsdp(2, 0x7fe1_0000, 0x7fe1_0100);
+        bytes[8] ^= 0xff;
+        assert_eq!(rsdp_from_bytes(bytes).validate(), Err(RsdpError::InvalidChecksum));
+    }
+
+    #[test]
+    fn test_validate_extended_checksum() {
+        // Corrupting the XSDT address is only caught by the extended checksum
+        let mut bytes = make_rsdp(2, 0x7fe1_0000, 0x7fe1_0100);
+        bytes[24] ^= 0x01;
+        assert_eq!(rsdp_from_bytes(bytes).validate(), Err(RsdpError::InvalidExtendedChecksum));
+
+        let mut bytes = make_rsdp(2, 0x7fe1_0000, 0x7fe1_0100);
+        bytes[32] ^= 0xff;
+        assert_eq!(rsdp_from_bytes(bytes).validate(), Err(RsdpError::InvalidExtendedChecksum));
+
+        // A Version 2.0+ RSDP that's too short to contain the extended fields
+        let mut bytes = make_rsdp(2, 0x7fe1_0000, 0x7fe1_0100);
+        bytes[20..24].copy_from_slice(&20u32.to_le_bytes());
+        assert_eq!(rsdp_from_bytes(bytes).validate(), Err(RsdpError::InvalidRevision));
+
+        // A Version 2.0+ RSDP that claims to be much longer than it is
+        let mut bytes = make_rsdp(2, 0x7fe1_0000, 0x7fe1_0100);
+        bytes[20..24].copy_from_slice(&0xffff_ffffu32.to_le_bytes());
+        assert_eq!(rsdp_from_bytes(bytes).validate(), Err(RsdpError::InvalidLength));
+    }
+
+    #[test]
+    fn test_validate_revision_0_ignores_extended_fields() {
+        // The fields after the first 20 bytes don't exist in a Version 1.0 RSDP, so garbage there is fine
+        let mut bytes = make_rsdp(0, 0x7fe1_0000, 0);
+        bytes.extend_from_slice(&0xdead_beefu32.to_le_bytes());
+        bytes.extend_from_slice(&[0xff; 12]);
+        assert_eq!(bytes.len(), 36);
+        assert_eq!(rsdp_from_bytes(bytes).validate(), Ok(()));
+    }
+}